@@ -691,3 +691,132 @@ export async function getSummaryStatsWithIntents() {
     })),
   };
 }
+
+// ============================================
+// Intent job queue operations
+// ============================================
+
+/**
+ * Enqueue a job for an intent (idempotent: one job per intent)
+ */
+export async function enqueueIntentJob(params: {
+  intentId: string;
+  step?: string;
+  maxAttempts?: number;
+  runAfter?: number;
+  txChain?: string;
+  txHash?: string;
+}) {
+  const now = Math.floor(Date.now() / 1000);
+  const sql = convertPlaceholders(`
+    INSERT INTO intent_jobs (id, intent_id, step, status, attempts, max_attempts, run_after, tx_chain, tx_hash, created_at, updated_at)
+    VALUES (?, ?, ?, 'pending', 0, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT (intent_id) DO NOTHING
+  `);
+  await query(sql, [
+    randomUUID(),
+    params.intentId,
+    params.step ?? 'route',
+    params.maxAttempts ?? 5,
+    params.runAfter ?? now,
+    params.txChain ?? null,
+    params.txHash ?? null,
+    now,
+    now,
+  ]);
+  return getIntentJob(params.intentId);
+}
+
+export async function getIntentJob(intentId: string) {
+  const sql = convertPlaceholders('SELECT * FROM intent_jobs WHERE intent_id = ?');
+  return queryOne(sql, [intentId]);
+}
+
+/**
+ * Lease ready jobs for a worker
+ * SKIP LOCKED lets several workers lease concurrently without double-claiming
+ */
+export async function leaseIntentJobs(owner: string, leaseSeconds: number, limit: number) {
+  const now = Math.floor(Date.now() / 1000);
+  return transaction(async (client) => {
+    const ready = await client.query(convertPlaceholders(`
+      SELECT id FROM intent_jobs
+      WHERE (status = 'pending' AND run_after <= ?)
+         OR (status = 'leased' AND lease_expires_at < ?)
+      ORDER BY run_after ASC
+      LIMIT ?
+      FOR UPDATE SKIP LOCKED
+    `), [now, now, limit]);
+
+    const ids = ready.rows.map((row: { id: string }) => row.id);
+    if (ids.length === 0) return [];
+
+    const claimed = await client.query(`
+      UPDATE intent_jobs
+      SET status = 'leased', lease_owner = $1, lease_expires_at = $2, attempts = attempts + 1, updated_at = $3
+      WHERE id = ANY($4)
+      RETURNING *
+    `, [owner, now + leaseSeconds, now, ids]);
+    return claimed.rows.sort((a: any, b: any) => a.run_after - b.run_after);
+  });
+}
+
+/**
+ * Extend a held lease; returns false if the lease was lost to another worker
+ */
+export async function renewIntentJobLease(id: string, owner: string, leaseSeconds: number): Promise<boolean> {
+  const now = Math.floor(Date.now() / 1000);
+  const sql = convertPlaceholders(`
+    UPDATE intent_jobs SET lease_expires_at = ?, updated_at = ?
+    WHERE id = ? AND status = 'leased' AND lease_owner = ?
+  `);
+  const result = await query(sql, [now + leaseSeconds, now, id, owner]);
+  return (result.rowCount || 0) > 0;
+}
+
+export async function updateIntentJob(id: string, updates: Record<string, any>): Promise<void> {
+  const fields: string[] = [];
+  const values: any[] = [];
+  let paramIndex = 1;
+
+  for (const [key, value] of Object.entries(updates)) {
+    if (value !== undefined) {
+      fields.push(`${key} = $${paramIndex++}`);
+      values.push(value);
+    }
+  }
+
+  if (fields.length === 0) return;
+
+  fields.push(`updated_at = $${paramIndex++}`);
+  values.push(Math.floor(Date.now() / 1000));
+  values.push(id);
+
+  await query(`UPDATE intent_jobs SET ${fields.join(', ')} WHERE id = $${paramIndex}`, values);
+}
+
+/**
+ * Checkpoint a broadcast tx on the intent's job
+ */
+export async function recordIntentJobTx(intentId: string, chain: string, txHash: string): Promise<void> {
+  const sql = convertPlaceholders(`
+    UPDATE intent_jobs SET step = 'confirm', tx_chain = ?, tx_hash = ?, updated_at = ?
+    WHERE intent_id = ? AND status = 'leased'
+  `);
+  await query(sql, [chain, txHash, Math.floor(Date.now() / 1000), intentId]);
+}
+
+/**
+ * Intents left mid-pipeline with no job (e.g. inline runs interrupted by a restart)
+ */
+export async function getOrphanedIntents(olderThan: number) {
+  const sql = convertPlaceholders(`
+    SELECT i.* FROM intents i
+    LEFT JOIN intent_jobs j ON j.intent_id = i.id
+    WHERE j.id IS NULL
+      AND i.status IN ('queued', 'routed', 'executing')
+      AND i.created_at < ?
+    ORDER BY i.created_at ASC
+  `);
+  return queryRows(sql, [olderThan]);
+}
@@ -111,9 +111,20 @@ function runColumnMigrations(database: Database.Database): void {
     'ALTER TABLE executions ADD COLUMN relayer_address TEXT',
     'ALTER TABLE executions ADD COLUMN session_id TEXT',
     'ALTER TABLE executions ADD COLUMN intent_id TEXT',
+    'ALTER TABLE executions ADD COLUMN reconciled_at INTEGER',
+    'ALTER TABLE executions ADD COLUMN linked_execution_id TEXT',
     // execution_steps table new columns for intent tracking
     'ALTER TABLE execution_steps ADD COLUMN stage TEXT',
     'ALTER TABLE execution_steps ADD COLUMN error_code TEXT',
+    // positions table funding accrual
+    'ALTER TABLE positions ADD COLUMN funding_usd REAL DEFAULT 0',
+    'ALTER TABLE positions ADD COLUMN funding_updated_at INTEGER',
+    // positions table indexer block tracking (reorg rollback)
+    'ALTER TABLE positions ADD COLUMN open_block_number INTEGER',
+    'ALTER TABLE positions ADD COLUMN close_block_number INTEGER',
+    // sessions table indexer block tracking (reorg rollback)
+    'ALTER TABLE sessions ADD COLUMN created_block_number INTEGER',
+    'ALTER TABLE sessions ADD COLUMN revoked_block_number INTEGER',
   ];
 
   for (const migration of migrations) {
@@ -173,6 +184,8 @@ export interface Execution {
   latency_ms?: number;
   relayer_address?: string;              // NEW: relayer that submitted tx
   session_id?: string;                   // NEW: session ID if session mode
+  reconciled_at?: number;                // Last receipt re-check by the reconciler
+  linked_execution_id?: string;          // Other leg of a cross-chain transfer
   created_at: number;
   updated_at: number;
 }
@@ -201,6 +214,8 @@ export interface Session {
   expires_at?: number;
   created_tx?: string;
   revoked_tx?: string;
+  created_block_number?: number;
+  revoked_block_number?: number;
   created_at: number;
   updated_at: number;
 }
@@ -320,6 +335,8 @@ export function updateExecution(
     usdEstimateIsEstimate: boolean;
     relayerAddress: string;
     sessionId: string;
+    reconciledAt: number;
+    linkedExecutionId: string;
   }>
 ): void {
   const db = getDatabase();
@@ -384,6 +401,14 @@ export function updateExecution(
     sets.push('session_id = ?');
     values.push(updates.sessionId);
   }
+  if (updates.reconciledAt !== undefined) {
+    sets.push('reconciled_at = ?');
+    values.push(updates.reconciledAt);
+  }
+  if (updates.linkedExecutionId !== undefined) {
+    sets.push('linked_execution_id = ?');
+    values.push(updates.linkedExecutionId);
+  }
 
   values.push(id);
   db.prepare(`UPDATE executions SET ${sets.join(', ')} WHERE id = ?`).run(...values);
@@ -569,10 +594,13 @@ export function upsertSession(params: {
   status: SessionStatus;
   expiresAt?: number;
   createdTx?: string;
+  blockNumber?: number;         // Block of the indexed event that set this status
 }): Session {
   const db = getDatabase();
   const id = randomUUID();
   const now = Math.floor(Date.now() / 1000);
+  const createdBlock = params.status === 'active' ? params.blockNumber : undefined;
+  const revokedBlock = params.status === 'revoked' ? params.blockNumber : undefined;
 
   const existing = db.prepare(
     'SELECT * FROM sessions WHERE chain = ? AND network = ? AND user_address = ? AND session_id = ?'
@@ -580,12 +608,15 @@ export function upsertSession(params: {
 
   if (existing) {
     db.prepare(`
-      UPDATE sessions SET status = ?, relayer_address = ?, expires_at = ?, updated_at = ?
+      UPDATE sessions SET status = ?, relayer_address = ?, expires_at = ?,
+        created_block_number = ?, revoked_block_number = ?, updated_at = ?
       WHERE id = ?
     `).run(
       params.status,
       params.relayerAddress ?? existing.relayer_address ?? null,
       params.expiresAt ?? existing.expires_at ?? null,
+      createdBlock ?? existing.created_block_number ?? null,
+      revokedBlock ?? existing.revoked_block_number ?? null,
       now,
       existing.id
     );
@@ -595,8 +626,8 @@ export function upsertSession(params: {
   db.prepare(`
     INSERT INTO sessions (
       id, chain, network, user_address, session_id, relayer_address,
-      status, expires_at, created_tx, created_at, updated_at
-    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+      status, expires_at, created_tx, created_block_number, revoked_block_number, created_at, updated_at
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
   `).run(
     id,
     params.chain,
@@ -607,6 +638,8 @@ export function upsertSession(params: {
     params.status,
     params.expiresAt ?? null,
     params.createdTx ?? null,
+    createdBlock ?? null,
+    revokedBlock ?? null,
     now,
     now
   );
@@ -621,6 +654,8 @@ export function upsertSession(params: {
     status: params.status,
     expires_at: params.expiresAt,
     created_tx: params.createdTx,
+    created_block_number: createdBlock,
+    revoked_block_number: revokedBlock,
     created_at: now,
     updated_at: now,
   };
@@ -699,6 +734,127 @@ export function listSessionsWithMeta(params?: {
   };
 }
 
+export interface SessionPolicyRecord {
+  session_id: string;
+  user_address: string;
+  policy_json: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface SessionPolicyUsage {
+  id: string;
+  session_id: string;
+  user_address: string;
+  spend_usd: number;
+  token_spend_json?: string;
+  instrument_type?: string;
+  tx_hash?: string;
+  created_at: number;
+}
+
+export function upsertSessionPolicy(sessionId: string, userAddress: string, policy: unknown): void {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+  db.prepare(`
+    INSERT INTO session_policies (session_id, user_address, policy_json, created_at, updated_at)
+    VALUES (?, ?, ?, ?, ?)
+    ON CONFLICT(session_id) DO UPDATE SET
+      policy_json = excluded.policy_json,
+      updated_at = excluded.updated_at
+  `).run(sessionId.toLowerCase(), userAddress.toLowerCase(), JSON.stringify(policy), now, now);
+}
+
+export function getSessionPolicy(sessionId: string): SessionPolicyRecord | undefined {
+  const db = getDatabase();
+  return db.prepare('SELECT * FROM session_policies WHERE session_id = ?')
+    .get(sessionId.toLowerCase()) as SessionPolicyRecord | undefined;
+}
+
+export function deleteSessionPolicy(sessionId: string): boolean {
+  const db = getDatabase();
+  return db.prepare('DELETE FROM session_policies WHERE session_id = ?').run(sessionId.toLowerCase()).changes > 0;
+}
+
+export function recordSessionPolicyUsage(params: {
+  sessionId: string;
+  userAddress: string;
+  spendUsd: number;
+  tokenSpend?: Record<string, number>;
+  instrumentType?: string;
+  txHash?: string;
+}): string {
+  const db = getDatabase();
+  const id = randomUUID();
+  db.prepare(`
+    INSERT INTO session_policy_usage (id, session_id, user_address, spend_usd, token_spend_json, instrument_type, tx_hash, created_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+  `).run(
+    id,
+    params.sessionId.toLowerCase(),
+    params.userAddress.toLowerCase(),
+    params.spendUsd,
+    params.tokenSpend ? JSON.stringify(params.tokenSpend) : null,
+    params.instrumentType ?? null,
+    params.txHash ?? null,
+    Math.floor(Date.now() / 1000)
+  );
+  return id;
+}
+
+/**
+ * Record usage for a relay that has not been sent yet, if `admit` allows it
+ * against the session's usage since `since`
+ * The check and the insert run in one transaction, so concurrent relays
+ * cannot both fit under a rolling limit that only one of them fits.
+ */
+export function reserveSessionPolicyUsage<T extends { allowed: boolean }>(
+  params: {
+    sessionId: string;
+    userAddress: string;
+    spendUsd: number;
+    tokenSpend?: Record<string, number>;
+    instrumentType?: string;
+    since: number;
+  },
+  admit: (usage: SessionPolicyUsage[]) => T
+): { result: T; usageId?: string } {
+  const db = getDatabase();
+  const reserve = db.transaction(() => {
+    const result = admit(getSessionPolicyUsageSince(params.sessionId, params.since));
+    if (!result.allowed) {
+      return { result };
+    }
+    return { result, usageId: recordSessionPolicyUsage(params) };
+  });
+  return reserve();
+}
+
+/**
+ * Attach the relayed tx to a reserved usage row
+ */
+export function attachSessionPolicyUsageTx(id: string, txHash: string): void {
+  const db = getDatabase();
+  db.prepare('UPDATE session_policy_usage SET tx_hash = ? WHERE id = ?').run(txHash, id);
+}
+
+/**
+ * Drop a reservation whose relay was never sent
+ */
+export function releaseSessionPolicyUsage(id: string): void {
+  const db = getDatabase();
+  db.prepare('DELETE FROM session_policy_usage WHERE id = ?').run(id);
+}
+
+export function getSessionPolicyUsageSince(sessionId: string, since: number): SessionPolicyUsage[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM session_policy_usage
+    WHERE session_id = ? AND created_at >= ?
+    ORDER BY created_at DESC
+  `).all(sessionId.toLowerCase(), since) as SessionPolicyUsage[];
+}
+
 // ============================================
 // Asset Operations
 // ============================================
@@ -927,6 +1083,232 @@ export function listWallets(params?: { chain?: Chain; network?: Network }): Wall
   return db.prepare(query).all(...values) as Wallet[];
 }
 
+// ============================================
+// Linked Wallet Operations
+// ============================================
+
+export interface LinkedWallet {
+  id: string;
+  user_address: string;
+  chain: Chain;
+  network: Network;
+  address: string;
+  label?: string;
+  created_at: number;
+}
+
+/**
+ * Link a wallet to a user (idempotent)
+ * Solana addresses keep their case; EVM addresses are lowercased
+ */
+export function linkWallet(params: {
+  userAddress: string;
+  chain: Chain;
+  network: Network;
+  address: string;
+  label?: string;
+}): LinkedWallet {
+  const db = getDatabase();
+  const userAddress = params.userAddress.toLowerCase();
+  const address = params.chain === 'ethereum' ? params.address.toLowerCase() : params.address;
+
+  db.prepare(`
+    INSERT INTO linked_wallets (id, user_address, chain, network, address, label, created_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(user_address, chain, network, address) DO UPDATE SET label = COALESCE(excluded.label, linked_wallets.label)
+  `).run(randomUUID(), userAddress, params.chain, params.network, address, params.label ?? null, Math.floor(Date.now() / 1000));
+
+  return db.prepare(`
+    SELECT * FROM linked_wallets WHERE user_address = ? AND chain = ? AND network = ? AND address = ?
+  `).get(userAddress, params.chain, params.network, address) as LinkedWallet;
+}
+
+export function unlinkWallet(userAddress: string, chain: Chain, network: Network, address: string): boolean {
+  const db = getDatabase();
+  const normalized = chain === 'ethereum' ? address.toLowerCase() : address;
+  return db.prepare(`
+    DELETE FROM linked_wallets WHERE user_address = ? AND chain = ? AND network = ? AND address = ?
+  `).run(userAddress.toLowerCase(), chain, network, normalized).changes > 0;
+}
+
+export function listLinkedWallets(userAddress: string): LinkedWallet[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM linked_wallets WHERE user_address = ? ORDER BY created_at ASC
+  `).all(userAddress.toLowerCase()) as LinkedWallet[];
+}
+
+// ============================================
+// Bridge Transfer Operations
+// ============================================
+
+export type BridgeTransferState =
+  | 'awaiting_signature' // Quoted and handed to the user to sign; no source tx yet
+  | 'source_pending'    // Source tx broadcast, no receipt yet
+  | 'source_confirmed'  // Source tx mined; provider hasn't picked it up
+  | 'in_flight'         // Provider is relaying to the destination
+  | 'dest_confirmed'    // Funds received on the destination chain
+  | 'refunded'          // Bridge gave up and returned funds on the source chain
+  | 'failed'            // Source tx reverted or the provider reports a failure
+  | 'stuck';            // Not final after BRIDGE_STUCK_AFTER_SECONDS (still polled)
+
+export interface BridgeTransfer {
+  id: string;
+  intent_id?: string;
+  provider: string;
+  tool?: string;
+  state: BridgeTransferState;
+  from_chain: Chain;
+  from_network: Network;
+  to_chain: Chain;
+  to_network: Network;
+  from_chain_id?: number;
+  to_chain_id?: number;
+  from_address: string;
+  to_address?: string;
+  from_token?: string;
+  to_token?: string;
+  amount_units?: string;
+  received_units?: string;
+  source_tx_hash?: string;          // Unset while awaiting_signature
+  dest_tx_hash?: string;
+  source_execution_id?: string;
+  dest_execution_id?: string;
+  provider_status?: string;
+  provider_substatus?: string;
+  status_message?: string;
+  poll_count: number;
+  last_polled_at?: number;
+  source_confirmed_at?: number;
+  completed_at?: number;
+  alerted_at?: number;
+  created_at: number;
+  updated_at: number;
+}
+
+export function createBridgeTransfer(params: {
+  intentId?: string;
+  provider: string;
+  tool?: string;
+  fromChain: Chain;
+  fromNetwork: Network;
+  toChain: Chain;
+  toNetwork: Network;
+  fromChainId?: number;
+  toChainId?: number;
+  fromAddress: string;
+  toAddress?: string;
+  fromToken?: string;
+  toToken?: string;
+  amountUnits?: string;
+  state?: 'awaiting_signature' | 'source_pending';   // Default source_pending
+  sourceTxHash?: string;
+  sourceExecutionId?: string;
+}): BridgeTransfer {
+  const db = getDatabase();
+  const id = randomUUID();
+  const now = Math.floor(Date.now() / 1000);
+
+  db.prepare(`
+    INSERT INTO bridge_transfers (
+      id, intent_id, provider, tool, state, from_chain, from_network, to_chain, to_network,
+      from_chain_id, to_chain_id, from_address, to_address, from_token, to_token,
+      amount_units, source_tx_hash, source_execution_id, created_at, updated_at
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `).run(
+    id,
+    params.intentId ?? null,
+    params.provider,
+    params.tool ?? null,
+    params.state ?? 'source_pending',
+    params.fromChain,
+    params.fromNetwork,
+    params.toChain,
+    params.toNetwork,
+    params.fromChainId ?? null,
+    params.toChainId ?? null,
+    params.fromChain === 'ethereum' ? params.fromAddress.toLowerCase() : params.fromAddress,
+    params.toAddress && params.toChain === 'ethereum' ? params.toAddress.toLowerCase() : params.toAddress ?? null,
+    params.fromToken ?? null,
+    params.toToken ?? null,
+    params.amountUnits ?? null,
+    params.sourceTxHash ?? null,
+    params.sourceExecutionId ?? null,
+    now,
+    now
+  );
+
+  return getBridgeTransfer(id)!;
+}
+
+export function getBridgeTransfer(id: string): BridgeTransfer | undefined {
+  const db = getDatabase();
+  return db.prepare('SELECT * FROM bridge_transfers WHERE id = ?').get(id) as BridgeTransfer | undefined;
+}
+
+export function getBridgeTransferBySourceTx(txHash: string): BridgeTransfer | undefined {
+  const db = getDatabase();
+  return db.prepare('SELECT * FROM bridge_transfers WHERE source_tx_hash = ?').get(txHash) as BridgeTransfer | undefined;
+}
+
+export function updateBridgeTransfer(
+  id: string,
+  updates: Partial<Omit<BridgeTransfer, 'id' | 'created_at' | 'updated_at'>>
+): void {
+  const db = getDatabase();
+  const setClauses: string[] = [];
+  const values: any[] = [];
+
+  for (const [key, value] of Object.entries(updates)) {
+    if (value !== undefined) {
+      setClauses.push(`${key} = ?`);
+      values.push(value);
+    }
+  }
+
+  if (setClauses.length === 0) return;
+
+  setClauses.push('updated_at = ?');
+  values.push(Math.floor(Date.now() / 1000));
+  values.push(id);
+
+  db.prepare(`UPDATE bridge_transfers SET ${setClauses.join(', ')} WHERE id = ?`).run(...values);
+}
+
+export function listBridgeTransfers(params: {
+  states?: BridgeTransferState[];
+  intentId?: string;
+  fromAddress?: string;
+  polledBefore?: number;       // Only rows not polled since this time
+  limit?: number;
+} = {}): BridgeTransfer[] {
+  const db = getDatabase();
+  let query = 'SELECT * FROM bridge_transfers WHERE 1=1';
+  const values: any[] = [];
+
+  if (params.states?.length) {
+    query += ` AND state IN (${params.states.map(() => '?').join(', ')})`;
+    values.push(...params.states);
+  }
+  if (params.intentId) {
+    query += ' AND intent_id = ?';
+    values.push(params.intentId);
+  }
+  if (params.fromAddress) {
+    query += ' AND from_address = ?';
+    values.push(params.fromAddress.toLowerCase());
+  }
+  if (params.polledBefore !== undefined) {
+    query += ' AND (last_polled_at IS NULL OR last_polled_at <= ?)';
+    values.push(params.polledBefore);
+  }
+
+  query += ' ORDER BY created_at DESC LIMIT ?';
+  values.push(params.limit ?? 50);
+
+  return db.prepare(query).all(...values) as BridgeTransfer[];
+}
+
 // ============================================
 // Summary / Stats Operations
 // ============================================
@@ -1540,113 +1922,430 @@ export function getSummaryStatsWithIntents(): StatsSummary & {
 }
 
 // ============================================
-// Positions table operations
+// Intent job queue operations
 // ============================================
 
-export interface Position {
+export type IntentJobStep = 'route' | 'execute' | 'confirm';
+export type IntentJobStatus = 'pending' | 'leased' | 'done' | 'dead';
+
+export interface IntentJob {
   id: string;
-  chain: 'ethereum' | 'solana';
-  network: string;
-  venue: string;
-  market: string;
-  side: 'long' | 'short';
-  leverage?: number;
-  margin_units?: string;
-  margin_display?: string;
-  size_units?: string;
-  entry_price?: string;
-  status: 'open' | 'closed' | 'liquidated';
-  opened_at: number;
-  closed_at?: number;
-  open_tx_hash?: string;
-  open_explorer_url?: string;
-  close_tx_hash?: string;
-  close_explorer_url?: string;
-  pnl?: string;
-  user_address: string;
-  on_chain_position_id?: string;
-  intent_id?: string;
-  execution_id?: string;
+  intent_id: string;
+  step: IntentJobStep;
+  status: IntentJobStatus;
+  attempts: number;
+  max_attempts: number;
+  run_after: number;
+  lease_owner?: string;
+  lease_expires_at?: number;
+  tx_chain?: string;
+  tx_hash?: string;
+  last_error?: string | null;
   created_at: number;
   updated_at: number;
 }
 
-export interface CreatePositionInput {
-  chain: 'ethereum' | 'solana';
-  network: string;
-  venue: string;
-  market: string;
-  side: 'long' | 'short';
-  leverage?: number;
-  margin_units?: string;
-  margin_display?: string;
-  size_units?: string;
-  entry_price?: string;
-  open_tx_hash?: string;
-  open_explorer_url?: string;
-  user_address: string;
-  on_chain_position_id?: string;
-  intent_id?: string;
-  execution_id?: string;
-}
-
-export function createPosition(input: CreatePositionInput): Position {
+/**
+ * Enqueue a job for an intent (idempotent: one job per intent)
+ */
+export function enqueueIntentJob(params: {
+  intentId: string;
+  step?: IntentJobStep;
+  maxAttempts?: number;
+  runAfter?: number;
+  txChain?: string;      // Checkpointed tx for a job that starts at confirm
+  txHash?: string;
+}): IntentJob {
   const db = getDatabase();
-  const id = crypto.randomUUID();
   const now = Math.floor(Date.now() / 1000);
 
   db.prepare(`
-    INSERT INTO positions (
-      id, chain, network, venue, market, side, leverage,
-      margin_units, margin_display, size_units, entry_price,
-      status, opened_at, open_tx_hash, open_explorer_url,
-      user_address, on_chain_position_id, intent_id, execution_id,
-      created_at, updated_at
-    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'open', ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    INSERT INTO intent_jobs (id, intent_id, step, status, attempts, max_attempts, run_after, tx_chain, tx_hash, created_at, updated_at)
+    VALUES (?, ?, ?, 'pending', 0, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(intent_id) DO NOTHING
   `).run(
-    id,
-    input.chain,
-    input.network,
-    input.venue,
-    input.market,
-    input.side,
-    input.leverage ?? null,
-    input.margin_units ?? null,
-    input.margin_display ?? null,
-    input.size_units ?? null,
-    input.entry_price ?? null,
-    now,
-    input.open_tx_hash ?? null,
-    input.open_explorer_url ?? null,
-    input.user_address,
-    input.on_chain_position_id ?? null,
-    input.intent_id ?? null,
-    input.execution_id ?? null,
+    randomUUID(),
+    params.intentId,
+    params.step ?? 'route',
+    params.maxAttempts ?? 5,
+    params.runAfter ?? now,
+    params.txChain ?? null,
+    params.txHash ?? null,
     now,
     now
   );
 
-  return getPosition(id)!;
+  return getIntentJob(params.intentId)!;
 }
 
-export function getPosition(id: string): Position | null {
+export function getIntentJob(intentId: string): IntentJob | undefined {
   const db = getDatabase();
-  const row = db.prepare('SELECT * FROM positions WHERE id = ?').get(id) as Position | undefined;
-  return row ?? null;
+  return db.prepare('SELECT * FROM intent_jobs WHERE intent_id = ?').get(intentId) as IntentJob | undefined;
 }
 
-export function getPositionByOnChainId(
-  chain: string,
-  network: string,
-  venue: string,
-  onChainPositionId: string
-): Position | null {
+export function listIntentJobs(params?: { status?: IntentJobStatus; limit?: number }): IntentJob[] {
   const db = getDatabase();
-  const row = db.prepare(`
-    SELECT * FROM positions
-    WHERE chain = ? AND network = ? AND venue = ? AND on_chain_position_id = ?
-  `).get(chain, network, venue, onChainPositionId) as Position | undefined;
-  return row ?? null;
+  const limit = params?.limit ?? 50;
+  if (params?.status) {
+    return db.prepare(`
+      SELECT * FROM intent_jobs WHERE status = ? ORDER BY updated_at DESC LIMIT ?
+    `).all(params.status, limit) as IntentJob[];
+  }
+  return db.prepare('SELECT * FROM intent_jobs ORDER BY updated_at DESC LIMIT ?').all(limit) as IntentJob[];
+}
+
+/**
+ * Lease ready jobs for a worker
+ * Picks pending jobs past their backoff plus leased jobs whose lease expired
+ * (worker crashed or restarted); each lease counts as an attempt.
+ */
+export function leaseIntentJobs(owner: string, leaseSeconds: number, limit: number): IntentJob[] {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+
+  const lease = db.transaction(() => {
+    const ready = db.prepare(`
+      SELECT id FROM intent_jobs
+      WHERE (status = 'pending' AND run_after <= ?)
+         OR (status = 'leased' AND lease_expires_at < ?)
+      ORDER BY run_after ASC
+      LIMIT ?
+    `).all(now, now, limit) as { id: string }[];
+
+    const claim = db.prepare(`
+      UPDATE intent_jobs
+      SET status = 'leased', lease_owner = ?, lease_expires_at = ?, attempts = attempts + 1, updated_at = ?
+      WHERE id = ?
+    `);
+    for (const { id } of ready) {
+      claim.run(owner, now + leaseSeconds, now, id);
+    }
+
+    return ready.map(({ id }) => db.prepare('SELECT * FROM intent_jobs WHERE id = ?').get(id) as IntentJob);
+  });
+
+  return lease();
+}
+
+/**
+ * Extend a held lease; returns false if the lease was lost to another worker
+ */
+export function renewIntentJobLease(id: string, owner: string, leaseSeconds: number): boolean {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+  const result = db.prepare(`
+    UPDATE intent_jobs SET lease_expires_at = ?, updated_at = ?
+    WHERE id = ? AND status = 'leased' AND lease_owner = ?
+  `).run(now + leaseSeconds, now, id, owner);
+  return result.changes > 0;
+}
+
+export function updateIntentJob(
+  id: string,
+  updates: Partial<Omit<IntentJob, 'id' | 'intent_id' | 'created_at'>>
+): void {
+  const db = getDatabase();
+  const setClauses: string[] = [];
+  const values: any[] = [];
+
+  for (const [key, value] of Object.entries(updates)) {
+    if (value !== undefined) {
+      setClauses.push(`${key} = ?`);
+      values.push(value);
+    }
+  }
+
+  if (setClauses.length === 0) return;
+
+  setClauses.push('updated_at = ?');
+  values.push(Math.floor(Date.now() / 1000));
+  values.push(id);
+
+  db.prepare(`UPDATE intent_jobs SET ${setClauses.join(', ')} WHERE id = ?`).run(...values);
+}
+
+/**
+ * Checkpoint a broadcast tx on the intent's job
+ * Moves the job to the confirm step so a retry never re-sends
+ */
+export function recordIntentJobTx(intentId: string, chain: string, txHash: string): void {
+  const db = getDatabase();
+  db.prepare(`
+    UPDATE intent_jobs SET step = 'confirm', tx_chain = ?, tx_hash = ?, updated_at = ?
+    WHERE intent_id = ? AND status = 'leased'
+  `).run(chain, txHash, Math.floor(Date.now() / 1000), intentId);
+}
+
+/**
+ * Intents left mid-pipeline with no job (e.g. inline runs interrupted by a restart)
+ */
+export function getOrphanedIntents(olderThan: number): Intent[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT i.* FROM intents i
+    LEFT JOIN intent_jobs j ON j.intent_id = i.id
+    WHERE j.id IS NULL
+      AND i.status IN ('queued', 'routed', 'executing')
+      AND i.created_at < ?
+    ORDER BY i.created_at ASC
+  `).all(olderThan) as Intent[];
+}
+
+// ============================================
+// Reconciliation operations
+// ============================================
+
+export type DiscrepancyKind = 'dropped' | 'reorged' | 'reverted_after_confirm' | 'missing_tx_hash';
+
+export interface ExecutionDiscrepancy {
+  id: string;
+  execution_id: string;
+  chain: Chain;
+  tx_hash?: string;
+  kind: DiscrepancyKind;
+  ledger_status: ExecutionStatus;
+  chain_status: string;
+  detail?: string;
+  detected_at: number;
+}
+
+/**
+ * Executions whose on-chain outcome should be re-checked
+ * Non-terminal rows past minAge, plus confirmed rows still inside the reorg window
+ */
+export function listExecutionsToReconcile(params: {
+  minAgeSeconds: number;
+  recheckAfterSeconds: number;
+  reorgWindowSeconds: number;
+  limit?: number;
+}): Execution[] {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+  return db.prepare(`
+    SELECT * FROM executions
+    WHERE (
+        status IN ('pending', 'submitted')
+        OR (status = 'confirmed' AND tx_hash IS NOT NULL AND created_at >= ?)
+      )
+      AND created_at <= ?
+      AND (reconciled_at IS NULL OR reconciled_at <= ?)
+    ORDER BY COALESCE(reconciled_at, 0) ASC, created_at ASC
+    LIMIT ?
+  `).all(
+    now - params.reorgWindowSeconds,
+    now - params.minAgeSeconds,
+    now - params.recheckAfterSeconds,
+    params.limit ?? 50
+  ) as Execution[];
+}
+
+/**
+ * Record a discrepancy (one row per execution and kind; re-detection refreshes it)
+ */
+export function recordExecutionDiscrepancy(params: {
+  executionId: string;
+  chain: Chain;
+  txHash?: string;
+  kind: DiscrepancyKind;
+  ledgerStatus: ExecutionStatus;
+  chainStatus: string;
+  detail?: string;
+}): void {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+  db.prepare(`
+    INSERT INTO execution_discrepancies (id, execution_id, chain, tx_hash, kind, ledger_status, chain_status, detail, detected_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(execution_id, kind) DO UPDATE SET
+      chain_status = excluded.chain_status,
+      detail = excluded.detail,
+      detected_at = excluded.detected_at
+  `).run(
+    randomUUID(),
+    params.executionId,
+    params.chain,
+    params.txHash ?? null,
+    params.kind,
+    params.ledgerStatus,
+    params.chainStatus,
+    params.detail ?? null,
+    now
+  );
+}
+
+/**
+ * List discrepancies with the execution's current status
+ */
+export function listExecutionDiscrepancies(params?: {
+  kind?: DiscrepancyKind;
+  chain?: Chain;
+  since?: number;
+  limit?: number;
+}): (ExecutionDiscrepancy & { current_status: ExecutionStatus; intent_id?: string; explorer_url?: string })[] {
+  const db = getDatabase();
+
+  let query = `
+    SELECT d.*, e.status AS current_status, e.intent_id, e.explorer_url
+    FROM execution_discrepancies d
+    JOIN executions e ON e.id = d.execution_id
+    WHERE 1=1
+  `;
+  const values: any[] = [];
+
+  if (params?.kind) {
+    query += ' AND d.kind = ?';
+    values.push(params.kind);
+  }
+  if (params?.chain) {
+    query += ' AND d.chain = ?';
+    values.push(params.chain);
+  }
+  if (params?.since) {
+    query += ' AND d.detected_at >= ?';
+    values.push(params.since);
+  }
+
+  query += ' ORDER BY d.detected_at DESC LIMIT ?';
+  values.push(params?.limit ?? 100);
+
+  return db.prepare(query).all(...values) as any[];
+}
+
+/**
+ * Discrepancy counts by kind, plus non-terminal executions still awaiting an outcome
+ */
+export function getReconciliationSummary(): {
+  discrepancies: Record<string, number>;
+  unresolved: number;
+} {
+  const db = getDatabase();
+  const rows = db.prepare(`
+    SELECT kind, COUNT(*) as count FROM execution_discrepancies GROUP BY kind
+  `).all() as { kind: string; count: number }[];
+  const unresolved = db.prepare(`
+    SELECT COUNT(*) as count FROM executions WHERE status IN ('pending', 'submitted')
+  `).get() as { count: number };
+
+  return {
+    discrepancies: Object.fromEntries(rows.map(row => [row.kind, row.count])),
+    unresolved: unresolved.count,
+  };
+}
+
+// ============================================
+// Positions table operations
+// ============================================
+
+export interface Position {
+  id: string;
+  chain: 'ethereum' | 'solana';
+  network: string;
+  venue: string;
+  market: string;
+  side: 'long' | 'short';
+  leverage?: number;
+  margin_units?: string;
+  margin_display?: string;
+  size_units?: string;
+  entry_price?: string;
+  status: 'open' | 'closed' | 'liquidated';
+  opened_at: number;
+  closed_at?: number;
+  open_tx_hash?: string;
+  open_explorer_url?: string;
+  close_tx_hash?: string;
+  close_explorer_url?: string;
+  pnl?: string;
+  funding_usd?: number;
+  funding_updated_at?: number;
+  user_address: string;
+  on_chain_position_id?: string;
+  open_block_number?: number;
+  close_block_number?: number;
+  intent_id?: string;
+  execution_id?: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface CreatePositionInput {
+  chain: 'ethereum' | 'solana';
+  network: string;
+  venue: string;
+  market: string;
+  side: 'long' | 'short';
+  leverage?: number;
+  margin_units?: string;
+  margin_display?: string;
+  size_units?: string;
+  entry_price?: string;
+  open_tx_hash?: string;
+  open_explorer_url?: string;
+  user_address: string;
+  on_chain_position_id?: string;
+  open_block_number?: number;
+  intent_id?: string;
+  execution_id?: string;
+}
+
+export function createPosition(input: CreatePositionInput): Position {
+  const db = getDatabase();
+  const id = crypto.randomUUID();
+  const now = Math.floor(Date.now() / 1000);
+
+  db.prepare(`
+    INSERT INTO positions (
+      id, chain, network, venue, market, side, leverage,
+      margin_units, margin_display, size_units, entry_price,
+      status, opened_at, open_tx_hash, open_explorer_url,
+      user_address, on_chain_position_id, open_block_number, intent_id, execution_id,
+      created_at, updated_at
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'open', ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `).run(
+    id,
+    input.chain,
+    input.network,
+    input.venue,
+    input.market,
+    input.side,
+    input.leverage ?? null,
+    input.margin_units ?? null,
+    input.margin_display ?? null,
+    input.size_units ?? null,
+    input.entry_price ?? null,
+    now,
+    input.open_tx_hash ?? null,
+    input.open_explorer_url ?? null,
+    input.user_address,
+    input.on_chain_position_id ?? null,
+    input.open_block_number ?? null,
+    input.intent_id ?? null,
+    input.execution_id ?? null,
+    now,
+    now
+  );
+
+  return getPosition(id)!;
+}
+
+export function getPosition(id: string): Position | null {
+  const db = getDatabase();
+  const row = db.prepare('SELECT * FROM positions WHERE id = ?').get(id) as Position | undefined;
+  return row ?? null;
+}
+
+export function getPositionByOnChainId(
+  chain: string,
+  network: string,
+  venue: string,
+  onChainPositionId: string
+): Position | null {
+  const db = getDatabase();
+  const row = db.prepare(`
+    SELECT * FROM positions
+    WHERE chain = ? AND network = ? AND venue = ? AND on_chain_position_id = ?
+  `).get(chain, network, venue, onChainPositionId) as Position | undefined;
+  return row ?? null;
 }
 
 export function updatePosition(id: string, updates: Partial<Omit<Position, 'id' | 'created_at'>>): void {
@@ -1675,7 +2374,8 @@ export function closePosition(
   closeTxHash: string,
   closeExplorerUrl: string,
   pnl?: string,
-  status: 'closed' | 'liquidated' = 'closed'
+  status: 'closed' | 'liquidated' = 'closed',
+  closeBlockNumber?: number
 ): void {
   const db = getDatabase();
   const now = Math.floor(Date.now() / 1000);
@@ -1687,9 +2387,23 @@ export function closePosition(
       close_tx_hash = ?,
       close_explorer_url = ?,
       pnl = ?,
+      close_block_number = ?,
       updated_at = ?
     WHERE id = ?
-  `).run(status, now, closeTxHash, closeExplorerUrl, pnl ?? null, now, id);
+  `).run(status, now, closeTxHash, closeExplorerUrl, pnl ?? null, closeBlockNumber ?? null, now, id);
+}
+
+/**
+ * Positions closed at a loss (negative PnL or liquidated) since a timestamp
+ */
+export function getLosingPositionsSince(userAddress: string, since: number): Position[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM positions
+    WHERE LOWER(user_address) = ? AND closed_at >= ?
+      AND (status = 'liquidated' OR (status = 'closed' AND pnl LIKE '-%'))
+    ORDER BY closed_at DESC
+  `).all(userAddress.toLowerCase(), since) as Position[];
 }
 
 export function getOpenPositions(filters?: {
@@ -1800,6 +2514,564 @@ export function upsertIndexerState(chain: string, network: string, contractAddre
   `).run(id, chain, network, contractAddress, lastIndexedBlock, now, lastIndexedBlock, now);
 }
 
+export function listIndexerStates(chain?: string, network?: string): IndexerState[] {
+  const db = getDatabase();
+  const conditions: string[] = [];
+  const values: any[] = [];
+  if (chain) {
+    conditions.push('chain = ?');
+    values.push(chain);
+  }
+  if (network) {
+    conditions.push('network = ?');
+    values.push(network);
+  }
+  const where = conditions.length > 0 ? `WHERE ${conditions.join(' AND ')}` : '';
+  return db.prepare(`SELECT * FROM indexer_state ${where} ORDER BY contract_address`).all(...values) as IndexerState[];
+}
+
+export interface IndexedEvent {
+  id: string;
+  chain: string;
+  network: string;
+  contract_name: string;
+  contract_address: string;
+  event_name: string;
+  block_number: number;
+  tx_hash: string;
+  log_index: number;
+  args_json: string;
+  created_at: number;
+}
+
+/**
+ * Record a decoded contract event
+ * Returns false if the log was already indexed (re-scans are idempotent)
+ */
+export function recordIndexedEvent(params: {
+  chain: string;
+  network: string;
+  contractName: string;
+  contractAddress: string;
+  eventName: string;
+  blockNumber: number;
+  txHash: string;
+  logIndex: number;
+  args: Record<string, unknown>;
+}): boolean {
+  const db = getDatabase();
+  const id = `${params.chain}:${params.network}:${params.txHash}:${params.logIndex}`;
+  const now = Math.floor(Date.now() / 1000);
+  const argsJson = JSON.stringify(params.args, (_, value) => typeof value === 'bigint' ? value.toString() : value);
+
+  const result = db.prepare(`
+    INSERT INTO indexed_events (id, chain, network, contract_name, contract_address, event_name, block_number, tx_hash, log_index, args_json, created_at)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    ON CONFLICT(id) DO NOTHING
+  `).run(
+    id,
+    params.chain,
+    params.network,
+    params.contractName,
+    params.contractAddress.toLowerCase(),
+    params.eventName,
+    params.blockNumber,
+    params.txHash,
+    params.logIndex,
+    argsJson,
+    now
+  );
+  return result.changes > 0;
+}
+
+export function listIndexedEvents(params?: {
+  contractName?: string;
+  eventName?: string;
+  fromBlock?: number;
+  limit?: number;
+}): IndexedEvent[] {
+  const db = getDatabase();
+  const conditions: string[] = [];
+  const values: any[] = [];
+  if (params?.contractName) {
+    conditions.push('contract_name = ?');
+    values.push(params.contractName);
+  }
+  if (params?.eventName) {
+    conditions.push('event_name = ?');
+    values.push(params.eventName);
+  }
+  if (params?.fromBlock !== undefined) {
+    conditions.push('block_number >= ?');
+    values.push(params.fromBlock);
+  }
+  const where = conditions.length > 0 ? `WHERE ${conditions.join(' AND ')}` : '';
+  values.push(params?.limit ?? 100);
+  return db.prepare(`
+    SELECT * FROM indexed_events ${where}
+    ORDER BY block_number DESC, log_index DESC
+    LIMIT ?
+  `).all(...values) as IndexedEvent[];
+}
+
+export interface IndexerBlock {
+  chain: string;
+  network: string;
+  block_number: number;
+  block_hash: string;
+  recorded_at: number;
+}
+
+export function recordIndexerBlock(chain: string, network: string, blockNumber: number, blockHash: string): void {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+  db.prepare(`
+    INSERT INTO indexer_blocks (chain, network, block_number, block_hash, recorded_at)
+    VALUES (?, ?, ?, ?, ?)
+    ON CONFLICT(chain, network, block_number)
+    DO UPDATE SET block_hash = excluded.block_hash, recorded_at = excluded.recorded_at
+  `).run(chain, network, blockNumber, blockHash.toLowerCase(), now);
+}
+
+export function listIndexerBlocks(chain: string, network: string, fromBlock: number): IndexerBlock[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM indexer_blocks
+    WHERE chain = ? AND network = ? AND block_number >= ?
+    ORDER BY block_number ASC
+  `).all(chain, network, fromBlock) as IndexerBlock[];
+}
+
+/**
+ * Forget hashes of blocks that are past the confirmation depth (final)
+ */
+export function pruneIndexerBlocks(chain: string, network: string, belowBlock: number): number {
+  const db = getDatabase();
+  return db.prepare(`
+    DELETE FROM indexer_blocks WHERE chain = ? AND network = ? AND block_number < ?
+  `).run(chain, network, belowBlock).changes;
+}
+
+export interface IndexerRollbackResult {
+  eventsRemoved: number;
+  positionsRemoved: number;
+  positionsReopened: number;
+  sessionsUnrevoked: number;
+  sessionsReverted: number;
+  cursorsRewound: number;
+}
+
+/**
+ * Undo everything the indexer derived from blocks after ancestorBlock (orphaned by a reorg)
+ * Positions opened in orphaned blocks are deleted, closes in orphaned blocks are reverted,
+ * sessions revoked or activated in orphaned blocks go back to active or preparing,
+ * and every cursor past the ancestor is rewound so the canonical blocks get re-indexed
+ */
+export function rollbackIndexerToBlock(chain: string, network: string, ancestorBlock: number): IndexerRollbackResult {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+
+  const rollback = db.transaction((): IndexerRollbackResult => {
+    const positionsRemoved = db.prepare(`
+      DELETE FROM positions WHERE chain = ? AND network = ? AND open_block_number > ?
+    `).run(chain, network, ancestorBlock).changes;
+
+    const positionsReopened = db.prepare(`
+      UPDATE positions SET
+        status = 'open',
+        closed_at = NULL,
+        close_tx_hash = NULL,
+        close_explorer_url = NULL,
+        pnl = NULL,
+        close_block_number = NULL,
+        updated_at = ?
+      WHERE chain = ? AND network = ? AND close_block_number > ?
+    `).run(now, chain, network, ancestorBlock).changes;
+
+    const sessionsUnrevoked = db.prepare(`
+      UPDATE sessions SET status = 'active', revoked_block_number = NULL, updated_at = ?
+      WHERE chain = ? AND network = ? AND revoked_block_number > ?
+    `).run(now, chain, network, ancestorBlock).changes;
+
+    // Re-indexing activates the session again if its creation made it onto the canonical chain
+    const sessionsReverted = db.prepare(`
+      UPDATE sessions SET status = 'preparing', created_block_number = NULL, updated_at = ?
+      WHERE chain = ? AND network = ? AND created_block_number > ?
+    `).run(now, chain, network, ancestorBlock).changes;
+
+    const eventsRemoved = db.prepare(`
+      DELETE FROM indexed_events WHERE chain = ? AND network = ? AND block_number > ?
+    `).run(chain, network, ancestorBlock).changes;
+
+    db.prepare(`
+      DELETE FROM indexer_blocks WHERE chain = ? AND network = ? AND block_number > ?
+    `).run(chain, network, ancestorBlock);
+
+    const cursorsRewound = db.prepare(`
+      UPDATE indexer_state SET last_indexed_block = ?, updated_at = ?
+      WHERE chain = ? AND network = ? AND last_indexed_block > ?
+    `).run(ancestorBlock, now, chain, network, ancestorBlock).changes;
+
+    return { eventsRemoved, positionsRemoved, positionsReopened, sessionsUnrevoked, sessionsReverted, cursorsRewound };
+  });
+
+  return rollback();
+}
+
+// ============================================
+// Sim account operations
+// ============================================
+
+export interface SimAccountRow {
+  user_id: string;
+  state_json: string;
+  created_at: number;
+  updated_at: number;
+}
+
+export function getSimAccount(userId: string): SimAccountRow | null {
+  const db = getDatabase();
+  const row = db.prepare('SELECT * FROM sim_accounts WHERE user_id = ?').get(userId) as SimAccountRow | undefined;
+  return row ?? null;
+}
+
+export function upsertSimAccount(userId: string, stateJson: string): void {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+
+  db.prepare(`
+    INSERT INTO sim_accounts (user_id, state_json, created_at, updated_at)
+    VALUES (?, ?, ?, ?)
+    ON CONFLICT(user_id)
+    DO UPDATE SET state_json = ?, updated_at = ?
+  `).run(userId, stateJson, now, now, stateJson, now);
+}
+
+export function listSimAccounts(): SimAccountRow[] {
+  const db = getDatabase();
+  return db.prepare('SELECT * FROM sim_accounts ORDER BY updated_at DESC').all() as SimAccountRow[];
+}
+
+export function deleteSimAccount(userId: string): void {
+  const db = getDatabase();
+  db.prepare('DELETE FROM sim_accounts WHERE user_id = ?').run(userId);
+}
+
+// ============================================
+// Chat thread operations
+// ============================================
+
+export interface ChatThreadRow {
+  id: string;
+  user_id: string;
+  title: string | null;
+  summary: string | null;
+  summarized_seq: number;
+  message_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+export type ChatMessageRole = 'user' | 'assistant';
+
+export interface ChatMessageRow {
+  id: string;
+  thread_id: string;
+  seq: number;
+  role: ChatMessageRole;
+  content: string;
+  actions_json: string | null;
+  execution_request_json: string | null;
+  refs_json: string | null;
+  created_at: number;
+}
+
+export function createChatThread(params: { userId: string; title?: string }): ChatThreadRow {
+  const db = getDatabase();
+  const id = randomUUID();
+  const now = Math.floor(Date.now() / 1000);
+
+  db.prepare(`
+    INSERT INTO chat_threads (id, user_id, title, summarized_seq, message_count, created_at, updated_at)
+    VALUES (?, ?, ?, 0, 0, ?, ?)
+  `).run(id, params.userId, params.title ?? null, now, now);
+
+  return {
+    id,
+    user_id: params.userId,
+    title: params.title ?? null,
+    summary: null,
+    summarized_seq: 0,
+    message_count: 0,
+    created_at: now,
+    updated_at: now,
+  };
+}
+
+export function getChatThread(id: string): ChatThreadRow | undefined {
+  const db = getDatabase();
+  return db.prepare('SELECT * FROM chat_threads WHERE id = ?').get(id) as ChatThreadRow | undefined;
+}
+
+export function getLatestChatThread(userId: string): ChatThreadRow | undefined {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM chat_threads WHERE user_id = ? ORDER BY updated_at DESC, created_at DESC LIMIT 1
+  `).get(userId) as ChatThreadRow | undefined;
+}
+
+export function listChatThreads(userId: string, limit = 20): ChatThreadRow[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM chat_threads WHERE user_id = ? ORDER BY updated_at DESC LIMIT ?
+  `).all(userId, limit) as ChatThreadRow[];
+}
+
+export function updateChatThread(
+  id: string,
+  updates: Partial<Pick<ChatThreadRow, 'title' | 'summary' | 'summarized_seq'>>
+): void {
+  const db = getDatabase();
+  const now = Math.floor(Date.now() / 1000);
+
+  const setClauses: string[] = ['updated_at = ?'];
+  const values: any[] = [now];
+
+  for (const [key, value] of Object.entries(updates)) {
+    if (value === undefined) continue;
+    setClauses.push(`${key} = ?`);
+    values.push(value);
+  }
+
+  values.push(id);
+  db.prepare(`UPDATE chat_threads SET ${setClauses.join(', ')} WHERE id = ?`).run(...values);
+}
+
+/**
+ * Append a message and bump the thread's count in one transaction
+ */
+export function appendChatMessage(params: {
+  threadId: string;
+  role: ChatMessageRole;
+  content: string;
+  actions?: unknown[];
+  executionRequest?: unknown;
+  refs?: Record<string, unknown>;
+}): ChatMessageRow {
+  const db = getDatabase();
+  const id = randomUUID();
+  const now = Math.floor(Date.now() / 1000);
+
+  const append = db.transaction((): ChatMessageRow => {
+    const row = db.prepare('SELECT message_count FROM chat_threads WHERE id = ?').get(params.threadId) as
+      | { message_count: number }
+      | undefined;
+    if (!row) {
+      throw new Error(`Chat thread not found: ${params.threadId}`);
+    }
+    const seq = row.message_count + 1;
+
+    const message: ChatMessageRow = {
+      id,
+      thread_id: params.threadId,
+      seq,
+      role: params.role,
+      content: params.content,
+      actions_json: params.actions && params.actions.length > 0 ? JSON.stringify(params.actions) : null,
+      execution_request_json: params.executionRequest ? JSON.stringify(params.executionRequest) : null,
+      refs_json: params.refs ? JSON.stringify(params.refs) : null,
+      created_at: now,
+    };
+
+    db.prepare(`
+      INSERT INTO chat_messages (id, thread_id, seq, role, content, actions_json, execution_request_json, refs_json, created_at)
+      VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    `).run(
+      message.id,
+      message.thread_id,
+      message.seq,
+      message.role,
+      message.content,
+      message.actions_json,
+      message.execution_request_json,
+      message.refs_json,
+      message.created_at
+    );
+    db.prepare('UPDATE chat_threads SET message_count = ?, updated_at = ? WHERE id = ?').run(seq, now, params.threadId);
+
+    return message;
+  });
+
+  return append();
+}
+
+export function listChatMessages(
+  threadId: string,
+  options: { afterSeq?: number; limit?: number } = {}
+): ChatMessageRow[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM chat_messages WHERE thread_id = ? AND seq > ? ORDER BY seq ASC LIMIT ?
+  `).all(threadId, options.afterSeq ?? 0, options.limit ?? 500) as ChatMessageRow[];
+}
+
+export function deleteChatThread(id: string): void {
+  const db = getDatabase();
+  db.transaction(() => {
+    db.prepare('DELETE FROM chat_messages WHERE thread_id = ?').run(id);
+    db.prepare('DELETE FROM chat_threads WHERE id = ?').run(id);
+  })();
+}
+
+// ============================================
+// Price history operations
+// ============================================
+
+export type PriceCandlePeriod = '1m' | '1h' | '1d';
+
+export const PRICE_CANDLE_PERIOD_SECONDS: Record<PriceCandlePeriod, number> = {
+  '1m': 60,
+  '1h': 3600,
+  '1d': 86400,
+};
+
+export interface PriceTickRow {
+  id: string;
+  symbol: string;
+  price_usd: number;
+  source: string;
+  confidence: string;
+  ts: number;
+}
+
+export interface PriceCandleRow {
+  symbol: string;
+  period: PriceCandlePeriod;
+  bucket_start: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  first_tick_at: number;
+  last_tick_at: number;
+  tick_count: number;
+}
+
+/**
+ * Record a tick and fold it into its 1m/1h/1d candles in one transaction
+ * Out-of-order ticks only move open/close if they are earlier/later than what set them
+ */
+export function insertPriceTick(params: {
+  symbol: string;
+  priceUsd: number;
+  source: string;
+  confidence: string;
+  ts?: number;
+}): PriceTickRow {
+  const db = getDatabase();
+  const tick: PriceTickRow = {
+    id: randomUUID(),
+    symbol: params.symbol.toUpperCase(),
+    price_usd: params.priceUsd,
+    source: params.source,
+    confidence: params.confidence,
+    ts: params.ts ?? Math.floor(Date.now() / 1000),
+  };
+
+  const upsertCandle = db.prepare(`
+    INSERT INTO price_candles (symbol, period, bucket_start, open, high, low, close, first_tick_at, last_tick_at, tick_count)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1)
+    ON CONFLICT(symbol, period, bucket_start) DO UPDATE SET
+      open = CASE WHEN excluded.first_tick_at < price_candles.first_tick_at THEN excluded.open ELSE price_candles.open END,
+      high = MAX(price_candles.high, excluded.high),
+      low = MIN(price_candles.low, excluded.low),
+      close = CASE WHEN excluded.last_tick_at >= price_candles.last_tick_at THEN excluded.close ELSE price_candles.close END,
+      first_tick_at = MIN(price_candles.first_tick_at, excluded.first_tick_at),
+      last_tick_at = MAX(price_candles.last_tick_at, excluded.last_tick_at),
+      tick_count = price_candles.tick_count + 1
+  `);
+
+  db.transaction(() => {
+    db.prepare(`
+      INSERT INTO price_ticks (id, symbol, price_usd, source, confidence, ts)
+      VALUES (?, ?, ?, ?, ?, ?)
+    `).run(tick.id, tick.symbol, tick.price_usd, tick.source, tick.confidence, tick.ts);
+
+    for (const [period, seconds] of Object.entries(PRICE_CANDLE_PERIOD_SECONDS)) {
+      const bucketStart = tick.ts - (tick.ts % seconds);
+      const price = tick.price_usd;
+      upsertCandle.run(tick.symbol, period, bucketStart, price, price, price, price, tick.ts, tick.ts);
+    }
+  })();
+
+  return tick;
+}
+
+export function listPriceTicks(
+  symbol: string,
+  options: { from?: number; to?: number; limit?: number } = {}
+): PriceTickRow[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM (
+      SELECT * FROM price_ticks WHERE symbol = ? AND ts >= ? AND ts <= ? ORDER BY ts DESC LIMIT ?
+    ) ORDER BY ts ASC
+  `).all(symbol.toUpperCase(), options.from ?? 0, options.to ?? Number.MAX_SAFE_INTEGER, options.limit ?? 1000) as PriceTickRow[];
+}
+
+/**
+ * Candles whose bucket starts within [from, to], oldest first
+ * With more than `limit` matches, the most recent `limit` are returned
+ */
+export function listPriceCandles(
+  symbol: string,
+  period: PriceCandlePeriod,
+  options: { from?: number; to?: number; limit?: number } = {}
+): PriceCandleRow[] {
+  const db = getDatabase();
+  return db.prepare(`
+    SELECT * FROM (
+      SELECT * FROM price_candles
+      WHERE symbol = ? AND period = ? AND bucket_start >= ? AND bucket_start <= ?
+      ORDER BY bucket_start DESC LIMIT ?
+    ) ORDER BY bucket_start ASC
+  `).all(
+    symbol.toUpperCase(),
+    period,
+    options.from ?? 0,
+    options.to ?? Number.MAX_SAFE_INTEGER,
+    options.limit ?? 500
+  ) as PriceCandleRow[];
+}
+
+export function listPriceHistorySymbols(): string[] {
+  const db = getDatabase();
+  const rows = db.prepare(`
+    SELECT DISTINCT symbol FROM price_candles WHERE period = '1d' ORDER BY symbol
+  `).all() as { symbol: string }[];
+  return rows.map(row => row.symbol);
+}
+
+/**
+ * Delete ticks older than ticksBefore and candles older than the per-period cutoffs
+ * (periods without a cutoff are kept forever)
+ */
+export function prunePriceHistory(params: {
+  ticksBefore: number;
+  candlesBefore?: Partial<Record<PriceCandlePeriod, number>>;
+}): { ticks: number; candles: number } {
+  const db = getDatabase();
+  return db.transaction(() => {
+    const ticks = db.prepare('DELETE FROM price_ticks WHERE ts < ?').run(params.ticksBefore).changes;
+    let candles = 0;
+    for (const [period, before] of Object.entries(params.candlesBefore ?? {})) {
+      if (before === undefined) continue;
+      candles += db.prepare('DELETE FROM price_candles WHERE period = ? AND bucket_start < ?').run(period, before).changes;
+    }
+    return { ticks, candles };
+  })();
+}
+
 // ============================================
 // Waitlist operations
 // ============================================
@@ -2204,6 +3476,109 @@ export async function getSummaryStatsWithIntentsAsync(): Promise<
   return Promise.resolve(getSummaryStatsWithIntents()) as any;
 }
 
+/**
+ * Async-capable intent job enqueue (uses Postgres if DATABASE_URL is set)
+ */
+export async function enqueueIntentJobAsync(params: {
+  intentId: string;
+  step?: IntentJobStep;
+  maxAttempts?: number;
+  runAfter?: number;
+  txChain?: string;
+  txHash?: string;
+}): Promise<IntentJob> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.enqueueIntentJob(params) as Promise<IntentJob>;
+  }
+
+  // SQLite: use synchronous version
+  return Promise.resolve(enqueueIntentJob(params));
+}
+
+/**
+ * Async-capable get intent job (uses Postgres if DATABASE_URL is set)
+ */
+export async function getIntentJobAsync(intentId: string): Promise<IntentJob | undefined> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.getIntentJob(intentId) as Promise<IntentJob | undefined>;
+  }
+
+  // SQLite: use synchronous version
+  return Promise.resolve(getIntentJob(intentId));
+}
+
+/**
+ * Async-capable intent job update (uses Postgres if DATABASE_URL is set)
+ */
+export async function updateIntentJobAsync(
+  id: string,
+  updates: Partial<Omit<IntentJob, 'id' | 'intent_id' | 'created_at'>>
+): Promise<void> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.updateIntentJob(id, updates);
+  }
+
+  // SQLite: use synchronous version
+  updateIntentJob(id, updates);
+  return Promise.resolve();
+}
+
+/**
+ * Async-capable intent job lease (uses Postgres if DATABASE_URL is set)
+ */
+export async function leaseIntentJobsAsync(owner: string, leaseSeconds: number, limit: number): Promise<IntentJob[]> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.leaseIntentJobs(owner, leaseSeconds, limit) as Promise<IntentJob[]>;
+  }
+
+  // SQLite: use synchronous version
+  return Promise.resolve(leaseIntentJobs(owner, leaseSeconds, limit));
+}
+
+/**
+ * Async-capable intent job lease renewal (uses Postgres if DATABASE_URL is set)
+ */
+export async function renewIntentJobLeaseAsync(id: string, owner: string, leaseSeconds: number): Promise<boolean> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.renewIntentJobLease(id, owner, leaseSeconds);
+  }
+
+  // SQLite: use synchronous version
+  return Promise.resolve(renewIntentJobLease(id, owner, leaseSeconds));
+}
+
+/**
+ * Async-capable intent job tx checkpoint (uses Postgres if DATABASE_URL is set)
+ */
+export async function recordIntentJobTxAsync(intentId: string, chain: string, txHash: string): Promise<void> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.recordIntentJobTx(intentId, chain, txHash);
+  }
+
+  // SQLite: use synchronous version
+  recordIntentJobTx(intentId, chain, txHash);
+  return Promise.resolve();
+}
+
+/**
+ * Async-capable orphaned intent lookup (uses Postgres if DATABASE_URL is set)
+ */
+export async function getOrphanedIntentsAsync(olderThan: number): Promise<Intent[]> {
+  if (dbType === 'postgres') {
+    const pgDb = await import('./db-pg.js');
+    return pgDb.getOrphanedIntents(olderThan) as Promise<Intent[]>;
+  }
+
+  // SQLite: use synchronous version
+  return Promise.resolve(getOrphanedIntents(olderThan));
+}
+
 /**
  * Get database identity hash for verifying same-DB across endpoints
  * Returns a safe hash of non-secret DB identifiers (NEVER includes passwords)
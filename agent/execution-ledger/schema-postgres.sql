@@ -27,6 +27,8 @@ CREATE TABLE IF NOT EXISTS executions (
     relayer_address TEXT,
     session_id TEXT,
     intent_id TEXT,
+    reconciled_at INTEGER,
+    linked_execution_id TEXT,
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL
 );
@@ -82,6 +84,8 @@ CREATE TABLE IF NOT EXISTS sessions (
     expires_at INTEGER,
     created_tx TEXT,
     revoked_tx TEXT,
+    created_block_number INTEGER,
+    revoked_block_number INTEGER,
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     UNIQUE(chain, network, user_address, session_id)
@@ -89,6 +93,27 @@ CREATE TABLE IF NOT EXISTS sessions (
 
 CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_address);
 
+CREATE TABLE IF NOT EXISTS session_policies (
+    session_id TEXT PRIMARY KEY,
+    user_address TEXT NOT NULL,
+    policy_json TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS session_policy_usage (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    user_address TEXT NOT NULL,
+    spend_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+    token_spend_json TEXT,
+    instrument_type TEXT,
+    tx_hash TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_usage_session ON session_policy_usage(session_id, created_at);
+
 CREATE TABLE IF NOT EXISTS assets (
     id TEXT PRIMARY KEY,
     chain TEXT NOT NULL,
@@ -116,6 +141,55 @@ CREATE TABLE IF NOT EXISTS wallets (
     UNIQUE(chain, network, address)
 );
 
+CREATE TABLE IF NOT EXISTS linked_wallets (
+    id TEXT PRIMARY KEY,
+    user_address TEXT NOT NULL,
+    chain TEXT NOT NULL,
+    network TEXT NOT NULL,
+    address TEXT NOT NULL,
+    label TEXT,
+    created_at INTEGER NOT NULL,
+    UNIQUE(user_address, chain, network, address)
+);
+
+CREATE TABLE IF NOT EXISTS bridge_transfers (
+    id TEXT PRIMARY KEY,
+    intent_id TEXT,
+    provider TEXT NOT NULL,
+    tool TEXT,
+    state TEXT NOT NULL DEFAULT 'source_pending',
+    from_chain TEXT NOT NULL,
+    from_network TEXT NOT NULL,
+    to_chain TEXT NOT NULL,
+    to_network TEXT NOT NULL,
+    from_chain_id BIGINT,
+    to_chain_id BIGINT,
+    from_address TEXT NOT NULL,
+    to_address TEXT,
+    from_token TEXT,
+    to_token TEXT,
+    amount_units TEXT,
+    received_units TEXT,
+    source_tx_hash TEXT,
+    dest_tx_hash TEXT,
+    source_execution_id TEXT,
+    dest_execution_id TEXT,
+    provider_status TEXT,
+    provider_substatus TEXT,
+    status_message TEXT,
+    poll_count INTEGER NOT NULL DEFAULT 0,
+    last_polled_at INTEGER,
+    source_confirmed_at INTEGER,
+    completed_at INTEGER,
+    alerted_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_state ON bridge_transfers(state);
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_intent ON bridge_transfers(intent_id);
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_source_tx ON bridge_transfers(source_tx_hash);
+
 CREATE TABLE IF NOT EXISTS intents (
     id TEXT PRIMARY KEY,
     created_at INTEGER NOT NULL,
@@ -137,6 +211,26 @@ CREATE TABLE IF NOT EXISTS intents (
 CREATE INDEX IF NOT EXISTS idx_intents_status ON intents(status);
 CREATE INDEX IF NOT EXISTS idx_intents_created ON intents(created_at);
 
+-- Durable work queue over intents (leases, retries, dead-letter)
+CREATE TABLE IF NOT EXISTS intent_jobs (
+    id TEXT PRIMARY KEY,
+    intent_id TEXT NOT NULL UNIQUE,
+    step TEXT NOT NULL DEFAULT 'route',
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_after INTEGER NOT NULL,
+    lease_owner TEXT,
+    lease_expires_at INTEGER,
+    tx_chain TEXT,
+    tx_hash TEXT,
+    last_error TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_intent_jobs_ready ON intent_jobs(status, run_after);
+
 CREATE TABLE IF NOT EXISTS positions (
     id TEXT PRIMARY KEY,
     chain TEXT NOT NULL,
@@ -157,8 +251,12 @@ CREATE TABLE IF NOT EXISTS positions (
     close_tx_hash TEXT,
     close_explorer_url TEXT,
     pnl TEXT,
+    funding_usd DOUBLE PRECISION DEFAULT 0,
+    funding_updated_at INTEGER,
     user_address TEXT NOT NULL,
     on_chain_position_id TEXT,
+    open_block_number INTEGER,
+    close_block_number INTEGER,
     intent_id TEXT,
     execution_id TEXT,
     created_at INTEGER NOT NULL,
@@ -178,6 +276,32 @@ CREATE TABLE IF NOT EXISTS indexer_state (
     UNIQUE(chain, network, contract_address)
 );
 
+CREATE TABLE IF NOT EXISTS indexed_events (
+    id TEXT PRIMARY KEY,
+    chain TEXT NOT NULL,
+    network TEXT NOT NULL,
+    contract_name TEXT NOT NULL,
+    contract_address TEXT NOT NULL,
+    event_name TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    args_json TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexed_events_contract ON indexed_events(contract_address, block_number);
+CREATE INDEX IF NOT EXISTS idx_indexed_events_name ON indexed_events(event_name, block_number);
+
+CREATE TABLE IF NOT EXISTS indexer_blocks (
+    chain TEXT NOT NULL,
+    network TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    block_hash TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    PRIMARY KEY (chain, network, block_number)
+);
+
 CREATE TABLE IF NOT EXISTS access_codes (
     id TEXT PRIMARY KEY,
     code TEXT NOT NULL UNIQUE,
@@ -204,3 +328,80 @@ CREATE TABLE IF NOT EXISTS waitlist (
 
 CREATE INDEX IF NOT EXISTS idx_waitlist_email ON waitlist(email);
 CREATE INDEX IF NOT EXISTS idx_waitlist_wallet ON waitlist(wallet_address);
+
+CREATE TABLE IF NOT EXISTS sim_accounts (
+    user_id TEXT PRIMARY KEY,
+    state_json TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sim_accounts_updated ON sim_accounts(updated_at);
+
+CREATE TABLE IF NOT EXISTS execution_discrepancies (
+    id TEXT PRIMARY KEY,
+    execution_id TEXT NOT NULL,
+    chain TEXT NOT NULL,
+    tx_hash TEXT,
+    kind TEXT NOT NULL,
+    ledger_status TEXT NOT NULL,
+    chain_status TEXT NOT NULL,
+    detail TEXT,
+    detected_at INTEGER NOT NULL,
+    UNIQUE(execution_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exec_discrepancies_detected ON execution_discrepancies(detected_at);
+
+CREATE TABLE IF NOT EXISTS chat_threads (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    title TEXT,
+    summary TEXT,
+    summarized_seq INTEGER NOT NULL DEFAULT 0,
+    message_count INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_threads_user ON chat_threads(user_id, updated_at);
+
+CREATE TABLE IF NOT EXISTS chat_messages (
+    id TEXT PRIMARY KEY,
+    thread_id TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    role TEXT NOT NULL,
+    content TEXT NOT NULL,
+    actions_json TEXT,
+    execution_request_json TEXT,
+    refs_json TEXT,
+    created_at INTEGER NOT NULL,
+    UNIQUE(thread_id, seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_thread ON chat_messages(thread_id, seq);
+
+CREATE TABLE IF NOT EXISTS price_ticks (
+    id TEXT PRIMARY KEY,
+    symbol TEXT NOT NULL,
+    price_usd DOUBLE PRECISION NOT NULL,
+    source TEXT NOT NULL,
+    confidence TEXT NOT NULL,
+    ts INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_ticks_symbol_ts ON price_ticks(symbol, ts);
+
+CREATE TABLE IF NOT EXISTS price_candles (
+    symbol TEXT NOT NULL,
+    period TEXT NOT NULL,
+    bucket_start INTEGER NOT NULL,
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    first_tick_at INTEGER NOT NULL,
+    last_tick_at INTEGER NOT NULL,
+    tick_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (symbol, period, bucket_start)
+);
@@ -31,6 +31,8 @@ CREATE TABLE IF NOT EXISTS executions (
     latency_ms INTEGER,                     -- End-to-end latency
     relayer_address TEXT,                   -- Relayer that submitted tx (for session mode)
     session_id TEXT,                        -- Session ID (for session mode)
+    reconciled_at INTEGER,                  -- Last receipt re-check by the reconciler
+    linked_execution_id TEXT,               -- Other leg of a cross-chain transfer (bridge source <-> destination)
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
 );
@@ -78,6 +80,8 @@ CREATE TABLE IF NOT EXISTS sessions (
     expires_at INTEGER,                     -- Unix timestamp expiration
     created_tx TEXT,                        -- TX that created the session
     revoked_tx TEXT,                        -- TX that revoked (if any)
+    created_block_number INTEGER,           -- Block of the indexed SessionCreated event (reorg rollback)
+    revoked_block_number INTEGER,           -- Block of the indexed SessionRevoked event
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
     UNIQUE(chain, network, user_address, session_id)
@@ -86,6 +90,35 @@ CREATE TABLE IF NOT EXISTS sessions (
 CREATE INDEX IF NOT EXISTS idx_sessions_user ON sessions(user_address);
 CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status);
 
+-- ============================================
+-- session_policies table
+-- Policy documents attached to relayer sessions (evaluated server-side)
+-- ============================================
+CREATE TABLE IF NOT EXISTS session_policies (
+    session_id TEXT PRIMARY KEY,                -- On-chain session ID
+    user_address TEXT NOT NULL,                 -- Session owner
+    policy_json TEXT NOT NULL,                  -- JSON: SessionPolicyDocument
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+-- ============================================
+-- session_policy_usage table
+-- One row per relayed plan submitted under a session (rolling limits, rate limits)
+-- ============================================
+CREATE TABLE IF NOT EXISTS session_policy_usage (
+    id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    user_address TEXT NOT NULL,
+    spend_usd REAL NOT NULL DEFAULT 0,          -- Total plan spend in USD
+    token_spend_json TEXT,                      -- JSON: { [symbol]: usd }
+    instrument_type TEXT,                       -- swap | perp | defi | event
+    tx_hash TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_usage_session ON session_policy_usage(session_id, created_at);
+
 -- ============================================
 -- assets table
 -- Tracks token balances and movements
@@ -124,6 +157,68 @@ CREATE TABLE IF NOT EXISTS wallets (
 
 CREATE INDEX IF NOT EXISTS idx_wallets_chain ON wallets(chain, network);
 
+-- ============================================
+-- linked_wallets table
+-- Wallets a user (keyed by their primary EVM address) has proven they own
+-- Addresses are stored as-is: Solana base58 is case-sensitive
+-- ============================================
+CREATE TABLE IF NOT EXISTS linked_wallets (
+    id TEXT PRIMARY KEY,
+    user_address TEXT NOT NULL,             -- Primary EVM address (lowercase)
+    chain TEXT NOT NULL,                    -- 'ethereum' | 'solana'
+    network TEXT NOT NULL,                  -- 'sepolia' | 'devnet'
+    address TEXT NOT NULL,                  -- Linked wallet address
+    label TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE(user_address, chain, network, address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_linked_wallets_user ON linked_wallets(user_address);
+
+-- ============================================
+-- bridge_transfers table
+-- One cross-chain transfer, tracked from the source tx to the destination leg
+-- state: [awaiting_signature ->] source_pending -> source_confirmed -> in_flight -> dest_confirmed
+--        (or refunded / failed; stuck once past the alert timeout)
+-- ============================================
+CREATE TABLE IF NOT EXISTS bridge_transfers (
+    id TEXT PRIMARY KEY,
+    intent_id TEXT,                         -- References intents.id (if from intent)
+    provider TEXT NOT NULL,                 -- 'lifi'
+    tool TEXT,                              -- Underlying bridge picked by the provider
+    state TEXT NOT NULL DEFAULT 'source_pending',
+    from_chain TEXT NOT NULL,               -- 'ethereum' | 'solana'
+    from_network TEXT NOT NULL,
+    to_chain TEXT NOT NULL,
+    to_network TEXT NOT NULL,
+    from_chain_id INTEGER,                  -- Provider chain IDs (used for status lookups)
+    to_chain_id INTEGER,
+    from_address TEXT NOT NULL,
+    to_address TEXT,                        -- Receiver; Solana addresses keep their case
+    from_token TEXT,                        -- Symbol
+    to_token TEXT,
+    amount_units TEXT,                      -- Sent, base units
+    received_units TEXT,                    -- Received on the destination, base units
+    source_tx_hash TEXT,                    -- Unset while awaiting_signature
+    dest_tx_hash TEXT,
+    source_execution_id TEXT,               -- References executions.id
+    dest_execution_id TEXT,
+    provider_status TEXT,                   -- Last raw provider status / substatus
+    provider_substatus TEXT,
+    status_message TEXT,
+    poll_count INTEGER NOT NULL DEFAULT 0,
+    last_polled_at INTEGER,
+    source_confirmed_at INTEGER,
+    completed_at INTEGER,
+    alerted_at INTEGER,                     -- When the stuck alert fired
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_state ON bridge_transfers(state);
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_intent ON bridge_transfers(intent_id);
+CREATE INDEX IF NOT EXISTS idx_bridge_transfers_source_tx ON bridge_transfers(source_tx_hash);
+
 -- ============================================
 -- execution_steps table (optional)
 -- Tracks individual steps within a multi-step execution
@@ -177,6 +272,29 @@ CREATE INDEX IF NOT EXISTS idx_intents_status ON intents(status);
 CREATE INDEX IF NOT EXISTS idx_intents_kind ON intents(intent_kind);
 CREATE INDEX IF NOT EXISTS idx_intents_created ON intents(created_at);
 
+-- ============================================
+-- intent_jobs table
+-- Durable work queue over intents (leases, retries, dead-letter)
+-- ============================================
+CREATE TABLE IF NOT EXISTS intent_jobs (
+    id TEXT PRIMARY KEY,
+    intent_id TEXT NOT NULL UNIQUE,             -- intents.id (one job per intent)
+    step TEXT NOT NULL DEFAULT 'route',         -- route | execute | confirm
+    status TEXT NOT NULL DEFAULT 'pending',     -- pending | leased | done | dead
+    attempts INTEGER NOT NULL DEFAULT 0,        -- Leases taken so far
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_after INTEGER NOT NULL,                 -- Unix timestamp of next eligible run (backoff)
+    lease_owner TEXT,                           -- Worker id holding the lease
+    lease_expires_at INTEGER,                   -- Expired leases are reclaimed by any worker
+    tx_chain TEXT,                              -- Set once a tx is broadcast, so retries confirm instead of resending
+    tx_hash TEXT,
+    last_error TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_intent_jobs_ready ON intent_jobs(status, run_after);
+
 -- ============================================
 -- positions table
 -- Tracks on-chain perp positions indexed from contract events
@@ -201,8 +319,12 @@ CREATE TABLE IF NOT EXISTS positions (
     close_tx_hash TEXT,                         -- Transaction that closed the position
     close_explorer_url TEXT,                    -- Explorer link for close tx
     pnl TEXT,                                   -- Realized PnL (if closed)
+    funding_usd REAL DEFAULT 0,                 -- Cumulative funding paid (negative = received)
+    funding_updated_at INTEGER,                 -- Unix timestamp of last funding accrual
     user_address TEXT NOT NULL,                 -- User/relayer address
     on_chain_position_id TEXT,                  -- Position ID from contract
+    open_block_number INTEGER,                  -- Block of the indexed open event (reorg rollback)
+    close_block_number INTEGER,                 -- Block of the indexed close/liquidation event
     intent_id TEXT,                             -- References intents.id (if from intent)
     execution_id TEXT,                          -- References executions.id
     created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
@@ -229,6 +351,141 @@ CREATE TABLE IF NOT EXISTS indexer_state (
     UNIQUE(chain, network, contract_address)
 );
 
+-- ============================================
+-- indexed_events table
+-- Decoded contract events from the event indexer (router, sessions, adapters, vaults)
+-- ============================================
+CREATE TABLE IF NOT EXISTS indexed_events (
+    id TEXT PRIMARY KEY,                        -- Unique key: chain:network:tx_hash:log_index
+    chain TEXT NOT NULL,
+    network TEXT NOT NULL,
+    contract_name TEXT NOT NULL,                -- Indexer registry entry (e.g. 'execution_router')
+    contract_address TEXT NOT NULL,
+    event_name TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    args_json TEXT NOT NULL,                    -- Decoded event args (bigints as strings)
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexed_events_contract ON indexed_events(contract_address, block_number);
+CREATE INDEX IF NOT EXISTS idx_indexed_events_name ON indexed_events(event_name, block_number);
+
+-- ============================================
+-- indexer_blocks table
+-- Block hashes the event indexer has seen inside the confirmation window (reorg detection)
+-- ============================================
+CREATE TABLE IF NOT EXISTS indexer_blocks (
+    chain TEXT NOT NULL,
+    network TEXT NOT NULL,
+    block_number INTEGER NOT NULL,
+    block_hash TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (chain, network, block_number)
+);
+
+-- ============================================
+-- sim_accounts table
+-- Per-user simulation books (perps, defi, event sims)
+-- ============================================
+CREATE TABLE IF NOT EXISTS sim_accounts (
+    user_id TEXT PRIMARY KEY,                   -- Normalized wallet address or session id
+    state_json TEXT NOT NULL,                   -- JSON: { perps, defi, event } sim state
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sim_accounts_updated ON sim_accounts(updated_at);
+
+-- ============================================
+-- execution_discrepancies table
+-- Ledger rows that disagree with on-chain receipts (found by the reconciler)
+-- ============================================
+CREATE TABLE IF NOT EXISTS execution_discrepancies (
+    id TEXT PRIMARY KEY,
+    execution_id TEXT NOT NULL,                 -- executions.id
+    chain TEXT NOT NULL,
+    tx_hash TEXT,
+    kind TEXT NOT NULL,                         -- dropped | reorged | reverted_after_confirm | missing_tx_hash
+    ledger_status TEXT NOT NULL,                -- Status recorded before reconciliation
+    chain_status TEXT NOT NULL,                 -- success | reverted | pending | not_found
+    detail TEXT,
+    detected_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE(execution_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exec_discrepancies_detected ON execution_discrepancies(detected_at);
+
+-- ============================================
+-- chat_threads table
+-- Per-user /api/chat conversations (multi-turn memory)
+-- ============================================
+CREATE TABLE IF NOT EXISTS chat_threads (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,                      -- Sim user id (wallet address or session id)
+    title TEXT,                                 -- First user message, truncated
+    summary TEXT,                               -- Rolling summary of messages no longer sent verbatim
+    summarized_seq INTEGER NOT NULL DEFAULT 0,  -- Highest message seq folded into summary
+    message_count INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_threads_user ON chat_threads(user_id, updated_at);
+
+-- ============================================
+-- chat_messages table
+-- Turns of a chat thread, with what the assistant proposed
+-- ============================================
+CREATE TABLE IF NOT EXISTS chat_messages (
+    id TEXT PRIMARY KEY,
+    thread_id TEXT NOT NULL,                    -- chat_threads.id
+    seq INTEGER NOT NULL,                       -- 1-based order within the thread
+    role TEXT NOT NULL,                         -- user | assistant
+    content TEXT NOT NULL,
+    actions_json TEXT,                          -- assistant: validated BlossomAction[]
+    execution_request_json TEXT,                -- assistant: BlossomExecutionRequest
+    refs_json TEXT,                             -- assistant: { draftId, positions[] } created by the turn
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE(thread_id, seq)
+);
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_thread ON chat_messages(thread_id, seq);
+
+-- ============================================
+-- price_ticks table
+-- Raw oracle prices recorded by the price history recorder (pruned after a few days)
+-- ============================================
+CREATE TABLE IF NOT EXISTS price_ticks (
+    id TEXT PRIMARY KEY,
+    symbol TEXT NOT NULL,                       -- Uppercase ticker, e.g. ETH
+    price_usd REAL NOT NULL,
+    source TEXT NOT NULL,                       -- Oracle source id or 'median'
+    confidence TEXT NOT NULL,                   -- high | medium | low
+    ts INTEGER NOT NULL                         -- Unix seconds
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_ticks_symbol_ts ON price_ticks(symbol, ts);
+
+-- ============================================
+-- price_candles table
+-- OHLC candles built from price_ticks as they are recorded
+-- ============================================
+CREATE TABLE IF NOT EXISTS price_candles (
+    symbol TEXT NOT NULL,
+    period TEXT NOT NULL,                       -- 1m | 1h | 1d
+    bucket_start INTEGER NOT NULL,              -- Unix seconds, aligned to the period (UTC)
+    open REAL NOT NULL,
+    high REAL NOT NULL,
+    low REAL NOT NULL,
+    close REAL NOT NULL,
+    first_tick_at INTEGER NOT NULL,             -- ts of the tick that set open
+    last_tick_at INTEGER NOT NULL,              -- ts of the tick that set close
+    tick_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (symbol, period, bucket_start)
+);
+
 -- ============================================
 -- Migrations for existing databases
 -- SQLite ALTER TABLE ADD COLUMN (safe for existing tables)
@@ -0,0 +1,429 @@
+#!/usr/bin/env node
+/**
+ * Local Anvil Integration Harness
+ * Runs the execution kernel end to end against a throwaway local chain (no testnet, no faucets)
+ *
+ *   1. Start Anvil with Sepolia's chain id (the backend's viem clients are pinned to sepolia)
+ *   2. Deploy ExecutionRouter + adapters with the Foundry deploy script
+ *   3. Start the backend against Anvil (public Sepolia fallbacks disabled, temp ledger DB)
+ *   4. Direct flow: prepare → sign → send → submit → receipt
+ *   5. Ledger flow: relayer intent → ledger execution row → reconciler agrees with the receipt
+ *
+ * Usage:
+ *   npm run harness:anvil
+ *
+ * Requirements: anvil + forge on PATH, contracts/lib installed (forge install)
+ *
+ * Environment Variables:
+ *   ANVIL_PORT - Anvil port (default: 8546)
+ *   HARNESS_BACKEND_PORT - Backend port (default: 3101)
+ *   ANVIL_DEPLOY_SCRIPT - Forge script target (default: script/DeploySepolia.s.sol:DeploySepolia)
+ *   HARNESS_INTENT - Intent text for the ledger flow (default: "hedge my portfolio")
+ *   HARNESS_KEEP_ALIVE - '1' to leave Anvil and the backend running after the checks
+ */
+
+import { spawn, spawnSync, ChildProcess } from 'child_process';
+import { mkdtempSync, rmSync } from 'fs';
+import { tmpdir } from 'os';
+import { join, resolve, dirname } from 'path';
+import { fileURLToPath } from 'url';
+import { createPublicClient, createWalletClient, encodeFunctionData, http, parseUnits } from 'viem';
+import { privateKeyToAccount } from 'viem/accounts';
+import { sepolia } from 'viem/chains';
+
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = dirname(__filename);
+const agentDir = resolve(__dirname, '..');
+const contractsDir = resolve(agentDir, '../contracts');
+
+const ANVIL_PORT = parseInt(process.env.ANVIL_PORT || '8546', 10);
+const BACKEND_PORT = parseInt(process.env.HARNESS_BACKEND_PORT || '3101', 10);
+const DEPLOY_SCRIPT = process.env.ANVIL_DEPLOY_SCRIPT || 'script/DeploySepolia.s.sol:DeploySepolia';
+const HARNESS_INTENT = process.env.HARNESS_INTENT || 'hedge my portfolio';
+const KEEP_ALIVE = process.env.HARNESS_KEEP_ALIVE === '1';
+
+const RPC_URL = `http://127.0.0.1:${ANVIL_PORT}`;
+const BASE_URL = `http://127.0.0.1:${BACKEND_PORT}`;
+const LEDGER_SECRET = 'anvil-harness';
+
+// Anvil's default dev accounts (test mnemonic, never funded anywhere real)
+const DEPLOYER_KEY = '0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80';
+const USER_KEY = '0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d';
+const RELAYER_KEY = '0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a';
+
+// Deploy log name → backend env var ("<Name> deployed at: 0x...")
+const DEPLOYED_ENV: Record<string, string> = {
+  executionrouter: 'EXECUTION_ROUTER_ADDRESS',
+  mockswapadapter: 'MOCK_SWAP_ADAPTER_ADDRESS',
+  uniswapv3swapadapter: 'UNISWAP_V3_ADAPTER_ADDRESS',
+  wethwrapadapter: 'WETH_WRAP_ADAPTER_ADDRESS',
+  erc20pulladapter: 'ERC20_PULL_ADAPTER_ADDRESS',
+  proofofexecutionadapter: 'PROOF_ADAPTER_ADDRESS',
+  proofadapter: 'PROOF_ADAPTER_ADDRESS',
+  demousdc: 'DEMO_USDC_ADDRESS',
+  demoweth: 'DEMO_WETH_ADDRESS',
+  demoswaprouter: 'DEMO_SWAP_ROUTER_ADDRESS',
+  demolendvault: 'DEMO_LEND_VAULT_ADDRESS',
+  demolendadapter: 'DEMO_LEND_ADAPTER_ADDRESS',
+  demoperpengine: 'DEMO_PERP_ENGINE_ADDRESS',
+  demoperpadapter: 'DEMO_PERP_ADAPTER_ADDRESS',
+  aavev3supplyadapter: 'AAVE_ADAPTER_ADDRESS',
+};
+
+// Colors for output
+const RED = '\x1b[31m';
+const GREEN = '\x1b[32m';
+const YELLOW = '\x1b[33m';
+const BLUE = '\x1b[34m';
+const NC = '\x1b[0m';
+
+let passed = 0;
+let failed = 0;
+const children: ChildProcess[] = [];
+
+function printPass(msg: string) {
+  console.log(`${GREEN}✓ PASS${NC} ${msg}`);
+  passed++;
+}
+
+function printFail(msg: string) {
+  console.log(`${RED}✗ FAIL${NC} ${msg}`);
+  failed++;
+}
+
+function printInfo(msg: string) {
+  console.log(`${BLUE}ℹ${NC} ${msg}`);
+}
+
+function printWarn(msg: string) {
+  console.log(`${YELLOW}⚠${NC} ${msg}`);
+}
+
+function shutdown(): void {
+  for (const child of children) {
+    if (!child.killed) child.kill('SIGTERM');
+  }
+}
+
+async function fetchJson(url: string, options?: RequestInit): Promise<any> {
+  const response = await fetch(url, options);
+  const body = await response.json().catch(() => ({}));
+  if (!response.ok && response.status !== 202) {
+    throw new Error(`HTTP ${response.status}: ${JSON.stringify(body).slice(0, 300)}`);
+  }
+  return body;
+}
+
+function postJson(path: string, body: unknown, headers: Record<string, string> = {}): Promise<any> {
+  return fetchJson(`${BASE_URL}${path}`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...headers },
+    body: JSON.stringify(body),
+  });
+}
+
+async function waitFor(label: string, check: () => Promise<boolean>, timeoutMs: number = 30000): Promise<void> {
+  const deadline = Date.now() + timeoutMs;
+  while (Date.now() < deadline) {
+    try {
+      if (await check()) return;
+    } catch {
+      // Not up yet
+    }
+    await new Promise(r => setTimeout(r, 500));
+  }
+  throw new Error(`Timed out waiting for ${label}`);
+}
+
+function requireBinary(name: string): void {
+  const result = spawnSync(name, ['--version'], { encoding: 'utf-8' });
+  if (result.error || result.status !== 0) {
+    console.error(`${RED}${name} not found on PATH.${NC} Install Foundry: https://book.getfoundry.sh/getting-started/installation`);
+    process.exit(1);
+  }
+}
+
+async function startAnvil(): Promise<void> {
+  const anvil = spawn('anvil', ['--port', String(ANVIL_PORT), '--chain-id', '11155111', '--silent'], { stdio: 'inherit' });
+  children.push(anvil);
+  await waitFor('anvil', async () => {
+    const res = await fetch(RPC_URL, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ jsonrpc: '2.0', id: 1, method: 'eth_chainId', params: [] }),
+    });
+    return res.ok;
+  });
+}
+
+function deployContracts(): Record<string, string> {
+  const result = spawnSync('forge', [
+    'script', DEPLOY_SCRIPT,
+    '--rpc-url', RPC_URL,
+    '--private-key', DEPLOYER_KEY,
+    '--broadcast',
+  ], {
+    cwd: contractsDir,
+    encoding: 'utf-8',
+    env: { ...process.env, DEPLOYER_PRIVATE_KEY: DEPLOYER_KEY, SEPOLIA_RPC_URL: RPC_URL },
+  });
+  if (result.status !== 0) {
+    console.error(result.stdout, result.stderr);
+    throw new Error(`forge script ${DEPLOY_SCRIPT} failed`);
+  }
+
+  const env: Record<string, string> = {};
+  const pattern = /([A-Za-z0-9_]+) deployed at:\s*(0x[a-fA-F0-9]{40})/g;
+  for (const [, name, address] of result.stdout.matchAll(pattern)) {
+    const key = DEPLOYED_ENV[name.toLowerCase().replace(/[^a-z0-9]/g, '')];
+    if (key && !env[key]) {
+      env[key] = address;
+    }
+  }
+  return env;
+}
+
+async function startBackend(deployed: Record<string, string>, ledgerDbPath: string): Promise<void> {
+  const backend = spawn('npx', ['tsx', 'src/server/http.ts'], {
+    cwd: agentDir,
+    stdio: ['ignore', 'ignore', 'inherit'],
+    env: {
+      ...process.env,
+      ...deployed,
+      PORT: String(BACKEND_PORT),
+      EXECUTION_MODE: 'eth_testnet',
+      EXECUTION_AUTH_MODE: 'direct',
+      ETH_TESTNET_RPC_URL: RPC_URL,
+      ETH_TESTNET_CHAIN_ID: '11155111',
+      ETH_RPC_PUBLIC_FALLBACKS: 'false',
+      ETH_RPC_FALLBACK_URLS: '',
+      ALCHEMY_RPC_URL: '',
+      INFURA_RPC_URL: '',
+      RELAYER_PRIVATE_KEY: RELAYER_KEY,
+      EXECUTION_LEDGER_DB_PATH: ledgerDbPath,
+      DATABASE_URL: '',
+      DEV_LEDGER_SECRET: LEDGER_SECRET,
+      ACCESS_GATE_ENABLED: 'false',
+      // Background loops stay off; the harness drives each step itself
+      RECONCILER_DISABLED: 'true',
+      RECONCILER_MIN_AGE_SECONDS: '0',
+      INTENT_QUEUE_DISABLED: 'true',
+      PERPS_MARK_ENGINE_DISABLED: 'true',
+      PERPS_FUNDING_ENGINE_DISABLED: 'true',
+    },
+  });
+  children.push(backend);
+  await waitFor('backend', async () => (await fetch(`${BASE_URL}/health`)).ok, 60000);
+}
+
+const PLAN_ABI = [
+  {
+    name: 'executeBySender',
+    type: 'function',
+    stateMutability: 'payable',
+    inputs: [{
+      name: 'plan',
+      type: 'tuple',
+      components: [
+        { name: 'user', type: 'address' },
+        { name: 'nonce', type: 'uint256' },
+        { name: 'deadline', type: 'uint256' },
+        {
+          name: 'actions',
+          type: 'tuple[]',
+          components: [
+            { name: 'actionType', type: 'uint8' },
+            { name: 'adapter', type: 'address' },
+            { name: 'data', type: 'bytes' },
+          ],
+        },
+      ],
+    }],
+    outputs: [],
+  },
+] as const;
+
+const ERC20_ABI = [
+  { name: 'mint', type: 'function', stateMutability: 'nonpayable', inputs: [{ name: 'to', type: 'address' }, { name: 'amount', type: 'uint256' }], outputs: [] },
+  { name: 'approve', type: 'function', stateMutability: 'nonpayable', inputs: [{ name: 'spender', type: 'address' }, { name: 'amount', type: 'uint256' }], outputs: [{ name: '', type: 'bool' }] },
+] as const;
+
+const publicClient = createPublicClient({ chain: sepolia, transport: http(RPC_URL) });
+const userAccount = privateKeyToAccount(USER_KEY);
+const userWallet = createWalletClient({ account: userAccount, chain: sepolia, transport: http(RPC_URL) });
+const deployerWallet = createWalletClient({ account: privateKeyToAccount(DEPLOYER_KEY), chain: sepolia, transport: http(RPC_URL) });
+
+/**
+ * prepare → sign → send → submit → receipt for one execution kind
+ */
+async function runDirectFlow(label: string, prepareBody: Record<string, unknown>): Promise<void> {
+  const draftId = `anvil-${label}-${Date.now()}`;
+  const prepared = await postJson('/api/execute/prepare', {
+    draftId,
+    userAddress: userAccount.address,
+    authMode: 'direct',
+    ...prepareBody,
+  });
+  if (!prepared.plan?.actions?.length) {
+    printFail(`${label}: prepare returned no plan actions`);
+    return;
+  }
+  printPass(`${label}: prepared ${prepared.plan.actions.length} action(s) (types ${prepared.plan.actions.map((a: any) => a.actionType).join(',')})`);
+
+  for (const approval of prepared.requirements?.approvals || []) {
+    const approveHash = await userWallet.writeContract({
+      address: approval.token,
+      abi: ERC20_ABI,
+      functionName: 'approve',
+      args: [approval.spender, BigInt(approval.amount)],
+    });
+    await publicClient.waitForTransactionReceipt({ hash: approveHash });
+    printInfo(`${label}: approved ${approval.token.slice(0, 10)}... for router`);
+  }
+
+  const plan = prepared.plan;
+  const txHash = await userWallet.sendTransaction({
+    to: prepared.to,
+    value: BigInt(prepared.value || '0x0'),
+    data: encodeFunctionData({
+      abi: PLAN_ABI,
+      functionName: 'executeBySender',
+      args: [{
+        user: plan.user,
+        nonce: BigInt(plan.nonce),
+        deadline: BigInt(plan.deadline),
+        actions: plan.actions.map((a: any) => ({ actionType: a.actionType, adapter: a.adapter, data: a.data })),
+      }],
+    }),
+  });
+  printPass(`${label}: signed and sent ${txHash}`);
+
+  const submitted = await postJson('/api/execute/submit', { draftId, txHash, userAddress: userAccount.address });
+  if (submitted.success && submitted.receiptStatus === 'confirmed') {
+    printPass(`${label}: submit confirmed at block ${submitted.blockNumber}`);
+  } else {
+    printFail(`${label}: submit returned ${submitted.receiptStatus || submitted.status} (${submitted.error || 'no error'})`);
+    return;
+  }
+
+  const receipt = await publicClient.getTransactionReceipt({ hash: txHash });
+  if (receipt.status === 'success' && Number(receipt.blockNumber) === submitted.blockNumber) {
+    printPass(`${label}: receipt matches submit result`);
+  } else {
+    printFail(`${label}: receipt ${receipt.status} at ${receipt.blockNumber} disagrees with submit`);
+  }
+}
+
+/**
+ * Relayer-executed intent → ledger execution row → reconciler finds no discrepancy
+ */
+async function runLedgerFlow(): Promise<void> {
+  const ledgerHeaders = { 'X-Ledger-Secret': LEDGER_SECRET };
+  const result = await postJson('/api/ledger/intents/execute', {
+    intentText: HARNESS_INTENT,
+    chain: 'ethereum',
+    metadata: { source: 'anvil-harness' },
+  }, ledgerHeaders);
+
+  if (!result.ok || !result.txHash) {
+    printFail(`ledger: intent "${HARNESS_INTENT}" ended ${result.status} (${result.error?.code || 'no tx'})`);
+    return;
+  }
+  printPass(`ledger: intent ${result.intentId.slice(0, 8)} ${result.status} with tx ${result.txHash}`);
+
+  const executions = await fetchJson(`${BASE_URL}/api/ledger/intents/${result.intentId}/executions`, { headers: ledgerHeaders });
+  const execution = (executions.data || []).find((e: any) => e.tx_hash === result.txHash);
+  if (!execution) {
+    printFail('ledger: no execution row recorded for the intent tx');
+    return;
+  }
+
+  const receipt = await publicClient.getTransactionReceipt({ hash: result.txHash });
+  if (execution.status === 'confirmed' && execution.block_number === Number(receipt.blockNumber)) {
+    printPass(`ledger: execution ${execution.id.slice(0, 8)} confirmed at block ${execution.block_number}`);
+  } else {
+    printFail(`ledger: execution ${execution.status}@${execution.block_number} vs receipt ${receipt.status}@${receipt.blockNumber}`);
+  }
+
+  await postJson('/api/ledger/reconcile/run', {}, ledgerHeaders);
+  const report = await fetchJson(`${BASE_URL}/api/ledger/reconcile/report`, { headers: ledgerHeaders });
+  const flagged = (report.data?.discrepancies || []).filter((d: any) => d.execution_id === execution.id);
+  if (flagged.length === 0) {
+    printPass('ledger: reconciler agrees with the chain');
+  } else {
+    printFail(`ledger: reconciler flagged ${flagged.map((d: any) => d.kind).join(', ')}`);
+  }
+}
+
+async function main() {
+  console.log(`${BLUE}=== Anvil Integration Harness ===${NC}\n`);
+  requireBinary('anvil');
+  requireBinary('forge');
+
+  const tempDir = mkdtempSync(join(tmpdir(), 'blossom-anvil-'));
+  process.on('exit', () => {
+    shutdown();
+    rmSync(tempDir, { recursive: true, force: true });
+  });
+  process.on('SIGINT', () => process.exit(130));
+
+  printInfo(`Starting anvil on ${RPC_URL}`);
+  await startAnvil();
+
+  printInfo(`Deploying ${DEPLOY_SCRIPT}`);
+  const deployed = deployContracts();
+  for (const [key, address] of Object.entries(deployed)) {
+    printInfo(`  ${key}=${address}`);
+  }
+  if (!deployed.EXECUTION_ROUTER_ADDRESS || !deployed.MOCK_SWAP_ADAPTER_ADDRESS) {
+    printFail('Deploy output is missing ExecutionRouter or MockSwapAdapter');
+    process.exit(1);
+  }
+  printPass(`Deployed ${Object.keys(deployed).length} contracts`);
+
+  printInfo(`Starting backend on ${BASE_URL}`);
+  await startBackend(deployed, join(tempDir, 'ledger.db'));
+  printPass('Backend healthy against anvil');
+
+  console.log(`\n${BLUE}--- Direct execution ---${NC}`);
+  if (deployed.PROOF_ADAPTER_ADDRESS) {
+    await runDirectFlow('perp-proof', {
+      executionKind: 'perp',
+      strategy: { instrumentType: 'perp', market: 'ETH-USD', direction: 'long', leverage: 2, riskPercent: 2 },
+    });
+  } else {
+    printWarn('perp-proof: skipped (no proof adapter deployed)');
+  }
+
+  if (deployed.DEMO_USDC_ADDRESS && deployed.DEMO_WETH_ADDRESS && deployed.ERC20_PULL_ADAPTER_ADDRESS) {
+    try {
+      const mintHash = await deployerWallet.writeContract({
+        address: deployed.DEMO_USDC_ADDRESS as `0x${string}`,
+        abi: ERC20_ABI,
+        functionName: 'mint',
+        args: [userAccount.address, parseUnits('1000', 6)],
+      });
+      await publicClient.waitForTransactionReceipt({ hash: mintHash });
+      await runDirectFlow('demo-swap', { executionKind: 'demo_swap' });
+    } catch (error: any) {
+      printFail(`demo-swap: ${error.shortMessage || error.message}`);
+    }
+  } else {
+    printWarn('demo-swap: skipped (demo tokens or pull adapter not deployed)');
+  }
+
+  console.log(`\n${BLUE}--- Ledger ---${NC}`);
+  await runLedgerFlow();
+
+  console.log(`\n${passed} passed, ${failed} failed`);
+  if (KEEP_ALIVE) {
+    printInfo(`Keeping anvil (${RPC_URL}) and backend (${BASE_URL}) up; Ctrl+C to stop`);
+    await new Promise(() => {});
+  }
+  process.exit(failed > 0 ? 1 : 0);
+}
+
+main().catch((error) => {
+  console.error(`${RED}Harness error:${NC}`, error.message);
+  process.exit(1);
+});
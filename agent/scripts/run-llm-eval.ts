@@ -0,0 +1,171 @@
+#!/usr/bin/env npx tsx
+/**
+ * LLM Eval Runner
+ *
+ * Scores intent-to-action accuracy of the model against the golden dataset
+ * (src/evals/goldenDataset.ts): field-level precision/recall per action type
+ * and provider.
+ *
+ * Usage:
+ *   npx tsx agent/scripts/run-llm-eval.ts                              # replay recordings (offline)
+ *   npx tsx agent/scripts/run-llm-eval.ts --mode record -p openai      # refresh openai recordings
+ *   npx tsx agent/scripts/run-llm-eval.ts --mode live -p anthropic --case perp-long-eth-leverage
+ *   npx tsx agent/scripts/run-llm-eval.ts --json eval-report.json --min-precision 0.8
+ */
+
+import { fileURLToPath } from 'url';
+import { dirname, resolve } from 'path';
+import { config } from 'dotenv';
+import { parseArgs } from 'util';
+import * as fs from 'fs';
+
+// Setup paths
+const __filename = fileURLToPath(import.meta.url);
+const __dirname = dirname(__filename);
+const agentDir = resolve(__dirname, '..');
+const rootDir = resolve(agentDir, '..');
+
+// Load environment
+config({ path: resolve(agentDir, '.env.local') });
+config({ path: resolve(rootDir, '.env.local') });
+
+import { runEval, formatEvalReport, DEFAULT_RECORDINGS_DIR } from '../src/evals/runner';
+import type { EvalMode, EvalCaseResult } from '../src/evals/runner';
+import { GOLDEN_DATASET } from '../src/evals/goldenDataset';
+import type { ModelProvider } from '../src/services/llmUsage';
+
+const PROVIDERS: ModelProvider[] = ['openai', 'anthropic', 'gemini', 'stub'];
+
+function printResult(result: EvalCaseResult): void {
+  const misses = result.scores.filter(score => score.outcome !== 'tp');
+  const icon = result.status === 'scored' && misses.length === 0 ? '✅' : result.status === 'scored' ? '⚠️ ' : '❌';
+  const timing = result.latencyMs !== undefined ? ` (${result.latencyMs}ms)` : '';
+  console.log(`${icon} [${result.provider}] ${result.caseId}: ${result.status}${result.stale ? ' (stale)' : ''}${timing}`);
+  if (result.error) {
+    console.log(`     ${result.error.slice(0, 120)}`);
+  }
+  for (const miss of misses) {
+    const detail = miss.outcome === 'wrong'
+      ? `expected ${JSON.stringify(miss.expected)}, got ${JSON.stringify(miss.actual)}`
+      : miss.outcome === 'fn' ? `missing (expected ${JSON.stringify(miss.expected)})` : `unexpected ${JSON.stringify(miss.actual)}`;
+    console.log(`     ${miss.category}.${miss.field}: ${detail}`);
+  }
+}
+
+/**
+ * Providers with a recordings file, for replay without --provider
+ */
+function recordedProviders(): ModelProvider[] {
+  return PROVIDERS.filter(provider => fs.existsSync(resolve(DEFAULT_RECORDINGS_DIR, `${provider}.json`)));
+}
+
+async function main() {
+  console.log('\n🌸 LLM Eval Runner\n');
+  console.log('═══════════════════════════════════════════════════════════\n');
+
+  const { values } = parseArgs({
+    options: {
+      mode: { type: 'string', short: 'm', default: 'replay' },
+      provider: { type: 'string', short: 'p', multiple: true },
+      case: { type: 'string', short: 'c', multiple: true },
+      tag: { type: 'string', short: 't', multiple: true },
+      json: { type: 'string' },
+      'min-precision': { type: 'string' },
+      quiet: { type: 'boolean', short: 'q', default: false },
+      help: { type: 'boolean', short: 'h' },
+    },
+    strict: false,
+    allowPositionals: true,
+  });
+
+  if (values.help) {
+    console.log(`Usage:
+  npx tsx agent/scripts/run-llm-eval.ts [options]
+
+Options:
+  --mode, -m        replay|record|live (default: replay)
+  --provider, -p    Provider(s) to evaluate: openai|anthropic|gemini|stub (repeatable)
+                    (default: replay = providers with recordings, otherwise BLOSSOM_MODEL_PROVIDER)
+  --case, -c        Only run these case ids (repeatable)
+  --tag, -t         Only run cases with this tag (repeatable)
+  --json            Write the full report to a JSON file
+  --min-precision   Exit 1 if any provider's overall precision is below this (0-1)
+  --quiet, -q       Only print the summary
+  --help, -h        Show this help
+`);
+    process.exit(0);
+  }
+
+  const mode = values.mode as EvalMode;
+  if (!['replay', 'record', 'live'].includes(mode)) {
+    console.error(`Unknown mode: ${mode}`);
+    process.exit(1);
+  }
+
+  let providers = (values.provider as string[] | undefined)?.map(p => p.toLowerCase() as ModelProvider);
+  if (!providers || providers.length === 0) {
+    providers = mode === 'replay'
+      ? recordedProviders()
+      : [(process.env.BLOSSOM_MODEL_PROVIDER || 'stub').toLowerCase() as ModelProvider];
+  }
+  const unknown = providers.filter(p => !PROVIDERS.includes(p));
+  if (unknown.length > 0) {
+    console.error(`Unknown provider(s): ${unknown.join(', ')}`);
+    process.exit(1);
+  }
+  if (providers.length === 0) {
+    console.error(`No recordings in ${DEFAULT_RECORDINGS_DIR}. Run with --mode record --provider <name> first.`);
+    process.exit(1);
+  }
+
+  const caseIds = values.case as string[] | undefined;
+  const tags = values.tag as string[] | undefined;
+  const cases = GOLDEN_DATASET.filter(c =>
+    (!caseIds?.length || caseIds.includes(c.id)) &&
+    (!tags?.length || tags.some(tag => c.tags?.includes(tag)))
+  );
+  if (cases.length === 0) {
+    console.error('No cases match the given --case/--tag filters');
+    process.exit(1);
+  }
+
+  console.log(`Mode:      ${mode}`);
+  console.log(`Providers: ${providers.join(', ')}`);
+  console.log(`Cases:     ${cases.length}/${GOLDEN_DATASET.length}`);
+  console.log('\n═══════════════════════════════════════════════════════════\n');
+
+  const report = await runEval({
+    providers,
+    mode,
+    cases,
+    onResult: values.quiet ? undefined : printResult,
+  });
+
+  console.log('\n═══════════════════════════════════════════════════════════');
+  console.log('EVAL SUMMARY');
+  console.log('═══════════════════════════════════════════════════════════\n');
+  console.log(formatEvalReport(report));
+
+  if (values.json) {
+    fs.writeFileSync(values.json as string, JSON.stringify(report, null, 2));
+    console.log(`Report written to ${values.json}`);
+  }
+  if (mode === 'record') {
+    console.log(`Recordings saved to ${DEFAULT_RECORDINGS_DIR}`);
+  }
+
+  const minPrecision = values['min-precision'] !== undefined ? parseFloat(values['min-precision'] as string) : null;
+  if (minPrecision !== null) {
+    const below = Object.entries(report.providers)
+      .filter(([, summary]) => summary.overall.precision !== null && summary.overall.precision < minPrecision);
+    if (below.length > 0) {
+      console.log(`\n❌ Precision below ${minPrecision}: ${below.map(([provider]) => provider).join(', ')}`);
+      process.exit(1);
+    }
+  }
+}
+
+main().catch(error => {
+  console.error('Fatal error:', error.message);
+  process.exit(1);
+});
@@ -0,0 +1,172 @@
+#!/usr/bin/env node
+/**
+ * Solana Local Validator Smoke Test
+ * Exercises SolanaClient's native transaction building against solana-test-validator
+ *
+ *   1. Start solana-test-validator (or use SOLANA_VALIDATOR_URL if already running)
+ *   2. Airdrop to a fresh keypair
+ *   3. SOL transfer + memo
+ *   4. Create an SPL mint, mint to the payer's ATA
+ *   5. SPL transferChecked to a recipient whose ATA doesn't exist yet
+ *
+ * Usage:
+ *   npm run solana:validator-smoke
+ *
+ * Requirements: solana-test-validator on PATH (Solana CLI / Agave tools)
+ *
+ * Environment Variables:
+ *   SOLANA_VALIDATOR_URL - Use an already running validator instead of spawning one
+ *   SOLANA_VALIDATOR_PORT - RPC port for the spawned validator (default: 8899)
+ */
+
+import { spawn, spawnSync, ChildProcess } from 'child_process';
+import { mkdtempSync, rmSync } from 'fs';
+import { tmpdir } from 'os';
+import { join } from 'path';
+import {
+  SolanaClient,
+  LAMPORTS_PER_SOL,
+  generateKeypair,
+  getAssociatedTokenAddress,
+  memoInstruction,
+  systemTransferInstruction,
+  verifySignature,
+  signMessage,
+} from '../src/solana/solanaClient';
+
+const VALIDATOR_PORT = parseInt(process.env.SOLANA_VALIDATOR_PORT || '8899', 10);
+const RPC_URL = process.env.SOLANA_VALIDATOR_URL || `http://127.0.0.1:${VALIDATOR_PORT}`;
+
+// Colors for output
+const RED = '\x1b[31m';
+const GREEN = '\x1b[32m';
+const BLUE = '\x1b[34m';
+const NC = '\x1b[0m';
+
+let passed = 0;
+let failed = 0;
+let validator: ChildProcess | null = null;
+
+function printPass(msg: string) {
+  console.log(`${GREEN}✓ PASS${NC} ${msg}`);
+  passed++;
+}
+
+function printFail(msg: string) {
+  console.log(`${RED}✗ FAIL${NC} ${msg}`);
+  failed++;
+}
+
+function printInfo(msg: string) {
+  console.log(`${BLUE}ℹ${NC} ${msg}`);
+}
+
+async function check(label: string, fn: () => Promise<string | void>): Promise<void> {
+  try {
+    const detail = await fn();
+    printPass(detail ? `${label}: ${detail}` : label);
+  } catch (error: any) {
+    printFail(`${label}: ${error.message}`);
+  }
+}
+
+function assert(condition: boolean, message: string): void {
+  if (!condition) throw new Error(message);
+}
+
+async function startValidator(client: SolanaClient, ledgerDir: string): Promise<void> {
+  const probe = spawnSync('solana-test-validator', ['--version'], { encoding: 'utf-8' });
+  if (probe.error || probe.status !== 0) {
+    console.error(`${RED}solana-test-validator not found on PATH.${NC} Install: https://docs.anza.xyz/cli/install`);
+    process.exit(1);
+  }
+
+  validator = spawn('solana-test-validator', [
+    '--reset', '--quiet',
+    '--ledger', ledgerDir,
+    '--rpc-port', String(VALIDATOR_PORT),
+  ], { stdio: 'ignore' });
+
+  const deadline = Date.now() + 60000;
+  while (Date.now() < deadline) {
+    if (await client.isHealthy()) return;
+    await new Promise(r => setTimeout(r, 1000));
+  }
+  throw new Error('Timed out waiting for solana-test-validator');
+}
+
+async function main(): Promise<void> {
+  console.log(`${BLUE}Solana validator smoke test${NC} (${RPC_URL})\n`);
+
+  const client = new SolanaClient({ rpcUrl: RPC_URL });
+  const tempDir = mkdtempSync(join(tmpdir(), 'solana-smoke-'));
+  process.on('exit', () => {
+    validator?.kill('SIGTERM');
+    rmSync(tempDir, { recursive: true, force: true });
+  });
+
+  if (!process.env.SOLANA_VALIDATOR_URL) {
+    printInfo('Starting solana-test-validator');
+    await startValidator(client, join(tempDir, 'ledger'));
+  }
+
+  const payer = generateKeypair();
+  const recipient = generateKeypair();
+  printInfo(`payer=${payer.publicKey} recipient=${recipient.publicKey}`);
+
+  await check('ed25519 sign/verify', async () => {
+    const message = Buffer.from('blossom');
+    assert(verifySignature(message, signMessage(message, payer.secretKey), payer.publicKey), 'signature did not verify');
+  });
+
+  await check('airdrop', async () => {
+    const signature = await client.requestAirdrop(payer.publicKey, 2 * LAMPORTS_PER_SOL);
+    await client.confirmTransaction(signature, 'confirmed');
+    const { sol } = await client.getBalance(payer.publicKey);
+    assert(sol === 2, `expected 2 SOL, got ${sol}`);
+    return `${sol} SOL`;
+  });
+
+  await check('SOL transfer + memo', async () => {
+    const result = await client.transferSol(payer, recipient.publicKey, LAMPORTS_PER_SOL / 10, 'blossom:smoke');
+    const { lamports } = await client.getBalance(recipient.publicKey);
+    assert(lamports === LAMPORTS_PER_SOL / 10, `recipient has ${lamports} lamports`);
+    return `${result.signature.slice(0, 16)}… slot ${result.slot}`;
+  });
+
+  await check('memo from a non-fee-payer signer', async () => {
+    const result = await client.sendAndConfirmTransaction(
+      [memoInstruction('co-signed', [recipient.publicKey]), systemTransferInstruction(payer.publicKey, recipient.publicKey, 1)],
+      [payer, recipient]
+    );
+    return result.signature.slice(0, 16) + '…';
+  });
+
+  let mint = '';
+  await check('create mint + mintTo', async () => {
+    mint = await client.createMint(payer, 6);
+    assert(await client.getMintDecimals(mint) === 6, 'mint decimals mismatch');
+    await client.mintTo(payer, mint, payer.publicKey, 1_000_000n);
+    const balance = await client.getTokenAccountBalance(getAssociatedTokenAddress(payer.publicKey, mint));
+    assert(balance?.amount === '1000000', `payer ATA balance ${balance?.amount}`);
+    return mint;
+  });
+
+  await check('SPL transferChecked (creates recipient ATA)', async () => {
+    assert(!!mint, 'no mint');
+    const recipientAta = getAssociatedTokenAddress(recipient.publicKey, mint);
+    assert(await client.getTokenAccountBalance(recipientAta) === null, 'recipient ATA already exists');
+    await client.transferToken({ owner: payer, mint, to: recipient.publicKey, amount: 250_000n, memo: 'blossom:spl' });
+    const balance = await client.getTokenAccountBalance(recipientAta);
+    assert(balance?.amount === '250000', `recipient ATA balance ${balance?.amount}`);
+    return `${balance?.uiAmount} tokens`;
+  });
+
+  console.log(`\n${passed} passed, ${failed} failed`);
+  process.exit(failed > 0 ? 1 : 0);
+}
+
+main().catch((error) => {
+  console.error(`${RED}Smoke test error:${NC}`, error.message);
+  process.exit(1);
+});
@@ -0,0 +1,244 @@
+/**
+ * Bridge Transfer Tracker Tests
+ * Provider status and source receipts are mocked - no network or chain access.
+ * The ledger runs against an in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+const mocks = vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  return {
+    getLiFiStatus: vi.fn(),
+    lookupTxReceipt: vi.fn(),
+    logEvent: vi.fn(),
+  };
+});
+
+vi.mock('../../config', () => ({
+  BRIDGE_TRACKER_INTERVAL_MS: 30000,
+  BRIDGE_TRACKER_BATCH_SIZE: 25,
+  BRIDGE_STUCK_AFTER_SECONDS: 3600,
+  BRIDGE_ALERT_WEBHOOK_URL: undefined,
+}));
+vi.mock('../lifi', () => ({ getLiFiStatus: mocks.getLiFiStatus }));
+vi.mock('../../executors/txReceipts', () => ({ lookupTxReceipt: mocks.lookupTxReceipt }));
+vi.mock('../../telemetry/logger', () => ({ logEvent: mocks.logEvent }));
+
+import { runBridgeTrackerCycle, getBridgeTransferView } from '../bridgeTracker';
+import {
+  getDatabase,
+  createBridgeTransfer,
+  getBridgeTransfer,
+  createExecution,
+  getExecution,
+  createIntent,
+  getIntent,
+  updateIntentStatus,
+} from '../../../execution-ledger/db';
+
+const RELAYER = '0x00000000000000000000000000000000000000a1';
+const SOLANA_DEST = 'GsbwXfJraMomNxBcjYLcG3mxkBUiyWXAB32fGbSMQRdW';
+
+function newTransfer(options: { intentId?: string; ageSeconds?: number } = {}) {
+  const source = createExecution({
+    chain: 'ethereum',
+    network: 'sepolia',
+    kind: 'bridge',
+    venue: 'lifi',
+    intent: 'bridge 10 usdc from sepolia to solana',
+    action: 'bridge',
+    fromAddress: RELAYER,
+  });
+  const transfer = createBridgeTransfer({
+    intentId: options.intentId,
+    provider: 'lifi',
+    tool: 'mayan',
+    fromChain: 'ethereum',
+    fromNetwork: 'sepolia',
+    toChain: 'solana',
+    toNetwork: 'mainnet',
+    fromChainId: 11155111,
+    toChainId: 1151111081099710,
+    fromAddress: RELAYER,
+    toAddress: SOLANA_DEST,
+    fromToken: 'USDC',
+    toToken: 'USDC',
+    amountUnits: '10000000',
+    sourceTxHash: `0x${Math.random().toString(16).slice(2).padStart(64, '0')}`,
+    sourceExecutionId: source.id,
+  });
+  if (options.ageSeconds) {
+    getDatabase().prepare('UPDATE bridge_transfers SET created_at = created_at - ? WHERE id = ?')
+      .run(options.ageSeconds, transfer.id);
+  }
+  return transfer;
+}
+
+function executingIntent() {
+  const intent = createIntent({ intentText: 'bridge 10 usdc from sepolia to solana' });
+  updateIntentStatus(intent.id, { status: 'executing' });
+  return intent;
+}
+
+const done = (txHash: string) => ({
+  ok: true,
+  status: 'DONE',
+  substatus: 'COMPLETED',
+  receiving: { txHash, chainId: 1151111081099710, amount: '9950000', token: { address: 'EPj', symbol: 'USDC' } },
+});
+
+describe('bridgeTracker', () => {
+  beforeEach(() => {
+    getDatabase().exec('DELETE FROM bridge_transfers; DELETE FROM executions; DELETE FROM intents;');
+    mocks.getLiFiStatus.mockReset();
+    mocks.lookupTxReceipt.mockReset();
+    mocks.logEvent.mockReset();
+  });
+
+  it('confirms the source leg from its receipt and moves in flight', async () => {
+    const transfer = newTransfer();
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success', blockNumber: 100 });
+    mocks.getLiFiStatus.mockResolvedValue({ ok: true, status: 'PENDING', substatus: 'WAIT_DESTINATION_TRANSACTION' });
+
+    const cycle = await runBridgeTrackerCycle();
+
+    const updated = getBridgeTransfer(transfer.id)!;
+    expect(cycle.advanced).toBe(1);
+    expect(updated.state).toBe('in_flight');
+    expect(updated.source_confirmed_at).toBeTruthy();
+    expect(updated.provider_substatus).toBe('WAIT_DESTINATION_TRANSACTION');
+    expect(updated.poll_count).toBe(1);
+    expect(getExecution(transfer.source_execution_id!)).toMatchObject({ status: 'confirmed', block_number: 100 });
+    expect(mocks.getLiFiStatus).toHaveBeenCalledWith(expect.objectContaining({
+      txHash: transfer.source_tx_hash,
+      bridge: 'mayan',
+      fromChain: 11155111,
+    }));
+  });
+
+  it('waits on the source receipt without asking the provider', async () => {
+    const transfer = newTransfer();
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'pending' });
+
+    await runBridgeTrackerCycle();
+
+    expect(getBridgeTransfer(transfer.id)!.state).toBe('source_pending');
+    expect(mocks.getLiFiStatus).not.toHaveBeenCalled();
+  });
+
+  it('fails the transfer and its intent when the source tx reverts', async () => {
+    const intent = executingIntent();
+    const transfer = newTransfer({ intentId: intent.id });
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'reverted', error: 'execution reverted' });
+
+    await runBridgeTrackerCycle();
+
+    expect(getBridgeTransfer(transfer.id)!.state).toBe('failed');
+    expect(getExecution(transfer.source_execution_id!)?.status).toBe('failed');
+    expect(getIntent(intent.id)?.status).toBe('failed');
+  });
+
+  it('leaves transfers awaiting the user signature alone', async () => {
+    const transfer = createBridgeTransfer({
+      provider: 'lifi',
+      state: 'awaiting_signature',
+      fromChain: 'ethereum',
+      fromNetwork: 'sepolia',
+      toChain: 'solana',
+      toNetwork: 'mainnet',
+      fromChainId: 11155111,
+      toChainId: 1151111081099710,
+      fromAddress: RELAYER,
+    });
+
+    const cycle = await runBridgeTrackerCycle();
+
+    expect(cycle.checked).toBe(0);
+    expect(mocks.lookupTxReceipt).not.toHaveBeenCalled();
+    const view = await getBridgeTransferView(transfer.id);
+    expect(view?.progress.step).toBe(0);
+    expect(view?.source.explorerUrl).toBeUndefined();
+  });
+
+  it('records the destination leg and settles the intent once delivered', async () => {
+    const intent = executingIntent();
+    const transfer = newTransfer({ intentId: intent.id });
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success' });
+    mocks.getLiFiStatus.mockResolvedValue(done('5destSig'));
+
+    await runBridgeTrackerCycle();
+
+    const updated = getBridgeTransfer(transfer.id)!;
+    expect(updated.state).toBe('dest_confirmed');
+    expect(updated.dest_tx_hash).toBe('5destSig');
+    expect(updated.received_units).toBe('9950000');
+
+    const dest = getExecution(updated.dest_execution_id!);
+    expect(dest?.chain).toBe('solana');
+    expect(dest?.status).toBe('confirmed');
+    expect(dest?.linked_execution_id).toBe(transfer.source_execution_id);
+    expect(getExecution(transfer.source_execution_id!)?.linked_execution_id).toBe(dest?.id);
+    expect(getIntent(intent.id)?.status).toBe('confirmed');
+  });
+
+  it('marks refunds as refunded', async () => {
+    const transfer = newTransfer();
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success' });
+    mocks.getLiFiStatus.mockResolvedValue({ ok: true, status: 'DONE', substatus: 'REFUNDED' });
+
+    await runBridgeTrackerCycle();
+
+    const updated = getBridgeTransfer(transfer.id)!;
+    expect(updated.state).toBe('refunded');
+    expect(updated.dest_execution_id).toBeFalsy();
+  });
+
+  it('alerts once on an overdue transfer and still resolves it later', async () => {
+    const transfer = newTransfer({ ageSeconds: 7200 });
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success' });
+    mocks.getLiFiStatus.mockResolvedValue({ ok: true, status: 'PENDING' });
+
+    const first = await runBridgeTrackerCycle();
+    expect(first.alerts).toBe(1);
+    expect(getBridgeTransfer(transfer.id)!.state).toBe('stuck');
+    expect(mocks.logEvent).toHaveBeenCalledWith('bridge_alert', expect.objectContaining({ txHash: transfer.source_tx_hash }));
+
+    // Next pass: still pending, no second alert
+    getDatabase().prepare('UPDATE bridge_transfers SET last_polled_at = 0 WHERE id = ?').run(transfer.id);
+    const second = await runBridgeTrackerCycle();
+    expect(second.alerts).toBe(0);
+    expect(getBridgeTransfer(transfer.id)!.state).toBe('stuck');
+
+    getDatabase().prepare('UPDATE bridge_transfers SET last_polled_at = 0 WHERE id = ?').run(transfer.id);
+    mocks.getLiFiStatus.mockResolvedValue(done('5lateSig'));
+    await runBridgeTrackerCycle();
+    expect(getBridgeTransfer(transfer.id)!.state).toBe('dest_confirmed');
+    expect(mocks.logEvent).toHaveBeenCalledTimes(1);
+  });
+
+  it('skips transfers polled within the tracker interval', async () => {
+    newTransfer();
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success' });
+    mocks.getLiFiStatus.mockResolvedValue({ ok: true, status: 'PENDING' });
+
+    expect((await runBridgeTrackerCycle()).checked).toBe(1);
+    expect((await runBridgeTrackerCycle()).checked).toBe(0);
+  });
+
+  it('describes progress by transfer id or source tx hash', async () => {
+    const transfer = newTransfer();
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'success' });
+    mocks.getLiFiStatus.mockResolvedValue({ ok: true, status: 'PENDING' });
+    await runBridgeTrackerCycle();
+
+    const view = await getBridgeTransferView(transfer.id);
+    expect(view?.final).toBe(false);
+    expect(view?.progress.steps[view.progress.step]).toBe('in_flight');
+    expect(view?.source.explorerUrl).toContain('sepolia.etherscan.io');
+    expect(view?.source.execution?.id).toBe(transfer.source_execution_id);
+
+    expect((await getBridgeTransferView(transfer.source_tx_hash))?.transfer.id).toBe(transfer.id);
+    expect(await getBridgeTransferView('missing')).toBeNull();
+  });
+});
@@ -0,0 +1,235 @@
+/**
+ * LiFi Bridge Execution Tests
+ * Runs against a local mock of the LiFi /quote API and a fake allowance reader -
+ * no network or chain access. The ledger is an in-memory SQLite DB.
+ */
+
+import { describe, it, expect, beforeAll, afterAll, beforeEach, vi } from 'vitest';
+import { createServer, type Server } from 'http';
+import type { AddressInfo } from 'net';
+
+const mockApi = vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  return { baseUrl: '' };
+});
+
+vi.mock('../../config', () => ({
+  get LIFI_API_BASE_URL() {
+    return mockApi.baseUrl;
+  },
+  LIFI_API_KEY: '',
+  BRIDGE_TRACKER_INTERVAL_MS: 30000,
+  BRIDGE_TRACKER_BATCH_SIZE: 25,
+  BRIDGE_STUCK_AFTER_SECONDS: 3600,
+  BRIDGE_ALERT_WEBHOOK_URL: undefined,
+}));
+
+import { prepareLiFiBridge, submitLiFiBridge } from '../lifiExecutor';
+import { getDatabase, getExecution, createIntent, getIntent, getBridgeTransfer } from '../../../execution-ledger/db';
+
+const USER = '0x00000000000000000000000000000000000000b2';
+const SOLANA_DEST = 'GsbwXfJraMomNxBcjYLcG3mxkBUiyWXAB32fGbSMQRdW';
+const USDC = '0x1c7d4b196cb0c7b01d743fbc6116a902379c7238';
+const DIAMOND = '0x1231deb6f5749ef6ce6943a275a1d3e7486f4eae';
+
+interface MockState {
+  quote: { status: number; body: any };
+  requests: { path: string; query: URLSearchParams }[];
+}
+
+const state: MockState = { quote: { status: 200, body: {} }, requests: [] };
+
+function quoteBody(fromToken: string, symbol: string, fromAmount: string) {
+  return {
+    id: 'quote-1',
+    type: 'lifi',
+    tool: 'mayan',
+    action: {
+      fromChainId: 11155111,
+      toChainId: 1151111081099710,
+      fromToken: { address: fromToken, symbol, decimals: symbol === 'ETH' ? 18 : 6 },
+      toToken: { address: 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v', symbol: 'USDC', decimals: 6 },
+      fromAmount,
+    },
+    estimate: { toAmount: '9950000', toAmountMin: '9900000', executionDuration: 60, approvalAddress: DIAMOND },
+    transactionRequest: { to: DIAMOND, data: '0xdeadbeef', value: symbol === 'ETH' ? '0x2386f26fc10000' : '0x0', gasLimit: '0x61a80' },
+  };
+}
+
+/**
+ * Fake public client returning a fixed allowance
+ */
+function allowanceClient(allowance: bigint) {
+  const calls = { allowance: [] as any[] };
+  return {
+    calls,
+    publicClient: {
+      readContract: async (args: any) => {
+        calls.allowance.push(args);
+        return allowance;
+      },
+    },
+  };
+}
+
+const OPTIONS = { chainId: 11155111 };
+
+function request(overrides: Record<string, any> = {}) {
+  return {
+    fromChain: 'sepolia',
+    toChain: 'solana',
+    fromToken: 'USDC',
+    toToken: 'USDC',
+    fromAmount: '10000000',
+    fromAddress: USER,
+    toAddress: SOLANA_DEST,
+    intentText: 'bridge 10 usdc from sepolia to solana',
+    ...overrides,
+  };
+}
+
+let server: Server;
+
+beforeAll(async () => {
+  server = createServer((req, res) => {
+    const url = new URL(req.url || '/', 'http://localhost');
+    state.requests.push({ path: url.pathname, query: url.searchParams });
+    res.setHeader('content-type', 'application/json');
+
+    if (url.pathname === '/quote') {
+      res.statusCode = state.quote.status;
+      res.end(JSON.stringify(state.quote.body));
+    } else {
+      res.statusCode = 404;
+      res.end('{}');
+    }
+  });
+  await new Promise<void>(resolve => server.listen(0, '127.0.0.1', resolve));
+  mockApi.baseUrl = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+});
+
+afterAll(() => {
+  server.close();
+});
+
+describe('prepareLiFiBridge', () => {
+  beforeEach(() => {
+    getDatabase().exec('DELETE FROM execution_steps; DELETE FROM executions; DELETE FROM bridge_transfers;');
+    state.quote = { status: 200, body: quoteBody(USDC, 'USDC', '10000000') };
+    state.requests = [];
+  });
+
+  it('quotes for the user and returns the transaction and approval to sign', async () => {
+    const intent = createIntent({ intentText: 'bridge 10 usdc from sepolia to solana' });
+    const { publicClient, calls } = allowanceClient(0n);
+
+    const result = await prepareLiFiBridge(request({ intentId: intent.id }), { ...OPTIONS, publicClient });
+
+    expect(result.ok).toBe(true);
+    expect(result.status).toBe('awaiting_signature');
+    expect(result.transaction).toEqual({ chainId: 11155111, to: DIAMOND, data: '0xdeadbeef', value: '0', gasLimit: '400000' });
+    expect(result.approvals).toEqual([{ token: USDC, spender: DIAMOND, amount: '10000000' }]);
+    expect(calls.allowance[0].args).toEqual([USER, DIAMOND]);
+
+    const quoteQuery = state.requests.find(r => r.path === '/quote')!.query;
+    expect(quoteQuery.get('fromAddress')).toBe(USER);
+    expect(quoteQuery.get('toAddress')).toBe(SOLANA_DEST);
+
+    const source = getExecution(result.sourceExecutionId!);
+    expect(source).toMatchObject({ chain: 'ethereum', network: 'sepolia', status: 'pending', from_address: USER });
+    expect(source?.intent_id).toBe(intent.id);
+
+    const transfer = getBridgeTransfer(result.bridgeTransferId!);
+    expect(transfer).toMatchObject({
+      state: 'awaiting_signature',
+      from_network: 'sepolia',
+      to_chain: 'solana',
+      to_network: 'mainnet',
+      from_address: USER,
+      to_address: SOLANA_DEST,
+    });
+    expect(transfer?.source_tx_hash).toBeFalsy();
+  });
+
+  it('skips the approval when the allowance already covers the amount', async () => {
+    const { publicClient } = allowanceClient(10_000_000n);
+
+    const result = await prepareLiFiBridge(request(), { ...OPTIONS, publicClient });
+
+    expect(result.ok).toBe(true);
+    expect(result.approvals).toEqual([]);
+  });
+
+  it('returns native ETH with the quoted value and no approval', async () => {
+    state.quote = { status: 200, body: quoteBody('0x0000000000000000000000000000000000000000', 'ETH', '10000000000000000') };
+
+    const result = await prepareLiFiBridge(request({ fromToken: 'ETH', fromAmount: '10000000000000000' }), OPTIONS);
+
+    expect(result.ok).toBe(true);
+    expect(result.approvals).toEqual([]);
+    expect(result.transaction?.value).toBe('10000000000000000');
+  });
+
+  it('refuses a quote for a different chain than the wallet', async () => {
+    const body = quoteBody(USDC, 'USDC', '10000000');
+    state.quote = { status: 200, body: { ...body, action: { ...body.action, fromChainId: 1 } } };
+
+    const result = await prepareLiFiBridge(request(), OPTIONS);
+
+    expect(result.ok).toBe(false);
+    expect(result.error).toMatchObject({ stage: 'quote', code: 'LIFI_CHAIN_MISMATCH' });
+    expect(result.transaction).toBeUndefined();
+    expect(result.sourceExecutionId).toBeUndefined();
+  });
+
+  it('fails at the quote stage without recording anything when there is no route', async () => {
+    state.quote = { status: 404, body: { message: 'No available quotes for the requested transfer', code: 1002 } };
+
+    const result = await prepareLiFiBridge(request(), OPTIONS);
+
+    expect(result.ok).toBe(false);
+    expect(result.error).toMatchObject({ stage: 'quote', code: 'LIFI_QUOTE_FAILED' });
+    expect(result.sourceExecutionId).toBeUndefined();
+  });
+});
+
+describe('submitLiFiBridge', () => {
+  const TX = `0x${'ab'.repeat(32)}`;
+
+  async function prepared(intentId?: string) {
+    state.quote = { status: 200, body: quoteBody(USDC, 'USDC', '10000000') };
+    const result = await prepareLiFiBridge(request({ intentId }), OPTIONS);
+    return result.bridgeTransferId!;
+  }
+
+  beforeEach(() => {
+    getDatabase().exec('DELETE FROM executions; DELETE FROM bridge_transfers;');
+  });
+
+  it('records the user tx and hands the transfer to the tracker', async () => {
+    const intent = createIntent({ intentText: 'bridge 10 usdc from sepolia to solana' });
+    const transferId = await prepared(intent.id);
+
+    const result = await submitLiFiBridge({ transferId, txHash: TX, fromAddress: USER.toUpperCase().replace('0X', '0x') });
+
+    expect(result.ok).toBe(true);
+    expect(result.explorerUrl).toContain('sepolia.etherscan.io');
+    expect(result.transfer).toMatchObject({ state: 'source_pending', source_tx_hash: TX });
+    expect(getExecution(result.transfer!.source_execution_id!)).toMatchObject({ status: 'submitted', tx_hash: TX });
+    expect(getIntent(intent.id)?.status).toBe('executing');
+  });
+
+  it('rejects another wallet, a second submission and a reused tx', async () => {
+    const transferId = await prepared();
+
+    const other = await submitLiFiBridge({ transferId, txHash: TX, fromAddress: '0x00000000000000000000000000000000000000c3' });
+    expect(other.error?.code).toBe('USER_MISMATCH');
+
+    expect((await submitLiFiBridge({ transferId, txHash: TX, fromAddress: USER })).ok).toBe(true);
+    expect((await submitLiFiBridge({ transferId, txHash: TX, fromAddress: USER })).error?.code).toBe('ALREADY_SUBMITTED');
+
+    const second = await prepared();
+    expect((await submitLiFiBridge({ transferId: second, txHash: TX, fromAddress: USER })).error?.code).toBe('DUPLICATE_TX');
+    expect((await submitLiFiBridge({ transferId: 'missing', txHash: TX, fromAddress: USER })).error?.code).toBe('TRANSFER_NOT_FOUND');
+  });
+});
@@ -0,0 +1,440 @@
+/**
+ * Bridge Transfer Tracker
+ * Follows cross-chain transfers (bridge_transfers) from the source tx to the destination leg.
+ *
+ *   awaiting_signature -> source_pending           (user submits the source tx; not polled)
+ *   source_pending   -> source_confirmed | failed   (source receipt)
+ *   source_confirmed -> in_flight -> dest_confirmed | refunded | failed   (provider status)
+ *
+ * A transfer that isn't final after BRIDGE_STUCK_AFTER_SECONDS moves to stuck and
+ * raises an alert. Stuck transfers keep being polled, so a late delivery or refund
+ * still resolves them. Reaching dest_confirmed records the destination execution,
+ * links it to the source execution and confirms the intent.
+ */
+
+import { logEvent } from '../telemetry/logger';
+import { buildExplorerUrl } from '../ledger/ledger';
+import { getLiFiStatus, type LiFiStatusResult } from './lifi';
+import {
+  BRIDGE_TRACKER_INTERVAL_MS,
+  BRIDGE_TRACKER_BATCH_SIZE,
+  BRIDGE_STUCK_AFTER_SECONDS,
+  BRIDGE_ALERT_WEBHOOK_URL,
+} from '../config';
+import type {
+  BridgeTransfer,
+  BridgeTransferState,
+  Chain,
+  Network,
+  Execution,
+} from '../../execution-ledger/db';
+
+// Provider chain ID → ledger chain/network
+// LiFi's Solana chain is mainnet-beta; it has no devnet chain ID
+const LEDGER_CHAINS: Record<number, { chain: Chain; network: Network }> = {
+  1: { chain: 'ethereum', network: 'mainnet' },
+  11155111: { chain: 'ethereum', network: 'sepolia' },
+  1151111081099710: { chain: 'solana', network: 'mainnet' },
+};
+
+// Non-terminal states the tracker polls
+const ACTIVE_STATES: BridgeTransferState[] = ['source_pending', 'source_confirmed', 'in_flight', 'stuck'];
+
+// Order of the happy path, for progress reporting
+const PROGRESS_STATES: BridgeTransferState[] = ['source_pending', 'source_confirmed', 'in_flight', 'dest_confirmed'];
+
+export interface BridgeTrackerCycleResult {
+  checked: number;
+  advanced: number;
+  completed: number;
+  alerts: number;
+  errors: number;
+  ranAt: number;
+}
+
+export interface BridgeTransferView {
+  transfer: BridgeTransfer;
+  final: boolean;
+  progress: {
+    step: number;               // Index into steps of the furthest state reached
+    steps: BridgeTransferState[];
+    elapsedSeconds: number;
+  };
+  source: { txHash?: string; explorerUrl?: string; execution?: Execution };
+  destination: { txHash?: string; explorerUrl?: string; execution?: Execution };
+}
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+let lastCycle: BridgeTrackerCycleResult | null = null;
+
+async function getLedgerDb() {
+  return import('../../execution-ledger/db');
+}
+
+export function ledgerChainForChainId(chainId: number | null | undefined): { chain: Chain; network: Network } | undefined {
+  return chainId == null ? undefined : LEDGER_CHAINS[chainId];
+}
+
+export function isFinalBridgeState(state: BridgeTransferState): boolean {
+  return state === 'dest_confirmed' || state === 'refunded' || state === 'failed';
+}
+
+/**
+ * Log an alert and forward it to BRIDGE_ALERT_WEBHOOK_URL (fail open)
+ */
+async function raiseBridgeAlert(transfer: BridgeTransfer, reason: string): Promise<void> {
+  const { updateBridgeTransfer } = await getLedgerDb();
+  const now = Math.floor(Date.now() / 1000);
+  updateBridgeTransfer(transfer.id, { alerted_at: now });
+
+  console.warn(`[bridgeTracker] ALERT ${transfer.id.slice(0, 8)} ${transfer.state}: ${reason}`);
+  logEvent('bridge_alert', {
+    txHash: transfer.source_tx_hash,
+    executionKind: 'bridge',
+    venue: transfer.provider,
+    notes: [`transfer: ${transfer.id}`, `state: ${transfer.state}`, `reason: ${reason}`],
+  });
+
+  if (!BRIDGE_ALERT_WEBHOOK_URL) return;
+  try {
+    await fetch(BRIDGE_ALERT_WEBHOOK_URL, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        type: 'bridge_alert',
+        text: `Bridge transfer ${transfer.id} is ${transfer.state}: ${reason}`,
+        transferId: transfer.id,
+        state: transfer.state,
+        reason,
+        provider: transfer.provider,
+        tool: transfer.tool,
+        sourceTxHash: transfer.source_tx_hash,
+        fromChain: transfer.from_chain,
+        toChain: transfer.to_chain,
+        ageSeconds: now - transfer.created_at,
+      }),
+      signal: AbortSignal.timeout(5000),
+    });
+  } catch (error: any) {
+    console.warn('[bridgeTracker] Alert webhook failed:', error.message?.slice(0, 100));
+  }
+}
+
+/**
+ * Record the destination execution and link both legs (and the intent)
+ */
+async function recordDestination(transfer: BridgeTransfer, status: LiFiStatusResult, now: number): Promise<string> {
+  const { createExecution, updateExecution, getExecution, linkExecutionToIntent } = await getLedgerDb();
+  const dest = ledgerChainForChainId(transfer.to_chain_id) ?? { chain: transfer.to_chain, network: transfer.to_network };
+  const source = transfer.source_execution_id ? getExecution(transfer.source_execution_id) : undefined;
+  const txHash = status.receiving!.txHash!;
+
+  const execution = createExecution({
+    chain: dest.chain,
+    network: dest.network,
+    kind: 'bridge',
+    venue: transfer.provider as any,
+    intent: source?.intent || `bridge ${transfer.from_token ?? ''} ${transfer.from_chain} to ${transfer.to_chain}`.trim(),
+    action: 'bridge_receive',
+    fromAddress: transfer.from_address,
+    toAddress: transfer.to_address,
+    token: status.receiving?.token?.symbol || transfer.to_token,
+    amountUnits: status.receiving?.amount,
+  });
+  updateExecution(execution.id, {
+    status: 'confirmed',
+    txHash,
+    explorerUrl: buildExplorerUrl(dest.chain, dest.network, txHash),
+    latencyMs: (now - transfer.created_at) * 1000,
+    linkedExecutionId: transfer.source_execution_id,
+  });
+  if (transfer.source_execution_id) {
+    updateExecution(transfer.source_execution_id, { linkedExecutionId: execution.id });
+  }
+  if (transfer.intent_id) {
+    linkExecutionToIntent(execution.id, transfer.intent_id);
+  }
+  return execution.id;
+}
+
+/**
+ * Settle an intent that was left executing while its transfer was in flight
+ */
+async function settleIntent(transfer: BridgeTransfer, state: BridgeTransferState, message?: string): Promise<void> {
+  if (!transfer.intent_id) return;
+  const { getIntent, updateIntentStatus } = await getLedgerDb();
+  const intent = getIntent(transfer.intent_id);
+  if (!intent || intent.status !== 'executing') return;
+
+  const now = Math.floor(Date.now() / 1000);
+  if (state === 'dest_confirmed') {
+    updateIntentStatus(transfer.intent_id, { status: 'confirmed', confirmedAt: now });
+  } else {
+    updateIntentStatus(transfer.intent_id, {
+      status: 'failed',
+      failureStage: 'confirm',
+      errorCode: state === 'refunded' ? 'LIFI_BRIDGE_REFUNDED' : 'LIFI_BRIDGE_FAILED',
+      errorMessage: message || `Bridge transfer ${state}`,
+    });
+  }
+}
+
+/**
+ * Apply a provider status to a transfer and return the updated row
+ * NOT_FOUND keeps the current state (the provider hasn't indexed the source tx yet)
+ */
+export async function applyProviderStatus(
+  transfer: BridgeTransfer,
+  status: LiFiStatusResult,
+  now: number = Math.floor(Date.now() / 1000)
+): Promise<BridgeTransfer> {
+  const { updateBridgeTransfer, getBridgeTransfer } = await getLedgerDb();
+  const polled = { last_polled_at: now, poll_count: transfer.poll_count + 1 };
+
+  if (!status.ok || !status.status) {
+    updateBridgeTransfer(transfer.id, polled);
+    return getBridgeTransfer(transfer.id)!;
+  }
+
+  const providerFields = {
+    ...polled,
+    provider_status: status.status,
+    provider_substatus: status.substatus,
+    status_message: status.substatusMessage,
+  };
+
+  let next: BridgeTransferState = transfer.state;
+  if (status.status === 'PENDING') {
+    next = 'in_flight';
+  } else if (status.status === 'DONE') {
+    // LiFi reports refunds as DONE/REFUNDED
+    next = status.substatus === 'REFUNDED' ? 'refunded' : 'dest_confirmed';
+  } else if (status.status === 'FAILED' || status.status === 'INVALID') {
+    next = status.substatus === 'REFUNDED' ? 'refunded' : 'failed';
+  }
+
+  if (next === 'dest_confirmed' && !status.receiving?.txHash) {
+    // DONE without a receiving tx yet; wait for the next poll
+    next = 'in_flight';
+  }
+
+  if (next === 'dest_confirmed') {
+    const destExecutionId = transfer.dest_execution_id || await recordDestination(transfer, status, now);
+    updateBridgeTransfer(transfer.id, {
+      ...providerFields,
+      state: next,
+      dest_tx_hash: status.receiving!.txHash,
+      dest_execution_id: destExecutionId,
+      received_units: status.receiving?.amount,
+      completed_at: now,
+    });
+  } else if (next === 'refunded' || next === 'failed') {
+    updateBridgeTransfer(transfer.id, { ...providerFields, state: next, completed_at: now });
+  } else {
+    // A stuck transfer stays stuck until it resolves
+    updateBridgeTransfer(transfer.id, { ...providerFields, state: transfer.state === 'stuck' ? 'stuck' : next });
+  }
+
+  const updated = getBridgeTransfer(transfer.id)!;
+  if (isFinalBridgeState(updated.state) && updated.state !== transfer.state) {
+    await settleIntent(updated, updated.state, status.substatusMessage);
+    if (updated.state === 'failed') {
+      await raiseBridgeAlert(updated, status.substatusMessage || `provider reports ${status.status}`);
+    }
+  }
+  return updated;
+}
+
+/**
+ * Advance a transfer one step: source receipt first, then provider status, then the stuck check
+ */
+export async function pollBridgeTransfer(
+  transfer: BridgeTransfer,
+  now: number = Math.floor(Date.now() / 1000)
+): Promise<BridgeTransfer> {
+  const { updateBridgeTransfer, getBridgeTransfer, updateExecution } = await getLedgerDb();
+  if (isFinalBridgeState(transfer.state)) return transfer;
+
+  if (transfer.state === 'awaiting_signature') return transfer;
+
+  if (transfer.state === 'source_pending') {
+    const { lookupTxReceipt } = await import('../executors/txReceipts');
+    const lookup = await lookupTxReceipt(transfer.from_chain, transfer.source_tx_hash!);
+    if (lookup.status === 'reverted') {
+      if (transfer.source_execution_id) {
+        updateExecution(transfer.source_execution_id, {
+          status: 'failed',
+          errorCode: 'TX_REVERTED',
+          errorMessage: (lookup.error || 'Bridge source transaction reverted on-chain').slice(0, 200),
+          blockNumber: lookup.blockNumber,
+        });
+      }
+      updateBridgeTransfer(transfer.id, {
+        state: 'failed',
+        status_message: lookup.error || 'Source transaction reverted',
+        completed_at: now,
+        last_polled_at: now,
+        poll_count: transfer.poll_count + 1,
+      });
+      const failed = getBridgeTransfer(transfer.id)!;
+      await settleIntent(failed, 'failed', failed.status_message);
+      return failed;
+    }
+    if (lookup.status === 'success') {
+      if (transfer.source_execution_id) {
+        updateExecution(transfer.source_execution_id, {
+          status: 'confirmed',
+          blockNumber: lookup.blockNumber,
+          gasUsed: lookup.gasUsed,
+          latencyMs: (now - transfer.created_at) * 1000,
+        });
+      }
+      updateBridgeTransfer(transfer.id, { state: 'source_confirmed', source_confirmed_at: now });
+      transfer = getBridgeTransfer(transfer.id)!;
+    }
+  }
+
+  if (transfer.state !== 'source_pending') {
+    const status = await getLiFiStatus({
+      txHash: transfer.source_tx_hash!,
+      bridge: transfer.tool ?? undefined,
+      fromChain: transfer.from_chain_id ?? undefined,
+      toChain: transfer.to_chain_id ?? undefined,
+    });
+    transfer = await applyProviderStatus(transfer, status, now);
+  } else {
+    updateBridgeTransfer(transfer.id, { last_polled_at: now, poll_count: transfer.poll_count + 1 });
+    transfer = getBridgeTransfer(transfer.id)!;
+  }
+
+  if (!isFinalBridgeState(transfer.state) && transfer.state !== 'stuck'
+    && now - transfer.created_at >= BRIDGE_STUCK_AFTER_SECONDS) {
+    updateBridgeTransfer(transfer.id, { state: 'stuck' });
+    transfer = getBridgeTransfer(transfer.id)!;
+    await raiseBridgeAlert(transfer, `not final after ${Math.round((now - transfer.created_at) / 60)} minutes`);
+    transfer = getBridgeTransfer(transfer.id)!;
+  }
+
+  return transfer;
+}
+
+/**
+ * Run a single pass over transfers that are not final
+ */
+export async function runBridgeTrackerCycle(limit: number = BRIDGE_TRACKER_BATCH_SIZE): Promise<BridgeTrackerCycleResult> {
+  const { listBridgeTransfers } = await getLedgerDb();
+  const now = Math.floor(Date.now() / 1000);
+  const transfers = listBridgeTransfers({
+    states: ACTIVE_STATES,
+    polledBefore: now - Math.floor(BRIDGE_TRACKER_INTERVAL_MS / 1000),
+    limit,
+  });
+
+  const result: BridgeTrackerCycleResult = { checked: 0, advanced: 0, completed: 0, alerts: 0, errors: 0, ranAt: now };
+  for (const transfer of transfers) {
+    result.checked++;
+    try {
+      const updated = await pollBridgeTransfer(transfer, now);
+      if (updated.state !== transfer.state) result.advanced++;
+      if (isFinalBridgeState(updated.state)) result.completed++;
+      if (updated.alerted_at && updated.alerted_at !== transfer.alerted_at) result.alerts++;
+    } catch (error: any) {
+      result.errors++;
+      console.warn(`[bridgeTracker] Poll failed for ${transfer.id.slice(0, 8)}:`, error.message?.slice(0, 100));
+    }
+  }
+
+  lastCycle = result;
+  if (result.advanced > 0 || result.alerts > 0) {
+    console.log(`[bridgeTracker] Checked ${result.checked}, advanced ${result.advanced}, alerts ${result.alerts}`);
+  }
+  return result;
+}
+
+/**
+ * Transfer with progress and both legs, for the API
+ */
+export async function getBridgeTransferView(id: string): Promise<BridgeTransferView | null> {
+  const { getBridgeTransfer, getBridgeTransferBySourceTx, getExecution } = await getLedgerDb();
+  const transfer = getBridgeTransfer(id) ?? getBridgeTransferBySourceTx(id);
+  if (!transfer) return null;
+
+  const final = isFinalBridgeState(transfer.state);
+  const reached = transfer.state === 'stuck'
+    ? (transfer.provider_status === 'PENDING' ? 2 : transfer.source_confirmed_at ? 1 : 0)
+    : PROGRESS_STATES.indexOf(transfer.state);
+  const destChain = ledgerChainForChainId(transfer.to_chain_id) ?? { chain: transfer.to_chain, network: transfer.to_network };
+
+  return {
+    transfer,
+    final,
+    progress: {
+      step: reached === -1 ? (transfer.source_confirmed_at ? 1 : 0) : reached,
+      steps: PROGRESS_STATES,
+      elapsedSeconds: (transfer.completed_at ?? Math.floor(Date.now() / 1000)) - transfer.created_at,
+    },
+    source: {
+      txHash: transfer.source_tx_hash,
+      explorerUrl: transfer.source_tx_hash
+        ? buildExplorerUrl(transfer.from_chain, transfer.from_network, transfer.source_tx_hash)
+        : undefined,
+      execution: transfer.source_execution_id ? getExecution(transfer.source_execution_id) : undefined,
+    },
+    destination: {
+      txHash: transfer.dest_tx_hash,
+      explorerUrl: transfer.dest_tx_hash ? buildExplorerUrl(destChain.chain, destChain.network, transfer.dest_tx_hash) : undefined,
+      execution: transfer.dest_execution_id ? getExecution(transfer.dest_execution_id) : undefined,
+    },
+  };
+}
+
+/**
+ * Start the tracker loop
+ */
+export function startBridgeTracker(intervalMs: number = BRIDGE_TRACKER_INTERVAL_MS): void {
+  if (isRunning) {
+    console.log('[bridgeTracker] Already running');
+    return;
+  }
+
+  console.log(`[bridgeTracker] Starting bridge transfer tracker (every ${intervalMs}ms)`);
+  isRunning = true;
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runBridgeTrackerCycle();
+    } catch (error: any) {
+      console.error('[bridgeTracker] Cycle error:', error.message?.slice(0, 100));
+    }
+
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  pollTimeout = setTimeout(poll, intervalMs);
+}
+
+/**
+ * Stop the tracker loop
+ */
+export function stopBridgeTracker(): void {
+  console.log('[bridgeTracker] Stopping bridge transfer tracker');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+export function isBridgeTrackerRunning(): boolean {
+  return isRunning;
+}
+
+/**
+ * Result of the most recent pass (null before the first one)
+ */
+export function getLastBridgeTrackerCycle(): BridgeTrackerCycleResult | null {
+  return lastCycle;
+}
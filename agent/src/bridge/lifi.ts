@@ -1,16 +1,22 @@
 /**
  * LiFi Bridge Quote Integration
  *
- * Minimal integration to attempt LiFi quotes for cross-chain bridging.
- * This is a "quote + failure" first approach - we attempt quotes but
- * may not execute if bridging is not fully implemented.
+ * Quotes (with the ready-to-send source transaction) and transfer status for
+ * cross-chain bridging. Execution lives in lifiExecutor.ts.
  *
- * Uses LiFi public API (no API key required for quotes)
+ * Uses LiFi public API (no API key required; LIFI_API_KEY raises rate limits)
  * https://docs.li.fi/li.fi-api/li.fi-api
  */
 
-// LiFi API base URL
-const LIFI_API_BASE = 'https://li.quest/v1';
+import { LIFI_API_BASE_URL, LIFI_API_KEY } from '../config';
+
+function lifiHeaders(): Record<string, string> {
+  const headers: Record<string, string> = { 'Accept': 'application/json' };
+  if (LIFI_API_KEY) {
+    headers['x-lifi-api-key'] = LIFI_API_KEY;
+  }
+  return headers;
+}
 
 // Supported chains for quoting
 const CHAIN_IDS = {
@@ -40,10 +46,21 @@ export interface LiFiQuoteParams {
   fromToken: string;      // Token symbol or address
   toToken: string;
   fromAmount: string;     // Amount in base units (wei, lamports)
-  fromAddress?: string;   // User address (optional for quote)
+  fromAddress?: string;   // User address (optional for quote; required for a sendable tx)
+  toAddress?: string;     // Receiver on the destination chain (defaults to fromAddress)
   slippage?: number;      // Default 0.5%
 }
 
+export interface LiFiTransactionRequest {
+  to: string;
+  data: string;
+  value?: string;         // Hex or decimal wei
+  gasLimit?: string;
+  gasPrice?: string;
+  chainId?: number;
+  from?: string;
+}
+
 export interface LiFiQuoteResult {
   ok: boolean;
   quote?: {
@@ -79,6 +96,8 @@ export interface LiFiQuoteResult {
       amount: string;
       amountUSD: string;
     }>;
+    approvalAddress?: string;                     // Spender to approve for ERC20 sources
+    transactionRequest?: LiFiTransactionRequest;  // Present when fromAddress was given
   };
   error?: {
     code: string;
@@ -94,12 +113,13 @@ export const LiFiErrorCodes = {
   LIFI_INVALID_PARAMS: 'LIFI_INVALID_PARAMS',
   LIFI_RATE_LIMITED: 'LIFI_RATE_LIMITED',
   LIFI_UNSUPPORTED_CHAIN: 'LIFI_UNSUPPORTED_CHAIN',
+  LIFI_STATUS_FAILED: 'LIFI_STATUS_FAILED',
 } as const;
 
 /**
  * Resolve chain name to LiFi chain ID
  */
-function resolveChainId(chain: string): number | null {
+export function resolveChainId(chain: string): number | null {
   const normalized = chain.toLowerCase();
   return (CHAIN_IDS as Record<string, number>)[normalized] ?? null;
 }
@@ -108,7 +128,7 @@ function resolveChainId(chain: string): number | null {
  * Resolve token to address for a given chain
  * Returns the native token placeholder (0x0...0) for native tokens
  */
-function resolveTokenAddress(token: string, chain: string): string {
+export function resolveTokenAddress(token: string, chain: string): string {
   // Native token
   if (['ETH', 'SOL'].includes(token.toUpperCase())) {
     return '0x0000000000000000000000000000000000000000';
@@ -179,17 +199,18 @@ export async function getLiFiQuote(params: LiFiQuoteParams): Promise<LiFiQuoteRe
     if (params.fromAddress) {
       queryParams.set('fromAddress', params.fromAddress);
     }
+    if (params.toAddress) {
+      queryParams.set('toAddress', params.toAddress);
+    }
 
     // Make request with timeout
     const controller = new AbortController();
     const timeout = setTimeout(() => controller.abort(), 10000); // 10s timeout
 
     try {
-      const response = await fetch(`${LIFI_API_BASE}/quote?${queryParams}`, {
+      const response = await fetch(`${LIFI_API_BASE_URL}/quote?${queryParams}`, {
         method: 'GET',
-        headers: {
-          'Accept': 'application/json',
-        },
+        headers: lifiHeaders(),
         signal: controller.signal,
       });
 
@@ -238,8 +259,9 @@ export async function getLiFiQuote(params: LiFiQuoteParams): Promise<LiFiQuoteRe
           type: quote.type || 'BRIDGE',
           tool: quote.tool || quote.toolDetails?.name || 'unknown',
           toolDetails: quote.toolDetails || { name: 'unknown', logoURI: '' },
-          fromChain: fromChainId,
-          toChain: toChainId,
+          // Chains LiFi actually quoted (callers check these against the wallet they send from)
+          fromChain: quote.action?.fromChainId ?? fromChainId,
+          toChain: quote.action?.toChainId ?? toChainId,
           fromToken: {
             address: quote.action?.fromToken?.address || fromTokenAddress,
             symbol: quote.action?.fromToken?.symbol || params.fromToken,
@@ -256,6 +278,8 @@ export async function getLiFiQuote(params: LiFiQuoteParams): Promise<LiFiQuoteRe
           estimatedDuration: quote.estimate?.executionDuration || 300,
           feeCosts: quote.estimate?.feeCosts || [],
           gasCosts: quote.estimate?.gasCosts || [],
+          approvalAddress: quote.estimate?.approvalAddress,
+          transactionRequest: quote.transactionRequest,
         },
       };
     } catch (fetchError: any) {
@@ -284,6 +308,91 @@ export async function getLiFiQuote(params: LiFiQuoteParams): Promise<LiFiQuoteRe
   }
 }
 
+export type LiFiTransferStatus = 'NOT_FOUND' | 'INVALID' | 'PENDING' | 'DONE' | 'FAILED';
+
+export interface LiFiStatusResult {
+  ok: boolean;
+  status?: LiFiTransferStatus;
+  substatus?: string;            // e.g. COMPLETED, PARTIAL, REFUNDED, WAIT_DESTINATION_TRANSACTION
+  substatusMessage?: string;
+  sending?: { txHash: string; chainId: number; amount?: string };
+  receiving?: { txHash?: string; chainId: number; amount?: string; token?: { address: string; symbol: string } };
+  explorerLink?: string;
+  error?: {
+    code: string;
+    message: string;
+  };
+}
+
+/**
+ * Status of a cross-chain transfer by its source transaction hash
+ * NOT_FOUND is normal right after submission (LiFi hasn't indexed the tx yet)
+ */
+export async function getLiFiStatus(params: {
+  txHash: string;
+  bridge?: string;
+  fromChain?: string | number;
+  toChain?: string | number;
+}): Promise<LiFiStatusResult> {
+  const queryParams = new URLSearchParams({ txHash: params.txHash });
+  if (params.bridge) queryParams.set('bridge', params.bridge);
+  for (const [key, chain] of [['fromChain', params.fromChain], ['toChain', params.toChain]] as const) {
+    if (chain === undefined) continue;
+    const chainId = typeof chain === 'number' ? chain : resolveChainId(chain);
+    if (chainId) queryParams.set(key, chainId.toString());
+  }
+
+  const controller = new AbortController();
+  const timeout = setTimeout(() => controller.abort(), 10000);
+
+  try {
+    const response = await fetch(`${LIFI_API_BASE_URL}/status?${queryParams}`, {
+      method: 'GET',
+      headers: lifiHeaders(),
+      signal: controller.signal,
+    });
+
+    if (response.status === 429) {
+      return { ok: false, error: { code: LiFiErrorCodes.LIFI_RATE_LIMITED, message: 'LiFi API rate limit exceeded' } };
+    }
+
+    const data = await response.json();
+
+    // LiFi answers 404 with status NOT_FOUND until it has seen the tx
+    if (!response.ok && data?.status !== 'NOT_FOUND') {
+      return {
+        ok: false,
+        error: {
+          code: LiFiErrorCodes.LIFI_STATUS_FAILED,
+          message: String(data?.message || `Status request failed (${response.status})`).slice(0, 200),
+        },
+      };
+    }
+
+    return {
+      ok: true,
+      status: data.status || 'NOT_FOUND',
+      substatus: data.substatus,
+      substatusMessage: data.substatusMessage,
+      sending: data.sending,
+      receiving: data.receiving,
+      explorerLink: data.lifiExplorerLink,
+    };
+  } catch (error: any) {
+    return {
+      ok: false,
+      error: {
+        code: LiFiErrorCodes.LIFI_UNREACHABLE,
+        message: error.name === 'AbortError'
+          ? 'LiFi status request timed out'
+          : `LiFi API error: ${error.message?.slice(0, 150) || 'Unknown error'}`,
+      },
+    };
+  } finally {
+    clearTimeout(timeout);
+  }
+}
+
 /**
  * Check if LiFi API is reachable
  */
@@ -292,7 +401,7 @@ export async function checkLiFiHealth(): Promise<boolean> {
     const controller = new AbortController();
     const timeout = setTimeout(() => controller.abort(), 5000);
 
-    const response = await fetch(`${LIFI_API_BASE}/chains`, {
+    const response = await fetch(`${LIFI_API_BASE_URL}/chains`, {
       method: 'GET',
       signal: controller.signal,
     });
@@ -309,7 +418,7 @@ export async function checkLiFiHealth(): Promise<boolean> {
  */
 export async function getLiFiChains(): Promise<{ id: number; name: string; key: string }[]> {
   try {
-    const response = await fetch(`${LIFI_API_BASE}/chains`, {
+    const response = await fetch(`${LIFI_API_BASE_URL}/chains`, {
       method: 'GET',
       headers: { 'Accept': 'application/json' },
     });
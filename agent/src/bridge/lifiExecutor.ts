@@ -0,0 +1,249 @@
+/**
+ * LiFi Bridge Execution
+ *
+ * Bridges are signed by the user, never by the relayer:
+ *   1. prepareLiFiBridge quotes with the user's address so LiFi returns a
+ *      ready-to-sign transactionRequest, checks the quote is for the chain the
+ *      user will sign on, and lists the ERC20 approval the quote needs (skipped
+ *      when the allowance already suffices)
+ *   2. The user sends the approval and the source transaction from their wallet
+ *   3. submitLiFiBridge records the source tx hash; the background bridge
+ *      tracker follows the transfer (bridge_transfers) to the destination leg
+ *
+ * Both legs are recorded in the ledger as bridge executions linked to each
+ * other (linked_execution_id) and to the intent. Until the user submits, the
+ * transfer waits in awaiting_signature and is not polled.
+ */
+
+import { erc20Abi } from 'viem';
+import { buildExplorerUrl } from '../ledger/ledger';
+import { getLiFiQuote, type LiFiQuoteParams, type LiFiQuoteResult } from './lifi';
+import { ledgerChainForChainId } from './bridgeTracker';
+import type { BridgeTransfer } from '../../execution-ledger/db';
+
+const NATIVE_TOKEN = '0x0000000000000000000000000000000000000000';
+
+export interface LiFiBridgeRequest extends LiFiQuoteParams {
+  fromAddress: string;   // The user's wallet; it signs the source transaction
+  intentId?: string;
+  intentText: string;
+  amountDisplay?: string;
+  usdEstimate?: number;
+}
+
+export interface LiFiPrepareOptions {
+  // Chain the user's wallet signs on; quotes for any other chain are rejected
+  chainId: number;
+  // Used to skip the approval when the allowance already covers the amount
+  publicClient?: { readContract(args: any): Promise<any> };
+}
+
+// Same shape as /api/execute/prepare requirements.approvals
+export interface BridgeApprovalRequirement {
+  token: string;
+  spender: string;
+  amount: string;
+}
+
+export interface LiFiPrepareResult {
+  ok: boolean;
+  status: 'awaiting_signature' | 'failed';
+  quote?: LiFiQuoteResult['quote'];
+  bridgeTransferId?: string;
+  sourceExecutionId?: string;
+  transaction?: {
+    chainId: number;
+    to: string;
+    data: string;
+    value: string;
+    gasLimit?: string;
+  };
+  approvals?: BridgeApprovalRequirement[];
+  error?: {
+    stage: 'quote' | 'execute';
+    code: string;
+    message: string;
+  };
+}
+
+export interface LiFiSubmitResult {
+  ok: boolean;
+  transfer?: BridgeTransfer;
+  explorerUrl?: string;
+  error?: { code: string; message: string };
+}
+
+/**
+ * Quote a LiFi transfer for the user to sign and record it as awaiting_signature
+ */
+export async function prepareLiFiBridge(
+  request: LiFiBridgeRequest,
+  options: LiFiPrepareOptions
+): Promise<LiFiPrepareResult> {
+  const { createExecution, linkExecutionToIntent, createBridgeTransfer } = await import('../../execution-ledger/db');
+
+  // 1. Quote with the user's address so LiFi builds their source transaction
+  const quoteResult = await getLiFiQuote({
+    fromChain: request.fromChain,
+    toChain: request.toChain,
+    fromToken: request.fromToken,
+    toToken: request.toToken,
+    fromAmount: request.fromAmount,
+    fromAddress: request.fromAddress,
+    toAddress: request.toAddress,
+    slippage: request.slippage,
+  });
+
+  if (!quoteResult.ok || !quoteResult.quote) {
+    return { ok: false, status: 'failed', error: { stage: 'quote', ...quoteResult.error! } };
+  }
+  const quote = quoteResult.quote;
+  const quoteError = (code: string, message: string): LiFiPrepareResult => ({
+    ok: false,
+    status: 'failed',
+    quote,
+    error: { stage: 'quote', code, message },
+  });
+
+  const txRequest = quote.transactionRequest;
+  if (!txRequest?.to || !txRequest.data) {
+    return quoteError('LIFI_NO_TRANSACTION', 'LiFi quote did not include a transaction request');
+  }
+
+  // Never hand out a quote built for another chain than the one the user signs on
+  if (quote.fromChain !== options.chainId) {
+    return quoteError('LIFI_CHAIN_MISMATCH', `Quote is for chain ${quote.fromChain} but the wallet is on chain ${options.chainId}`);
+  }
+
+  // Both legs are recorded on the chains the quote actually uses
+  const sourceLedger = ledgerChainForChainId(quote.fromChain);
+  const destLedger = ledgerChainForChainId(quote.toChain);
+  if (!sourceLedger || !destLedger) {
+    return quoteError('LIFI_UNSUPPORTED_CHAIN', `No ledger chain for LiFi chain ${sourceLedger ? quote.toChain : quote.fromChain}`);
+  }
+
+  // 2. Approval for ERC20 sources
+  const approvals: BridgeApprovalRequirement[] = [];
+  const fromToken = quote.fromToken.address;
+  const fromAmount = BigInt(quote.fromAmount);
+  if (fromToken.toLowerCase() !== NATIVE_TOKEN && quote.approvalAddress) {
+    let allowance = 0n;
+    if (options.publicClient) {
+      try {
+        allowance = await options.publicClient.readContract({
+          address: fromToken as `0x${string}`,
+          abi: erc20Abi,
+          functionName: 'allowance',
+          args: [request.fromAddress as `0x${string}`, quote.approvalAddress as `0x${string}`],
+        }) as bigint;
+      } catch {
+        // Unknown allowance: ask for the approval
+      }
+    }
+    if (allowance < fromAmount) {
+      approvals.push({ token: fromToken, spender: quote.approvalAddress, amount: fromAmount.toString() });
+    }
+  }
+
+  // 3. Record the source leg and the transfer; the tx hash arrives with submitLiFiBridge
+  const source = createExecution({
+    chain: sourceLedger.chain,
+    network: sourceLedger.network,
+    kind: 'bridge',
+    venue: 'lifi',
+    intent: request.intentText,
+    action: 'bridge',
+    fromAddress: request.fromAddress,
+    toAddress: request.toAddress,
+    token: quote.fromToken.symbol,
+    amountUnits: quote.fromAmount,
+    amountDisplay: request.amountDisplay,
+    usdEstimate: request.usdEstimate,
+    usdEstimateIsEstimate: true,
+  });
+  if (request.intentId) {
+    linkExecutionToIntent(source.id, request.intentId);
+  }
+
+  const transfer = createBridgeTransfer({
+    intentId: request.intentId,
+    provider: 'lifi',
+    tool: quote.tool,
+    state: 'awaiting_signature',
+    fromChain: sourceLedger.chain,
+    fromNetwork: sourceLedger.network,
+    toChain: destLedger.chain,
+    toNetwork: destLedger.network,
+    fromChainId: quote.fromChain,
+    toChainId: quote.toChain,
+    fromAddress: request.fromAddress,
+    toAddress: request.toAddress,
+    fromToken: quote.fromToken.symbol,
+    toToken: quote.toToken.symbol,
+    amountUnits: quote.fromAmount,
+    sourceExecutionId: source.id,
+  });
+
+  return {
+    ok: true,
+    status: 'awaiting_signature',
+    quote,
+    bridgeTransferId: transfer.id,
+    sourceExecutionId: source.id,
+    transaction: {
+      chainId: quote.fromChain,
+      to: txRequest.to,
+      data: txRequest.data,
+      value: txRequest.value ? BigInt(txRequest.value).toString() : '0',
+      gasLimit: txRequest.gasLimit ? BigInt(txRequest.gasLimit).toString() : undefined,
+    },
+    approvals,
+  };
+}
+
+/**
+ * Record the source tx the user sent for a prepared transfer
+ * The bridge tracker takes it from source_pending; the intent stays executing until it settles
+ */
+export async function submitLiFiBridge(params: {
+  transferId: string;
+  txHash: string;
+  fromAddress: string;
+}): Promise<LiFiSubmitResult> {
+  const {
+    getBridgeTransfer,
+    getBridgeTransferBySourceTx,
+    updateBridgeTransfer,
+    updateExecution,
+    updateIntentStatus,
+  } = await import('../../execution-ledger/db');
+
+  const transfer = getBridgeTransfer(params.transferId);
+  if (!transfer) {
+    return { ok: false, error: { code: 'TRANSFER_NOT_FOUND', message: `Bridge transfer ${params.transferId} not found` } };
+  }
+  if (transfer.state !== 'awaiting_signature') {
+    return { ok: false, error: { code: 'ALREADY_SUBMITTED', message: `Bridge transfer is ${transfer.state}` } };
+  }
+  if (transfer.from_address.toLowerCase() !== params.fromAddress.toLowerCase()) {
+    return { ok: false, error: { code: 'USER_MISMATCH', message: 'Transfer was prepared for a different wallet' } };
+  }
+  if (!/^0x[0-9a-fA-F]{64}$/.test(params.txHash)) {
+    return { ok: false, error: { code: 'INVALID_TX_HASH', message: 'txHash must be a 32-byte hex hash' } };
+  }
+  if (getBridgeTransferBySourceTx(params.txHash)) {
+    return { ok: false, error: { code: 'DUPLICATE_TX', message: 'Transaction already recorded for another transfer' } };
+  }
+
+  const now = Math.floor(Date.now() / 1000);
+  const explorerUrl = buildExplorerUrl(transfer.from_chain, transfer.from_network, params.txHash);
+  updateBridgeTransfer(transfer.id, { state: 'source_pending', source_tx_hash: params.txHash });
+  if (transfer.source_execution_id) {
+    updateExecution(transfer.source_execution_id, { status: 'submitted', txHash: params.txHash, explorerUrl });
+  }
+  if (transfer.intent_id) {
+    updateIntentStatus(transfer.intent_id, { status: 'executing', executedAt: now });
+  }
+
+  return { ok: true, transfer: getBridgeTransfer(transfer.id)!, explorerUrl };
+}
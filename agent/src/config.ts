@@ -75,6 +75,9 @@ export const ETH_TESTNET_RPC_URL = process.env.ETH_TESTNET_RPC_URL;
 // Primary: ETH_TESTNET_RPC_URL (recommend Alchemy for reliability)
 // Fallbacks: ETH_RPC_FALLBACK_URLS (comma-separated) OR individual vars
 // Order: Primary -> ETH_RPC_FALLBACK_URLS -> ALCHEMY_RPC_URL -> INFURA_RPC_URL -> public RPC
+// ETH_RPC_PUBLIC_FALLBACKS=false keeps a local chain (e.g. Anvil) from failing over to public Sepolia
+export const ETH_RPC_PUBLIC_FALLBACKS = process.env.ETH_RPC_PUBLIC_FALLBACKS !== 'false';
+
 const collectFallbackUrls = (): string[] => {
   const urls: string[] = [];
 
@@ -95,11 +98,11 @@ const collectFallbackUrls = (): string[] => {
   }
 
   // 3. Public Sepolia RPCs as last resort (no API key required, multiple for redundancy)
-  const publicRpcs = [
+  const publicRpcs = ETH_RPC_PUBLIC_FALLBACKS ? [
     'https://ethereum-sepolia-rpc.publicnode.com',
     'https://1rpc.io/sepolia',
     'https://rpc.sepolia.org',
-  ];
+  ] : [];
 
   for (const rpc of publicRpcs) {
     try {
@@ -196,6 +199,10 @@ export const UNISWAP_V3_ROUTER_ADDRESS = process.env.UNISWAP_V3_ROUTER_ADDRESS |
 // If true, fail when live quote fails. If false, gracefully fall back to deterministic quote.
 export const ROUTING_REQUIRE_LIVE_QUOTE = process.env.ROUTING_REQUIRE_LIVE_QUOTE === 'true';
 
+// Multi-venue route selection: per-venue quote timeout and gas price used to cost quotes
+export const ROUTING_QUOTE_TIMEOUT_MS = parseInt(process.env.ROUTING_QUOTE_TIMEOUT_MS || '4000', 10);
+export const ROUTING_GAS_PRICE_GWEI = parseFloat(process.env.ROUTING_GAS_PRICE_GWEI || '20');
+
 // V1: Default to session mode for eth_testnet (one-click execution)
 // Can be overridden with EXECUTION_AUTH_MODE=direct for testing
 export const EXECUTION_AUTH_MODE = process.env.EXECUTION_AUTH_MODE || 
@@ -214,6 +221,104 @@ export const EXECUTION_DISABLED = process.env.EXECUTION_DISABLED === 'true';
 const rawFeeBps = parseInt(process.env.BLOSSOM_FEE_BPS || '25', 10);
 export const BLOSSOM_FEE_BPS = Math.min(50, Math.max(10, isNaN(rawFeeBps) ? 25 : rawFeeBps));
 
+// Perps sim mark-to-market engine
+// Interval between revaluation passes and maintenance margin (bps of notional) for liquidation
+export const PERPS_MARK_INTERVAL_MS = parseInt(process.env.PERPS_MARK_INTERVAL_MS || '15000', 10);
+export const PERPS_MAINTENANCE_MARGIN_BPS = parseInt(process.env.PERPS_MAINTENANCE_MARGIN_BPS || '500', 10); // 5% default
+
+// Perp funding model (sim perps + DemoPerpEngine ledger positions)
+// 'fixed': constant hourly rate; 'premium': derived from a premium index ('mock' or 'hyperliquid'); 'off': disabled
+export const PERPS_FUNDING_MODE: 'fixed' | 'premium' | 'off' =
+  (process.env.PERPS_FUNDING_MODE as 'fixed' | 'premium' | 'off') || 'fixed';
+export const PERPS_FUNDING_RATE_BPS_PER_HOUR = parseFloat(process.env.PERPS_FUNDING_RATE_BPS_PER_HOUR || '0.125'); // 0.01% per 8h
+export const PERPS_FUNDING_PREMIUM_SOURCE: 'mock' | 'hyperliquid' =
+  (process.env.PERPS_FUNDING_PREMIUM_SOURCE as 'mock' | 'hyperliquid') || 'mock';
+export const PERPS_FUNDING_INTERVAL_MS = parseInt(process.env.PERPS_FUNDING_INTERVAL_MS || '3600000', 10); // hourly
+
+// Durable intent job queue (leases, retries with exponential backoff, dead-letter)
+export const INTENT_QUEUE_POLL_MS = parseInt(process.env.INTENT_QUEUE_POLL_MS || '1000', 10);
+export const INTENT_QUEUE_LEASE_SECONDS = parseInt(process.env.INTENT_QUEUE_LEASE_SECONDS || '120', 10);
+export const INTENT_QUEUE_CONCURRENCY = parseInt(process.env.INTENT_QUEUE_CONCURRENCY || '2', 10);
+export const INTENT_QUEUE_MAX_ATTEMPTS = parseInt(process.env.INTENT_QUEUE_MAX_ATTEMPTS || '5', 10);
+export const INTENT_QUEUE_BACKOFF_BASE_MS = parseInt(process.env.INTENT_QUEUE_BACKOFF_BASE_MS || '2000', 10);
+export const INTENT_QUEUE_BACKOFF_MAX_MS = parseInt(process.env.INTENT_QUEUE_BACKOFF_MAX_MS || '300000', 10);
+
+// Execution ledger reconciler (re-checks receipts of unresolved and recently confirmed txs)
+export const RECONCILER_INTERVAL_MS = parseInt(process.env.RECONCILER_INTERVAL_MS || '60000', 10);
+export const RECONCILER_BATCH_SIZE = parseInt(process.env.RECONCILER_BATCH_SIZE || '25', 10);
+export const RECONCILER_MIN_AGE_SECONDS = parseInt(process.env.RECONCILER_MIN_AGE_SECONDS || '120', 10); // let executors finish their own wait
+export const RECONCILER_DROP_AFTER_SECONDS = parseInt(process.env.RECONCILER_DROP_AFTER_SECONDS || '1800', 10);
+export const RECONCILER_REORG_WINDOW_SECONDS = parseInt(process.env.RECONCILER_REORG_WINDOW_SECONDS || '3600', 10);
+export const RECONCILER_EVM_FINALITY_BLOCKS = parseInt(process.env.RECONCILER_EVM_FINALITY_BLOCKS || '64', 10); // ~2 epochs
+
+// Contract event indexer (router, sessions, perp engine, adapters, vaults)
+export const INDEXER_NETWORK = (process.env.INDEXER_NETWORK || 'sepolia') as 'sepolia' | 'mainnet';
+export const INDEXER_POLL_INTERVAL_MS = parseInt(process.env.INDEXER_POLL_INTERVAL_MS || '15000', 10);
+export const INDEXER_MAX_BLOCKS_PER_POLL = parseInt(process.env.INDEXER_MAX_BLOCKS_PER_POLL || '1000', 10);
+export const INDEXER_START_BLOCK = parseInt(process.env.INDEXER_START_BLOCK || '10100000', 10); // first block for contracts without a cursor
+export const INDEXER_CONFIRMATION_DEPTH = parseInt(process.env.INDEXER_CONFIRMATION_DEPTH || '12', 10); // blocks deeper than this are treated as final
+
+// LI.FI bridge execution
+// Without LIFI_EXECUTION_ENABLED=true bridge intents only quote and record proof txs
+export const LIFI_API_BASE_URL = process.env.LIFI_API_BASE_URL || 'https://li.quest/v1';
+export const LIFI_API_KEY = process.env.LIFI_API_KEY;
+export const LIFI_EXECUTION_ENABLED = process.env.LIFI_EXECUTION_ENABLED === 'true';
+
+// Bridge transfer tracker (background polling of in-flight transfers)
+export const BRIDGE_TRACKER_INTERVAL_MS = parseInt(process.env.BRIDGE_TRACKER_INTERVAL_MS || '30000', 10);
+export const BRIDGE_TRACKER_BATCH_SIZE = parseInt(process.env.BRIDGE_TRACKER_BATCH_SIZE || '25', 10);
+export const BRIDGE_STUCK_AFTER_SECONDS = parseInt(process.env.BRIDGE_STUCK_AFTER_SECONDS || '3600', 10);
+export const BRIDGE_ALERT_WEBHOOK_URL = process.env.BRIDGE_ALERT_WEBHOOK_URL; // optional, receives stuck/failed alerts
+
+// Solana devnet token registry (portfolio valuation)
+// SOLANA_EXTRA_TOKENS: comma-separated MINT:SYMBOL:DECIMALS entries
+export const SOLANA_USDC_MINT = process.env.SOLANA_USDC_MINT || '4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU'; // Circle devnet USDC
+export const SOLANA_EXTRA_TOKENS = process.env.SOLANA_EXTRA_TOKENS || '';
+
+// Price oracle (services/prices.ts): sources are queried in parallel and aggregated by median
+// PRICE_ORACLE_SOURCES: comma-separated, any of coingecko,chainlink,uniswap_twap,dflow (unconfigured ones are skipped)
+export const PRICE_ORACLE_SOURCES = process.env.PRICE_ORACLE_SOURCES || 'coingecko,chainlink,uniswap_twap,dflow';
+export const PRICE_CACHE_TTL_MS = parseInt(process.env.PRICE_CACHE_TTL_MS || '12000', 10);
+export const PRICE_SOURCE_TIMEOUT_MS = parseInt(process.env.PRICE_SOURCE_TIMEOUT_MS || '3000', 10);
+export const PRICE_MAX_AGE_SECONDS = parseInt(process.env.PRICE_MAX_AGE_SECONDS || '600', 10); // Older observations are stale
+// Per-source overrides, comma-separated SOURCE:SECONDS (e.g. coingecko:300); Chainlink feeds use their own heartbeat
+export const PRICE_SOURCE_MAX_AGE_SECONDS = process.env.PRICE_SOURCE_MAX_AGE_SECONDS || '';
+export const PRICE_MAX_DEVIATION_BPS = parseInt(process.env.PRICE_MAX_DEVIATION_BPS || '200', 10); // Allowed spread around the median
+// Lowest confidence session policy accepts when pricing spend: low (accepts static/stale prices), medium, high
+const requestedPolicyConfidence = (process.env.PRICE_POLICY_MIN_CONFIDENCE || 'low').trim().toLowerCase();
+const policyConfidenceValid = ['low', 'medium', 'high'].includes(requestedPolicyConfidence);
+if (!policyConfidenceValid) {
+  console.warn(`[config] Invalid PRICE_POLICY_MIN_CONFIDENCE "${process.env.PRICE_POLICY_MIN_CONFIDENCE}" (expected low, medium or high); using low`);
+}
+export const PRICE_POLICY_MIN_CONFIDENCE = (policyConfidenceValid ? requestedPolicyConfidence : 'low') as 'low' | 'medium' | 'high';
+// Per-source symbol maps, comma-separated SYMBOL:VALUE entries
+export const PRICE_COINGECKO_IDS = process.env.PRICE_COINGECKO_IDS || ''; // e.g. ARB:arbitrum,OP:optimism
+export const CHAINLINK_RPC_URL = process.env.CHAINLINK_RPC_URL || ETH_TESTNET_RPC_URL;
+export const CHAINLINK_FEEDS = process.env.CHAINLINK_FEEDS || ''; // SYMBOL:AGGREGATOR[:HEARTBEAT_SECONDS] USD feeds (Sepolia ETH/BTC/LINK/USDC built in)
+export const UNISWAP_TWAP_POOLS = process.env.UNISWAP_TWAP_POOLS || ''; // SYMBOL:POOL, paired with a USD stablecoin
+export const UNISWAP_TWAP_WINDOW_SECONDS = parseInt(process.env.UNISWAP_TWAP_WINDOW_SECONDS || '1800', 10);
+export const PRICE_DFLOW_TOKENS = process.env.PRICE_DFLOW_TOKENS || ''; // SYMBOL:MINT:DECIMALS mainnet mints (SOL built in)
+export const PRICE_DFLOW_USDC_MINT = process.env.PRICE_DFLOW_USDC_MINT || 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v';
+
+// Price history (services/priceHistory.ts): oracle ticks folded into 1m/1h/1d OHLC candles in the execution ledger
+// The recorder samples PRICE_HISTORY_SYMBOLS every PRICE_HISTORY_SAMPLE_MS; any other getPrice lookup is recorded too
+export const PRICE_HISTORY_SYMBOLS = process.env.PRICE_HISTORY_SYMBOLS || 'ETH,BTC,SOL';
+export const PRICE_HISTORY_SAMPLE_MS = parseInt(process.env.PRICE_HISTORY_SAMPLE_MS || '15000', 10);
+export const PRICE_HISTORY_TICK_RETENTION_HOURS = parseInt(process.env.PRICE_HISTORY_TICK_RETENTION_HOURS || '48', 10);
+export const PRICE_HISTORY_1M_RETENTION_DAYS = parseInt(process.env.PRICE_HISTORY_1M_RETENTION_DAYS || '7', 10);
+export const PRICE_HISTORY_1H_RETENTION_DAYS = parseInt(process.env.PRICE_HISTORY_1H_RETENTION_DAYS || '180', 10); // 1d candles are kept
+
+// Chat conversation memory (per-user threads in the execution ledger)
+// Recent messages go to the model verbatim; older ones are folded into a rolling summary
+export const CHAT_HISTORY_MESSAGES = parseInt(process.env.CHAT_HISTORY_MESSAGES || '8', 10);
+export const CHAT_SUMMARIZE_AFTER_MESSAGES = parseInt(process.env.CHAT_SUMMARIZE_AFTER_MESSAGES || '16', 10);
+export const CHAT_THREAD_IDLE_SECONDS = parseInt(process.env.CHAT_THREAD_IDLE_SECONDS || '1800', 10); // Requests without threadId continue a thread this fresh
+
+// Wallet sign-in (utils/walletAuth.ts): EIP-191 challenge → signed bearer token
+// Without WALLET_AUTH_SECRET a random secret is used, so tokens do not survive a restart
+export const WALLET_AUTH_SECRET = process.env.WALLET_AUTH_SECRET;
+export const WALLET_AUTH_TTL_SECONDS = parseInt(process.env.WALLET_AUTH_TTL_SECONDS || '86400', 10);
+
 // Aave V3 Pool on Sepolia (single config constant, validated at startup)
 export const AAVE_POOL_ADDRESS_SEPOLIA = process.env.AAVE_POOL_ADDRESS_SEPOLIA || 
   '0x6Ae43d3271ff6888e7Fc43Fd7321a503ff738951'; // Official Aave V3 Pool on Sepolia
@@ -0,0 +1,148 @@
+/**
+ * LLM Eval Harness Tests
+ * Prompts and provider calls are mocked; recordings go to a temp directory.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import { join } from 'path';
+
+const mocks = vi.hoisted(() => ({
+  buildBlossomPrompts: vi.fn(),
+  callLlm: vi.fn(),
+}));
+
+vi.mock('../../utils/actionParser', async (importOriginal) => ({
+  ...(await importOriginal<typeof import('../../utils/actionParser')>()),
+  buildBlossomPrompts: mocks.buildBlossomPrompts,
+}));
+vi.mock('../../services/llmClient', () => ({ callLlm: mocks.callLlm }));
+
+import { runEval, extractPrediction, formatEvalReport } from '../runner';
+import { scoreResponse, computeStats, valuesMatch } from '../scoring';
+import type { EvalCase } from '../goldenDataset';
+
+const PERP_CASE: EvalCase = {
+  id: 'perp-long-eth-leverage',
+  prompt: 'Long ETH with 5x leverage using 3% risk',
+  expected: {
+    actions: [{ type: 'perp', action: 'open', market: 'ETH-PERP', side: 'long', riskPct: 3 }],
+    executionRequest: { kind: 'perp', market: 'ETH-PERP', side: 'long', leverage: 5 },
+  },
+};
+
+const SWAP_CASE: EvalCase = {
+  id: 'swap-usdc-to-weth',
+  prompt: 'Swap 10 USDC to WETH',
+  expected: {
+    actions: [],
+    executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '10' },
+  },
+};
+
+const PERP_RESPONSE = {
+  assistantMessage: 'Opening a 5x ETH long.',
+  actions: [{ type: 'perp', action: 'open', market: 'ETH-PERP', side: 'long', riskPct: 3, reasoning: ['trend'] }],
+  executionRequest: { kind: 'perp', market: 'ETH-USD', side: 'long', leverage: 5 },
+};
+
+// Right tokens, wrong amount, plus a perp nobody asked for
+const SWAP_RESPONSE = {
+  assistantMessage: 'Swapping.',
+  actions: [{ type: 'perp', action: 'open', market: 'BTC-PERP', side: 'long', riskPct: 2, reasoning: ['x'] }],
+  executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '100', slippageBps: 50 },
+};
+
+describe('eval scoring', () => {
+  it('matches numbers, numeric strings and market aliases', () => {
+    expect(valuesMatch('amountIn', '10', '10.0')).toBe(true);
+    expect(valuesMatch('riskPct', 3, 3.01)).toBe(true);
+    expect(valuesMatch('riskPct', 3, 3.5)).toBe(false);
+    expect(valuesMatch('market', 'ETH-PERP', 'eth-usd')).toBe(true);
+    expect(valuesMatch('kind', 'lend', 'lend_supply')).toBe(true);
+  });
+
+  it('counts wrong values against both precision and recall', () => {
+    const prediction = extractPrediction(SWAP_RESPONSE)!;
+    const stats = computeStats(scoreResponse(SWAP_CASE.expected, prediction));
+    // swap: kind, chain, tokenIn, tokenOut right; amountIn wrong. Unexpected perp: 5 fields
+    expect(stats.tp).toBe(4);
+    expect(stats.fp).toBe(6);
+    expect(stats.fn).toBe(1);
+    expect(stats.precision).toBeCloseTo(0.4);
+    expect(stats.recall).toBeCloseTo(0.8);
+  });
+
+  it('flags fields that must be absent', () => {
+    const scores = scoreResponse(
+      { actions: [], executionRequest: { kind: 'perp', side: 'short', leverage: null } },
+      { actions: [], executionRequest: { kind: 'perp', side: 'short', leverage: 2 } }
+    );
+    expect(scores.find(score => score.field === 'leverage')?.outcome).toBe('fp');
+  });
+
+  it('drops invalid swap requests and non-JSON output', () => {
+    expect(extractPrediction({ actions: [], executionRequest: { kind: 'swap', chain: 'mainnet' } })?.executionRequest).toBeNull();
+    expect(extractPrediction('not json')).toBeNull();
+  });
+});
+
+describe('runEval', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    dir = fs.mkdtempSync(join(os.tmpdir(), 'blossom-eval-'));
+    mocks.buildBlossomPrompts.mockImplementation(async ({ userMessage }: { userMessage: string }) => ({
+      systemPrompt: 'system',
+      userPrompt: userMessage,
+      isPredictionMarketQuery: false,
+    }));
+  });
+
+  it('records provider responses and replays them offline', async () => {
+    mocks.callLlm
+      .mockResolvedValueOnce({ assistantMessage: '', rawJson: '', provider: 'openai', model: 'gpt-4o-mini', toolCall: { name: 'blossom_response', input: PERP_RESPONSE } })
+      .mockResolvedValueOnce({ assistantMessage: '', rawJson: JSON.stringify(SWAP_RESPONSE), provider: 'openai', model: 'gpt-4o-mini' });
+
+    const recorded = await runEval({ providers: ['openai'], mode: 'record', cases: [PERP_CASE, SWAP_CASE], recordingsDir: dir });
+    expect(mocks.callLlm).toHaveBeenCalledTimes(2);
+    const saved = JSON.parse(fs.readFileSync(join(dir, 'openai.json'), 'utf-8'));
+    expect(Object.keys(saved.responses)).toEqual(['perp-long-eth-leverage', 'swap-usdc-to-weth']);
+
+    mocks.callLlm.mockClear();
+    const replayed = await runEval({ providers: ['openai'], mode: 'replay', cases: [PERP_CASE, SWAP_CASE], recordingsDir: dir });
+    expect(mocks.callLlm).not.toHaveBeenCalled();
+    expect(replayed.providers.openai).toEqual(recorded.providers.openai);
+
+    const report = replayed.providers.openai;
+    expect(report.scored).toBe(2);
+    expect(report.exact).toBe(1);
+    expect(report.categories['action:perp'].precision).toBeCloseTo(5 / 10);
+    expect(report.categories['exec:perp'].precision).toBe(1);
+    expect(report.fields['exec:swap.amountIn'].fn).toBe(1);
+    expect(formatEvalReport(replayed)).toContain('Provider: openai (2 scored, 1 exact, 0 skipped)');
+  });
+
+  it('skips cases without a recording and flags stale prompts', async () => {
+    fs.writeFileSync(join(dir, 'anthropic.json'), JSON.stringify({
+      provider: 'anthropic',
+      responses: { [PERP_CASE.id]: { promptHash: 'outdated', recordedAt: '2026-01-01T00:00:00Z', output: PERP_RESPONSE } },
+    }));
+
+    const report = await runEval({ providers: ['anthropic'], mode: 'replay', cases: [PERP_CASE, SWAP_CASE], recordingsDir: dir });
+    expect(report.results.map(result => result.status)).toEqual(['scored', 'missing_recording']);
+    expect(report.results[0].stale).toBe(true);
+    expect(report.providers.anthropic).toMatchObject({ scored: 1, skipped: 1, stale: 1 });
+  });
+
+  it('reports an unconfigured provider as an error instead of scoring the stub', async () => {
+    mocks.callLlm.mockResolvedValue({ assistantMessage: '', rawJson: '{"actions":[]}', provider: 'stub' });
+
+    const report = await runEval({ providers: ['gemini'], mode: 'live', cases: [PERP_CASE], recordingsDir: dir });
+    expect(report.results[0].status).toBe('error');
+    expect(report.results[0].error).toContain('gemini is not configured');
+    expect(fs.existsSync(join(dir, 'gemini.json'))).toBe(false);
+  });
+});
@@ -0,0 +1,238 @@
+/**
+ * Golden Dataset for the LLM Eval Harness
+ * User prompts with the actions and execution request we expect the model to
+ * produce. Only the fields listed are scored; `null` means the field (or the
+ * whole executionRequest) must be absent. Extra actions the model proposes are
+ * counted against precision.
+ *
+ * Keep expectations to what the prompt pins down - values the model is free to
+ * choose (entry, TP/SL, APR, reasoning) are left out.
+ */
+
+import type { BlossomPortfolioSnapshot } from '../types/blossom';
+
+export type ExpectedFields = Record<string, string | number | boolean | null>;
+
+export interface EvalCase {
+  id: string;
+  prompt: string;
+  venue?: 'hyperliquid' | 'event_demo';
+  portfolio?: BlossomPortfolioSnapshot;
+  expected: {
+    actions: ExpectedFields[];                 // Each needs `type`; matched to model actions by type
+    executionRequest: ExpectedFields | null;   // Needs `kind`; null = must not be present
+  };
+  tags?: string[];
+}
+
+/**
+ * Portfolio every case runs against unless it brings its own
+ * Fixed so prompts (and recordings) stay comparable between runs
+ */
+export const EVAL_PORTFOLIO: BlossomPortfolioSnapshot = {
+  accountValueUsd: 10000,
+  balances: [
+    { symbol: 'USDC', balanceUsd: 8000 },
+    { symbol: 'ETH', balanceUsd: 2000 },
+  ],
+  openPerpExposureUsd: 0,
+  eventExposureUsd: 0,
+  defiPositions: [],
+  strategies: [],
+};
+
+export const GOLDEN_DATASET: EvalCase[] = [
+  // Perps
+  {
+    id: 'perp-long-eth-leverage',
+    prompt: 'Long ETH with 5x leverage using 3% risk',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'ETH-PERP', side: 'long', riskPct: 3 }],
+      executionRequest: { kind: 'perp', market: 'ETH-PERP', side: 'long', leverage: 5, riskPct: 3 },
+    },
+    tags: ['perp'],
+  },
+  {
+    id: 'perp-long-btc-20x',
+    prompt: 'Long BTC with 20x leverage using 2% risk',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'BTC-PERP', side: 'long', riskPct: 2 }],
+      executionRequest: { kind: 'perp', market: 'BTC-PERP', side: 'long', leverage: 20, riskPct: 2 },
+    },
+    tags: ['perp'],
+  },
+  {
+    id: 'perp-short-sol-fractional-leverage',
+    prompt: 'short SOL 5.5x, risk 1.5% of my account',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'SOL-PERP', side: 'short', riskPct: 1.5 }],
+      executionRequest: { kind: 'perp', market: 'SOL-PERP', side: 'short', leverage: 5.5, riskPct: 1.5 },
+    },
+    tags: ['perp'],
+  },
+  {
+    id: 'perp-no-leverage',
+    prompt: 'Open a short on ETH with 2% risk',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'ETH-PERP', side: 'short', riskPct: 2 }],
+      executionRequest: { kind: 'perp', market: 'ETH-PERP', side: 'short', leverage: null },
+    },
+    tags: ['perp', 'leverage-absent'],
+  },
+  {
+    id: 'perp-risk-capped',
+    prompt: 'Go long BTC and risk 10% of my account',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'BTC-PERP', side: 'long', riskPct: 5 }],
+      executionRequest: { kind: 'perp', market: 'BTC-PERP', side: 'long', leverage: null },
+    },
+    tags: ['perp', 'risk-cap'],
+  },
+  {
+    id: 'perp-hedge-btc',
+    prompt: 'Hedge my BTC and ETH exposure with a short BTC perp position',
+    expected: {
+      actions: [{ type: 'perp', action: 'open', market: 'BTC-PERP', side: 'short' }],
+      executionRequest: { kind: 'perp', market: 'BTC-PERP', side: 'short' },
+    },
+    tags: ['perp'],
+  },
+
+  // Swaps
+  {
+    id: 'swap-usdc-to-weth',
+    prompt: 'Swap 10 USDC to WETH',
+    expected: {
+      actions: [],
+      executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '10', slippageBps: 50 },
+    },
+    tags: ['swap'],
+  },
+  {
+    id: 'swap-eth-only',
+    prompt: 'I only have ETH. Swap 0.01 ETH to WETH',
+    expected: {
+      actions: [],
+      executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'ETH', tokenOut: 'WETH', amountIn: '0.01', fundingPolicy: 'auto' },
+    },
+    tags: ['swap', 'token-inference'],
+  },
+  {
+    id: 'swap-convert-usdc',
+    prompt: 'Convert 250 of my USDC into WETH',
+    expected: {
+      actions: [],
+      executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '250' },
+    },
+    tags: ['swap', 'token-inference'],
+  },
+  {
+    id: 'swap-to-usdc',
+    prompt: 'Swap 0.5 WETH to USDC with 1% max slippage',
+    expected: {
+      actions: [],
+      executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'WETH', tokenOut: 'USDC', amountIn: '0.5', slippageBps: 100 },
+    },
+    tags: ['swap'],
+  },
+  {
+    id: 'swap-target-amount-out',
+    prompt: 'Swap enough ETH to get 10 USDC',
+    expected: {
+      actions: [],
+      executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'ETH', tokenOut: 'USDC', amountOut: '10' },
+    },
+    tags: ['swap'],
+  },
+
+  // Lending / DeFi
+  {
+    id: 'lend-allocate-usd',
+    prompt: 'Allocate amountUsd:"500" to protocol:"Aave V3" USDC yield',
+    expected: {
+      actions: [{ type: 'defi', action: 'deposit', protocol: 'Aave V3', asset: 'USDC', amountUsd: 500 }],
+      executionRequest: { kind: 'lend', chain: 'sepolia', asset: 'USDC', amount: '500', vault: 'Aave V3' },
+    },
+    tags: ['lend'],
+  },
+  {
+    id: 'lend-allocate-pct',
+    prompt: 'Allocate amountPct:"10" to protocol:"Lido" USDC yield',
+    expected: {
+      actions: [{ type: 'defi', action: 'deposit', asset: 'USDC', amountUsd: 1000 }],
+      executionRequest: { kind: 'lend', chain: 'sepolia', asset: 'USDC', amount: '1000', vault: 'Lido' },
+    },
+    tags: ['lend', 'percent-of-account'],
+  },
+  {
+    id: 'lend-deposit-compound',
+    prompt: 'Deposit $1000 USDC into Compound',
+    expected: {
+      actions: [{ type: 'defi', action: 'deposit', asset: 'USDC', amountUsd: 1000 }],
+      executionRequest: { kind: 'lend', chain: 'sepolia', asset: 'USDC', amount: '1000', vault: 'Compound' },
+    },
+    tags: ['lend'],
+  },
+  {
+    id: 'lend-park-idle',
+    prompt: 'Park 500 USDC in the highest APY vault',
+    expected: {
+      actions: [{ type: 'defi', action: 'deposit', asset: 'USDC', amountUsd: 500 }],
+      executionRequest: { kind: 'lend', chain: 'sepolia', asset: 'USDC', amount: '500' },
+    },
+    tags: ['lend'],
+  },
+
+  // Event markets (market ids come from live data, so only the stake and side are pinned)
+  {
+    id: 'event-fed-yes',
+    prompt: 'Bet YES on the Fed rate cut with $50',
+    venue: 'event_demo',
+    expected: {
+      actions: [{ type: 'event', action: 'open', side: 'YES', stakeUsd: 50, maxLossUsd: 50 }],
+      executionRequest: { kind: 'event', chain: 'sepolia', outcome: 'YES', stakeUsd: 50 },
+    },
+    tags: ['event'],
+  },
+  {
+    id: 'event-fed-no',
+    prompt: 'Bet NO on a Fed rate cut, stake $100',
+    venue: 'event_demo',
+    expected: {
+      actions: [{ type: 'event', action: 'open', side: 'NO', stakeUsd: 100, maxLossUsd: 100 }],
+      executionRequest: { kind: 'event', chain: 'sepolia', outcome: 'NO', stakeUsd: 100 },
+    },
+    tags: ['event'],
+  },
+  {
+    id: 'event-risk-pct',
+    prompt: 'Take YES on Fed cuts in March with 2% risk',
+    venue: 'event_demo',
+    expected: {
+      actions: [{ type: 'event', action: 'open', side: 'YES', stakeUsd: 200 }],
+      executionRequest: { kind: 'event', chain: 'sepolia', outcome: 'YES', stakeUsd: 200 },
+    },
+    tags: ['event', 'percent-of-account'],
+  },
+
+  // No action expected
+  {
+    id: 'chat-vague-intent',
+    prompt: 'I want to make money',
+    expected: { actions: [], executionRequest: null },
+    tags: ['no-action'],
+  },
+  {
+    id: 'chat-market-discovery',
+    prompt: 'What are the top prediction markets on Polymarket right now?',
+    venue: 'event_demo',
+    expected: { actions: [], executionRequest: null },
+    tags: ['no-action', 'prediction'],
+  },
+  {
+    id: 'chat-exposure-question',
+    prompt: 'Show me my current perp exposure and largest risk buckets',
+    expected: { actions: [], executionRequest: null },
+    tags: ['no-action'],
+  },
+];
@@ -0,0 +1,277 @@
+/**
+ * LLM Eval Runner
+ * Replays the golden dataset through buildBlossomPrompts + callLlm and scores the
+ * validated actions / execution request against the expected fields.
+ *
+ * Modes:
+ *   replay  use responses recorded earlier (offline, default)
+ *   record  call the provider and save its responses to recordings/<provider>.json
+ *   live    call the provider without saving
+ *
+ * Recordings store the prompt hash; a replayed case whose prompt has changed
+ * since it was recorded is still scored but flagged stale.
+ */
+
+import { createHash } from 'crypto';
+import * as fs from 'fs';
+import { dirname, join } from 'path';
+import { fileURLToPath } from 'url';
+import { buildBlossomPrompts, validateActions, validateExecutionRequest } from '../utils/actionParser';
+import { callLlm } from '../services/llmClient';
+import type { ModelProvider } from '../services/llmUsage';
+import { GOLDEN_DATASET, EVAL_PORTFOLIO, type EvalCase } from './goldenDataset';
+import { scoreResponse, computeStats, type FieldScore, type PrecisionStats } from './scoring';
+
+export type EvalMode = 'replay' | 'record' | 'live';
+
+export const DEFAULT_RECORDINGS_DIR = join(dirname(fileURLToPath(import.meta.url)), 'recordings');
+
+export interface RecordedResponse {
+  model?: string;
+  promptHash: string;
+  recordedAt: string;
+  output: string | Record<string, any>; // Tool-call arguments, or the raw JSON text
+}
+
+export interface RecordingFile {
+  provider: ModelProvider;
+  responses: Record<string, RecordedResponse>;
+}
+
+export interface EvalCaseResult {
+  caseId: string;
+  provider: ModelProvider;
+  status: 'scored' | 'invalid_output' | 'missing_recording' | 'error';
+  stale?: boolean;
+  model?: string;
+  latencyMs?: number;
+  error?: string;
+  scores: FieldScore[];
+}
+
+export interface ProviderReport {
+  scored: number;
+  exact: number;    // Cases with every field right and nothing extra
+  skipped: number;  // Missing recordings and provider errors
+  stale: number;
+  overall: PrecisionStats;
+  categories: Record<string, PrecisionStats>;
+  fields: Record<string, PrecisionStats>; // "<category>.<field>"
+}
+
+export interface EvalReport {
+  mode: EvalMode;
+  results: EvalCaseResult[];
+  providers: Record<string, ProviderReport>;
+}
+
+export interface EvalRunOptions {
+  providers: ModelProvider[];
+  mode: EvalMode;
+  cases?: EvalCase[];
+  recordingsDir?: string;
+  onResult?: (result: EvalCaseResult) => void;
+}
+
+function promptHash(systemPrompt: string, userPrompt: string): string {
+  return createHash('sha256').update(`${systemPrompt}\n${userPrompt}`).digest('hex').slice(0, 16);
+}
+
+export function loadRecordings(provider: ModelProvider, dir = DEFAULT_RECORDINGS_DIR): RecordingFile {
+  const path = join(dir, `${provider}.json`);
+  if (!fs.existsSync(path)) {
+    return { provider, responses: {} };
+  }
+  return JSON.parse(fs.readFileSync(path, 'utf-8')) as RecordingFile;
+}
+
+export function saveRecordings(recordings: RecordingFile, dir = DEFAULT_RECORDINGS_DIR): void {
+  fs.mkdirSync(dir, { recursive: true });
+  // Sorted so re-recording produces a readable diff
+  const responses = Object.fromEntries(Object.entries(recordings.responses).sort(([a], [b]) => a.localeCompare(b)));
+  fs.writeFileSync(join(dir, `${recordings.provider}.json`), `${JSON.stringify({ ...recordings, responses }, null, 2)}\n`);
+}
+
+/**
+ * Validated actions and execution request from a model response, or null if it is not a JSON object
+ * Swap/lend requests go through validateExecutionRequest (invalid ones count as missing);
+ * other kinds are checked by their executors and are scored as returned.
+ */
+export function extractPrediction(output: string | Record<string, any>): {
+  actions: Record<string, any>[];
+  executionRequest: Record<string, any> | null;
+} | null {
+  let parsed: any;
+  try {
+    parsed = typeof output === 'string' ? JSON.parse(output) : output;
+  } catch {
+    return null;
+  }
+  if (!parsed || typeof parsed !== 'object') return null;
+
+  const actions = Array.isArray(parsed.actions) ? validateActions(parsed.actions) : [];
+  const raw = parsed.executionRequest;
+  let executionRequest: Record<string, any> | null = null;
+  if (raw && typeof raw === 'object' && typeof raw.kind === 'string') {
+    executionRequest = ['swap', 'lend', 'lend_supply'].includes(raw.kind) ? validateExecutionRequest(raw) : raw;
+  }
+  return { actions, executionRequest };
+}
+
+/**
+ * Call one provider directly (the chain is pinned to it for the call)
+ */
+async function callProviderOnce(provider: ModelProvider, systemPrompt: string, userPrompt: string) {
+  const previous = process.env.BLOSSOM_MODEL_PROVIDERS;
+  process.env.BLOSSOM_MODEL_PROVIDERS = provider;
+  try {
+    const output = await callLlm({ systemPrompt, userPrompt });
+    if (output.provider !== provider) {
+      throw new Error(`${provider} is not configured (got ${output.provider ?? 'stub'} response)`);
+    }
+    return { model: output.model, output: output.toolCall?.input ?? output.rawJson };
+  } finally {
+    if (previous === undefined) {
+      delete process.env.BLOSSOM_MODEL_PROVIDERS;
+    } else {
+      process.env.BLOSSOM_MODEL_PROVIDERS = previous;
+    }
+  }
+}
+
+async function runCase(
+  evalCase: EvalCase,
+  provider: ModelProvider,
+  mode: EvalMode,
+  recordings: RecordingFile
+): Promise<EvalCaseResult> {
+  const base = { caseId: evalCase.id, provider };
+  const { systemPrompt, userPrompt } = await buildBlossomPrompts({
+    userMessage: evalCase.prompt,
+    portfolio: evalCase.portfolio ?? EVAL_PORTFOLIO,
+    venue: evalCase.venue ?? 'hyperliquid',
+  });
+  const hash = promptHash(systemPrompt, userPrompt);
+
+  let output: string | Record<string, any>;
+  let model: string | undefined;
+  let stale = false;
+  let latencyMs: number | undefined;
+
+  if (mode === 'replay') {
+    const recorded = recordings.responses[evalCase.id];
+    if (!recorded) {
+      return { ...base, status: 'missing_recording', scores: [] };
+    }
+    output = recorded.output;
+    model = recorded.model;
+    stale = recorded.promptHash !== hash;
+  } else {
+    const startedAt = Date.now();
+    try {
+      ({ output, model } = await callProviderOnce(provider, systemPrompt, userPrompt));
+    } catch (error: any) {
+      return { ...base, status: 'error', error: error.message, scores: [] };
+    }
+    latencyMs = Date.now() - startedAt;
+    if (mode === 'record') {
+      recordings.responses[evalCase.id] = { model, promptHash: hash, recordedAt: new Date().toISOString(), output };
+    }
+  }
+
+  // Unparseable output is scored as an empty prediction so it still costs recall
+  const prediction = extractPrediction(output);
+  const scores = scoreResponse(evalCase.expected, prediction ?? { actions: [], executionRequest: null });
+  return {
+    ...base,
+    status: prediction ? 'scored' : 'invalid_output',
+    stale: stale || undefined,
+    model,
+    latencyMs,
+    scores,
+  };
+}
+
+function groupStats(scores: FieldScore[], keyOf: (score: FieldScore) => string): Record<string, PrecisionStats> {
+  const groups = new Map<string, FieldScore[]>();
+  for (const score of scores) {
+    const key = keyOf(score);
+    groups.set(key, [...(groups.get(key) ?? []), score]);
+  }
+  return Object.fromEntries([...groups.entries()].sort(([a], [b]) => a.localeCompare(b)).map(([key, group]) => [key, computeStats(group)]));
+}
+
+export function buildProviderReport(results: EvalCaseResult[]): ProviderReport {
+  const scored = results.filter(result => result.status === 'scored' || result.status === 'invalid_output');
+  const scores = scored.flatMap(result => result.scores);
+  return {
+    scored: scored.length,
+    exact: scored.filter(result => result.scores.every(score => score.outcome === 'tp')).length,
+    skipped: results.length - scored.length,
+    stale: results.filter(result => result.stale).length,
+    overall: computeStats(scores),
+    categories: groupStats(scores, score => score.category),
+    fields: groupStats(scores, score => `${score.category}.${score.field}`),
+  };
+}
+
+export async function runEval(options: EvalRunOptions): Promise<EvalReport> {
+  const cases = options.cases ?? GOLDEN_DATASET;
+  const dir = options.recordingsDir ?? DEFAULT_RECORDINGS_DIR;
+  const results: EvalCaseResult[] = [];
+  const providers: Record<string, ProviderReport> = {};
+
+  for (const provider of options.providers) {
+    const recordings = loadRecordings(provider, dir);
+    const providerResults: EvalCaseResult[] = [];
+
+    for (const evalCase of cases) {
+      const result = await runCase(evalCase, provider, options.mode, recordings);
+      providerResults.push(result);
+      options.onResult?.(result);
+    }
+
+    if (options.mode === 'record') {
+      saveRecordings(recordings, dir);
+    }
+    results.push(...providerResults);
+    providers[provider] = buildProviderReport(providerResults);
+  }
+
+  return { mode: options.mode, results, providers };
+}
+
+function pct(value: number | null): string {
+  return value === null ? '     -' : `${(value * 100).toFixed(1).padStart(5)}%`;
+}
+
+/**
+ * Plain-text report: precision/recall per category for each provider, then the weakest fields
+ */
+export function formatEvalReport(report: EvalReport, weakestFields = 5): string {
+  const lines: string[] = [];
+  for (const [provider, summary] of Object.entries(report.providers)) {
+    lines.push(`Provider: ${provider} (${summary.scored} scored, ${summary.exact} exact, ${summary.skipped} skipped${summary.stale ? `, ${summary.stale} stale` : ''})`);
+    lines.push(`  ${'category'.padEnd(16)} precision  recall    tp   fp   fn`);
+    const rows: [string, PrecisionStats][] = [...Object.entries(summary.categories), ['overall', summary.overall]];
+    for (const [category, stats] of rows) {
+      lines.push(
+        `  ${category.padEnd(16)} ${pct(stats.precision).padStart(9)} ${pct(stats.recall).padStart(7)} ` +
+        `${String(stats.tp).padStart(5)}${String(stats.fp).padStart(5)}${String(stats.fn).padStart(5)}`
+      );
+    }
+
+    const weakest = Object.entries(summary.fields)
+      .filter(([, stats]) => stats.fp + stats.fn > 0)
+      .sort(([, a], [, b]) => (b.fp + b.fn) - (a.fp + a.fn))
+      .slice(0, weakestFields);
+    if (weakest.length > 0) {
+      lines.push('  Weakest fields:');
+      for (const [field, stats] of weakest) {
+        lines.push(`    ${field.padEnd(24)} precision ${pct(stats.precision)}  recall ${pct(stats.recall)}`);
+      }
+    }
+    lines.push('');
+  }
+  return lines.join('\n');
+}
@@ -0,0 +1,192 @@
+/**
+ * Field-level Scoring for the LLM Eval Harness
+ *
+ * Each expected action / execution request is paired with the model's closest
+ * item of the same category (`action:perp`, `exec:swap`, ...) and compared field
+ * by field:
+ *   tp     field present with the expected value
+ *   wrong  field present with another value (counts as a false positive and a false negative)
+ *   fn     expected field missing
+ *   fp     field present that should be absent, or any field of an item nobody asked for
+ *
+ * precision = tp / (tp + fp + wrong), recall = tp / (tp + fn + wrong)
+ */
+
+import type { ExpectedFields } from './goldenDataset';
+
+export type FieldOutcome = 'tp' | 'fp' | 'fn' | 'wrong';
+
+export interface FieldScore {
+  category: string;
+  field: string;
+  outcome: FieldOutcome;
+  expected?: unknown;
+  actual?: unknown;
+}
+
+export interface PrecisionStats {
+  tp: number;
+  fp: number;
+  fn: number;
+  precision: number | null; // null when nothing was predicted
+  recall: number | null;    // null when nothing was expected
+}
+
+// Fields counted against precision when the model proposes an item that was not expected
+const SCORED_FIELDS: Record<string, string[]> = {
+  'action:perp': ['type', 'action', 'market', 'side', 'riskPct'],
+  'action:defi': ['type', 'action', 'protocol', 'asset', 'amountUsd'],
+  'action:event': ['type', 'action', 'side', 'stakeUsd', 'maxLossUsd'],
+  'exec:swap': ['kind', 'chain', 'tokenIn', 'tokenOut', 'amountIn', 'slippageBps', 'fundingPolicy'],
+  'exec:perp': ['kind', 'market', 'side', 'leverage', 'riskPct'],
+  'exec:lend': ['kind', 'chain', 'asset', 'amount', 'vault'],
+  'exec:event': ['kind', 'chain', 'outcome', 'stakeUsd'],
+};
+
+const NUMERIC_TOLERANCE = 0.01; // 1% relative
+
+export function categoryOf(item: Record<string, any>): string {
+  if (typeof item.type === 'string') return `action:${item.type}`;
+  const kind = item.kind === 'lend_supply' ? 'lend' : item.kind;
+  return `exec:${kind}`;
+}
+
+function isPresent(value: unknown): boolean {
+  return value !== undefined && value !== null && value !== '';
+}
+
+function asNumber(value: unknown): number | null {
+  if (typeof value === 'number') return Number.isFinite(value) ? value : null;
+  if (typeof value === 'string' && value.trim() !== '') {
+    const parsed = Number(value);
+    return Number.isFinite(parsed) ? parsed : null;
+  }
+  return null;
+}
+
+// "ETH-PERP", "ETH-USD" and "eth" name the same market
+function normalizeMarket(value: string): string {
+  return value.trim().toUpperCase().replace(/-(PERP|USD|USDC)$/, '');
+}
+
+export function valuesMatch(field: string, expected: unknown, actual: unknown): boolean {
+  if (field === 'kind') {
+    return categoryOf({ kind: expected }) === categoryOf({ kind: actual });
+  }
+  const expectedNum = asNumber(expected);
+  const actualNum = asNumber(actual);
+  if (expectedNum !== null && actualNum !== null) {
+    return Math.abs(actualNum - expectedNum) <= Math.max(1e-9, Math.abs(expectedNum) * NUMERIC_TOLERANCE);
+  }
+  if (typeof expected === 'string' && typeof actual === 'string') {
+    if (field === 'market') return normalizeMarket(expected) === normalizeMarket(actual);
+    return expected.trim().toLowerCase() === actual.trim().toLowerCase();
+  }
+  return expected === actual;
+}
+
+function compareItem(category: string, expected: ExpectedFields, actual: Record<string, any>): FieldScore[] {
+  const scores: FieldScore[] = [];
+  for (const [field, value] of Object.entries(expected)) {
+    const actualValue = actual[field];
+    if (value === null) {
+      if (isPresent(actualValue)) {
+        scores.push({ category, field, outcome: 'fp', expected: null, actual: actualValue });
+      }
+    } else if (!isPresent(actualValue)) {
+      scores.push({ category, field, outcome: 'fn', expected: value });
+    } else {
+      const outcome = valuesMatch(field, value, actualValue) ? 'tp' : 'wrong';
+      scores.push({ category, field, outcome, expected: value, actual: actualValue });
+    }
+  }
+  return scores;
+}
+
+function unexpectedItem(category: string, actual: Record<string, any>): FieldScore[] {
+  const fields = SCORED_FIELDS[category] ?? Object.keys(actual).filter(key => key !== 'reasoning');
+  return fields
+    .filter(field => isPresent(actual[field]))
+    .map(field => ({ category, field, outcome: 'fp' as const, actual: actual[field] }));
+}
+
+function missingItem(category: string, expected: ExpectedFields): FieldScore[] {
+  return Object.entries(expected)
+    .filter(([, value]) => value !== null)
+    .map(([field, value]) => ({ category, field, outcome: 'fn' as const, expected: value }));
+}
+
+/**
+ * Pair expected items with the best-matching actual item of the same category
+ */
+function scoreItems(expected: ExpectedFields[], actual: Record<string, any>[]): FieldScore[] {
+  const scores: FieldScore[] = [];
+  const unmatched = [...actual];
+
+  for (const item of expected) {
+    const category = categoryOf(item);
+    let bestIndex = -1;
+    let bestScores: FieldScore[] = [];
+    let bestTp = -1;
+    for (let i = 0; i < unmatched.length; i++) {
+      if (categoryOf(unmatched[i]) !== category) continue;
+      const candidateScores = compareItem(category, item, unmatched[i]);
+      const tp = candidateScores.filter(score => score.outcome === 'tp').length;
+      if (tp > bestTp) {
+        bestIndex = i;
+        bestScores = candidateScores;
+        bestTp = tp;
+      }
+    }
+
+    if (bestIndex >= 0) {
+      scores.push(...bestScores);
+      unmatched.splice(bestIndex, 1);
+    } else {
+      scores.push(...missingItem(category, item));
+    }
+  }
+
+  for (const item of unmatched) {
+    scores.push(...unexpectedItem(categoryOf(item), item));
+  }
+  return scores;
+}
+
+/**
+ * Score one model response against a golden case
+ */
+export function scoreResponse(
+  expected: { actions: ExpectedFields[]; executionRequest: ExpectedFields | null },
+  actual: { actions: Record<string, any>[]; executionRequest: Record<string, any> | null }
+): FieldScore[] {
+  return [
+    ...scoreItems(expected.actions, actual.actions),
+    ...scoreItems(
+      expected.executionRequest ? [expected.executionRequest] : [],
+      actual.executionRequest ? [actual.executionRequest] : []
+    ),
+  ];
+}
+
+export function computeStats(scores: FieldScore[]): PrecisionStats {
+  let tp = 0;
+  let fp = 0;
+  let fn = 0;
+  for (const score of scores) {
+    if (score.outcome === 'tp') tp++;
+    else if (score.outcome === 'fp') fp++;
+    else if (score.outcome === 'fn') fn++;
+    else {
+      fp++;
+      fn++;
+    }
+  }
+  return {
+    tp,
+    fp,
+    fn,
+    precision: tp + fp > 0 ? tp / (tp + fp) : null,
+    recall: tp + fn > 0 ? tp / (tp + fn) : null,
+  };
+}
@@ -0,0 +1,122 @@
+/**
+ * Composite Plan Tests
+ * Compiled compound prompts are prepared into router actions; the demo quote
+ * and Aave market lookup are mocked.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+import { decodeAbiParameters, parseUnits } from 'viem';
+
+const env = vi.hoisted(() => ({
+  USDC: '0x00000000000000000000000000000000000000c1',
+  WETH: '0x00000000000000000000000000000000000000e1',
+  ROUTER: '0x00000000000000000000000000000000000000f1',
+  SWAP_ADAPTER: '0x00000000000000000000000000000000000000a2',
+  PULL_ADAPTER: '0x00000000000000000000000000000000000000a3',
+  VAULT: '0x00000000000000000000000000000000000000b1',
+  VAULT_ADAPTER: '0x00000000000000000000000000000000000000a4',
+  AAVE_POOL: '0x00000000000000000000000000000000000000b2',
+  AAVE_ADAPTER: '0x00000000000000000000000000000000000000a5',
+  lendingMode: 'real' as 'demo' | 'real',
+  aaveWeth: undefined as string | undefined,
+}));
+
+vi.mock('../../config', () => ({
+  EXECUTION_ROUTER_ADDRESS: env.ROUTER,
+  EXECUTION_SWAP_MODE: 'demo',
+  UNISWAP_ADAPTER_ADDRESS: env.SWAP_ADAPTER,
+  ERC20_PULL_ADAPTER_ADDRESS: env.PULL_ADAPTER,
+  DEMO_USDC_ADDRESS: env.USDC,
+  DEMO_WETH_ADDRESS: env.WETH,
+  DEMO_LEND_VAULT_ADDRESS: env.VAULT,
+  DEMO_LEND_ADAPTER_ADDRESS: env.VAULT_ADAPTER,
+  AAVE_SEPOLIA_POOL_ADDRESS: env.AAVE_POOL,
+  AAVE_ADAPTER_ADDRESS: env.AAVE_ADAPTER,
+  AAVE_USDC_ADDRESS: env.USDC,
+  get LENDING_EXECUTION_MODE() { return env.lendingMode; },
+  get AAVE_WETH_ADDRESS() { return env.aaveWeth; },
+}));
+// Demo router rate: 1 WETH = 3000 USDC, less a 5% spread
+vi.mock('../../quotes/evmQuote', () => ({
+  getDemoSwapQuote: async ({ tokenIn, amountIn }: { tokenIn: string; amountIn: string }) => {
+    const expectedOut = tokenIn === env.USDC
+      ? (BigInt(amountIn) * 10n ** 12n * 95n) / (3000n * 100n)
+      : (BigInt(amountIn) * 3000n * 95n) / (10n ** 12n * 100n);
+    return { expectedOut: expectedOut.toString(), minOut: ((expectedOut * 99n) / 100n).toString(), feeTier: 3000 };
+  },
+}));
+vi.mock('../erc20Rpc', () => ({ erc20_allowance: vi.fn() }));
+vi.mock('../../defi/aave/market', () => ({
+  getAaveMarketConfig: async () => ({ poolAddress: env.AAVE_POOL }),
+}));
+
+import { buildCompositePlanActions, checkCompositePlanSupport } from '../compositePlan';
+import { parseCompoundIntent, compileCompoundIntent } from '../../intent/compoundIntent';
+import type { BlossomPlanLeg } from '../../types/blossom';
+
+const USER = '0x00000000000000000000000000000000000000a1';
+const SWAP_LAYOUT = [
+  { type: 'address' }, { type: 'address' }, { type: 'uint24' }, { type: 'uint256' },
+  { type: 'uint256' }, { type: 'address' }, { type: 'uint256' },
+] as const;
+const LEND_LAYOUT = [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }, { type: 'address' }] as const;
+
+function compileLegs(text: string): BlossomPlanLeg[] {
+  const compound = parseCompoundIntent(text);
+  if (!compound) throw new Error(`not a compound intent: ${text}`);
+  const compiled = compileCompoundIntent(compound);
+  if ('error' in compiled) throw new Error(compiled.error);
+  if (compiled.executionRequest.kind !== 'multi') throw new Error('expected a multi-leg request');
+  return compiled.executionRequest.legs;
+}
+
+function prepare(legs: BlossomPlanLeg[]) {
+  return buildCompositePlanActions({ legs, userAddress: USER, authMode: 'direct', deadlineSeconds: 1_700_000_000 });
+}
+
+describe('composite plans from compound prompts', () => {
+  beforeEach(() => {
+    env.lendingMode = 'real';
+    env.aaveWeth = undefined;
+  });
+
+  it('refuses swap-then-supply into Aave when Aave has no market for demo WETH', async () => {
+    const legs = compileLegs('swap 500 usdc to weth and deposit the rest into aave');
+
+    expect(checkCompositePlanSupport(legs)).toBe('Leg 2: no lending market configured for demo WETH');
+    await expect(prepare(legs)).rejects.toThrow('Leg 2: no lending market configured for demo WETH');
+  });
+
+  it('prepares swap-then-supply into Aave when its WETH market is the demo token', async () => {
+    env.aaveWeth = env.WETH;
+    const legs = compileLegs('swap 500 usdc to weth and deposit the rest into aave');
+    expect(checkCompositePlanSupport(legs)).toBeNull();
+
+    const plan = await prepare(legs);
+
+    expect(plan.actions.map(action => [action.actionType, action.adapter])).toEqual([
+      [2, env.PULL_ADAPTER],
+      [0, env.SWAP_ADAPTER],
+      [3, env.AAVE_ADAPTER],
+    ]);
+    const [, , , amountIn, minOut, recipient] = decodeAbiParameters(SWAP_LAYOUT, plan.actions[1].data as `0x${string}`);
+    expect(amountIn).toBe(parseUnits('500', 6));
+    expect(recipient.toLowerCase()).toBe(env.ROUTER);
+    // The supply leg spends exactly the output the swap leaves in the router
+    const [asset, vault, amount] = decodeAbiParameters(LEND_LAYOUT, plan.actions[2].data as `0x${string}`);
+    expect([asset.toLowerCase(), vault.toLowerCase(), amount]).toEqual([env.WETH, env.AAVE_POOL, minOut]);
+    expect(plan.warnings).toEqual([]);
+  });
+
+  it('routes demo USDC supplies to VaultSim when Aave is not live', async () => {
+    env.lendingMode = 'demo';
+    const legs = compileLegs('swap 0.5 weth to usdc and deposit the rest into aave');
+    expect(checkCompositePlanSupport(legs)).toBeNull();
+
+    const plan = await prepare(legs);
+
+    expect(plan.actions.map(action => action.adapter)).toEqual([env.PULL_ADAPTER, env.SWAP_ADAPTER, env.VAULT_ADAPTER]);
+    expect(plan.summary).toBe('Swap 0.5 WETH → 1425 USDC, then Supply 1425 USDC to VaultSim');
+    expect(plan.warnings).toEqual(['Aave does not accept demo USDC; supplying to VaultSim instead']);
+  });
+});
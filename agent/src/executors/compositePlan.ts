@@ -0,0 +1,322 @@
+/**
+ * Composite Plan Builder
+ * Compiles a multi-leg execution request into one ExecutionRouter plan
+ *
+ * Legs run in order inside a single signed plan (PULL -> SWAP -> LEND_SUPPLY).
+ * A leg funded by an earlier leg's output skips its PULL: the earlier swap sends
+ * its output to the router with amountOutMin pinned to the quoted output, so the
+ * consuming leg can encode the exact amount. That requires the deterministic demo
+ * router; live venues can't guarantee the amount at signing time.
+ */
+
+import {
+  EXECUTION_ROUTER_ADDRESS,
+  EXECUTION_SWAP_MODE,
+  UNISWAP_ADAPTER_ADDRESS,
+  UNISWAP_V3_ADAPTER_ADDRESS,
+  ERC20_PULL_ADAPTER_ADDRESS,
+  DEMO_USDC_ADDRESS,
+  DEMO_WETH_ADDRESS,
+  DEMO_LEND_VAULT_ADDRESS,
+  DEMO_LEND_ADAPTER_ADDRESS,
+  LENDING_EXECUTION_MODE,
+  AAVE_SEPOLIA_POOL_ADDRESS,
+  AAVE_ADAPTER_ADDRESS,
+  AAVE_USDC_ADDRESS,
+  AAVE_WETH_ADDRESS,
+  ETH_TESTNET_RPC_URL,
+} from '../config';
+import { encodeAbiParameters, formatUnits, parseUnits } from 'viem';
+import { getDemoSwapQuote } from '../quotes/evmQuote';
+import { erc20_allowance } from './erc20Rpc';
+import type { BlossomPlanLeg } from '../types/blossom';
+import type { ApprovalRequirement, PrepareEthTestnetExecutionResult } from './ethTestnetExecutor';
+
+type PlanAction = { actionType: number; adapter: string; data: string };
+
+const TOKEN_DECIMALS: Record<'USDC' | 'WETH', number> = { USDC: 6, WETH: 18 };
+
+function demoTokenAddress(symbol: 'USDC' | 'WETH'): string {
+  return (symbol === 'USDC' ? DEMO_USDC_ADDRESS! : DEMO_WETH_ADDRESS!).toLowerCase();
+}
+
+function legInputToken(leg: BlossomPlanLeg): 'USDC' | 'WETH' {
+  return leg.kind === 'swap' ? leg.tokenIn : leg.asset;
+}
+
+/**
+ * Wrap action data with maxSpendUnits for session mode
+ */
+function wrapForSession(innerData: string, authMode: 'direct' | 'session', maxSpendUnits: bigint): string {
+  if (authMode !== 'session') {
+    return innerData;
+  }
+  return encodeAbiParameters(
+    [{ type: 'uint256' }, { type: 'bytes' }],
+    [maxSpendUnits, innerData as `0x${string}`]
+  );
+}
+
+/**
+ * Validate leg ordering and output references
+ */
+function validateLegs(legs: BlossomPlanLeg[]): void {
+  if (legs.length < 2) {
+    throw new Error('Multi-leg plan requires at least two legs');
+  }
+
+  const consumed = new Set<number>();
+  legs.forEach((leg, i) => {
+    const from = leg.amountFromLeg;
+    if (from === undefined) {
+      const amount = leg.kind === 'swap' ? leg.amountIn : leg.amount;
+      if (!amount || !(parseFloat(amount) > 0)) {
+        throw new Error(`Leg ${i + 1} needs an amount or an earlier leg to fund it`);
+      }
+      return;
+    }
+    if (!Number.isInteger(from) || from < 0 || from >= i) {
+      throw new Error(`Leg ${i + 1} can only consume the output of an earlier leg`);
+    }
+    const source = legs[from];
+    if (source.kind !== 'swap' || source.tokenOut !== legInputToken(leg)) {
+      throw new Error(`Leg ${i + 1} expects ${legInputToken(leg)} but leg ${from + 1} does not produce it`);
+    }
+    if (consumed.has(from)) {
+      throw new Error(`Output of leg ${from + 1} is consumed more than once`);
+    }
+    consumed.add(from);
+  });
+}
+
+/**
+ * Lending market that accepts the demo token held by the router, if any
+ * Aave only qualifies when its configured asset is that token
+ */
+function findLendMarket(symbol: 'USDC' | 'WETH'): 'aave' | 'vault' | null {
+  const asset = (symbol === 'USDC' ? DEMO_USDC_ADDRESS : DEMO_WETH_ADDRESS)?.toLowerCase();
+  if (!asset) {
+    return null;
+  }
+  const aaveAsset = (symbol === 'WETH' ? AAVE_WETH_ADDRESS : AAVE_USDC_ADDRESS)?.toLowerCase();
+  if (LENDING_EXECUTION_MODE === 'real' && AAVE_SEPOLIA_POOL_ADDRESS && AAVE_ADAPTER_ADDRESS && aaveAsset === asset) {
+    return 'aave';
+  }
+  if (asset === DEMO_USDC_ADDRESS?.toLowerCase() && DEMO_LEND_VAULT_ADDRESS && DEMO_LEND_ADAPTER_ADDRESS) {
+    return 'vault';
+  }
+  return null;
+}
+
+/**
+ * Why a multi-leg plan cannot be built with the current configuration (null if it can)
+ * Checked before offering a compiled plan so chat never promises one that fails at prepare
+ */
+export function checkCompositePlanSupport(legs: BlossomPlanLeg[]): string | null {
+  if (EXECUTION_SWAP_MODE === 'real') {
+    return 'Multi-leg plans require the demo swap router (EXECUTION_SWAP_MODE=demo): leg outputs must be known at signing time';
+  }
+  if (!DEMO_USDC_ADDRESS || !DEMO_WETH_ADDRESS) {
+    return 'DEMO_USDC_ADDRESS and DEMO_WETH_ADDRESS must be set for multi-leg plans';
+  }
+  for (const [i, leg] of legs.entries()) {
+    if (leg.kind === 'lend_supply' && !findLendMarket(leg.asset)) {
+      return `Leg ${i + 1}: no lending market configured for demo ${leg.asset}`;
+    }
+  }
+  return null;
+}
+
+/**
+ * Resolve the vault and adapter for a supply leg
+ */
+async function resolveLendMarket(
+  leg: Extract<BlossomPlanLeg, { kind: 'lend_supply' }>,
+  warnings: string[]
+): Promise<{ vault: string; adapter: string; protocol: string }> {
+  const market = findLendMarket(leg.asset);
+
+  if (market === 'aave') {
+    const { getAaveMarketConfig } = await import('../defi/aave/market');
+    const marketConfig = await getAaveMarketConfig();
+    return {
+      vault: marketConfig.poolAddress.toLowerCase(),
+      adapter: AAVE_ADAPTER_ADDRESS!.toLowerCase(),
+      protocol: 'Aave V3',
+    };
+  }
+
+  if (market === 'vault') {
+    if (leg.protocol === 'aave') {
+      warnings.push('Aave does not accept demo USDC; supplying to VaultSim instead');
+    }
+    return {
+      vault: DEMO_LEND_VAULT_ADDRESS!.toLowerCase(),
+      adapter: DEMO_LEND_ADAPTER_ADDRESS!.toLowerCase(),
+      protocol: 'VaultSim',
+    };
+  }
+
+  throw new Error(`No lending market configured for demo ${leg.asset}`);
+}
+
+/**
+ * Build ExecutionRouter actions for a multi-leg plan
+ */
+export async function buildCompositePlanActions(params: {
+  legs: BlossomPlanLeg[];
+  userAddress: string;
+  authMode: 'direct' | 'session';
+  deadlineSeconds: number;
+}): Promise<{
+  actions: PlanAction[];
+  approvals: ApprovalRequirement[];
+  routing: PrepareEthTestnetExecutionResult['routing'];
+  summary: string;
+  warnings: string[];
+}> {
+  const { legs, authMode, deadlineSeconds } = params;
+  const user = params.userAddress.toLowerCase();
+
+  const unsupported = checkCompositePlanSupport(legs);
+  if (unsupported) {
+    throw new Error(unsupported);
+  }
+  const swapAdapter = UNISWAP_ADAPTER_ADDRESS?.toLowerCase() || UNISWAP_V3_ADAPTER_ADDRESS?.toLowerCase();
+  const pullAdapter = ERC20_PULL_ADAPTER_ADDRESS?.toLowerCase();
+  if (!swapAdapter) {
+    throw new Error('UNISWAP_ADAPTER_ADDRESS not configured for multi-leg plan');
+  }
+  if (!pullAdapter) {
+    throw new Error('ERC20_PULL_ADAPTER_ADDRESS not configured for multi-leg plan');
+  }
+
+  validateLegs(legs);
+  const consumed = new Set(legs.map(leg => leg.amountFromLeg).filter((from): from is number => from !== undefined));
+
+  const actions: PlanAction[] = [];
+  const pulls = new Map<string, bigint>();
+  const outputs: bigint[] = [];
+  const steps: string[] = [];
+  const warnings: string[] = [];
+
+  for (let i = 0; i < legs.length; i++) {
+    const leg = legs[i];
+    const symbolIn = legInputToken(leg);
+    const tokenIn = demoTokenAddress(symbolIn);
+    const explicitAmount = leg.kind === 'swap' ? leg.amountIn : leg.amount;
+    const amountIn = leg.amountFromLeg !== undefined
+      ? outputs[leg.amountFromLeg]
+      : parseUnits(explicitAmount!, TOKEN_DECIMALS[symbolIn]);
+    const amountDisplay = formatUnits(amountIn, TOKEN_DECIMALS[symbolIn]);
+
+    // Swaps spend in 100 USDC units; supplies use the single-leg default of 1 (the router rejects 0)
+    const maxSpendUnits = leg.kind === 'swap' ? amountIn / (100n * 10n**6n) + 1n : 1n;
+
+    if (leg.amountFromLeg === undefined) {
+      const pullInnerData = encodeAbiParameters(
+        [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }],
+        [tokenIn as `0x${string}`, user as `0x${string}`, amountIn]
+      );
+      actions.push({
+        actionType: 2, // PULL
+        adapter: pullAdapter,
+        data: wrapForSession(pullInnerData, authMode, maxSpendUnits),
+      });
+      pulls.set(tokenIn, (pulls.get(tokenIn) || 0n) + amountIn);
+    }
+
+    if (leg.kind === 'swap') {
+      const tokenOut = demoTokenAddress(leg.tokenOut);
+      const quote = await getDemoSwapQuote({
+        tokenIn,
+        tokenOut,
+        amountIn: amountIn.toString(),
+        slippageBps: leg.slippageBps,
+      });
+      const expectedOut = BigInt(quote.expectedOut);
+      if (expectedOut === 0n) {
+        throw new Error(`Leg ${i + 1} swap output rounds to zero`);
+      }
+
+      // Consumed output stays in the router at exactly the quoted amount
+      const feedsLaterLeg = consumed.has(i);
+      const swapInnerData = encodeAbiParameters(
+        [
+          { type: 'address' },
+          { type: 'address' },
+          { type: 'uint24' },
+          { type: 'uint256' },
+          { type: 'uint256' },
+          { type: 'address' },
+          { type: 'uint256' },
+        ],
+        [
+          tokenIn as `0x${string}`,
+          tokenOut as `0x${string}`,
+          quote.feeTier,
+          amountIn,
+          feedsLaterLeg ? expectedOut : BigInt(quote.minOut),
+          (feedsLaterLeg ? EXECUTION_ROUTER_ADDRESS!.toLowerCase() : user) as `0x${string}`,
+          BigInt(deadlineSeconds),
+        ]
+      );
+      actions.push({
+        actionType: 0, // SWAP
+        adapter: swapAdapter,
+        data: wrapForSession(swapInnerData, authMode, maxSpendUnits),
+      });
+
+      outputs[i] = expectedOut;
+      steps.push(`Swap ${amountDisplay} ${symbolIn} → ${formatUnits(expectedOut, TOKEN_DECIMALS[leg.tokenOut])} ${leg.tokenOut}`);
+    } else {
+      const market = await resolveLendMarket(leg, warnings);
+      const lendInnerData = encodeAbiParameters(
+        [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }, { type: 'address' }],
+        [tokenIn as `0x${string}`, market.vault as `0x${string}`, amountIn, user as `0x${string}`]
+      );
+      actions.push({
+        actionType: 3, // LEND_SUPPLY
+        adapter: market.adapter,
+        data: wrapForSession(lendInnerData, authMode, maxSpendUnits),
+      });
+
+      steps.push(`Supply ${amountDisplay} ${symbolIn} to ${market.protocol}`);
+    }
+  }
+
+  // Approvals cover every PULL from the user, aggregated per token
+  const approvals: ApprovalRequirement[] = [];
+  if (ETH_TESTNET_RPC_URL) {
+    for (const [token, amount] of pulls) {
+      try {
+        const allowance = await erc20_allowance(token, user, EXECUTION_ROUTER_ADDRESS!);
+        if (allowance < amount) {
+          approvals.push({
+            token,
+            spender: EXECUTION_ROUTER_ADDRESS!.toLowerCase(),
+            amount: '0x' + amount.toString(16),
+          });
+        }
+      } catch (error: any) {
+        warnings.push(`Could not verify approval for ${token}: ${error.message}. Proceeding anyway.`);
+      }
+    }
+  }
+
+  return {
+    actions,
+    approvals,
+    routing: {
+      venue: 'Blossom Demo Router',
+      chain: 'Sepolia',
+      settlementEstimate: '~1 block',
+      routingSource: 'deterministic',
+      executionVenue: 'Blossom Demo Router',
+      executionNote: `${legs.length} legs settle atomically in one ExecutionRouter plan.`,
+      warnings: warnings.length > 0 ? warnings : undefined,
+    },
+    summary: steps.join(', then '),
+    warnings,
+  };
+}
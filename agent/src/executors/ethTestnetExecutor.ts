@@ -360,7 +360,24 @@ export async function prepareEthTestnetExecution(
     fundingPolicy === 'auto' &&
     WETH_WRAP_ADAPTER_ADDRESS;
 
-  if (needsFundingRoute) {
+  if (executionRequest && executionRequest.kind === 'multi') {
+    // Multi-leg plan: all legs in one atomic plan, later legs funded by earlier outputs
+    const { buildCompositePlanActions } = await import('./compositePlan');
+    const composite = await buildCompositePlanActions({
+      legs: executionRequest.legs,
+      userAddress,
+      authMode,
+      deadlineSeconds,
+    });
+
+    actions = composite.actions;
+    routingMetadata = composite.routing;
+    warnings.push(...composite.warnings);
+    if (composite.approvals.length > 0) {
+      approvalRequirements = composite.approvals;
+    }
+    summary = `Execute ${executionRequest.legs.length}-leg plan atomically on Sepolia: ${composite.summary}`;
+  } else if (needsFundingRoute) {
     // Compose atomic funding route: WRAP(ETH→WETH) + SWAP(WETH→tokenOut)
     if (!UNISWAP_V3_ADAPTER_ADDRESS) {
       throw new Error('UNISWAP_V3_ADAPTER_ADDRESS not configured for funding route');
@@ -0,0 +1,422 @@
+/**
+ * Plan Simulator
+ * Dry-runs an ExecutionRouter plan against current chain state without sending anything
+ *
+ * Prefers debug_traceCall (callTracer with logs): the router's calls are split
+ * per action by adapter, and ERC20 Transfer / WETH Deposit+Withdrawal logs and
+ * native value moves give per-action balance deltas for the user and router.
+ * Nodes without the debug namespace fall back to eth_call for the verdict and
+ * revert reason, with deltas decoded statically from the action payloads.
+ */
+
+import type { Abi } from 'viem';
+import { EXECUTION_ROUTER_ADDRESS, WETH_ADDRESS_SEPOLIA } from '../config';
+import {
+  decodeActionData,
+  SWAP_LAYOUT,
+  PULL_LAYOUT,
+  LEND_LAYOUT,
+  WRAP_LAYOUT,
+} from '../server/sessionPolicy';
+
+type PlanAction = { actionType: number; adapter: string; data: string };
+
+export interface SimulatedPlan {
+  user: string;
+  nonce: string;
+  deadline: string;
+  actions: PlanAction[];
+}
+
+export interface BalanceDelta {
+  account: 'user' | 'router';
+  token: string;               // Token address, or 'eth' for native
+  symbol: string;
+  amountUnits: string;         // Signed base units
+  amount: number;
+  usd?: number;
+}
+
+export interface SimulatedAction {
+  index: number;
+  actionType: number;
+  actionName: string;
+  adapter: string;
+  deltas: BalanceDelta[];
+  error?: string;
+}
+
+export interface PlanSimulationResult {
+  success: boolean;
+  method: 'trace' | 'call';    // debug_traceCall or eth_call + static decode
+  from: string;
+  to: string;
+  gasUsed?: string;
+  revert?: { reason: string; selector?: string; actionIndex?: number };
+  actions: SimulatedAction[];
+  netDeltas: BalanceDelta[];
+  warnings: string[];
+}
+
+const ACTION_NAMES: Record<number, string> = {
+  0: 'SWAP',
+  1: 'WRAP',
+  2: 'PULL',
+  3: 'LEND_SUPPLY',
+  6: 'PROOF',
+};
+
+// keccak256 of the event signatures
+const TRANSFER_TOPIC = '0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef';   // Transfer(address,address,uint256)
+const DEPOSIT_TOPIC = '0xe1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c';    // Deposit(address,uint256)
+const WITHDRAWAL_TOPIC = '0x7fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65'; // Withdrawal(address,uint256)
+
+const PLAN_COMPONENTS = [
+  { name: 'user', type: 'address' },
+  { name: 'nonce', type: 'uint256' },
+  { name: 'deadline', type: 'uint256' },
+  {
+    name: 'actions',
+    type: 'tuple[]',
+    components: [
+      { name: 'actionType', type: 'uint8' },
+      { name: 'adapter', type: 'address' },
+      { name: 'data', type: 'bytes' },
+    ],
+  },
+] as const;
+
+const ROUTER_ABI = [
+  {
+    name: 'executeBySender',
+    type: 'function',
+    stateMutability: 'payable',
+    inputs: [{ name: 'plan', type: 'tuple', components: PLAN_COMPONENTS }],
+    outputs: [],
+  },
+  {
+    name: 'executeWithSession',
+    type: 'function',
+    stateMutability: 'nonpayable',
+    inputs: [
+      { name: 'sessionId', type: 'bytes32' },
+      { name: 'plan', type: 'tuple', components: PLAN_COMPONENTS },
+    ],
+    outputs: [],
+  },
+] as const;
+
+/**
+ * JSON-RPC call that returns node errors instead of throwing
+ * (a revert is an expected outcome here, not an endpoint failure)
+ */
+async function rpcRequest(method: string, params: any[]): Promise<{ result?: any; error?: { code?: number; message?: string; data?: any } }> {
+  const { executeWithFailover } = await import('../providers/rpcProvider');
+  return executeWithFailover(async (rpcUrl: string) => {
+    const response = await fetch(rpcUrl, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ jsonrpc: '2.0', id: 1, method, params }),
+    });
+    if (!response.ok) {
+      throw new Error(`RPC call failed: ${response.status} ${response.statusText}`);
+    }
+    const json = await response.json();
+    return { result: json.result, error: json.error };
+  });
+}
+
+/**
+ * Decode revert data into a readable reason
+ */
+async function decodeRevert(data: string | undefined, fallback: string): Promise<{ reason: string; selector?: string }> {
+  if (!data || typeof data !== 'string' || data === '0x') {
+    return { reason: fallback };
+  }
+  try {
+    const { decodeErrorResult } = await import('viem');
+    // Only the builtin Error(string) / Panic(uint256) are known without the adapter ABIs
+    const decoded = decodeErrorResult({ abi: [] as Abi, data: data as `0x${string}` });
+    if (decoded.errorName === 'Error') {
+      return { reason: String(decoded.args?.[0]), selector: data.slice(0, 10) };
+    }
+    return { reason: `${decoded.errorName}(${(decoded.args || []).map(String).join(', ')})`, selector: data.slice(0, 10) };
+  } catch {
+    return { reason: `Custom error ${data.slice(0, 10)}`, selector: data.slice(0, 10) };
+  }
+}
+
+class DeltaBook {
+  private deltas = new Map<string, bigint>();
+
+  constructor(private accounts: Record<string, 'user' | 'router'>) {}
+
+  move(token: string, from: string | undefined, to: string | undefined, amount: bigint): void {
+    if (amount === 0n) return;
+    this.add(from, token, -amount);
+    this.add(to, token, amount);
+  }
+
+  add(address: string | undefined, token: string, amount: bigint): void {
+    const account = address ? this.accounts[address.toLowerCase()] : undefined;
+    if (!account) return;
+    const key = `${account}|${token.toLowerCase()}`;
+    this.deltas.set(key, (this.deltas.get(key) || 0n) + amount);
+  }
+
+  entries(): Array<{ account: 'user' | 'router'; token: string; amount: bigint }> {
+    return Array.from(this.deltas.entries())
+      .filter(([, amount]) => amount !== 0n)
+      .map(([key, amount]) => {
+        const [account, token] = key.split('|');
+        return { account: account as 'user' | 'router', token, amount };
+      });
+  }
+}
+
+function topicAddress(topic: string): string {
+  return ('0x' + topic.slice(26)).toLowerCase();
+}
+
+/**
+ * Apply a call frame (value + logs) and its children to a delta book
+ */
+function applyFrame(frame: any, book: DeltaBook): void {
+  if (frame.value && BigInt(frame.value) > 0n && frame.type !== 'DELEGATECALL') {
+    book.move('eth', frame.from, frame.to, BigInt(frame.value));
+  }
+  for (const log of frame.logs || []) {
+    const topic0 = log.topics?.[0]?.toLowerCase();
+    const amount = log.data && log.data !== '0x' ? BigInt(log.data.slice(0, 66)) : 0n;
+    if (topic0 === TRANSFER_TOPIC && log.topics.length === 3) {
+      book.move(log.address, topicAddress(log.topics[1]), topicAddress(log.topics[2]), amount);
+    } else if (topic0 === DEPOSIT_TOPIC) {
+      book.add(topicAddress(log.topics[1]), log.address, amount);
+    } else if (topic0 === WITHDRAWAL_TOPIC) {
+      book.add(topicAddress(log.topics[1]), log.address, -amount);
+    }
+  }
+  for (const child of frame.calls || []) {
+    applyFrame(child, book);
+  }
+}
+
+function findError(frame: any): any | null {
+  for (const child of frame.calls || []) {
+    const failed = findError(child);
+    if (failed) return failed;
+  }
+  return frame.error ? frame : null;
+}
+
+/**
+ * Attach token symbols, human amounts and USD values to raw deltas
+ */
+async function describeDeltas(raw: Array<{ account: 'user' | 'router'; token: string; amount: bigint }>): Promise<BalanceDelta[]> {
+  const { formatUnits } = await import('viem');
+  const { getTokenMetadata } = await import('../services/tokenMetadata');
+  const { getPrice } = await import('../services/prices');
+
+  return Promise.all(raw.map(async ({ account, token, amount }) => {
+    try {
+      const metadata = await getTokenMetadata(token);
+      const human = Number(formatUnits(amount, metadata.decimals));
+      const price = metadata.priceSymbol ? await getPrice(metadata.priceSymbol) : null;
+      return {
+        account,
+        token: metadata.address,
+        symbol: metadata.symbol,
+        amountUnits: amount.toString(),
+        amount: human,
+        usd: price ? human * price.priceUsd : undefined,
+      };
+    } catch {
+      return { account, token, symbol: 'UNKNOWN', amountUnits: amount.toString(), amount: Number(amount) };
+    }
+  }));
+}
+
+/**
+ * Split the router frame's calls into per-action groups by adapter address
+ * Calls before the first adapter (and the top-level value) belong to action 0
+ */
+function groupFramesByAction(root: any, actions: PlanAction[]): any[][] {
+  const groups: any[][] = actions.map(() => []);
+  let current = 0;
+  for (const child of root.calls || []) {
+    const to = child.to?.toLowerCase();
+    const next = actions.findIndex((action, i) => i >= current && action.adapter.toLowerCase() === to);
+    if (next !== -1) {
+      current = next;
+    }
+    groups[current]?.push(child);
+  }
+  return groups;
+}
+
+/**
+ * Static deltas from the action payloads (minimum outputs; used when tracing is unavailable)
+ */
+async function staticActionDeltas(plan: SimulatedPlan, value: bigint, accounts: Record<string, 'user' | 'router'>): Promise<DeltaBook[]> {
+  const user = plan.user.toLowerCase();
+  const router = EXECUTION_ROUTER_ADDRESS!.toLowerCase();
+
+  const books: DeltaBook[] = [];
+  for (const action of plan.actions) {
+    const book = new DeltaBook(accounts);
+    try {
+      if (action.actionType === 2) {
+        const [token, from, amount] = await decodeActionData(PULL_LAYOUT, action.data);
+        book.move(token, from, router, amount);
+      } else if (action.actionType === 0) {
+        const [tokenIn, tokenOut, , amountIn, amountOutMin, recipient] = await decodeActionData(SWAP_LAYOUT, action.data);
+        book.add(router, tokenIn, -amountIn);
+        book.add(recipient, tokenOut, amountOutMin);
+      } else if (action.actionType === 3) {
+        const [asset, , amount] = await decodeActionData(LEND_LAYOUT, action.data);
+        book.add(router, asset, -amount);
+      } else if (action.actionType === 1) {
+        const [recipient] = await decodeActionData(WRAP_LAYOUT, action.data);
+        book.add(user, 'eth', -value);
+        if (WETH_ADDRESS_SEPOLIA) {
+          book.add(recipient, WETH_ADDRESS_SEPOLIA, value);
+        }
+      }
+    } catch {
+      // Undecodable payload: no static deltas for this action
+    }
+    books.push(book);
+  }
+  return books;
+}
+
+/**
+ * Simulate a plan as the user (direct) or as the relayer (session)
+ */
+export async function simulatePlan(params: {
+  plan: SimulatedPlan;
+  value?: string;
+  sessionId?: string;
+  relayerAddress?: string;
+}): Promise<PlanSimulationResult> {
+  if (!EXECUTION_ROUTER_ADDRESS) {
+    throw new Error('EXECUTION_ROUTER_ADDRESS not configured');
+  }
+
+  const { encodeFunctionData } = await import('viem');
+  const { plan, sessionId } = params;
+  const value = BigInt(params.value || '0x0');
+  const router = EXECUTION_ROUTER_ADDRESS.toLowerCase();
+  const user = plan.user.toLowerCase();
+  const accounts: Record<string, 'user' | 'router'> = { [user]: 'user', [router]: 'router' };
+
+  const planArg = {
+    user: plan.user as `0x${string}`,
+    nonce: BigInt(plan.nonce),
+    deadline: BigInt(plan.deadline),
+    actions: plan.actions.map(a => ({
+      actionType: a.actionType,
+      adapter: a.adapter as `0x${string}`,
+      data: a.data as `0x${string}`,
+    })),
+  };
+
+  const from = (sessionId ? params.relayerAddress : plan.user)?.toLowerCase();
+  if (!from) {
+    throw new Error('relayerAddress is required to simulate a session plan');
+  }
+  const data = sessionId
+    ? encodeFunctionData({ abi: ROUTER_ABI, functionName: 'executeWithSession', args: [sessionId as `0x${string}`, planArg] })
+    : encodeFunctionData({ abi: ROUTER_ABI, functionName: 'executeBySender', args: [planArg] });
+  const tx = { from, to: router, data, value: '0x' + value.toString(16) };
+
+  const warnings: string[] = [];
+  const baseActions = plan.actions.map((action, index) => ({
+    index,
+    actionType: action.actionType,
+    actionName: ACTION_NAMES[action.actionType] || `UNKNOWN_${action.actionType}`,
+    adapter: action.adapter.toLowerCase(),
+  }));
+
+  // Preferred: full call trace
+  const trace = await rpcRequest('debug_traceCall', [tx, 'latest', { tracer: 'callTracer', tracerConfig: { withLog: true } }]);
+  if (trace.result) {
+    const root = trace.result;
+    const groups = groupFramesByAction(root, plan.actions);
+    const failed = findError(root);
+
+    const actions: SimulatedAction[] = [];
+    for (const [i, frames] of groups.entries()) {
+      const book = new DeltaBook(accounts);
+      if (i === 0 && root.value && BigInt(root.value) > 0n) {
+        book.move('eth', root.from, root.to, BigInt(root.value));
+      }
+      if (i === 0) {
+        applyFrame({ logs: root.logs }, book);
+      }
+      frames.forEach(frame => applyFrame(frame, book));
+      const actionFailed = frames.map(findError).find(Boolean);
+      actions.push({
+        ...baseActions[i],
+        deltas: await describeDeltas(book.entries()),
+        error: actionFailed ? actionFailed.revertReason || actionFailed.error : undefined,
+      });
+    }
+
+    const net = new DeltaBook(accounts);
+    applyFrame(root, net);
+
+    let revert: PlanSimulationResult['revert'];
+    if (root.error) {
+      const decoded = await decodeRevert(root.output, root.revertReason || root.error);
+      const actionIndex = actions.findIndex(action => action.error);
+      revert = { ...decoded, actionIndex: actionIndex === -1 ? undefined : actionIndex };
+    } else if (failed) {
+      warnings.push(`Inner call reverted and was handled: ${failed.revertReason || failed.error}`);
+    }
+
+    return {
+      success: !root.error,
+      method: 'trace',
+      from,
+      to: router,
+      gasUsed: root.gasUsed ? BigInt(root.gasUsed).toString() : undefined,
+      revert,
+      // A reverted plan moves nothing
+      actions: root.error ? actions.map(action => ({ ...action, deltas: [] })) : actions,
+      netDeltas: root.error ? [] : await describeDeltas(net.entries()),
+      warnings,
+    };
+  }
+
+  // Fallback: eth_call verdict + static deltas
+  warnings.push(`debug_traceCall unavailable (${trace.error?.message || 'no result'}); deltas are decoded from the plan and use minimum outputs`);
+  const call = await rpcRequest('eth_call', [tx, 'latest']);
+  const gas = call.error ? null : await rpcRequest('eth_estimateGas', [tx]);
+
+  let revert: PlanSimulationResult['revert'];
+  if (call.error) {
+    revert = await decodeRevert(call.error.data?.data ?? call.error.data, call.error.message || 'execution reverted');
+  }
+
+  const books = await staticActionDeltas(plan, value, accounts);
+  const net = new DeltaBook(accounts);
+  const actions: SimulatedAction[] = [];
+  for (const [i, book] of books.entries()) {
+    const entries = book.entries();
+    entries.forEach(entry => net.add(entry.account === 'user' ? user : router, entry.token, entry.amount));
+    actions.push({ ...baseActions[i], deltas: revert ? [] : await describeDeltas(entries) });
+  }
+
+  return {
+    success: !call.error,
+    method: 'call',
+    from,
+    to: router,
+    gasUsed: gas?.result ? BigInt(gas.result).toString() : undefined,
+    revert,
+    actions,
+    netDeltas: revert ? [] : await describeDeltas(net.entries()),
+    warnings,
+  };
+}
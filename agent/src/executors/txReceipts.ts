@@ -0,0 +1,62 @@
+/**
+ * Transaction Receipt Lookup
+ * Chain-agnostic, non-blocking receipt fetch for txs recorded in the ledger
+ */
+
+export type TxLookupStatus = 'success' | 'reverted' | 'pending' | 'not_found';
+
+export interface TxLookupResult {
+  status: TxLookupStatus;
+  blockNumber?: number;     // EVM block number or Solana slot
+  gasUsed?: string;
+  finalized?: boolean;      // Solana only: reached finalized commitment
+  error?: string;
+}
+
+/**
+ * Fetch the current on-chain status of a tx without waiting
+ * pending = known to the node but not yet included; not_found = unknown (dropped or never broadcast)
+ */
+export async function lookupTxReceipt(chain: 'ethereum' | 'solana', txHash: string): Promise<TxLookupResult> {
+  if (chain === 'solana') {
+    const { createSolanaClient } = await import('../solana/solanaClient');
+    const [status] = await createSolanaClient().getSignatureStatuses([txHash]);
+    if (!status) {
+      return { status: 'not_found' };
+    }
+    if (status.err) {
+      return { status: 'reverted', blockNumber: status.slot, error: JSON.stringify(status.err) };
+    }
+    if (status.confirmationStatus === 'confirmed' || status.confirmationStatus === 'finalized') {
+      return { status: 'success', blockNumber: status.slot, finalized: status.confirmationStatus === 'finalized' };
+    }
+    return { status: 'pending', blockNumber: status.slot };
+  }
+
+  const { createFailoverPublicClient } = await import('../providers/rpcProvider');
+  const publicClient = createFailoverPublicClient();
+  const hash = txHash as `0x${string}`;
+
+  try {
+    const receipt = await publicClient.getTransactionReceipt({ hash });
+    return {
+      status: receipt.status === 'success' ? 'success' : 'reverted',
+      blockNumber: Number(receipt.blockNumber),
+      gasUsed: receipt.gasUsed.toString(),
+    };
+  } catch (error: any) {
+    if (error.name !== 'TransactionReceiptNotFoundError') {
+      throw error;
+    }
+  }
+
+  try {
+    await publicClient.getTransaction({ hash });
+    return { status: 'pending' };
+  } catch (error: any) {
+    if (error.name === 'TransactionNotFoundError') {
+      return { status: 'not_found' };
+    }
+    throw error;
+  }
+}
@@ -0,0 +1,343 @@
+/**
+ * Event Indexer Reorg Tests
+ * Drives the indexer with an in-memory chain that can fork - no RPC access.
+ * The ledger runs against an in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
+
+vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+});
+
+vi.mock('../../config', () => ({
+  INDEXER_NETWORK: 'sepolia',
+  INDEXER_POLL_INTERVAL_MS: 1000,
+  INDEXER_MAX_BLOCKS_PER_POLL: 100,
+  INDEXER_START_BLOCK: 1,
+  INDEXER_CONFIRMATION_DEPTH: 6,
+  EXECUTION_ROUTER_ADDRESS: undefined,
+  PROOF_ADAPTER_ADDRESS: undefined,
+  DEMO_PERP_ENGINE_ADDRESS: undefined,
+  AAVE_ADAPTER_ADDRESS: undefined,
+  AAVE_POOL_ADDRESS_SEPOLIA: undefined,
+  DEMO_LEND_VAULT_ADDRESS: undefined,
+}));
+
+import {
+  registerIndexedContract,
+  unregisterIndexedContract,
+  runIndexerPoll,
+  getLastIndexerPoll,
+  type IndexerClient,
+} from '../eventIndexer';
+import { perpEngineContract } from '../perpIndexer';
+import { routerContract } from '../contracts';
+import {
+  getDatabase,
+  listSessions,
+  getPositionByOnChainId,
+  getIndexerState,
+  listIndexerBlocks,
+  listIndexedEvents,
+} from '../../../execution-ledger/db';
+
+const ENGINE = '0x00000000000000000000000000000000000000e1';
+const ROUTER = '0x00000000000000000000000000000000000000f1';
+const USER = '0x00000000000000000000000000000000000000a1';
+
+interface MockLog {
+  address?: string;             // Defaults to the perp engine
+  eventName: string;
+  args: Record<string, any>;
+  txHash: string;
+}
+
+interface MockBlock {
+  number: number;
+  hash: string;
+  logs: MockLog[];
+}
+
+/**
+ * Minimal chain: a list of blocks on the current branch
+ * fork(n) drops block n and everything above; later blocks get new hashes
+ */
+class MockForkChain implements IndexerClient {
+  blocks: MockBlock[] = [];
+  private branch = 0;
+  beforeGetLogs?: () => void;
+
+  constructor() {
+    this.mine();
+  }
+
+  mine(logs: MockLog[] = []): number {
+    const number = this.blocks.length;
+    const hash = `0x${this.branch.toString(16).padStart(2, '0')}${number.toString(16).padStart(62, '0')}`;
+    this.blocks.push({ number, hash, logs });
+    return number;
+  }
+
+  mineEmpty(count: number): void {
+    for (let i = 0; i < count; i++) this.mine();
+  }
+
+  fork(fromBlock: number): void {
+    this.blocks = this.blocks.slice(0, fromBlock);
+    this.branch++;
+  }
+
+  async getBlockNumber(): Promise<bigint> {
+    return BigInt(this.blocks.length - 1);
+  }
+
+  async getBlock({ blockNumber }: { blockNumber: bigint }) {
+    const block = this.blocks[Number(blockNumber)];
+    return { hash: block?.hash ?? null, number: block ? BigInt(block.number) : null };
+  }
+
+  async getLogs({ address, fromBlock, toBlock }: { address: string; fromBlock: bigint; toBlock: bigint }) {
+    this.beforeGetLogs?.();
+    this.beforeGetLogs = undefined;
+    return this.blocks
+      .filter(block => block.number >= Number(fromBlock) && block.number <= Number(toBlock))
+      .flatMap(block => block.logs.map((log, logIndex) => ({ log, logIndex })))
+      .filter(({ log }) => (log.address ?? ENGINE) === address.toLowerCase())
+      .map(({ log, logIndex }) => ({
+        eventName: log.eventName,
+        args: log.args,
+        address: log.address ?? ENGINE,
+        blockNumber: BigInt(block.number),
+        blockHash: block.hash,
+        transactionHash: log.txHash,
+        logIndex,
+      }));
+  }
+}
+
+function opened(positionId: number): MockLog {
+  return {
+    eventName: 'PositionOpened',
+    txHash: `0xopen${positionId}`,
+    args: {
+      user: USER,
+      positionId: BigInt(positionId),
+      market: 0,
+      side: 0,
+      margin: 100_000_000n,
+      size: 500_000_000n,
+      leverage: 5n,
+      entryPrice: 60_000_000_000n,
+    },
+  };
+}
+
+function closed(positionId: number): MockLog {
+  return {
+    eventName: 'PositionClosed',
+    txHash: `0xclose${positionId}`,
+    args: { user: USER, positionId: BigInt(positionId), exitPrice: 61_000_000_000n, pnl: 8_000_000n, marginReturned: 108_000_000n },
+  };
+}
+
+function sessionCreated(sessionNumber: number): MockLog {
+  return {
+    address: ROUTER,
+    eventName: 'SessionCreated',
+    txHash: `0xsession${sessionNumber}`,
+    args: {
+      sessionId: `0x${String(sessionNumber).padStart(64, '0')}`,
+      owner: USER,
+      executor: '0x00000000000000000000000000000000000000b2',
+      expiresAt: 2_000_000_000n,
+      maxSpend: 10n,
+    },
+  };
+}
+
+function sessionRevoked(sessionNumber: number): MockLog {
+  return {
+    address: ROUTER,
+    eventName: 'SessionRevoked',
+    txHash: `0xrevoke${sessionNumber}`,
+    args: { sessionId: `0x${String(sessionNumber).padStart(64, '0')}`, owner: USER },
+  };
+}
+
+function session(sessionNumber: number) {
+  const sessionId = `0x${String(sessionNumber).padStart(64, '0')}`;
+  return listSessions({ chain: 'ethereum', network: 'sepolia' }).find(s => s.session_id === sessionId);
+}
+
+function position(positionId: number) {
+  return getPositionByOnChainId('ethereum', 'sepolia', 'demo_perp', String(positionId));
+}
+
+describe('eventIndexer reorg handling', () => {
+  let chain: MockForkChain;
+
+  beforeEach(() => {
+    getDatabase().exec(`
+      DELETE FROM positions;
+      DELETE FROM sessions;
+      DELETE FROM indexed_events;
+      DELETE FROM indexer_blocks;
+      DELETE FROM indexer_state;
+    `);
+    registerIndexedContract(perpEngineContract(ENGINE));
+    chain = new MockForkChain();
+  });
+
+  afterEach(() => {
+    unregisterIndexedContract('demo_perp_engine');
+    unregisterIndexedContract('execution_router');
+  });
+
+  it('indexes opens and closes with their block numbers', async () => {
+    chain.mineEmpty(1);
+    chain.mine([opened(1)]);
+    chain.mine([closed(1)]);
+    chain.mineEmpty(2);
+
+    await runIndexerPoll(chain);
+
+    const pos = position(1);
+    expect(pos?.status).toBe('closed');
+    expect(pos?.open_block_number).toBe(2);
+    expect(pos?.close_block_number).toBe(3);
+    expect(getIndexerState('ethereum', 'sepolia', ENGINE)?.last_indexed_block).toBe(5);
+    expect(getLastIndexerPoll()?.reorg).toBeUndefined();
+  });
+
+  it('removes a position whose open block was orphaned', async () => {
+    chain.mineEmpty(1);
+    chain.mine([opened(1)]);   // block 2 - survives
+    chain.mineEmpty(1);
+    chain.mine([opened(2)]);   // block 4 - orphaned
+    chain.mineEmpty(1);
+    await runIndexerPoll(chain);
+    expect(position(2)).not.toBeNull();
+
+    chain.fork(4);
+    chain.mineEmpty(3);
+    await runIndexerPoll(chain);
+
+    expect(position(1)?.status).toBe('open');
+    expect(position(2)).toBeNull();
+    expect(listIndexedEvents({ eventName: 'PositionOpened' })).toHaveLength(1);
+
+    const reorg = getLastIndexerPoll()?.reorg;
+    expect(reorg?.detectedAt).toBe(4);
+    expect(reorg?.ancestorBlock).toBe(2);
+    expect(reorg?.positionsRemoved).toBe(1);
+    expect(getIndexerState('ethereum', 'sepolia', ENGINE)?.last_indexed_block).toBe(6);
+  });
+
+  it('reopens a position whose close block was orphaned', async () => {
+    chain.mine([opened(1)]);   // block 1
+    chain.mineEmpty(2);
+    chain.mine([closed(1)]);   // block 4 - orphaned
+    await runIndexerPoll(chain);
+    expect(position(1)?.status).toBe('closed');
+
+    chain.fork(4);
+    chain.mineEmpty(2);
+    await runIndexerPoll(chain);
+
+    const pos = position(1);
+    expect(pos?.status).toBe('open');
+    expect(pos?.closed_at).toBeFalsy();
+    expect(pos?.close_tx_hash).toBeFalsy();
+    expect(pos?.close_block_number).toBeFalsy();
+    expect(getLastIndexerPoll()?.reorg?.positionsReopened).toBe(1);
+  });
+
+  it('reverts sessions created or revoked in orphaned blocks', async () => {
+    registerIndexedContract(routerContract(ROUTER));
+    chain.mine([sessionCreated(1)]);                      // block 1 - survives
+    chain.mineEmpty(2);
+    chain.mine([sessionRevoked(1), sessionCreated(2)]);   // block 4 - orphaned
+    await runIndexerPoll(chain);
+    expect(session(1)).toMatchObject({ status: 'revoked', created_block_number: 1, revoked_block_number: 4 });
+    expect(session(2)).toMatchObject({ status: 'active', created_block_number: 4 });
+
+    chain.fork(4);
+    chain.mineEmpty(2);
+    await runIndexerPoll(chain);
+
+    expect(session(1)).toMatchObject({ status: 'active', created_block_number: 1, revoked_block_number: null });
+    expect(session(2)).toMatchObject({ status: 'preparing', created_block_number: null });
+    expect(getLastIndexerPoll()?.reorg).toMatchObject({ sessionsUnrevoked: 1, sessionsReverted: 1 });
+  });
+
+  it('re-indexes events that moved to a different block on the new branch', async () => {
+    chain.mineEmpty(2);
+    chain.mine([opened(1)]);   // block 3
+    chain.mine([closed(1)]);   // block 4
+    await runIndexerPoll(chain);
+
+    chain.fork(3);
+    chain.mineEmpty(1);
+    chain.mine([opened(1)]);   // block 4
+    chain.mineEmpty(1);
+    chain.mine([closed(1)]);   // block 6
+    await runIndexerPoll(chain);
+
+    const pos = position(1);
+    expect(pos?.status).toBe('closed');
+    expect(pos?.open_block_number).toBe(4);
+    expect(pos?.close_block_number).toBe(6);
+    expect(listIndexedEvents({})).toHaveLength(2);
+  });
+
+  it('detects a reorg to a shorter chain', async () => {
+    chain.mine([opened(1)]);   // block 1
+    chain.mineEmpty(3);
+    chain.mine([opened(2)]);   // block 5 - orphaned
+    await runIndexerPoll(chain);
+
+    chain.fork(4);
+    chain.mine();              // new head is block 4
+    await runIndexerPoll(chain);
+
+    expect(position(1)).not.toBeNull();
+    expect(position(2)).toBeNull();
+    expect(getIndexerState('ethereum', 'sepolia', ENGINE)?.last_indexed_block).toBe(4);
+  });
+
+  it('does not advance the cursor when the chain reorganizes mid-poll', async () => {
+    chain.mineEmpty(2);
+    chain.mine([opened(1)]);   // block 3 (head)
+
+    // The head is replaced between reading its hash and reading logs
+    chain.beforeGetLogs = () => {
+      chain.fork(3);
+      chain.mine([opened(1)]);
+    };
+    const results = await runIndexerPoll(chain);
+
+    expect(results[0].error).toMatch(/hash changed mid-poll/);
+    expect(getIndexerState('ethereum', 'sepolia', ENGINE)).toBeNull();
+    expect(position(1)).toBeNull();
+
+    await runIndexerPoll(chain);
+    expect(position(1)?.open_block_number).toBe(3);
+  });
+
+  it('only keeps hashes within the confirmation depth', async () => {
+    chain.mine([opened(1)]);   // block 1
+    chain.mineEmpty(19);       // head 20, blocks below 14 are final
+    await runIndexerPoll(chain);
+
+    expect(listIndexerBlocks('ethereum', 'sepolia', 0).map(b => b.block_number)).toEqual([20]);
+
+    // A fork below the confirmation depth is treated as final
+    chain.fork(1);
+    chain.mineEmpty(21);
+    await runIndexerPoll(chain);
+
+    expect(position(1)).not.toBeNull();
+    expect(getLastIndexerPoll()?.reorg?.ancestorBlock).toBe(14);
+  });
+});
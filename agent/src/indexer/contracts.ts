@@ -0,0 +1,123 @@
+/**
+ * Indexed Contract Registry
+ * Builds event indexer entries for every configured Blossom contract
+ *
+ * - ExecutionRouter: actions plus session lifecycle (the router is the session manager)
+ * - ProofOfExecutionAdapter: proof records for perp/event intents
+ * - DemoPerpEngine: positions (see perpIndexer)
+ * - Aave V3 Pool: supplies/withdrawals made through the Aave adapter
+ * - DemoLendVault: ERC-4626 deposits and withdrawals
+ */
+
+import { parseAbiItem } from 'viem';
+import {
+  EXECUTION_ROUTER_ADDRESS,
+  PROOF_ADAPTER_ADDRESS,
+  DEMO_PERP_ENGINE_ADDRESS,
+  AAVE_ADAPTER_ADDRESS,
+  AAVE_POOL_ADDRESS_SEPOLIA,
+  DEMO_LEND_VAULT_ADDRESS,
+} from '../config';
+import { upsertLedgerSession } from '../ledger/ledger';
+import { registerIndexedContract, type IndexedContract } from './eventIndexer';
+import { perpEngineContract } from './perpIndexer';
+
+const ACTION_EXECUTED = parseAbiItem(
+  'event ActionExecuted(bytes32 planHash, uint256 index, uint8 actionType, address adapter)'
+);
+const SESSION_CREATED = parseAbiItem(
+  'event SessionCreated(bytes32 indexed sessionId, address indexed owner, address indexed executor, uint64 expiresAt, uint256 maxSpend)'
+);
+const SESSION_REVOKED = parseAbiItem(
+  'event SessionRevoked(bytes32 indexed sessionId, address indexed owner)'
+);
+const PROOF_RECORDED = parseAbiItem(
+  'event ProofRecorded(address user, uint8 venueType, bytes32 intentHash, string summary, uint256 timestamp)'
+);
+const AAVE_SUPPLY = parseAbiItem(
+  'event Supply(address indexed reserve, address user, address indexed onBehalfOf, uint256 amount, uint16 indexed referralCode)'
+);
+const AAVE_WITHDRAW = parseAbiItem(
+  'event Withdraw(address indexed reserve, address indexed user, address indexed to, uint256 amount)'
+);
+const VAULT_DEPOSIT = parseAbiItem(
+  'event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares)'
+);
+const VAULT_WITHDRAW = parseAbiItem(
+  'event Withdraw(address indexed sender, address indexed receiver, address indexed owner, uint256 assets, uint256 shares)'
+);
+
+export function routerContract(address: string): IndexedContract {
+  return {
+    name: 'execution_router',
+    address,
+    events: [ACTION_EXECUTED, SESSION_CREATED, SESSION_REVOKED],
+    handle: async (log, ctx) => {
+      if (log.eventName === 'SessionCreated') {
+        await upsertLedgerSession({
+          chain: ctx.chain,
+          network: ctx.network,
+          userAddress: log.args.owner,
+          sessionId: log.args.sessionId,
+          relayerAddress: log.args.executor,
+          status: 'active',
+          expiresAt: Number(log.args.expiresAt),
+          createdTx: log.transactionHash,
+          blockNumber: Number(log.blockNumber),
+        });
+      } else if (log.eventName === 'SessionRevoked') {
+        await upsertLedgerSession({
+          chain: ctx.chain,
+          network: ctx.network,
+          userAddress: log.args.owner,
+          sessionId: log.args.sessionId,
+          status: 'revoked',
+          blockNumber: Number(log.blockNumber),
+        });
+      }
+    },
+  };
+}
+
+function aavePoolContract(poolAddress: string, adapterAddress: string): IndexedContract {
+  const adapter = adapterAddress.toLowerCase();
+  return {
+    name: 'aave_adapter',
+    address: poolAddress,
+    events: [AAVE_SUPPLY, AAVE_WITHDRAW],
+    // The pool is shared; keep only calls made by our adapter
+    filter: log => String(log.args.user || '').toLowerCase() === adapter,
+  };
+}
+
+/**
+ * Build registry entries for the contracts present in config
+ */
+export function getConfiguredContracts(): IndexedContract[] {
+  const contracts: IndexedContract[] = [];
+  if (EXECUTION_ROUTER_ADDRESS) {
+    contracts.push(routerContract(EXECUTION_ROUTER_ADDRESS));
+  }
+  if (PROOF_ADAPTER_ADDRESS) {
+    contracts.push({ name: 'proof_adapter', address: PROOF_ADAPTER_ADDRESS, events: [PROOF_RECORDED] });
+  }
+  if (DEMO_PERP_ENGINE_ADDRESS) {
+    contracts.push(perpEngineContract(DEMO_PERP_ENGINE_ADDRESS));
+  }
+  if (AAVE_ADAPTER_ADDRESS && AAVE_POOL_ADDRESS_SEPOLIA) {
+    contracts.push(aavePoolContract(AAVE_POOL_ADDRESS_SEPOLIA, AAVE_ADAPTER_ADDRESS));
+  }
+  if (DEMO_LEND_VAULT_ADDRESS) {
+    contracts.push({ name: 'demo_lend_vault', address: DEMO_LEND_VAULT_ADDRESS, events: [VAULT_DEPOSIT, VAULT_WITHDRAW] });
+  }
+  return contracts;
+}
+
+/**
+ * Register every configured contract with the event indexer
+ */
+export function registerConfiguredContracts(): IndexedContract[] {
+  const contracts = getConfiguredContracts();
+  contracts.forEach(registerIndexedContract);
+  return contracts;
+}
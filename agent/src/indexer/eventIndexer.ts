@@ -0,0 +1,395 @@
+/**
+ * Contract Event Indexer
+ *
+ * Polls a registry of (contract, events, handler) entries and syncs decoded
+ * logs to the ledger. Every log is stored in indexed_events; entries that
+ * drive ledger state (positions, sessions) also supply a handler.
+ *
+ * Each contract has its own cursor in indexer_state, so a contract added later
+ * backfills from its start block without holding the others back.
+ *
+ * Reorgs: hashes of blocks the indexer has read within INDEXER_CONFIRMATION_DEPTH
+ * of head are kept in indexer_blocks. Each poll first re-checks those hashes
+ * against the chain; on a mismatch everything derived from blocks after the
+ * last matching block (events, positions, closes, cursors) is rolled back and
+ * re-indexed from the canonical chain. Blocks deeper than the confirmation
+ * depth are treated as final.
+ */
+
+import type { AbiEvent } from 'viem';
+import {
+  INDEXER_NETWORK,
+  INDEXER_POLL_INTERVAL_MS,
+  INDEXER_MAX_BLOCKS_PER_POLL,
+  INDEXER_START_BLOCK,
+  INDEXER_CONFIRMATION_DEPTH,
+} from '../config';
+import {
+  getIndexerState,
+  upsertIndexerState,
+  recordIndexedEvent,
+  recordIndexerBlock,
+  listIndexerBlocks,
+  pruneIndexerBlocks,
+  rollbackIndexerToBlock,
+  type Chain,
+  type Network,
+} from '../ledger/ledger';
+
+export interface IndexedLog {
+  eventName: string;
+  args: Record<string, any>;
+  address: string;
+  blockNumber: bigint;
+  blockHash: string;
+  transactionHash: string;
+  logIndex: number;
+}
+
+export interface IndexerContext {
+  chain: Chain;
+  network: Network;
+  contract: IndexedContract;
+}
+
+export interface IndexedContract {
+  name: string;                 // Registry key; stored on indexed_events rows
+  address: string;
+  events: readonly AbiEvent[];
+  startBlock?: number;          // First block when no cursor exists (default INDEXER_START_BLOCK)
+  filter?: (log: IndexedLog) => boolean;   // Drop logs that aren't ours (e.g. shared pools)
+  handle?: (log: IndexedLog, ctx: IndexerContext) => Promise<void>;
+}
+
+// Subset of viem's PublicClient the indexer uses
+export interface IndexerClient {
+  getBlockNumber(): Promise<bigint>;
+  getBlock(args: { blockNumber: bigint }): Promise<{ hash: string | null; number: bigint | null }>;
+  getLogs(args: {
+    address: `0x${string}`;
+    events: readonly AbiEvent[];
+    fromBlock: bigint;
+    toBlock: bigint;
+  }): Promise<any[]>;
+}
+
+export interface ContractPollResult {
+  name: string;
+  fromBlock?: number;
+  toBlock?: number;
+  logs: number;
+  newEvents: number;
+  error?: string;
+}
+
+export interface ReorgResult {
+  detectedAt: number;           // First stored block whose hash no longer matches
+  ancestorBlock: number;        // Last block kept; everything above was rolled back
+  eventsRemoved: number;
+  positionsRemoved: number;
+  positionsReopened: number;
+  sessionsUnrevoked: number;
+  sessionsReverted: number;
+  cursorsRewound: number;
+}
+
+const CHAIN: Chain = 'ethereum';
+
+const registry = new Map<string, IndexedContract>();
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+let lastPoll: { ranAt: number; head: number; reorg?: ReorgResult; contracts: ContractPollResult[] } | null = null;
+let lastReorg: (ReorgResult & { ranAt: number }) | null = null;
+
+/**
+ * Block hashes seen during one poll
+ * Fetched once per block; a log disagreeing with an earlier hash means the
+ * chain reorganized mid-poll
+ */
+export class BlockHashes {
+  private hashes = new Map<bigint, string>();
+
+  constructor(private client: IndexerClient) {}
+
+  async get(blockNumber: bigint): Promise<string> {
+    const cached = this.hashes.get(blockNumber);
+    if (cached) return cached;
+    const block = await this.client.getBlock({ blockNumber });
+    if (!block?.hash) {
+      throw new Error(`Block ${blockNumber} not available`);
+    }
+    return this.observe(blockNumber, block.hash);
+  }
+
+  observe(blockNumber: bigint, hash: string): string {
+    const normalized = hash.toLowerCase();
+    const cached = this.hashes.get(blockNumber);
+    if (cached && cached !== normalized) {
+      throw new Error(`Block ${blockNumber} hash changed mid-poll (${cached.slice(0, 10)} -> ${normalized.slice(0, 10)})`);
+    }
+    this.hashes.set(blockNumber, normalized);
+    return normalized;
+  }
+}
+
+function finalizedBelow(head: bigint): bigint {
+  const boundary = head - BigInt(INDEXER_CONFIRMATION_DEPTH);
+  return boundary > 0n ? boundary : 0n;
+}
+
+/**
+ * Add (or replace) a contract in the registry
+ */
+export function registerIndexedContract(contract: IndexedContract): void {
+  registry.set(contract.name, { ...contract, address: contract.address.toLowerCase() });
+}
+
+/**
+ * Remove a contract from the registry (its cursor is kept)
+ */
+export function unregisterIndexedContract(name: string): boolean {
+  return registry.delete(name);
+}
+
+export function getIndexedContracts(): IndexedContract[] {
+  return Array.from(registry.values());
+}
+
+/**
+ * Compare stored hashes of unconfirmed blocks against the chain and roll back
+ * to the last matching block if any of them were orphaned
+ */
+export async function checkForReorg(
+  client: IndexerClient,
+  head: bigint,
+  network: Network = INDEXER_NETWORK,
+  hashes: BlockHashes = new BlockHashes(client)
+): Promise<ReorgResult | null> {
+  const windowStart = finalizedBelow(head);
+  const stored = await listIndexerBlocks(CHAIN, network, Number(windowStart));
+
+  let lastMatch: number | null = null;
+  for (const block of stored) {
+    const blockNumber = BigInt(block.block_number);
+    // A stored block above head was dropped by a reorg to a shorter chain
+    const canonical = blockNumber <= head ? await hashes.get(blockNumber) : null;
+    if (canonical === block.block_hash.toLowerCase()) {
+      lastMatch = block.block_number;
+      continue;
+    }
+
+    // Unrecorded blocks between the last match and this one may also have
+    // changed, so keep only what is known to be canonical
+    const ancestorBlock = lastMatch ?? Math.max(Number(windowStart) - 1, 0);
+    const counts = await rollbackIndexerToBlock(CHAIN, network, ancestorBlock);
+    const reorg: ReorgResult = { detectedAt: block.block_number, ancestorBlock, ...counts };
+    lastReorg = { ...reorg, ranAt: Math.floor(Date.now() / 1000) };
+    console.warn(
+      `[indexer] Reorg detected at block ${block.block_number}; rolled back to ${ancestorBlock} ` +
+      `(${counts.eventsRemoved} events, ${counts.positionsRemoved} positions removed, ${counts.positionsReopened} reopened, ` +
+      `${counts.sessionsUnrevoked + counts.sessionsReverted} sessions reverted)`
+    );
+    return reorg;
+  }
+  return null;
+}
+
+/**
+ * Index one contract from its cursor towards head
+ */
+export async function indexContract(
+  client: IndexerClient,
+  contract: IndexedContract,
+  head: bigint,
+  network: Network = INDEXER_NETWORK,
+  hashes: BlockHashes = new BlockHashes(client)
+): Promise<ContractPollResult> {
+  const state = await getIndexerState(CHAIN, network, contract.address);
+  const startBlock = BigInt(contract.startBlock ?? INDEXER_START_BLOCK);
+  const cursor = state ? BigInt(state.last_indexed_block) : startBlock - 1n;
+
+  if (cursor >= head) {
+    return { name: contract.name, logs: 0, newEvents: 0 };
+  }
+
+  const fromBlock = cursor + 1n;
+  const maxToBlock = cursor + BigInt(INDEXER_MAX_BLOCKS_PER_POLL);
+  const toBlock = maxToBlock > head ? head : maxToBlock;
+  const finalized = finalizedBelow(head);
+
+  // Pin the range end before reading logs so a reorg in between is caught below
+  const toBlockHash = toBlock >= finalized ? await hashes.get(toBlock) : null;
+
+  const rawLogs = await client.getLogs({
+    address: contract.address as `0x${string}`,
+    events: contract.events,
+    fromBlock,
+    toBlock,
+  });
+
+  // Remember hashes of every unconfirmed block we derived state from
+  const unconfirmed = new Map<bigint, string>();
+  if (toBlockHash) unconfirmed.set(toBlock, toBlockHash);
+  for (const log of rawLogs) {
+    if (log.blockNumber === null || !log.blockHash) continue;
+    const blockNumber = BigInt(log.blockNumber);
+    if (blockNumber >= finalized) {
+      unconfirmed.set(blockNumber, hashes.observe(blockNumber, log.blockHash));
+    }
+  }
+
+  const logs: IndexedLog[] = rawLogs
+    .filter(log => log.eventName && log.blockNumber !== null)
+    .map(log => ({
+      eventName: log.eventName,
+      args: log.args || {},
+      address: log.address,
+      blockNumber: BigInt(log.blockNumber),
+      blockHash: log.blockHash,
+      transactionHash: log.transactionHash,
+      logIndex: Number(log.logIndex),
+    }))
+    .filter(log => !contract.filter || contract.filter(log))
+    .sort((a, b) => Number(a.blockNumber - b.blockNumber) || a.logIndex - b.logIndex);
+
+  const ctx: IndexerContext = { chain: CHAIN, network, contract };
+  let newEvents = 0;
+  for (const log of logs) {
+    const inserted = await recordIndexedEvent({
+      chain: CHAIN,
+      network,
+      contractName: contract.name,
+      contractAddress: contract.address,
+      eventName: log.eventName,
+      blockNumber: Number(log.blockNumber),
+      txHash: log.transactionHash,
+      logIndex: log.logIndex,
+      args: log.args,
+    });
+    if (inserted) newEvents++;
+
+    if (contract.handle) {
+      try {
+        await contract.handle(log, ctx);
+      } catch (err: any) {
+        console.error(`[indexer] ${contract.name} ${log.eventName} handler error:`, err.message);
+      }
+    }
+  }
+
+  for (const [blockNumber, hash] of unconfirmed) {
+    await recordIndexerBlock(CHAIN, network, Number(blockNumber), hash);
+  }
+  await upsertIndexerState(CHAIN, network, contract.address, Number(toBlock));
+
+  if (newEvents > 0) {
+    console.log(`[indexer] ${contract.name}: ${newEvents} new event(s) in blocks ${fromBlock}-${toBlock}`);
+  }
+  return { name: contract.name, fromBlock: Number(fromBlock), toBlock: Number(toBlock), logs: logs.length, newEvents };
+}
+
+/**
+ * Single poll over every registered contract
+ * One contract failing (bad ABI, RPC range limits) doesn't hold back the others
+ */
+export async function runIndexerPoll(client: IndexerClient): Promise<ContractPollResult[]> {
+  const head = await client.getBlockNumber();
+  const hashes = new BlockHashes(client);
+  const results: ContractPollResult[] = [];
+
+  const reorg = await checkForReorg(client, head, INDEXER_NETWORK, hashes);
+
+  for (const contract of registry.values()) {
+    try {
+      results.push(await indexContract(client, contract, head, INDEXER_NETWORK, hashes));
+    } catch (err: any) {
+      console.error(`[indexer] ${contract.name} poll error:`, err.message?.slice(0, 100));
+      results.push({ name: contract.name, logs: 0, newEvents: 0, error: err.message?.slice(0, 200) });
+    }
+  }
+
+  await pruneIndexerBlocks(CHAIN, INDEXER_NETWORK, Number(finalizedBelow(head)));
+
+  lastPoll = { ranAt: Math.floor(Date.now() / 1000), head: Number(head), reorg: reorg ?? undefined, contracts: results };
+  return results;
+}
+
+async function getClient(): Promise<IndexerClient> {
+  const { createFailoverPublicClient } = await import('../providers/rpcProvider');
+  return createFailoverPublicClient() as unknown as IndexerClient;
+}
+
+/**
+ * Start the indexer loop over the registered contracts
+ */
+export async function startEventIndexer(intervalMs: number = INDEXER_POLL_INTERVAL_MS): Promise<void> {
+  if (isRunning) {
+    console.log('[indexer] Already running');
+    return;
+  }
+
+  if (registry.size === 0) {
+    console.log('[indexer] No contracts registered, skipping');
+    return;
+  }
+
+  console.log(`[indexer] Starting event indexer (${Array.from(registry.keys()).join(', ')})`);
+  isRunning = true;
+  const client = await getClient();
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runIndexerPoll(client);
+    } catch (err: any) {
+      console.error('[indexer] Poll error:', err.message?.slice(0, 100));
+    }
+
+    // Schedule next poll
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  poll();
+}
+
+/**
+ * Stop the indexer loop
+ */
+export function stopEventIndexer(): void {
+  console.log('[indexer] Stopping event indexer');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the indexer is running
+ */
+export function isEventIndexerRunning(): boolean {
+  return isRunning;
+}
+
+/**
+ * Result of the most recent poll (null before the first one)
+ */
+export function getLastIndexerPoll() {
+  return lastPoll;
+}
+
+/**
+ * Most recent reorg rollback since startup (null if none)
+ */
+export function getLastIndexerReorg() {
+  return lastReorg;
+}
+
+/**
+ * Manually trigger a single poll
+ */
+export async function triggerIndexerPoll(): Promise<ContractPollResult[]> {
+  return runIndexerPoll(await getClient());
+}
@@ -1,28 +1,22 @@
 /**
  * Perp Position Indexer
  *
- * Watches DemoPerpEngine + DemoPerpAdapter events on Sepolia
- * and syncs position state to the ledger database.
+ * Event indexer entry for DemoPerpEngine: syncs position state to the ledger
+ * positions table as the engine's events are indexed.
  *
  * Events indexed:
  * - PositionOpened (DemoPerpEngine)
  * - PositionClosed (DemoPerpEngine)
- * - PerpPositionOpened (DemoPerpAdapter)
- * - PerpPositionClosed (DemoPerpAdapter)
- * - MarginDeposited (DemoPerpEngine)
  * - LiquidationTriggered (DemoPerpEngine)
  */
 
-import { createPublicClient, http, parseAbiItem } from 'viem';
-import { sepolia } from 'viem/chains';
+import { parseAbiItem } from 'viem';
 import {
-  getIndexerState,
-  upsertIndexerState,
   createPosition,
   getPositionByOnChainId,
   closePosition as dbClosePosition,
-  type Position,
 } from '../ledger/ledger';
+import type { IndexedContract, IndexedLog, IndexerContext } from './eventIndexer';
 
 // Event ABIs
 const POSITION_OPENED_ABI = parseAbiItem(
@@ -50,18 +44,7 @@ const SIDE_MAP: Record<number, 'long' | 'short'> = {
   1: 'short',
 };
 
-// Indexer configuration
-const INDEXER_CONFIG = {
-  chain: 'ethereum' as const,
-  network: 'sepolia' as const,
-  venue: 'demo_perp' as const,
-  pollIntervalMs: 15000, // 15 seconds
-  maxBlocksPerPoll: 1000, // Don't index more than 1000 blocks at once
-  startBlock: 10100000, // Start from a recent block (adjust based on deployment)
-};
-
-let isRunning = false;
-let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+const VENUE = 'demo_perp';
 
 /**
  * Build explorer URL for a transaction
@@ -73,11 +56,7 @@ function buildExplorerUrl(txHash: string): string {
 /**
  * Process PositionOpened event
  */
-async function processPositionOpened(
-  log: any,
-  perpEngineAddress: string,
-  timestamp: number
-): Promise<void> {
+async function processPositionOpened(log: IndexedLog, ctx: IndexerContext): Promise<void> {
   try {
     const args = log.args;
     if (!args) return;
@@ -93,9 +72,9 @@ async function processPositionOpened(
 
     // Check if position already exists
     const existing = await getPositionByOnChainId(
-      INDEXER_CONFIG.chain,
-      INDEXER_CONFIG.network,
-      INDEXER_CONFIG.venue,
+      ctx.chain,
+      ctx.network,
+      VENUE,
       positionId
     );
 
@@ -107,9 +86,9 @@ async function processPositionOpened(
     // Create new position
     const txHash = log.transactionHash || '';
     await createPosition({
-      chain: INDEXER_CONFIG.chain,
-      network: INDEXER_CONFIG.network,
-      venue: INDEXER_CONFIG.venue,
+      chain: ctx.chain,
+      network: ctx.network,
+      venue: VENUE,
       market,
       side,
       leverage,
@@ -121,6 +100,7 @@ async function processPositionOpened(
       open_explorer_url: txHash ? buildExplorerUrl(txHash) : undefined,
       user_address: user,
       on_chain_position_id: positionId,
+      open_block_number: Number(log.blockNumber),
     });
 
     console.log(`[indexer] Indexed new position: ${market} ${side} (id=${positionId})`);
@@ -132,7 +112,7 @@ async function processPositionOpened(
 /**
  * Process PositionClosed event
  */
-async function processPositionClosed(log: any): Promise<void> {
+async function processPositionClosed(log: IndexedLog, ctx: IndexerContext): Promise<void> {
   try {
     const args = log.args;
     if (!args) return;
@@ -142,9 +122,9 @@ async function processPositionClosed(log: any): Promise<void> {
 
     // Find the position
     const position = await getPositionByOnChainId(
-      INDEXER_CONFIG.chain,
-      INDEXER_CONFIG.network,
-      INDEXER_CONFIG.venue,
+      ctx.chain,
+      ctx.network,
+      VENUE,
       positionId
     );
 
@@ -164,7 +144,8 @@ async function processPositionClosed(log: any): Promise<void> {
       txHash,
       txHash ? buildExplorerUrl(txHash) : '',
       pnl,
-      'closed'
+      'closed',
+      Number(log.blockNumber)
     );
 
     console.log(`[indexer] Closed position: ${position.market} ${position.side} (id=${positionId})`);
@@ -176,7 +157,7 @@ async function processPositionClosed(log: any): Promise<void> {
 /**
  * Process LiquidationTriggered event
  */
-async function processLiquidation(log: any): Promise<void> {
+async function processLiquidation(log: IndexedLog, ctx: IndexerContext): Promise<void> {
   try {
     const args = log.args;
     if (!args) return;
@@ -186,9 +167,9 @@ async function processLiquidation(log: any): Promise<void> {
 
     // Find the position
     const position = await getPositionByOnChainId(
-      INDEXER_CONFIG.chain,
-      INDEXER_CONFIG.network,
-      INDEXER_CONFIG.venue,
+      ctx.chain,
+      ctx.network,
+      VENUE,
       positionId
     );
 
@@ -207,7 +188,8 @@ async function processLiquidation(log: any): Promise<void> {
       txHash,
       txHash ? buildExplorerUrl(txHash) : '',
       loss,
-      'liquidated'
+      'liquidated',
+      Number(log.blockNumber)
     );
 
     console.log(`[indexer] Liquidated position: ${position.market} ${position.side} (id=${positionId})`);
@@ -217,168 +199,22 @@ async function processLiquidation(log: any): Promise<void> {
 }
 
 /**
- * Index events from a block range
+ * Registry entry for a DemoPerpEngine deployment
  */
-async function indexBlockRange(
-  client: ReturnType<typeof createPublicClient>,
-  perpEngineAddress: string,
-  fromBlock: bigint,
-  toBlock: bigint
-): Promise<void> {
-  // Get PositionOpened events
-  const openedLogs = await client.getLogs({
-    address: perpEngineAddress as `0x${string}`,
-    event: POSITION_OPENED_ABI,
-    fromBlock,
-    toBlock,
-  });
-
-  for (const log of openedLogs) {
-    const block = await client.getBlock({ blockNumber: log.blockNumber! });
-    await processPositionOpened(log, perpEngineAddress, Number(block.timestamp));
-  }
-
-  // Get PositionClosed events
-  const closedLogs = await client.getLogs({
-    address: perpEngineAddress as `0x${string}`,
-    event: POSITION_CLOSED_ABI,
-    fromBlock,
-    toBlock,
-  });
-
-  for (const log of closedLogs) {
-    await processPositionClosed(log);
-  }
-
-  // Get Liquidation events
-  const liquidationLogs = await client.getLogs({
-    address: perpEngineAddress as `0x${string}`,
-    event: LIQUIDATION_ABI,
-    fromBlock,
-    toBlock,
-  });
-
-  for (const log of liquidationLogs) {
-    await processLiquidation(log);
-  }
-}
-
-/**
- * Single indexer poll iteration
- */
-async function pollOnce(
-  client: ReturnType<typeof createPublicClient>,
-  perpEngineAddress: string
-): Promise<void> {
-  try {
-    // Get current block
-    const currentBlock = await client.getBlockNumber();
-
-    // Get last indexed block
-    const state = await getIndexerState(
-      INDEXER_CONFIG.chain,
-      INDEXER_CONFIG.network,
-      perpEngineAddress
-    );
-
-    const lastIndexedBlock = state?.last_indexed_block
-      ? BigInt(state.last_indexed_block)
-      : BigInt(INDEXER_CONFIG.startBlock);
-
-    // Nothing to index if we're caught up
-    if (lastIndexedBlock >= currentBlock) {
-      return;
-    }
-
-    // Calculate range to index
-    const fromBlock = lastIndexedBlock + 1n;
-    const maxToBlock = fromBlock + BigInt(INDEXER_CONFIG.maxBlocksPerPoll);
-    const toBlock = maxToBlock > currentBlock ? currentBlock : maxToBlock;
-
-    // Index the range
-    await indexBlockRange(client, perpEngineAddress, fromBlock, toBlock);
-
-    // Update state
-    await upsertIndexerState(
-      INDEXER_CONFIG.chain,
-      INDEXER_CONFIG.network,
-      perpEngineAddress,
-      Number(toBlock)
-    );
-
-    if (toBlock - fromBlock > 0n) {
-      console.log(`[indexer] Indexed blocks ${fromBlock}-${toBlock}`);
-    }
-  } catch (err: any) {
-    // Don't spam logs - only log errors once per minute
-    console.error(`[indexer] Poll error:`, err.message?.slice(0, 100));
-  }
-}
-
-/**
- * Start the indexer loop
- */
-export function startPerpIndexer(rpcUrl: string, perpEngineAddress: string): void {
-  if (isRunning) {
-    console.log('[indexer] Already running');
-    return;
-  }
-
-  if (!perpEngineAddress) {
-    console.log('[indexer] No perp engine address configured, skipping');
-    return;
-  }
-
-  console.log('[indexer] Starting perp position indexer');
-  console.log(`[indexer] Contract: ${perpEngineAddress}`);
-
-  isRunning = true;
-
-  const client = createPublicClient({
-    chain: sepolia,
-    transport: http(rpcUrl),
-  });
-
-  const poll = async () => {
-    if (!isRunning) return;
-
-    await pollOnce(client, perpEngineAddress);
-
-    // Schedule next poll
-    pollTimeout = setTimeout(poll, INDEXER_CONFIG.pollIntervalMs);
+export function perpEngineContract(address: string): IndexedContract {
+  return {
+    name: 'demo_perp_engine',
+    address,
+    events: [POSITION_OPENED_ABI, POSITION_CLOSED_ABI, LIQUIDATION_ABI],
+    handle: async (log, ctx) => {
+      switch (log.eventName) {
+        case 'PositionOpened':
+          return processPositionOpened(log, ctx);
+        case 'PositionClosed':
+          return processPositionClosed(log, ctx);
+        case 'LiquidationTriggered':
+          return processLiquidation(log, ctx);
+      }
+    },
   };
-
-  // Start polling
-  poll();
-}
-
-/**
- * Stop the indexer
- */
-export function stopPerpIndexer(): void {
-  console.log('[indexer] Stopping perp position indexer');
-  isRunning = false;
-  if (pollTimeout) {
-    clearTimeout(pollTimeout);
-    pollTimeout = null;
-  }
-}
-
-/**
- * Check if indexer is running
- */
-export function isIndexerRunning(): boolean {
-  return isRunning;
-}
-
-/**
- * Manually trigger a single poll (for testing)
- */
-export async function triggerIndexerPoll(rpcUrl: string, perpEngineAddress: string): Promise<void> {
-  const client = createPublicClient({
-    chain: sepolia,
-    transport: http(rpcUrl),
-  });
-
-  await pollOnce(client, perpEngineAddress);
 }
@@ -0,0 +1,101 @@
+/**
+ * Compound Intent Tests
+ * Parsing and compilation only - nothing is routed or executed
+ */
+
+import { describe, it, expect } from 'vitest';
+import { parseCompoundIntent, compileCompoundIntent } from '../compoundIntent';
+
+function compile(text: string) {
+  const compound = parseCompoundIntent(text);
+  if (!compound) throw new Error(`not a compound intent: ${text}`);
+  return compileCompoundIntent(compound);
+}
+
+describe('parseCompoundIntent', () => {
+  it('leaves single-leg prompts to parseIntent', () => {
+    expect(parseCompoundIntent('swap 100 usdc to weth')).toBeNull();
+    // "and" only splits in front of a leg verb
+    expect(parseCompoundIntent('swap 100 usdc to weth and eth')).toBeNull();
+  });
+
+  it('splits on and, then, commas and semicolons', () => {
+    const prompts = [
+      'swap 100 usdc to weth and deposit 50 usdc into aave',
+      'swap 100 usdc to weth then deposit 50 usdc into aave',
+      'swap 100 usdc to weth, and then deposit 50 usdc into aave',
+      'swap 100 usdc to weth; deposit 50 usdc into aave',
+    ];
+    for (const prompt of prompts) {
+      const compound = parseCompoundIntent(prompt);
+      expect(compound?.legs.map(l => [l.kind, l.amount, l.amountUnit])).toEqual([
+        ['swap', '100', 'USDC'],
+        ['deposit', '50', 'USDC'],
+      ]);
+    }
+  });
+
+  it('resolves references to the previous leg output', () => {
+    const rest = parseCompoundIntent('swap 500 usdc to weth and deposit the rest into aave')!;
+    expect(rest.legs[1]).toMatchObject({ kind: 'deposit', amountUnit: 'WETH', venue: 'aave', amountFromLeg: 0, index: 1 });
+    expect(rest.legs[1].amount).toBeUndefined();
+    expect(rest.legs[1].rawParams.original).toBe('deposit the rest into aave');
+
+    const pronoun = parseCompoundIntent('swap 10 usdc to weth, then swap it to usdc')!;
+    expect(pronoun.legs[1]).toMatchObject({ kind: 'swap', amountUnit: 'WETH', targetAsset: 'USDC', amountFromLeg: 0 });
+
+    // No asset named: implicitly the previous output
+    const implicit = parseCompoundIntent('swap 10 usdc to weth and deposit into aave')!;
+    expect(implicit.legs[1]).toMatchObject({ kind: 'deposit', amountUnit: 'WETH', amountFromLeg: 0 });
+  });
+
+  it('keeps explicit amounts and does not chain off legs without an output', () => {
+    const explicit = parseCompoundIntent('swap 10 usdc to weth and swap 1 weth to usdc')!;
+    expect(explicit.legs[1]).toMatchObject({ amount: '1', amountUnit: 'WETH' });
+    expect(explicit.legs[1].amountFromLeg).toBeUndefined();
+
+    const afterDeposit = parseCompoundIntent('deposit 100 usdc into aave and swap it to weth')!;
+    expect(afterDeposit.legs[1].amountFromLeg).toBeUndefined();
+  });
+
+  it('rejects prompts with an unparseable leg', () => {
+    expect(parseCompoundIntent('swap 100 usdc to weth and supply it')).toBeNull();
+  });
+});
+
+describe('compileCompoundIntent', () => {
+  it('compiles a swap-then-supply chain into one multi-leg request', () => {
+    expect(compile('swap 500 usdc to weth and deposit the rest into aave')).toEqual({
+      executionRequest: {
+        kind: 'multi',
+        chain: 'sepolia',
+        legs: [
+          { kind: 'swap', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '500', amountFromLeg: undefined, slippageBps: 50 },
+          { kind: 'lend_supply', asset: 'WETH', amount: undefined, amountFromLeg: 0, protocol: 'aave' },
+        ],
+      },
+    });
+  });
+
+  it('maps ETH to WETH and other venues to the demo vault', () => {
+    const result = compile('swap 100 usdc to eth then deposit it into vault');
+    expect(result).toMatchObject({
+      executionRequest: {
+        legs: [
+          { kind: 'swap', tokenOut: 'WETH' },
+          { kind: 'lend_supply', asset: 'WETH', amountFromLeg: 0, protocol: 'demo' },
+        ],
+      },
+    });
+  });
+
+  it('names the leg that cannot be composed', () => {
+    expect(compile('swap 100 usdc to dai and deposit it into aave'))
+      .toEqual({ error: 'Leg 1 (swap 100 usdc to dai): only USDC/WETH swaps can be composed' });
+    expect(compile('swap 1 weth to usdc and deposit 50 dai into aave'))
+      .toEqual({ error: 'Leg 2 (deposit 50 dai into aave): only USDC or WETH can be supplied' });
+    // Nothing to reference after a supply, so "it" is not an asset
+    expect(compile('deposit 100 usdc into aave and swap it to weth'))
+      .toMatchObject({ error: expect.stringMatching(/^Leg 2 \(swap it to weth\)/) });
+  });
+});
@@ -0,0 +1,261 @@
+/**
+ * Intent Job Queue Tests
+ * The intent runner and tx receipts are mocked; the ledger runs against an
+ * in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+const mocks = vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  return {
+    routeQueuedIntent: vi.fn(),
+    executeIntentById: vi.fn(),
+    lookupTxReceipt: vi.fn(),
+  };
+});
+
+vi.mock('../../config', () => ({
+  INTENT_QUEUE_POLL_MS: 1000,
+  INTENT_QUEUE_LEASE_SECONDS: 60,
+  INTENT_QUEUE_CONCURRENCY: 4,
+  INTENT_QUEUE_MAX_ATTEMPTS: 3,
+  INTENT_QUEUE_BACKOFF_BASE_MS: 1000,
+  INTENT_QUEUE_BACKOFF_MAX_MS: 8000,
+}));
+vi.mock('../intentRunner', () => ({
+  createQueuedIntent: vi.fn(),
+  routeQueuedIntent: mocks.routeQueuedIntent,
+  executeIntentById: mocks.executeIntentById,
+}));
+vi.mock('../../executors/txReceipts', () => ({ lookupTxReceipt: mocks.lookupTxReceipt }));
+vi.mock('../../telemetry/logger', () => ({ logEvent: vi.fn() }));
+
+import {
+  computeBackoffMs,
+  processIntentJob,
+  recoverOrphanedIntents,
+  retryDeadIntentJob,
+} from '../intentQueue';
+import {
+  getDatabase,
+  createIntent,
+  getIntent,
+  updateIntentStatus,
+  createExecution,
+  updateExecution,
+  linkExecutionToIntent,
+  getExecutionsForIntent,
+  createBridgeTransfer,
+  enqueueIntentJob,
+  getIntentJob,
+  leaseIntentJobs,
+  renewIntentJobLease,
+  updateIntentJob,
+} from '../../../execution-ledger/db';
+
+const TX_HASH = `0x${'ab'.repeat(32)}`;
+
+function newIntent(status?: string, ageSeconds = 0) {
+  const intent = createIntent({ intentText: 'swap 10 usdc to weth', intentKind: 'swap' });
+  if (status) updateIntentStatus(intent.id, { status: status as any });
+  if (ageSeconds) {
+    getDatabase().prepare('UPDATE intents SET created_at = created_at - ? WHERE id = ?').run(ageSeconds, intent.id);
+  }
+  return intent;
+}
+
+function leaseOne(intentId: string, step: 'route' | 'execute' | 'confirm' = 'execute') {
+  enqueueIntentJob({ intentId, step, maxAttempts: 3 });
+  const [job] = leaseIntentJobs('worker-a', 60, 10);
+  expect(job.intent_id).toBe(intentId);
+  return job;
+}
+
+describe('intent job queue', () => {
+  beforeEach(() => {
+    const db = getDatabase();
+    db.prepare('DELETE FROM intent_jobs').run();
+    db.prepare('DELETE FROM bridge_transfers').run();
+    db.prepare('DELETE FROM executions').run();
+    db.prepare('DELETE FROM intents').run();
+    mocks.routeQueuedIntent.mockReset();
+    mocks.executeIntentById.mockReset();
+    mocks.lookupTxReceipt.mockReset();
+  });
+
+  describe('computeBackoffMs', () => {
+    it('doubles per attempt up to the cap', () => {
+      expect([1, 2, 3, 4, 5].map(computeBackoffMs)).toEqual([1000, 2000, 4000, 8000, 8000]);
+    });
+  });
+
+  describe('leases', () => {
+    it('leases ready jobs once, counting an attempt per lease', () => {
+      const ready = newIntent();
+      const later = newIntent();
+      enqueueIntentJob({ intentId: ready.id });
+      enqueueIntentJob({ intentId: later.id, runAfter: Math.floor(Date.now() / 1000) + 600 });
+
+      const leased = leaseIntentJobs('worker-a', 60, 10);
+      expect(leased.map(j => j.intent_id)).toEqual([ready.id]);
+      expect(leased[0]).toMatchObject({ status: 'leased', lease_owner: 'worker-a', attempts: 1 });
+
+      // Held leases are not handed to another worker
+      expect(leaseIntentJobs('worker-b', 60, 10)).toEqual([]);
+    });
+
+    it('reclaims an expired lease from a crashed worker', () => {
+      const intent = newIntent();
+      const job = leaseOne(intent.id);
+      updateIntentJob(job.id, { lease_expires_at: Math.floor(Date.now() / 1000) - 1 });
+
+      const [reclaimed] = leaseIntentJobs('worker-b', 60, 10);
+      expect(reclaimed).toMatchObject({ id: job.id, lease_owner: 'worker-b', attempts: 2 });
+    });
+
+    it('only renews a lease for its owner', () => {
+      const job = leaseOne(newIntent().id);
+      expect(renewIntentJobLease(job.id, 'worker-a', 120)).toBe(true);
+      expect(renewIntentJobLease(job.id, 'worker-b', 120)).toBe(false);
+
+      updateIntentJob(job.id, { status: 'done' });
+      expect(renewIntentJobLease(job.id, 'worker-a', 120)).toBe(false);
+    });
+  });
+
+  describe('processIntentJob', () => {
+    it('finishes the job when execution succeeds', async () => {
+      const intent = newIntent('routed');
+      mocks.executeIntentById.mockResolvedValue({ ok: true, intentId: intent.id, status: 'confirmed' });
+
+      await processIntentJob(leaseOne(intent.id));
+
+      expect(mocks.executeIntentById).toHaveBeenCalledWith(intent.id, { resume: true });
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'done' });
+    });
+
+    it('routes first, then executes', async () => {
+      const intent = newIntent();
+      mocks.routeQueuedIntent.mockResolvedValue({ ok: true });
+      mocks.executeIntentById.mockResolvedValue({ ok: true, intentId: intent.id, status: 'confirmed' });
+
+      await processIntentJob(leaseOne(intent.id, 'route'));
+
+      expect(getIntentJob(intent.id)).toMatchObject({ step: 'execute', status: 'done' });
+    });
+
+    it('backs off after a retryable failure', async () => {
+      const intent = newIntent('routed');
+      mocks.executeIntentById.mockResolvedValue({
+        ok: false,
+        intentId: intent.id,
+        status: 'failed',
+        error: { stage: 'execute', code: 'EXECUTION_ERROR', message: 'rpc timeout' },
+      });
+
+      const before = Math.floor(Date.now() / 1000);
+      await processIntentJob(leaseOne(intent.id));
+
+      const job = getIntentJob(intent.id)!;
+      expect(job).toMatchObject({ status: 'pending', attempts: 1, last_error: 'rpc timeout' });
+      expect(job.run_after).toBeGreaterThanOrEqual(before + 1);
+      expect(job.run_after).toBeLessThanOrEqual(before + 2);
+    });
+
+    it('does not retry deterministic failures', async () => {
+      const intent = newIntent('routed');
+      mocks.executeIntentById.mockResolvedValue({
+        ok: false,
+        intentId: intent.id,
+        status: 'failed',
+        error: { stage: 'execute', code: 'INSUFFICIENT_BALANCE', message: 'not enough USDC' },
+      });
+
+      await processIntentJob(leaseOne(intent.id));
+
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'done', last_error: 'not enough USDC' });
+    });
+
+    it('dead-letters after the last attempt and can be retried by hand', async () => {
+      const intent = newIntent('routed');
+      mocks.executeIntentById.mockRejectedValue(new Error('rpc down'));
+      const job = leaseOne(intent.id);
+      updateIntentJob(job.id, { attempts: 3 });
+
+      await processIntentJob({ ...job, attempts: 3 });
+
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'dead', last_error: 'rpc down' });
+      expect(getIntent(intent.id)).toMatchObject({ status: 'failed', error_code: 'DEAD_LETTERED' });
+
+      expect(await retryDeadIntentJob(intent.id)).toBe(true);
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'pending', attempts: 0 });
+      expect(await retryDeadIntentJob(intent.id)).toBe(false);
+    });
+
+    it('confirms a checkpointed tx from its receipt instead of re-sending', async () => {
+      const intent = newIntent('executing');
+      enqueueIntentJob({ intentId: intent.id, step: 'confirm', txChain: 'ethereum', txHash: TX_HASH });
+      const [job] = leaseIntentJobs('worker-a', 60, 10);
+
+      mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'pending' });
+      await processIntentJob(job);
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'pending', step: 'confirm' });
+
+      mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'success', blockNumber: 100, gasUsed: '21000' });
+      updateIntentJob(job.id, { run_after: 0 });
+      await processIntentJob(leaseIntentJobs('worker-a', 60, 10)[0]);
+
+      expect(mocks.executeIntentById).not.toHaveBeenCalled();
+      expect(getIntentJob(intent.id)).toMatchObject({ status: 'done' });
+      expect(getIntent(intent.id)).toMatchObject({ status: 'confirmed' });
+      expect(getExecutionsForIntent(intent.id)).toMatchObject([{ tx_hash: TX_HASH, status: 'confirmed' }]);
+    });
+  });
+
+  describe('recoverOrphanedIntents', () => {
+    it('requeues intents that had not started executing', async () => {
+      const queued = newIntent(undefined, 600);
+      const routed = newIntent('routed', 600);
+      newIntent('routed');  // Still within the grace period
+
+      expect(await recoverOrphanedIntents()).toEqual({ requeued: 2, confirming: 0, bridging: 0, failed: 0 });
+      expect(getIntentJob(queued.id)).toMatchObject({ step: 'route' });
+      expect(getIntentJob(routed.id)).toMatchObject({ step: 'execute' });
+    });
+
+    it('reconciles executing intents before failing them', async () => {
+      const sent = newIntent('executing', 600);
+      const execution = createExecution({
+        chain: 'ethereum',
+        network: 'sepolia',
+        intent: 'swap 10 usdc to weth',
+        action: 'swap',
+        fromAddress: '0x00000000000000000000000000000000000000a1',
+      });
+      updateExecution(execution.id, { status: 'submitted', txHash: TX_HASH });
+      linkExecutionToIntent(execution.id, sent.id);
+
+      const bridging = newIntent('executing', 600);
+      createBridgeTransfer({
+        intentId: bridging.id,
+        provider: 'lifi',
+        fromChain: 'ethereum',
+        fromNetwork: 'sepolia',
+        toChain: 'solana',
+        toNetwork: 'mainnet',
+        fromAddress: '0x00000000000000000000000000000000000000b2',
+        sourceTxHash: `0x${'cd'.repeat(32)}`,
+      });
+
+      const interrupted = newIntent('executing', 600);
+
+      expect(await recoverOrphanedIntents()).toEqual({ requeued: 0, confirming: 1, bridging: 1, failed: 1 });
+      expect(getIntentJob(sent.id)).toMatchObject({ step: 'confirm', tx_chain: 'ethereum', tx_hash: TX_HASH });
+      expect(getIntent(sent.id)).toMatchObject({ status: 'executing' });
+      expect(getIntentJob(bridging.id)).toBeUndefined();
+      expect(getIntent(bridging.id)).toMatchObject({ status: 'executing' });
+      expect(getIntent(interrupted.id)).toMatchObject({ status: 'failed', error_code: 'EXECUTION_INTERRUPTED' });
+    });
+  });
+});
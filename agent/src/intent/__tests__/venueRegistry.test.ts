@@ -0,0 +1,172 @@
+/**
+ * Venue Adapter Registry Tests
+ * Uses the in-memory mock adapter - no chain, ledger or network access
+ */
+
+import { describe, it, expect, afterEach, vi } from 'vitest';
+
+// Live quote sources are unavailable; prices are fixed
+vi.mock('../../quotes/oneInchQuote', () => ({
+  getOneInchQuote: vi.fn(),
+  isOneInchAvailable: () => false,
+}));
+vi.mock('../../quotes/uniswapQuoter', () => ({
+  getUniswapV3Quote: vi.fn(),
+  isUniswapQuoterAvailable: () => false,
+}));
+vi.mock('../../integrations/dflow/dflowClient', () => ({
+  getSwapQuote: vi.fn(),
+  isDflowCapabilityAvailable: () => false,
+}));
+vi.mock('../../services/prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: 3000, source: 'coingecko', fetchedAt: Date.now() }),
+}));
+
+import { routeIntent, ParsedIntent, RouteDecision } from '../intentRunner';
+import {
+  registerVenueAdapter,
+  unregisterVenueAdapter,
+  getVenueAdapter,
+  findVenueAdapters,
+} from '../venues/registry';
+import { createMockVenueAdapter } from '../venues/mockAdapter';
+
+function swapIntent(overrides: Partial<ParsedIntent> = {}): ParsedIntent {
+  return {
+    kind: 'swap',
+    action: 'swap',
+    amount: '100',
+    amountUnit: 'usdc',
+    targetAsset: 'weth',
+    rawParams: { original: 'swap 100 usdc to weth' },
+    ...overrides,
+  };
+}
+
+describe('Venue adapter registry', () => {
+  afterEach(() => {
+    unregisterVenueAdapter('mock_venue');
+    unregisterVenueAdapter('mock_cheap');
+  });
+
+  it('registers the built-in venues', () => {
+    expect(getVenueAdapter('demo_dex')).toBeDefined();
+    expect(getVenueAdapter('demo_vault')).toBeDefined();
+    expect(getVenueAdapter('demo_perp')).toBeDefined();
+  });
+
+  it('routes to the built-in venue when nothing else matches', async () => {
+    const route = await routeIntent(swapIntent());
+    expect('error' in route).toBe(false);
+    expect(route).toMatchObject({ chain: 'ethereum', venue: 'demo_dex', executionType: 'real' });
+  });
+
+  it('discovers a newly registered adapter by priority', async () => {
+    const mock = createMockVenueAdapter({ capabilities: { priority: 10 } });
+    registerVenueAdapter(mock);
+
+    const route = await routeIntent(swapIntent());
+    expect(route).toMatchObject({ venue: 'mock_venue', executionType: 'real' });
+    expect(mock.calls.buildPlan).toHaveLength(1);
+    expect(findVenueAdapters('swap', 'ethereum')[0].id).toBe('mock_venue');
+  });
+
+  it('discovers a newly registered deposit adapter by priority', async () => {
+    const mock = createMockVenueAdapter({ capabilities: { kinds: ['deposit'], priority: 10 } });
+    registerVenueAdapter(mock);
+
+    const route = await routeIntent({ kind: 'deposit', action: 'deposit', amount: '100', amountUnit: 'usdc', rawParams: {} });
+    expect(route).toMatchObject({ venue: 'mock_venue', executionType: 'real' });
+    expect(mock.calls.buildPlan).toHaveLength(1);
+    expect(findVenueAdapters('deposit', 'ethereum')[0].id).toBe('mock_venue');
+  });
+
+  it('routes to a requested venue by alias', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ capabilities: { aliases: ['mockswap'], explicitOnly: true } }));
+
+    expect(await routeIntent(swapIntent())).toMatchObject({ venue: 'demo_dex' });
+    expect(await routeIntent(swapIntent({ venue: 'MockSwap' }))).toMatchObject({ venue: 'mock_venue' });
+  });
+
+  it('ignores a requested venue that does not support the target chain', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ capabilities: { chains: ['solana'] } }));
+
+    expect(await routeIntent(swapIntent({ venue: 'mock_venue' }))).toMatchObject({ venue: 'demo_dex' });
+  });
+
+  it('executes through the adapter', async () => {
+    const mock = createMockVenueAdapter();
+    registerVenueAdapter(mock);
+
+    const route = await routeIntent(swapIntent({ venue: 'mock_venue' }));
+    if ('error' in route) throw new Error('unexpected routing error');
+
+    const result = await getVenueAdapter(route.venue)!.execute('intent-1', swapIntent(), route);
+    expect(result).toMatchObject({ ok: true, intentId: 'intent-1', status: 'confirmed' });
+    expect(mock.calls.execute).toEqual([{ intentId: 'intent-1', route }]);
+  });
+
+  it('still fails routing for known but unintegrated perp venues', async () => {
+    const route = await routeIntent({ kind: 'perp', action: 'long', venue: 'drift', rawParams: {} });
+    expect(route).toMatchObject({ error: { code: 'VENUE_NOT_IMPLEMENTED' } });
+  });
+});
+
+describe('Cost-based venue selection', () => {
+  afterEach(() => {
+    unregisterVenueAdapter('mock_venue');
+    unregisterVenueAdapter('mock_cheap');
+  });
+
+  it('picks the venue with the best net output after gas and ranks the rest', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ quote: { expectedOutUsd: 99, gasUsd: 1 } }));
+    registerVenueAdapter(createMockVenueAdapter({ id: 'mock_cheap', quote: { expectedOutUsd: 98, gasUsd: 0.5 } }));
+
+    const route = await routeIntent(swapIntent()) as RouteDecision;
+    expect(route.venue).toBe('mock_venue');
+    expect(route.alternatives!.map(q => q.venue)).toEqual(['mock_venue', 'mock_cheap', 'demo_dex']);
+    expect(route.alternatives![0]).toMatchObject({ rank: 1, netOutUsd: 98, selected: true });
+    expect(route.alternatives![1]).toMatchObject({ rank: 2, netOutUsd: 97.5, selected: false });
+    expect(route.selectionReason).toContain('mock_cheap');
+  });
+
+  it('costs the demo DEX with its 5% fee and gas', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ quote: { expectedOutUsd: 50 } }));
+
+    const route = await routeIntent(swapIntent()) as RouteDecision;
+    const demo = route.alternatives!.find(q => q.venue === 'demo_dex')!;
+    expect(route.venue).toBe('demo_dex');
+    expect(demo.inputUsd).toBeCloseTo(100);
+    expect(demo.expectedOutUsd).toBeCloseTo(95);
+    expect(demo.feeUsd).toBeCloseTo(5);
+    expect(demo.gasUsd).toBeGreaterThan(0);
+  });
+
+  it('ranks quote-only venues for reference but never selects them', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ capabilities: { quoteOnly: true }, quote: { expectedOutUsd: 99 } }));
+
+    const route = await routeIntent(swapIntent()) as RouteDecision;
+    expect(route.venue).toBe('demo_dex');
+    expect(route.alternatives!.map(q => [q.venue, q.rank, q.selected, !!q.quoteOnly])).toEqual([
+      ['mock_venue', 1, false, true],
+      ['demo_dex', 2, true, false],
+    ]);
+    // Beating demo_dex on price is not a choice demo_dex won
+    expect(route.selectionReason).toBe('demo_dex is the only venue that can settle this intent; mock_venue quoted for reference only');
+    expect(route.warnings).toContain('mock_venue quotes are reference prices only; no venue choice was made.');
+  });
+
+  it('settles a requested live venue through the demo DEX', async () => {
+    const route = await routeIntent(swapIntent({ venue: 'uniswap' })) as RouteDecision;
+    expect(route).toMatchObject({ venue: 'demo_dex', executionType: 'real' });
+    expect(route.warnings).toEqual([expect.stringContaining('uniswap is quote-only')]);
+  });
+
+  it('skips venues whose quote fails', async () => {
+    registerVenueAdapter(createMockVenueAdapter({ quote: { expectedOutUsd: 0 } }));
+
+    const route = await routeIntent(swapIntent()) as RouteDecision;
+    expect(route.venue).toBe('demo_dex');
+    expect(route.alternatives!.map(q => q.venue)).toEqual(['demo_dex']);
+  });
+});
@@ -0,0 +1,157 @@
+/**
+ * Compound Intents
+ * Splits multi-leg prompts ("swap 500 usdc to weth and deposit the rest into aave")
+ * into an ordered leg list where later legs can consume an earlier leg's output,
+ * and compiles them into a single multi-leg execution request.
+ */
+
+import { parseIntent, ParsedIntent } from './intentRunner';
+import type { BlossomExecutionRequest, BlossomPlanLeg } from '../types/blossom';
+
+export interface IntentLeg extends ParsedIntent {
+  index: number;
+  amountFromLeg?: number;       // Leg whose full output funds this leg (amount is then unset)
+}
+
+export interface CompoundIntent {
+  legs: IntentLeg[];
+  rawText: string;
+}
+
+// Verbs that start a new leg; separators only split in front of these
+const LEG_VERBS = '(?:swap|convert|trade|deposit|supply|lend)\\b';
+const LEG_SEPARATOR = new RegExp(
+  `\\s*(?:,\\s*(?:and\\s+)?(?:then\\s+)?|;\\s*|\\s+and\\s+then\\s+|\\s+then\\s+|\\s+and\\s+)(?=${LEG_VERBS})`,
+  'i'
+);
+
+// References to the previous leg's output
+const OUTPUT_REFERENCE = /\b(?:the\s+(?:rest|remainder|proceeds|output)|all\s+of\s+it|everything|it|them|that)\b/i;
+
+// "deposit into aave" - no asset named, implicitly the previous output
+const IMPLICIT_REFERENCE = /^((?:deposit|supply|lend)\s+)(?=(?:to|into|in)\s)/i;
+
+// Composable tokens (demo router pairs)
+const COMPOSABLE_TOKENS: Record<string, 'USDC' | 'WETH'> = {
+  USDC: 'USDC',
+  WETH: 'WETH',
+  ETH: 'WETH',
+};
+
+/**
+ * Output asset of a leg, if it produces one a later leg can consume
+ */
+function legOutputAsset(leg: ParsedIntent): string | undefined {
+  return leg.kind === 'swap' ? leg.targetAsset : undefined;
+}
+
+/**
+ * Parse a prompt into an ordered list of legs
+ * Returns null for single-leg prompts (use parseIntent instead)
+ */
+export function parseCompoundIntent(intentText: string): CompoundIntent | null {
+  const segments = intentText.trim().split(LEG_SEPARATOR).map(s => s.trim()).filter(Boolean);
+  if (segments.length < 2) {
+    return null;
+  }
+
+  const legs: IntentLeg[] = [];
+  for (const segment of segments) {
+    const index = legs.length;
+    const previousOutput = index > 0 ? legOutputAsset(legs[index - 1]) : undefined;
+
+    let text = segment;
+    let amountFromLeg: number | undefined;
+    if (previousOutput && !/\d/.test(segment)) {
+      const asset = previousOutput.toLowerCase();
+      if (OUTPUT_REFERENCE.test(segment)) {
+        text = segment.replace(OUTPUT_REFERENCE, asset);
+        amountFromLeg = index - 1;
+      } else if (IMPLICIT_REFERENCE.test(segment)) {
+        text = segment.replace(IMPLICIT_REFERENCE, `$1${asset} `);
+        amountFromLeg = index - 1;
+      }
+    }
+
+    const parsed = parseIntent(text);
+    if (parsed.kind === 'unknown') {
+      return null;
+    }
+
+    legs.push({
+      ...parsed,
+      ...(amountFromLeg !== undefined ? { amount: undefined, amountFromLeg } : {}),
+      rawParams: { ...parsed.rawParams, original: segment },
+      index,
+    });
+  }
+
+  return { legs, rawText: intentText };
+}
+
+/**
+ * Compile a compound intent into a multi-leg execution request
+ */
+export function compileCompoundIntent(
+  compound: CompoundIntent
+): { executionRequest: BlossomExecutionRequest } | { error: string } {
+  const planLegs: BlossomPlanLeg[] = [];
+
+  for (const leg of compound.legs) {
+    const label = `Leg ${leg.index + 1} (${leg.rawParams.original})`;
+
+    if (leg.kind === 'swap') {
+      const tokenIn = COMPOSABLE_TOKENS[(leg.amountUnit || '').toUpperCase()];
+      const tokenOut = COMPOSABLE_TOKENS[(leg.targetAsset || '').toUpperCase()];
+      if (!tokenIn || !tokenOut || tokenIn === tokenOut) {
+        return { error: `${label}: only USDC/WETH swaps can be composed` };
+      }
+      planLegs.push({
+        kind: 'swap',
+        tokenIn,
+        tokenOut,
+        amountIn: leg.amount,
+        amountFromLeg: leg.amountFromLeg,
+        slippageBps: 50,
+      });
+    } else if (leg.kind === 'deposit') {
+      const asset = COMPOSABLE_TOKENS[(leg.amountUnit || '').toUpperCase()];
+      if (!asset) {
+        return { error: `${label}: only USDC or WETH can be supplied` };
+      }
+      planLegs.push({
+        kind: 'lend_supply',
+        asset,
+        amount: leg.amount,
+        amountFromLeg: leg.amountFromLeg,
+        protocol: leg.venue === 'aave' ? 'aave' : 'demo',
+      });
+    } else {
+      return { error: `${label}: ${leg.kind} legs cannot be composed into a single plan` };
+    }
+  }
+
+  return {
+    executionRequest: {
+      kind: 'multi',
+      chain: 'sepolia',
+      legs: planLegs,
+    },
+  };
+}
+
+/**
+ * Human-readable description of a compiled plan
+ */
+export function describePlanLegs(legs: BlossomPlanLeg[]): string {
+  return legs
+    .map((leg, i) => {
+      const amount = leg.amountFromLeg !== undefined
+        ? `the output of step ${leg.amountFromLeg + 1}`
+        : leg.kind === 'swap' ? `${leg.amountIn} ${leg.tokenIn}` : `${leg.amount} ${leg.asset}`;
+      return leg.kind === 'swap'
+        ? `${i + 1}. Swap ${amount} to ${leg.tokenOut}`
+        : `${i + 1}. Supply ${amount}${leg.amountFromLeg !== undefined ? ` (${leg.asset})` : ''} to ${leg.protocol === 'aave' ? 'Aave' : 'the demo vault'}`;
+    })
+    .join('\n');
+}
@@ -0,0 +1,420 @@
+/**
+ * Intent Job Queue
+ * Durable execution of intents on top of the ledger's intents table
+ *
+ * Jobs move through route → execute → confirm. A worker leases a job for a
+ * bounded time and heartbeats while it runs; a lease left behind by a crash or
+ * restart expires and is picked up by the next poll. Once a tx is broadcast its
+ * hash is checkpointed on the job, so a retry confirms that tx instead of
+ * sending another. Failed attempts back off exponentially; jobs that exhaust
+ * max_attempts are dead-lettered and their intent is marked failed.
+ */
+
+import { randomUUID } from 'crypto';
+import { hostname } from 'os';
+import {
+  createQueuedIntent,
+  routeQueuedIntent,
+  executeIntentById,
+  type ChainTarget,
+  type IntentExecutionResult,
+} from './intentRunner';
+import { lookupTxReceipt } from '../executors/txReceipts';
+import type { Chain, Network } from '../ledger/ledger';
+import type { IntentJob } from '../../execution-ledger/db';
+import { logEvent } from '../telemetry/logger';
+import {
+  INTENT_QUEUE_POLL_MS,
+  INTENT_QUEUE_LEASE_SECONDS,
+  INTENT_QUEUE_CONCURRENCY,
+  INTENT_QUEUE_MAX_ATTEMPTS,
+  INTENT_QUEUE_BACKOFF_BASE_MS,
+  INTENT_QUEUE_BACKOFF_MAX_MS,
+} from '../config';
+
+// Executor error codes that are worth retrying (RPC/transport failures)
+const RETRYABLE_ERROR_CODES = new Set([
+  'EXECUTION_ERROR',
+  'PERP_EXECUTION_ERROR',
+  'PROOF_TX_FAILED',
+  'SOLANA_PROOF_TX_FAILED',
+]);
+
+// Intents orphaned by an interrupted inline run are only recovered once stale
+const ORPHAN_GRACE_SECONDS = 5 * 60;
+
+const workerId = `${hostname()}:${process.pid}:${randomUUID().slice(0, 8)}`;
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+
+async function getLedgerDb() {
+  return import('../../execution-ledger/db');
+}
+
+/**
+ * Backoff before the next attempt (exponential, capped)
+ */
+export function computeBackoffMs(attempts: number): number {
+  return Math.min(INTENT_QUEUE_BACKOFF_BASE_MS * 2 ** Math.max(0, attempts - 1), INTENT_QUEUE_BACKOFF_MAX_MS);
+}
+
+/**
+ * Create an intent and queue it for background execution
+ */
+export async function enqueueIntent(
+  intentText: string,
+  options: { chain?: ChainTarget; metadata?: Record<string, any> } = {}
+): Promise<{ intentId: string; jobId: string }> {
+  const { enqueueIntentJobAsync } = await getLedgerDb();
+  const { intentId } = await createQueuedIntent(intentText, options);
+  const job = await enqueueIntentJobAsync({ intentId, maxAttempts: INTENT_QUEUE_MAX_ATTEMPTS });
+  return { intentId, jobId: job.id };
+}
+
+/**
+ * Queue execution of a previously planned intent (confirm-mode flow)
+ */
+export async function enqueuePlannedIntent(intentId: string): Promise<{ intentId: string; jobId: string }> {
+  const { enqueueIntentJobAsync } = await getLedgerDb();
+  const job = await enqueueIntentJobAsync({ intentId, step: 'execute', maxAttempts: INTENT_QUEUE_MAX_ATTEMPTS });
+  return { intentId, jobId: job.id };
+}
+
+/**
+ * Put a dead-lettered job back in the queue with a fresh attempt budget
+ */
+export async function retryDeadIntentJob(intentId: string): Promise<boolean> {
+  const { getIntentJobAsync, updateIntentJobAsync } = await getLedgerDb();
+  const job = await getIntentJobAsync(intentId);
+  if (!job || job.status !== 'dead') {
+    return false;
+  }
+
+  await updateIntentJobAsync(job.id, {
+    status: 'pending',
+    attempts: 0,
+    run_after: Math.floor(Date.now() / 1000),
+  });
+  return true;
+}
+
+/**
+ * Schedule another attempt, or dead-letter the job when attempts run out
+ */
+async function retryOrDeadLetter(job: IntentJob, error: string): Promise<void> {
+  const { updateIntentJobAsync, updateIntentStatusAsync } = await getLedgerDb();
+
+  if (job.attempts >= job.max_attempts) {
+    await updateIntentJobAsync(job.id, { status: 'dead', last_error: error.slice(0, 500) });
+    await updateIntentStatusAsync(job.intent_id, {
+      status: 'failed',
+      failureStage: job.step,
+      errorCode: 'DEAD_LETTERED',
+      errorMessage: `Gave up after ${job.attempts} attempts: ${error}`,
+    });
+    console.warn(`[intentQueue] Dead-lettered ${job.intent_id} at ${job.step}: ${error.slice(0, 100)}`);
+    logEvent('intent_dead_lettered', {
+      txHash: job.tx_hash,
+      error: error.slice(0, 200),
+      notes: [`intent: ${job.intent_id}`, `step: ${job.step}`, `attempts: ${job.attempts}`],
+    });
+    return;
+  }
+
+  const backoffMs = computeBackoffMs(job.attempts);
+  await updateIntentJobAsync(job.id, {
+    status: 'pending',
+    run_after: Math.floor((Date.now() + backoffMs) / 1000),
+    last_error: error.slice(0, 500),
+  });
+  console.log(`[intentQueue] Retrying ${job.intent_id} (${job.step}) in ${backoffMs}ms: ${error.slice(0, 100)}`);
+}
+
+/**
+ * Confirm step: settle the intent from the checkpointed tx's receipt
+ */
+async function confirmCheckpointedTx(job: IntentJob): Promise<'done' | 'retry'> {
+  const {
+    getIntentAsync,
+    getExecutionsForIntentAsync,
+    updateExecutionAsync,
+    finalizeExecutionTransactionAsync,
+    linkExecutionToIntentAsync,
+    updateIntentJobAsync,
+  } = await getLedgerDb();
+
+  if (!job.tx_chain || !job.tx_hash) {
+    throw new Error('Confirm step has no checkpointed tx');
+  }
+  const chain = job.tx_chain as Chain;
+  const txHash = job.tx_hash;
+
+  const receipt = await lookupTxReceipt(chain, txHash);
+  if (receipt.status === 'pending' || receipt.status === 'not_found') {
+    await updateIntentJobAsync(job.id, { last_error: `tx ${txHash} ${receipt.status}` });
+    return 'retry';
+  }
+
+  const intent = await getIntentAsync(job.intent_id);
+  const succeeded = receipt.status === 'success';
+
+  // The executor may have recorded the outcome before the interruption
+  const existing = (await getExecutionsForIntentAsync(job.intent_id)).find(e => e.tx_hash === txHash);
+  if (existing) {
+    if (existing.status !== 'confirmed' && existing.status !== 'failed') {
+      await updateExecutionAsync(existing.id, {
+        status: succeeded ? 'confirmed' : 'failed',
+        blockNumber: receipt.blockNumber,
+        gasUsed: receipt.gasUsed,
+      });
+    }
+    if (intent?.status === 'confirmed' || intent?.status === 'failed') {
+      return 'done';
+    }
+  }
+
+  const { buildExplorerUrl } = await import('../ledger/ledger');
+  const metadata = JSON.parse(intent?.metadata_json || '{}');
+  const parsed = metadata.parsed || {};
+  const route = metadata.route || {};
+  const network: Network = route.network || (chain === 'solana' ? 'devnet' : 'sepolia');
+  const explorerUrl = buildExplorerUrl(chain, network, txHash);
+  const now = Math.floor(Date.now() / 1000);
+  const metadataJson = JSON.stringify({ ...metadata, txHash, explorerUrl, recoveredBy: 'intent_queue' });
+
+  if (existing) {
+    await finalizeIntentStatus(job.intent_id, succeeded, now, metadataJson);
+    return 'done';
+  }
+
+  const result = await finalizeExecutionTransactionAsync({
+    intentId: job.intent_id,
+    execution: {
+      chain,
+      network,
+      kind: parsed.kind === 'unknown' ? 'proof' : parsed.kind,
+      venue: route.venue,
+      intent: intent?.intent_text || 'Intent execution',
+      action: parsed.action || 'proof',
+      fromAddress: '0x0000000000000000000000000000000000000000',
+      token: parsed.amountUnit,
+      txHash,
+      explorerUrl,
+      status: succeeded ? 'confirmed' : 'failed',
+      ...(succeeded ? {} : { errorCode: 'TX_REVERTED', errorMessage: 'Transaction reverted on-chain' }),
+    },
+    intentStatus: succeeded
+      ? { status: 'confirmed', confirmedAt: now, metadataJson }
+      : { status: 'failed', failureStage: 'confirm', errorCode: 'TX_REVERTED', errorMessage: 'Transaction reverted on-chain', metadataJson },
+  });
+  await linkExecutionToIntentAsync(result.executionId, job.intent_id);
+  return 'done';
+}
+
+async function finalizeIntentStatus(intentId: string, succeeded: boolean, now: number, metadataJson: string): Promise<void> {
+  const { updateIntentStatusAsync } = await getLedgerDb();
+  await updateIntentStatusAsync(intentId, succeeded
+    ? { status: 'confirmed', confirmedAt: now, metadataJson }
+    : { status: 'failed', failureStage: 'confirm', errorCode: 'TX_REVERTED', errorMessage: 'Transaction reverted on-chain', metadataJson });
+}
+
+/**
+ * Run one leased job from its current step to completion or the next retry
+ */
+export async function processIntentJob(job: IntentJob): Promise<void> {
+  const { getIntentAsync, getIntentJobAsync, updateIntentJobAsync, renewIntentJobLeaseAsync } = await getLedgerDb();
+
+  // Heartbeat so long confirmations don't lose the lease
+  const heartbeat = setInterval(() => {
+    renewIntentJobLeaseAsync(job.id, workerId, INTENT_QUEUE_LEASE_SECONDS)
+      .then((renewed) => {
+        if (!renewed) console.warn(`[intentQueue] Lost lease on ${job.intent_id}`);
+      })
+      .catch((error: any) => console.warn(`[intentQueue] Lease renewal failed for ${job.intent_id}:`, error.message));
+  }, (INTENT_QUEUE_LEASE_SECONDS * 1000) / 3);
+
+  try {
+    // Nothing left to do if the intent already settled (e.g. completed before a crash)
+    const intent = await getIntentAsync(job.intent_id);
+    if (!intent || (intent.status === 'confirmed' && job.step !== 'confirm')) {
+      await updateIntentJobAsync(job.id, { status: 'done' });
+      return;
+    }
+
+    if (job.step === 'route') {
+      const routed = await routeQueuedIntent(job.intent_id);
+      if (!routed.ok) {
+        // Routing failures are deterministic; the intent already records why
+        await updateIntentJobAsync(job.id, { status: 'done', last_error: routed.error.message });
+        return;
+      }
+      await updateIntentJobAsync(job.id, { step: 'execute' });
+      job = { ...job, step: 'execute' };
+    }
+
+    if (job.step === 'execute') {
+      const result: IntentExecutionResult = await executeIntentById(job.intent_id, { resume: true });
+
+      // A tx broadcast during this attempt moves the job to confirm
+      job = (await getIntentJobAsync(job.intent_id)) ?? job;
+      if (result.ok) {
+        await updateIntentJobAsync(job.id, { status: 'done', last_error: null });
+        return;
+      }
+      if (job.step !== 'confirm') {
+        if (result.error && RETRYABLE_ERROR_CODES.has(result.error.code)) {
+          await retryOrDeadLetter(job, result.error.message || result.error.code);
+        } else {
+          await updateIntentJobAsync(job.id, { status: 'done', last_error: result.error?.message });
+        }
+        return;
+      }
+    }
+
+    if (job.step === 'confirm') {
+      const outcome = await confirmCheckpointedTx(job);
+      if (outcome === 'done') {
+        await updateIntentJobAsync(job.id, { status: 'done' });
+      } else {
+        await retryOrDeadLetter(job, `tx ${job.tx_hash} not yet confirmed`);
+      }
+    }
+  } catch (error: any) {
+    const latest = await getIntentJobAsync(job.intent_id).catch(() => undefined);
+    await retryOrDeadLetter(latest || job, error.message || String(error));
+  } finally {
+    clearInterval(heartbeat);
+  }
+}
+
+/**
+ * Lease and process a batch of ready jobs
+ */
+export async function runQueueCycle(): Promise<number> {
+  const { leaseIntentJobsAsync } = await getLedgerDb();
+  const jobs = await leaseIntentJobsAsync(workerId, INTENT_QUEUE_LEASE_SECONDS, INTENT_QUEUE_CONCURRENCY);
+  await Promise.all(jobs.map(job => processIntentJob(job)));
+  return jobs.length;
+}
+
+/**
+ * Resume-on-boot: queue intents an interrupted inline run left behind
+ * queued/routed intents are safe to drive forward. An executing intent is
+ * reconciled rather than re-sent: a bridge waits on its transfer (settled by
+ * the bridge tracker), a recorded tx is handed to a confirm job that checks
+ * its receipt, and only an intent with no tx at all is failed
+ */
+export async function recoverOrphanedIntents(): Promise<{ requeued: number; confirming: number; bridging: number; failed: number }> {
+  const {
+    getOrphanedIntentsAsync,
+    enqueueIntentJobAsync,
+    updateIntentStatusAsync,
+    getExecutionsForIntentAsync,
+    listBridgeTransfers,
+  } = await getLedgerDb();
+  const orphans = await getOrphanedIntentsAsync(Math.floor(Date.now() / 1000) - ORPHAN_GRACE_SECONDS);
+
+  let requeued = 0;
+  let confirming = 0;
+  let bridging = 0;
+  let failed = 0;
+  for (const intent of orphans) {
+    if (intent.status !== 'executing') {
+      await enqueueIntentJobAsync({
+        intentId: intent.id,
+        step: intent.status === 'routed' ? 'execute' : 'route',
+        maxAttempts: INTENT_QUEUE_MAX_ATTEMPTS,
+      });
+      requeued++;
+      continue;
+    }
+
+    // The user signed the source tx; the bridge tracker settles the intent
+    if (listBridgeTransfers({ intentId: intent.id, limit: 1 }).length > 0) {
+      bridging++;
+      continue;
+    }
+
+    // Newest first: the last tx the run broadcast decides the outcome
+    const broadcast = (await getExecutionsForIntentAsync(intent.id)).find(e => e.tx_hash);
+    if (broadcast) {
+      await enqueueIntentJobAsync({
+        intentId: intent.id,
+        step: 'confirm',
+        maxAttempts: INTENT_QUEUE_MAX_ATTEMPTS,
+        txChain: broadcast.chain,
+        txHash: broadcast.tx_hash,
+      });
+      confirming++;
+      continue;
+    }
+
+    await updateIntentStatusAsync(intent.id, {
+      status: 'failed',
+      failureStage: 'execute',
+      errorCode: 'EXECUTION_INTERRUPTED',
+      errorMessage: 'Execution interrupted by a restart before a tx was recorded',
+    });
+    failed++;
+  }
+
+  if (orphans.length > 0) {
+    console.log(`[intentQueue] Recovered orphaned intents: requeued=${requeued} confirming=${confirming} bridging=${bridging} failed=${failed}`);
+  }
+  return { requeued, confirming, bridging, failed };
+}
+
+/**
+ * Start the queue worker (recovers orphans, then polls for ready jobs)
+ */
+export async function startIntentQueue(pollMs: number = INTENT_QUEUE_POLL_MS): Promise<void> {
+  if (isRunning) {
+    console.log('[intentQueue] Already running');
+    return;
+  }
+
+  console.log(`[intentQueue] Starting worker ${workerId} (poll ${pollMs}ms, concurrency ${INTENT_QUEUE_CONCURRENCY})`);
+  isRunning = true;
+
+  try {
+    await recoverOrphanedIntents();
+  } catch (error: any) {
+    console.warn('[intentQueue] Orphan recovery failed:', error.message?.slice(0, 100));
+  }
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runQueueCycle();
+    } catch (error: any) {
+      console.error('[intentQueue] Cycle error:', error.message?.slice(0, 100));
+    }
+
+    // Schedule next poll
+    pollTimeout = setTimeout(poll, pollMs);
+  };
+
+  // Expired leases from a previous process are reclaimed on the first poll
+  poll();
+}
+
+/**
+ * Stop the queue worker (in-flight leases expire and are picked up on restart)
+ */
+export function stopIntentQueue(): void {
+  console.log('[intentQueue] Stopping worker');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the worker is running
+ */
+export function isIntentQueueRunning(): boolean {
+  return isRunning;
+}
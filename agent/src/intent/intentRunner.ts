@@ -6,7 +6,7 @@
  * 1. Accept raw intent_text (e.g., "long btc 20x", "swap 5000 usdc to weth")
  * 2. Create ledger intent row (status=queued)
  * 3. Plan: Parse intent, detect kind, extract parameters
- * 4. Route: Map to implemented venues or fail with clear error
+ * 4. Route: Map to a registered venue adapter or fail with clear error
  * 5. Execute: Run transaction via appropriate chain executor
  * 6. Confirm: Wait for confirmation and update ledger
  *
@@ -14,6 +14,16 @@
  */
 
 import { randomUUID } from 'crypto';
+import {
+  registerVenueAdapter,
+  getVenueAdapter,
+  findVenueAdapters,
+  adapterSupports,
+  rankVenueQuotes,
+  RankedVenueQuote,
+} from './venues/registry';
+import { quoteDemoDex } from './venues/swapVenues';
+import { ROUTING_QUOTE_TIMEOUT_MS, LIFI_EXECUTION_ENABLED } from '../config';
 
 /**
  * Helper to merge new metadata with existing metadata, preserving caller info (source, domain, runId).
@@ -37,6 +47,19 @@ function mergeMetadata(existingJson: string | undefined, newData: Record<string,
   return JSON.stringify({ ...preserved, ...newData });
 }
 
+/**
+ * Checkpoint a broadcast tx on the intent's queue job (no-op for inline runs)
+ * Lets a retried job confirm the tx instead of sending it again
+ */
+async function checkpointSubmittedTx(intentId: string, chain: 'ethereum' | 'solana', txHash: string): Promise<void> {
+  try {
+    const { recordIntentJobTxAsync } = await import('../../execution-ledger/db');
+    await recordIntentJobTxAsync(intentId, chain, txHash);
+  } catch (error: any) {
+    console.warn('[intentRunner] Failed to checkpoint submitted tx:', error.message);
+  }
+}
+
 // Type definitions (duplicated to avoid rootDir issues)
 type IntentKind = 'perp' | 'deposit' | 'swap' | 'bridge' | 'unknown';
 type IntentStatus = 'queued' | 'planned' | 'routed' | 'executing' | 'confirmed' | 'failed';
@@ -68,6 +91,8 @@ export interface RouteDecision {
   adapter?: string;
   executionType: 'real' | 'proof_only';
   warnings?: string[];
+  alternatives?: RankedVenueQuote[];  // All quoted venues, best net output first
+  selectionReason?: string;
 }
 
 // Execution result
@@ -86,24 +111,11 @@ export interface IntentExecutionResult {
   metadata?: Record<string, any>;
 }
 
-// Known venue implementations
-const IMPLEMENTED_VENUES: Record<string, Record<string, string[]>> = {
-  ethereum: {
-    deposit: ['demo_vault', 'aave'],
-    swap: ['demo_dex', 'uniswap'],
-    bridge: ['bridge_proof'],  // Proof only, not real bridging
-    perp: ['demo_perp'],       // Proof only
-    proof: ['native'],
-    unknown: ['native'],
-  },
-  solana: {
-    deposit: ['solana_vault'],
-    swap: ['demo_dex'],
-    bridge: ['bridge_proof'],
-    perp: ['demo_perp'],
-    proof: ['native'],
-    unknown: ['native'],
-  },
+// Venues users ask for that are not integrated yet (no adapter registered)
+// perp: fail routing; deposit: record as proof-only
+const PENDING_VENUES: Record<string, string[]> = {
+  perp: ['drift', 'hl', 'hyperliquid', 'dydx'],
+  deposit: ['kamino', 'drift'],
 };
 
 // Extended IntentKind to include new types
@@ -260,7 +272,8 @@ export function parseIntent(intentText: string): ParsedIntent {
   for (const [name, pattern] of Object.entries(INTENT_PATTERNS.bridge)) {
     const match = text.match(pattern);
     if (match) {
-      const amount = match[1]?.replace(/,/g, '') || '1000';
+      // No default amount: a real bridge moves user funds, so the amount must be explicit
+      const amount = match[1]?.replace(/,/g, '');
       const asset = match[2].toUpperCase();
       const sourceChain = match[3].toLowerCase();
       const destChain = match[4].toLowerCase();
@@ -314,10 +327,10 @@ export function parseIntent(intentText: string): ParsedIntent {
 /**
  * Determine execution route for a parsed intent
  */
-export function routeIntent(
+export async function routeIntent(
   parsed: ParsedIntent,
   preferredChain?: ChainTarget
-): RouteDecision | { error: { stage: IntentFailureStage; code: string; message: string } } {
+): Promise<RouteDecision | { error: { stage: IntentFailureStage; code: string; message: string } }> {
   const { kind, venue, sourceChain, destChain, rawParams } = parsed;
 
   // Determine target chain
@@ -396,15 +409,35 @@ export function routeIntent(
     };
   }
 
-  // Check venue implementation
-  const implementedVenues = IMPLEMENTED_VENUES[targetChain][kind] || [];
+  const requestedVenue = venue?.toLowerCase();
 
-  // Handle perp intents
-  if (kind === 'perp') {
-    const requestedVenue = venue?.toLowerCase();
+  // Handle bridge intents
+  if (kind === 'bridge') {
+    // Check if bridging between different chains
+    if (sourceChain && destChain && sourceChain !== destChain) {
+      if (LIFI_EXECUTION_ENABLED) {
+        return {
+          chain: 'ethereum',
+          network: 'sepolia',
+          venue: 'lifi',
+          executionType: 'real',
+        };
+      }
 
-    // If they request a specific venue like drift/hl, fail clearly
-    if (requestedVenue && ['drift', 'hl', 'hyperliquid', 'dydx'].includes(requestedVenue)) {
+      // Quote + proof txs unless LiFi execution is enabled
+      return {
+        chain: targetChain,
+        network,
+        venue: 'lifi',
+        executionType: 'proof_only',
+        warnings: ['Bridge execution not fully implemented. Will attempt LiFi quote.'],
+      };
+    }
+  }
+
+  // Requested venue is known but not integrated
+  if (requestedVenue && !getVenueAdapter(requestedVenue) && PENDING_VENUES[kind]?.includes(requestedVenue)) {
+    if (kind === 'perp') {
       return {
         error: {
           stage: 'route',
@@ -414,95 +447,163 @@ export function routeIntent(
       };
     }
 
-    // Check if demo perp adapter is configured for real execution
-    const demoPerpAdapter = process.env.DEMO_PERP_ADAPTER_ADDRESS;
-    if (demoPerpAdapter && targetChain === 'ethereum') {
-      // Real execution via DemoPerpAdapter on Sepolia
-      return {
-        chain: 'ethereum',
-        network: 'sepolia',
-        venue: 'demo_perp',
-        adapter: demoPerpAdapter,
-        executionType: 'real',
-      };
-    }
-
-    // Fallback to proof-only if adapter not configured
+    // Route to proof_only instead of failing
     return {
       chain: targetChain,
       network,
-      venue: 'demo_perp',
+      venue: requestedVenue,
       executionType: 'proof_only',
-      warnings: ['PROOF_ONLY: DemoPerpAdapter not configured. Recording intent proof on-chain.'],
+      warnings: [
+        `PROOF_ONLY: Deposit venue "${requestedVenue}" is not yet integrated.`,
+        'Recording intent proof on-chain.',
+      ],
     };
   }
 
-  // Handle bridge intents
-  if (kind === 'bridge') {
-    // Check if bridging between different chains
-    if (sourceChain && destChain && sourceChain !== destChain) {
-      // Bridge is quote-only for now
+  // Discover a venue from the adapter registry
+  // A requested venue wins if it supports this kind on the target chain
+  const ctx = { chain: targetChain, network };
+  const requestedAdapter = getVenueAdapter(requestedVenue);
+  if (requestedAdapter && adapterSupports(requestedAdapter, kind, targetChain)) {
+    return requestedAdapter.buildPlan(parsed, ctx);
+  }
+
+  // Otherwise quote the highest-priority venues and pick the best net output (after fees, impact and gas)
+  // Quote-only venues (today every live DEX) are priced alongside for reference but never selected;
+  // when they are the only competition the route says no choice was made
+  const candidates = findVenueAdapters(kind, targetChain);
+  const routable = candidates.filter(candidate => !candidate.capabilities.quoteOnly);
+  const topPriority = routable[0]?.capabilities.priority || 0;
+  const quoting = candidates.filter(candidate =>
+    candidate.quote &&
+    (candidate.capabilities.quoteOnly || (candidate.capabilities.priority || 0) === topPriority)
+  );
+  if (quoting.length > 1) {
+    const ranked = await rankVenueQuotes(quoting, parsed, ctx, ROUTING_QUOTE_TIMEOUT_MS);
+    const selected = ranked.find(quote => quote.selected);
+    const best = selected && getVenueAdapter(selected.venue);
+    if (selected && best) {
+      const runnerUp = ranked.find(quote => !quote.selected && !quote.quoteOnly);
+      const references = ranked.filter(quote => quote.quoteOnly).map(quote => quote.venue);
+      const route = best.buildPlan(parsed, ctx);
+      if (runnerUp) {
+        return {
+          ...route,
+          alternatives: ranked,
+          selectionReason: `${best.id} nets $${selected.netOutUsd.toFixed(2)} vs $${runnerUp.netOutUsd.toFixed(2)} on ${runnerUp.venue}`,
+        };
+      }
       return {
-        chain: targetChain,
-        network,
-        venue: 'lifi',
-        executionType: 'proof_only',
-        warnings: ['Bridge execution not fully implemented. Will attempt LiFi quote.'],
+        ...route,
+        alternatives: ranked,
+        selectionReason: references.length > 0
+          ? `${best.id} is the only venue that can settle this intent; ${references.join(', ')} quoted for reference only`
+          : `${best.id} was the only venue to return a quote`,
+        warnings: references.length > 0
+          ? [...(route.warnings || []), `${references.join(', ')} quotes are reference prices only; no venue choice was made.`]
+          : route.warnings,
       };
     }
   }
 
-  // Handle deposit intents
-  if (kind === 'deposit') {
-    const requestedVenue = venue?.toLowerCase();
+  if (routable[0]) {
+    return routable[0].buildPlan(parsed, ctx);
+  }
 
-    // Check for unimplemented venues - route to proof_only instead of failing
-    if (requestedVenue && ['kamino', 'drift'].includes(requestedVenue)) {
-      return {
-        chain: targetChain,
-        network,
-        venue: requestedVenue,
-        executionType: 'proof_only',
-        warnings: [
-          `PROOF_ONLY: Deposit venue "${requestedVenue}" is not yet integrated.`,
-          'Recording intent proof on-chain.',
-        ],
-      };
-    }
+  // Unknown/proof
+  return {
+    chain: targetChain,
+    network,
+    venue: 'native',
+    executionType: 'proof_only',
+    warnings: ['Intent not recognized. Recording proof-of-execution only.'],
+  };
+}
 
-    // Route to appropriate venue
-    if (requestedVenue === 'aave' && targetChain === 'ethereum') {
+// ============================================
+// Built-in venue adapters
+// ============================================
+
+/**
+ * Chain-level executor for venues that share the generic ethereum/solana paths
+ */
+function executeByChain(intentId: string, parsed: ParsedIntent, route: RouteDecision): Promise<IntentExecutionResult> {
+  return route.chain === 'ethereum'
+    ? executeEthereum(intentId, parsed, route)
+    : executeSolana(intentId, parsed, route);
+}
+
+// DemoPerpAdapter on Sepolia (proof-only when the adapter is not configured)
+registerVenueAdapter({
+  id: 'demo_perp',
+  capabilities: { chains: ['ethereum', 'solana'], kinds: ['perp'] },
+  buildPlan(parsed, { chain, network }) {
+    const demoPerpAdapter = process.env.DEMO_PERP_ADAPTER_ADDRESS;
+    if (demoPerpAdapter && chain === 'ethereum') {
+      // Real execution via DemoPerpAdapter on Sepolia
       return {
         chain: 'ethereum',
         network: 'sepolia',
-        venue: 'aave',
+        venue: 'demo_perp',
+        adapter: demoPerpAdapter,
         executionType: 'real',
       };
     }
 
-    // Default to demo vault - Solana goes to proof_only for now
-    if (targetChain === 'solana') {
-      return {
-        chain: 'solana',
-        network: 'devnet',
-        venue: 'solana_vault',
-        executionType: 'proof_only',
-        warnings: ['PROOF_ONLY: Solana vault integration pending. Recording intent proof on-chain.'],
-      };
-    }
-
     return {
-      chain: targetChain,
+      chain,
       network,
-      venue: 'demo_vault',
-      executionType: 'real',
+      venue: 'demo_perp',
+      executionType: 'proof_only',
+      warnings: ['PROOF_ONLY: DemoPerpAdapter not configured. Recording intent proof on-chain.'],
     };
-  }
-
-  // Handle swap intents
-  if (kind === 'swap') {
-    // Solana swaps go to proof_only since execution isn't fully wired
-    if (targetChain === 'solana') {
+  },
+  execute: executePerpEthereum,
+});
+
+// Demo vault (default deposit venue on Sepolia)
+registerVenueAdapter({
+  id: 'demo_vault',
+  capabilities: { chains: ['ethereum'], kinds: ['deposit'] },
+  buildPlan(parsed, { chain, network }) {
+    return { chain, network, venue: 'demo_vault', executionType: 'real' };
+  },
+  execute: executeByChain,
+});
+
+// Aave V3 on Sepolia (only when requested by name)
+registerVenueAdapter({
+  id: 'aave',
+  capabilities: { chains: ['ethereum'], kinds: ['deposit'], explicitOnly: true },
+  buildPlan() {
+    return { chain: 'ethereum', network: 'sepolia', venue: 'aave', executionType: 'real' };
+  },
+  execute: executeByChain,
+});
+
+// Solana vault (integration pending)
+registerVenueAdapter({
+  id: 'solana_vault',
+  capabilities: { chains: ['solana'], kinds: ['deposit'] },
+  buildPlan() {
+    return {
+      chain: 'solana',
+      network: 'devnet',
+      venue: 'solana_vault',
+      executionType: 'proof_only',
+      warnings: ['PROOF_ONLY: Solana vault integration pending. Recording intent proof on-chain.'],
+    };
+  },
+  execute: executeByChain,
+});
+
+// Demo DEX (Solana swaps are proof-only since execution isn't fully wired)
+registerVenueAdapter({
+  id: 'demo_dex',
+  capabilities: { chains: ['ethereum', 'solana'], kinds: ['swap'] },
+  quote: quoteDemoDex,
+  buildPlan(parsed, { chain, network }) {
+    if (chain === 'solana') {
       return {
         chain: 'solana',
         network: 'devnet',
@@ -511,24 +612,10 @@ export function routeIntent(
         warnings: ['PROOF_ONLY: Solana swap integration pending. Recording intent proof on-chain.'],
       };
     }
-
-    return {
-      chain: targetChain,
-      network,
-      venue: 'demo_dex',
-      executionType: 'real',
-    };
-  }
-
-  // Unknown/proof
-  return {
-    chain: targetChain,
-    network,
-    venue: 'native',
-    executionType: 'proof_only',
-    warnings: ['Intent not recognized. Recording proof-of-execution only.'],
-  };
-}
+    return { chain, network, venue: 'demo_dex', executionType: 'real' };
+  },
+  execute: executeByChain,
+});
 
 /**
  * Estimate USD value for an intent
@@ -617,7 +704,7 @@ export async function runIntent(
       metadataJson: buildMetadata({ options: { ...options, metadata: undefined } }),
     });
 
-    const route = routeIntent(parsed, options.chain);
+    const route = await routeIntent(parsed, options.chain);
 
     // Check for routing error
     if ('error' in route) {
@@ -688,6 +775,8 @@ export async function runIntent(
             venue: route.venue,
             executionType: route.executionType,
             warnings: route.warnings,
+            alternatives: route.alternatives,
+            selectionReason: route.selectionReason,
           },
         },
       };
@@ -722,19 +811,23 @@ export async function runIntent(
 /**
  * Execute a previously planned intent by ID
  * Used for confirm-mode flow where user reviews plan first
+ *
+ * resume: queue retries also accept intents a previous attempt left
+ * routed, executing or failed
  */
 export async function executeIntentById(
-  intentId: string
+  intentId: string,
+  options: { resume?: boolean } = {}
 ): Promise<IntentExecutionResult> {
   const {
-    getIntent,
-    updateIntentStatus,
+    getIntentAsync,
+    updateIntentStatusAsync,
   } = await import('../../execution-ledger/db');
 
   const now = Math.floor(Date.now() / 1000);
 
   // Get the intent
-  const intent = getIntent(intentId);
+  const intent = await getIntentAsync(intentId);
   if (!intent) {
     return {
       ok: false,
@@ -748,8 +841,9 @@ export async function executeIntentById(
     };
   }
 
-  // Verify intent is in planned status
-  if (intent.status !== 'planned') {
+  // Verify intent is in an executable status
+  const executableStatuses = options.resume ? ['planned', 'routed', 'executing', 'failed'] : ['planned'];
+  if (!executableStatuses.includes(intent.status)) {
     return {
       ok: false,
       intentId,
@@ -757,7 +851,7 @@ export async function executeIntentById(
       error: {
         stage: 'execute',
         code: 'INVALID_STATUS',
-        message: `Intent is in ${intent.status} status, expected 'planned'`,
+        message: `Intent is in ${intent.status} status, expected '${executableStatuses.join("' | '")}'`,
       },
     };
   }
@@ -782,7 +876,7 @@ export async function executeIntentById(
     }
 
     // Update status to executing
-    await updateIntentStatus(intentId, {
+    await updateIntentStatusAsync(intentId, {
       status: 'executing',
       executedAt: now,
     });
@@ -798,7 +892,7 @@ export async function executeIntentById(
     return execResult;
 
   } catch (error: any) {
-    await updateIntentStatus(intentId, {
+    await updateIntentStatusAsync(intentId, {
       status: 'failed',
       failureStage: 'execute',
       errorCode: 'EXECUTION_ERROR',
@@ -818,6 +912,80 @@ export async function executeIntentById(
   }
 }
 
+/**
+ * Record a queued intent without running it
+ * The intent job queue drives it through route → execute → confirm
+ */
+export async function createQueuedIntent(
+  intentText: string,
+  options: {
+    chain?: ChainTarget;
+    metadata?: Record<string, any>;
+  } = {}
+): Promise<{ intentId: string; parsed: ParsedIntent }> {
+  const { createIntentAsync } = await import('../../execution-ledger/db');
+
+  const parsed = parseIntent(intentText);
+  const intent = await createIntentAsync({
+    intentText,
+    intentKind: parsed.kind,
+    requestedVenue: parsed.venue,
+    usdEstimate: estimateIntentUsd(parsed),
+    metadataJson: JSON.stringify({
+      ...(options.metadata || {}),
+      parsed,
+      options: { chain: options.chain, queued: true },
+    }),
+  });
+
+  return { intentId: intent.id, parsed };
+}
+
+/**
+ * Route a queued intent and persist the decision
+ * Idempotent: an intent that already has a route is left as is
+ */
+export async function routeQueuedIntent(
+  intentId: string
+): Promise<{ ok: true } | { ok: false; error: { stage: IntentFailureStage; code: string; message: string } }> {
+  const { getIntentAsync, updateIntentStatusAsync } = await import('../../execution-ledger/db');
+
+  const intent = await getIntentAsync(intentId);
+  if (!intent) {
+    return { ok: false, error: { stage: 'route', code: 'INTENT_NOT_FOUND', message: `Intent ${intentId} not found` } };
+  }
+
+  const metadata = JSON.parse(intent.metadata_json || '{}');
+  if (metadata.route) {
+    return { ok: true };
+  }
+
+  const parsed = (metadata.parsed as ParsedIntent) || parseIntent(intent.intent_text);
+  await updateIntentStatusAsync(intentId, {
+    status: 'planned',
+    plannedAt: Math.floor(Date.now() / 1000),
+  });
+
+  const route = await routeIntent(parsed, metadata.options?.chain);
+  if ('error' in route) {
+    await updateIntentStatusAsync(intentId, {
+      status: 'failed',
+      failureStage: route.error.stage,
+      errorCode: route.error.code,
+      errorMessage: route.error.message,
+    });
+    return { ok: false, error: route.error };
+  }
+
+  await updateIntentStatusAsync(intentId, {
+    status: 'routed',
+    requestedChain: route.chain,
+    requestedVenue: route.venue,
+    metadataJson: JSON.stringify({ ...metadata, parsed, route }),
+  });
+  return { ok: true };
+}
+
 /**
  * Handle bridge intent with LiFi quote
  * Produces proof txs on both chains to record the bridge intent attempt
@@ -836,6 +1004,10 @@ async function handleBridgeIntent(
   const { buildExplorerUrl } = await import('../ledger/ledger');
   const { getLiFiQuote } = await import('../bridge/lifi');
 
+  if (route.executionType === 'real') {
+    return await executeLiFiBridgeIntent(intentId, parsed, route);
+  }
+
   const now = Math.floor(Date.now() / 1000);
 
   // Attempt LiFi quote
@@ -936,6 +1108,135 @@ async function handleBridgeIntent(
   return sourceProofResult;
 }
 
+/**
+ * Prepare a bridge intent through LiFi (LIFI_EXECUTION_ENABLED)
+ * The user signs the source leg on Sepolia: the intent stays planned with the
+ * LiFi transaction and approvals in its metadata until the user submits the
+ * tx hash (POST /api/execute/submit with bridgeTransferId). The bridge tracker
+ * then follows the transfer and settles the intent.
+ *
+ * The caller passes the user's wallet as metadata.userAddress. The amount must
+ * be explicit; the recipient is rawParams.toAddress, else the user's own
+ * address on EVM destinations or their linked Solana wallet.
+ */
+async function executeLiFiBridgeIntent(
+  intentId: string,
+  parsed: ParsedIntent,
+  route: RouteDecision
+): Promise<IntentExecutionResult> {
+  const { getIntent, updateIntentStatus, listLinkedWallets } = await import('../../execution-ledger/db');
+
+  const fail = async (
+    stage: IntentFailureStage,
+    code: string,
+    message: string,
+    extra: Record<string, any> = {}
+  ): Promise<IntentExecutionResult> => {
+    await updateIntentStatus(intentId, {
+      status: 'failed',
+      failureStage: stage,
+      errorCode: code,
+      errorMessage: message,
+    });
+    return { ok: false, intentId, status: 'failed', error: { stage, code, message }, ...extra };
+  };
+
+  let metadata: Record<string, any> = {};
+  try {
+    metadata = JSON.parse(getIntent(intentId)?.metadata_json || '{}');
+  } catch {}
+
+  const userAddress = metadata.userAddress;
+  if (typeof userAddress !== 'string' || !/^0x[0-9a-fA-F]{40}$/.test(userAddress)) {
+    return fail('plan', 'USER_ADDRESS_REQUIRED', 'Bridge intents need the signing wallet in metadata.userAddress');
+  }
+  if (!parsed.amount) {
+    return fail('plan', 'AMOUNT_REQUIRED', 'Bridge intents need an explicit amount, e.g. "bridge 100 USDC from sepolia to solana"');
+  }
+
+  const destChain = parsed.destChain || 'solana';
+  let toAddress: string | undefined = parsed.rawParams.toAddress;
+  if (!toAddress) {
+    if (destChain === 'solana') {
+      toAddress = listLinkedWallets(userAddress).find(wallet => wallet.chain === 'solana')?.address;
+    } else {
+      toAddress = userAddress;
+    }
+  }
+  if (!toAddress) {
+    return fail('plan', 'RECIPIENT_REQUIRED', 'No recipient for the destination chain; link a Solana wallet or give a toAddress');
+  }
+
+  const { parseUnits, createPublicClient, http } = await import('viem');
+  const { sepolia } = await import('viem/chains');
+  const { ETH_TESTNET_RPC_URL } = await import('../config');
+  const { prepareLiFiBridge } = await import('../bridge/lifiExecutor');
+
+  const asset = (parsed.amountUnit || 'USDC').toUpperCase();
+  const decimals = asset === 'ETH' || asset === 'WETH' ? 18 : 6;
+  const amount = parsed.amount;
+
+  const result = await prepareLiFiBridge(
+    {
+      // The user signs on Sepolia; a quote for any other chain is rejected
+      fromChain: 'sepolia',
+      toChain: destChain,
+      fromToken: asset,
+      toToken: asset,
+      fromAmount: parseUnits(amount, decimals).toString(),
+      fromAddress: userAddress,
+      toAddress,
+      intentId,
+      intentText: parsed.rawParams.original || `bridge ${amount} ${asset}`,
+      amountDisplay: `${amount} ${asset}`,
+      usdEstimate: estimateIntentUsd(parsed),
+    },
+    {
+      chainId: sepolia.id,
+      publicClient: ETH_TESTNET_RPC_URL
+        ? createPublicClient({ chain: sepolia, transport: http(ETH_TESTNET_RPC_URL) })
+        : undefined,
+    }
+  );
+
+  const bridgeMetadata = {
+    tool: result.quote?.tool,
+    toAmount: result.quote?.toAmount,
+    toAddress,
+    bridgeTransferId: result.bridgeTransferId,
+    sourceExecutionId: result.sourceExecutionId,
+    transaction: result.transaction,
+    approvals: result.approvals,
+  };
+
+  if (!result.ok) {
+    return fail(result.error!.stage, result.error!.code, result.error!.message, {
+      metadata: { executedKind: 'real', bridge: bridgeMetadata },
+    });
+  }
+
+  // Awaiting the user's signature; submit moves the intent to executing
+  await updateIntentStatus(intentId, {
+    status: 'planned',
+    plannedAt: Math.floor(Date.now() / 1000),
+    metadataJson: JSON.stringify({
+      ...metadata,
+      parsed,
+      route,
+      executedKind: 'real',
+      awaitingSignature: true,
+      bridge: bridgeMetadata,
+    }),
+  });
+  return {
+    ok: true,
+    intentId,
+    status: 'planned',
+    executionId: result.sourceExecutionId,
+    metadata: { executedKind: 'real', awaitingSignature: true, bridge: bridgeMetadata },
+  };
+}
+
 /**
  * Execute intent on the appropriate chain
  */
@@ -963,8 +1264,18 @@ async function executeOnChain(
     return await executeProofOnly(intentId, parsed, route);
   }
 
-  // Real perp execution via DemoPerpAdapter
-  if (parsed.kind === 'perp' && route.executionType === 'real' && route.chain === 'ethereum') {
+  // Real execution via the routed venue's adapter
+  const adapter = getVenueAdapter(route.venue);
+  if (adapter) {
+    const result = await adapter.execute(intentId, parsed, route);
+    if (adapter.confirm && result.ok && result.status !== 'confirmed') {
+      return await adapter.confirm(intentId, result);
+    }
+    return result;
+  }
+
+  // Fallback for routes whose venue is no longer registered
+  if (parsed.kind === 'perp' && route.chain === 'ethereum') {
     return await executePerpEthereum(intentId, parsed, route);
   }
 
@@ -1280,6 +1591,7 @@ async function executePerpEthereum(
       functionName: 'execute',
       args: [encodedInnerData as `0x${string}`],
     });
+    await checkpointSubmittedTx(intentId, 'ethereum', txHash);
 
     // Wait for confirmation
     const receipt = await publicClient.waitForTransactionReceipt({
@@ -1571,6 +1883,7 @@ async function executeProofOnly(
       value: transferAmount,
       data: proofHex as `0x${string}`,
     });
+    await checkpointSubmittedTx(intentId, 'ethereum', txHash);
 
     // Wait for confirmation
     const receipt = await publicClient.waitForTransactionReceipt({
@@ -1716,67 +2029,15 @@ async function executeProofOnlySolana(
   }
 
   try {
-    // Use the existing Solana proof tx logic from solana-ledger-smoke
-    const { SolanaClient } = await import('../solana/solanaClient');
-    const crypto = await import('crypto');
-
-    // Base58 helpers
-    const BASE58_ALPHABET = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
-
-    function base58Decode(str: string): Buffer {
-      const bytes = [0];
-      for (const char of str) {
-        let value = BASE58_ALPHABET.indexOf(char);
-        if (value === -1) throw new Error(`Invalid base58 character: ${char}`);
-        for (let i = 0; i < bytes.length; i++) {
-          const product = bytes[i] * 58 + value;
-          bytes[i] = product % 256;
-          value = Math.floor(product / 256);
-        }
-        while (value > 0) {
-          bytes.push(value % 256);
-          value = Math.floor(value / 256);
-        }
-      }
-      for (const char of str) {
-        if (char !== '1') break;
-        bytes.push(0);
-      }
-      return Buffer.from(bytes.reverse());
-    }
-
-    function base58Encode(buffer: Buffer): string {
-      const digits = [0];
-      for (let i = 0; i < buffer.length; i++) {
-        let carry = buffer[i];
-        for (let j = 0; j < digits.length; j++) {
-          carry += digits[j] << 8;
-          digits[j] = carry % 58;
-          carry = Math.floor(carry / 58);
-        }
-        while (carry > 0) {
-          digits.push(carry % 58);
-          carry = Math.floor(carry / 58);
-        }
-      }
-      let output = '';
-      for (let i = 0; i < buffer.length && buffer[i] === 0; i++) {
-        output += BASE58_ALPHABET[0];
-      }
-      for (let i = digits.length - 1; i >= 0; i--) {
-        output += BASE58_ALPHABET[digits[i]];
-      }
-      return output;
-    }
+    const {
+      SolanaClient,
+      keypairFromSecretKey,
+      systemTransferInstruction,
+    } = await import('../solana/solanaClient');
 
     // Parse sender keypair
-    const secretKey = base58Decode(solanaPrivateKey);
-    if (secretKey.length !== 64) {
-      throw new Error(`Invalid Solana secret key length: ${secretKey.length}`);
-    }
-    const privateKey = secretKey.slice(0, 32);
-    const publicKey = secretKey.slice(32, 64);
-    const senderPubkey = base58Encode(publicKey);
+    const sender = keypairFromSecretKey(solanaPrivateKey);
+    const senderPubkey = sender.publicKey;
 
     // Create execution record
     const execution = await createExecutionAsync({
@@ -1794,71 +2055,18 @@ async function executeProofOnlySolana(
 
     await linkExecutionToIntentAsync(execution.id, intentId);
 
-    // Use SolanaClient to send a small transfer as proof
+    // Use SolanaClient to send a small self-transfer as proof
     const client = new SolanaClient();
-    const DEVNET_RPC = 'https://api.devnet.solana.com';
-    const LAMPORTS_PER_SOL = 1_000_000_000;
     const transferLamports = 1000; // 0.000001 SOL as proof marker
 
-    // Get recent blockhash
-    const { blockhash } = await client.getRecentBlockhash();
-
-    // Build and sign transaction (using the existing pattern from smoke test)
-    // System Program ID (all zeros)
-    const systemProgramId = Buffer.alloc(32);
-
-    function encodeCompactU16(value: number): Buffer {
-      if (value < 128) return Buffer.from([value]);
-      if (value < 16384) return Buffer.from([(value & 0x7f) | 0x80, value >> 7]);
-      return Buffer.from([(value & 0x7f) | 0x80, ((value >> 7) & 0x7f) | 0x80, value >> 14]);
-    }
-
-    // Transfer instruction data
-    const instructionData = Buffer.alloc(12);
-    instructionData.writeUInt32LE(2, 0); // Transfer instruction
-    instructionData.writeBigUInt64LE(BigInt(transferLamports), 4);
-
-    // For self-transfer: only include sender once, reference it twice in instruction
-    // Header: [num_sigs, num_readonly_signed, num_readonly_unsigned]
-    // For self-transfer: 1 signer (sender), 0 readonly signed, 1 readonly unsigned (system program)
-    const header = Buffer.from([1, 0, 1]);
-    const accountsLength = encodeCompactU16(2);
-    // accounts: [sender (writable, signer), system_program (readonly)]
-    const accounts = Buffer.concat([publicKey, systemProgramId]);
-    const blockhashBytes = base58Decode(blockhash);
-
-    const instructionsLength = encodeCompactU16(1);
-    const programIdIndex = Buffer.from([1]); // System program is at index 1
-    const accountIndicesLength = encodeCompactU16(2);
-    // For self-transfer: source=0, dest=0 (same account)
-    const accountIndices = Buffer.from([0, 0]);
-    const dataLength = encodeCompactU16(instructionData.length);
-
-    const instruction = Buffer.concat([
-      programIdIndex, accountIndicesLength, accountIndices, dataLength, instructionData
-    ]);
-
-    const message = Buffer.concat([
-      header, accountsLength, accounts, blockhashBytes, instructionsLength, instruction
-    ]);
-
-    // Sign message
-    const keyObject = crypto.createPrivateKey({
-      key: Buffer.concat([Buffer.from('302e020100300506032b657004220420', 'hex'), privateKey]),
-      format: 'der',
-      type: 'pkcs8',
-    });
-    const signature = Buffer.from(crypto.sign(null, message, keyObject));
-
-    // Build signed transaction
-    const signedTx = Buffer.concat([Buffer.from([1]), signature, message]);
-    const signedTxBase64 = signedTx.toString('base64');
-
-    // Send transaction
-    const txSignature = await client.sendTransaction(signedTxBase64);
+    const { signature: txSignature, lastValidBlockHeight } = await client.signAndSendTransaction(
+      [systemTransferInstruction(senderPubkey, senderPubkey, transferLamports)],
+      [sender]
+    );
+    await checkpointSubmittedTx(intentId, 'solana', txSignature);
 
     // Wait for confirmation
-    const result = await client.confirmTransaction(txSignature, 'confirmed', 60000);
+    const result = await client.confirmTransaction(txSignature, 'confirmed', 60000, lastValidBlockHeight);
 
     const latencyMs = Date.now() - startTime;
     const explorerUrl = buildExplorerUrl('solana', 'devnet', txSignature);
@@ -2004,6 +2212,7 @@ async function executeEthereum(
       to: account.address,
       value: transferAmount,
     });
+    await checkpointSubmittedTx(intentId, 'ethereum', txHash);
 
     // Wait for confirmation
     const receipt = await publicClient.waitForTransactionReceipt({
@@ -0,0 +1,66 @@
+/**
+ * Mock Venue Adapter
+ * In-memory venue for tests: records every call and never touches a chain
+ */
+
+import type { ParsedIntent, RouteDecision, IntentExecutionResult } from '../intentRunner';
+import type { VenueAdapter, VenueCapabilities, VenueContext, VenueQuote } from './registry';
+
+export interface MockVenueAdapter extends VenueAdapter {
+  calls: {
+    quote: ParsedIntent[];
+    buildPlan: ParsedIntent[];
+    execute: { intentId: string; route: RouteDecision }[];
+  };
+}
+
+/**
+ * Create a mock adapter
+ * Defaults to a real-execution swap venue on both chains; override as needed
+ */
+export function createMockVenueAdapter(options: {
+  id?: string;
+  capabilities?: Partial<VenueCapabilities>;
+  quote?: Partial<VenueQuote>;
+  executionResult?: Partial<IntentExecutionResult>;
+} = {}): MockVenueAdapter {
+  const id = options.id || 'mock_venue';
+  const calls: MockVenueAdapter['calls'] = { quote: [], buildPlan: [], execute: [] };
+
+  return {
+    id,
+    calls,
+    capabilities: {
+      chains: ['ethereum', 'solana'],
+      kinds: ['swap'],
+      ...options.capabilities,
+    },
+
+    async quote(parsed: ParsedIntent, ctx: VenueContext): Promise<VenueQuote> {
+      calls.quote.push(parsed);
+      return { venue: id, chain: ctx.chain, feeUsd: 0, gasUsd: 0, ...options.quote };
+    },
+
+    buildPlan(parsed: ParsedIntent, ctx: VenueContext): RouteDecision {
+      calls.buildPlan.push(parsed);
+      return {
+        chain: ctx.chain,
+        network: ctx.network,
+        venue: id,
+        executionType: 'real',
+      };
+    },
+
+    async execute(intentId: string, parsed: ParsedIntent, route: RouteDecision): Promise<IntentExecutionResult> {
+      calls.execute.push({ intentId, route });
+      return {
+        ok: true,
+        intentId,
+        status: 'confirmed',
+        txHash: `0x${'0'.repeat(63)}${calls.execute.length}`,
+        metadata: { executedKind: 'real', venue: id },
+        ...options.executionResult,
+      };
+    },
+  };
+}
@@ -0,0 +1,169 @@
+/**
+ * Venue Adapter Registry
+ *
+ * Each execution venue (demo vault, demo DEX, DemoPerpAdapter, ...) registers a
+ * VenueAdapter describing what it can do and how to plan/execute an intent.
+ * routeIntent discovers venues from this registry, so adding a venue is a
+ * single module that calls registerVenueAdapter().
+ */
+
+import type { ParsedIntent, RouteDecision, IntentExecutionResult } from '../intentRunner';
+
+export type VenueChain = 'ethereum' | 'solana';
+
+export interface VenueCapabilities {
+  chains: VenueChain[];
+  kinds: ParsedIntent['kind'][];
+  aliases?: string[];           // Alternate names users may request (e.g., 'hyperliquid')
+  explicitOnly?: boolean;       // Only routed when the user names the venue
+  priority?: number;            // Higher wins when several adapters match (default 0); equal priorities compete on quotes
+  quoteOnly?: boolean;          // Prices intents for comparison but cannot settle them; never selected
+}
+
+export interface VenueQuote {
+  venue: string;
+  chain: VenueChain;
+  inputUsd?: number;            // Value of what the user sends
+  expectedOutUsd?: number;      // Value of what the user receives (after fees and price impact)
+  expectedOut?: string;         // Human-readable output amount (e.g., "0.0475")
+  feeUsd?: number;              // Venue/LP fee component of the cost
+  priceImpactUsd?: number;      // Price impact / slippage component of the cost
+  gasUsd?: number;
+  source?: string;              // Where the quote came from (e.g., 'uniswap_quoter', 'deterministic')
+  warnings?: string[];
+}
+
+// Quote scored and ranked against other eligible venues
+export interface RankedVenueQuote extends VenueQuote {
+  rank: number;                 // 1 = best
+  netOutUsd: number;            // expectedOutUsd - gasUsd
+  selected: boolean;
+  quoteOnly?: boolean;          // Reference price from a venue that cannot settle the intent
+}
+
+export interface VenueContext {
+  chain: VenueChain;
+  network: 'sepolia' | 'devnet';
+}
+
+export interface VenueAdapter {
+  id: string;
+  capabilities: VenueCapabilities;
+  /** Price the intent on this venue (optional - used for venue comparison) */
+  quote?(parsed: ParsedIntent, ctx: VenueContext): Promise<VenueQuote | null>;
+  /** Build the route for this venue (may downgrade to proof_only when not configured) */
+  buildPlan(parsed: ParsedIntent, ctx: VenueContext): RouteDecision;
+  /** Whether the venue can currently be routed to automatically (default: always) */
+  isAvailable?(): boolean;
+  /** Execute a routed intent for real */
+  execute(intentId: string, parsed: ParsedIntent, route: RouteDecision): Promise<IntentExecutionResult>;
+  /** Confirm an execution that returned before reaching 'confirmed' (optional) */
+  confirm?(intentId: string, result: IntentExecutionResult): Promise<IntentExecutionResult>;
+}
+
+const adapters = new Map<string, VenueAdapter>();
+
+/**
+ * Register (or replace) a venue adapter
+ */
+export function registerVenueAdapter(adapter: VenueAdapter): void {
+  adapters.set(adapter.id, adapter);
+}
+
+/**
+ * Remove a venue adapter (used by tests)
+ */
+export function unregisterVenueAdapter(id: string): boolean {
+  return adapters.delete(id);
+}
+
+/**
+ * Look up an adapter by id or alias
+ */
+export function getVenueAdapter(venue: string | undefined): VenueAdapter | undefined {
+  if (!venue) return undefined;
+  const name = venue.toLowerCase();
+  const direct = adapters.get(name);
+  if (direct) return direct;
+  for (const adapter of adapters.values()) {
+    if (adapter.capabilities.aliases?.includes(name)) {
+      return adapter;
+    }
+  }
+  return undefined;
+}
+
+/**
+ * List registered adapters
+ */
+export function listVenueAdapters(): VenueAdapter[] {
+  return Array.from(adapters.values());
+}
+
+/**
+ * Check whether an adapter can handle an intent kind on a chain
+ */
+export function adapterSupports(adapter: VenueAdapter, kind: string, chain: VenueChain): boolean {
+  return adapter.capabilities.kinds.includes(kind as ParsedIntent['kind']) &&
+    adapter.capabilities.chains.includes(chain);
+}
+
+/**
+ * Find adapters for an intent kind on a chain, best first
+ * Explicit-only venues are excluded unless includeExplicit is set
+ */
+export function findVenueAdapters(
+  kind: string,
+  chain: VenueChain,
+  includeExplicit: boolean = false
+): VenueAdapter[] {
+  return listVenueAdapters()
+    .filter(adapter => adapterSupports(adapter, kind, chain))
+    .filter(adapter => includeExplicit || !adapter.capabilities.explicitOnly)
+    .filter(adapter => !adapter.isAvailable || adapter.isAvailable())
+    .sort((a, b) => (b.capabilities.priority || 0) - (a.capabilities.priority || 0));
+}
+
+/**
+ * Net value of a quote: output after fees and price impact, minus gas
+ */
+export function scoreVenueQuote(quote: VenueQuote): number {
+  return (quote.expectedOutUsd ?? 0) - (quote.gasUsd ?? 0);
+}
+
+/**
+ * Quote an intent on every adapter that supports quoting and rank the results
+ * Venues that fail, time out, or return no output are left out of the ranking;
+ * quote-only venues are ranked for reference but never selected
+ */
+export async function rankVenueQuotes(
+  candidates: VenueAdapter[],
+  parsed: ParsedIntent,
+  ctx: VenueContext,
+  timeoutMs: number
+): Promise<RankedVenueQuote[]> {
+  const results = await Promise.all(candidates
+    .filter(adapter => adapter.quote)
+    .map(async adapter => {
+      let timeoutId: ReturnType<typeof setTimeout> | undefined;
+      try {
+        const timeout = new Promise<null>(resolve => {
+          timeoutId = setTimeout(() => resolve(null), timeoutMs);
+        });
+        const quote = await Promise.race([adapter.quote!(parsed, ctx), timeout]);
+        return quote && adapter.capabilities.quoteOnly ? { ...quote, quoteOnly: true } : quote;
+      } catch (error: any) {
+        console.warn(`[routing] ${adapter.id} quote failed:`, error.message);
+        return null;
+      } finally {
+        clearTimeout(timeoutId);
+      }
+    }));
+
+  const ranked = results
+    .filter((quote): quote is VenueQuote & { quoteOnly?: boolean } => !!quote && (quote.expectedOutUsd ?? 0) > 0)
+    .map(quote => ({ ...quote, netOutUsd: scoreVenueQuote(quote) }))
+    .sort((a, b) => b.netOutUsd - a.netOutUsd);
+  const selected = ranked.find(quote => !quote.quoteOnly);
+  return ranked.map((quote, index) => ({ ...quote, rank: index + 1, selected: quote === selected }));
+}
@@ -0,0 +1,246 @@
+/**
+ * Swap Venues
+ * Quote-capable swap venues for multi-venue route selection
+ *
+ * Uniswap V3, 1inch and dFlow are quote-only: their live prices are ranked
+ * next to the demo DEX for comparison, but only venues that settle the swap
+ * can be selected (the demo router pairs are not listed on them).
+ */
+
+import {
+  DEMO_USDC_ADDRESS,
+  DEMO_WETH_ADDRESS,
+  ROUTING_MODE,
+  ETH_TESTNET_CHAIN_ID,
+  ROUTING_GAS_PRICE_GWEI,
+} from '../../config';
+import { parseUnits } from 'viem';
+import { getPrice } from '../../services/prices';
+import { getUniswapV3Quote, isUniswapQuoterAvailable } from '../../quotes/uniswapQuoter';
+import { getOneInchQuote, isOneInchAvailable } from '../../quotes/oneInchQuote';
+import { getSwapQuote as getDflowSwapQuote, isDflowCapabilityAvailable } from '../../integrations/dflow/dflowClient';
+import { registerVenueAdapter, getVenueAdapter, VenueAdapter, VenueQuote, VenueContext } from './registry';
+import type { ParsedIntent } from '../intentRunner';
+
+// Demo router keeps 95% of input value (5% fee), no price impact
+const DEMO_DEX_FEE_BPS = 500;
+const DEMO_DEX_GAS_UNITS = 150000;
+
+// Uniswap V3 fee tier used for quoting (0.3%)
+const UNISWAP_FEE_TIER = 3000;
+
+interface SwapToken {
+  symbol: 'USDC' | 'WETH';
+  decimals: number;
+  address?: string;
+}
+
+const SWAP_TOKENS: Record<string, SwapToken> = {
+  USDC: { symbol: 'USDC', decimals: 6, address: DEMO_USDC_ADDRESS },
+  WETH: { symbol: 'WETH', decimals: 18, address: DEMO_WETH_ADDRESS },
+  ETH: { symbol: 'WETH', decimals: 18, address: DEMO_WETH_ADDRESS },
+};
+
+export interface SwapLeg {
+  tokenIn: SwapToken;
+  tokenOut: SwapToken;
+  amountIn: number;
+  amountInRaw: string;
+  priceIn: number;
+  priceOut: number;
+  ethPriceUsd: number;
+}
+
+async function getTokenPriceUsd(token: SwapToken): Promise<number> {
+  return token.symbol === 'USDC' ? 1 : (await getPrice('ETH')).priceUsd;
+}
+
+/**
+ * Resolve a swap intent into priced token legs (USDC/WETH pairs only)
+ */
+export async function resolveSwapLeg(parsed: ParsedIntent): Promise<SwapLeg | null> {
+  const tokenIn = SWAP_TOKENS[(parsed.amountUnit || 'USDC').toUpperCase()];
+  const tokenOut = SWAP_TOKENS[(parsed.targetAsset || 'WETH').toUpperCase()];
+  const amountIn = parseFloat(parsed.amount || '0');
+  if (!tokenIn || !tokenOut || tokenIn.symbol === tokenOut.symbol || !(amountIn > 0)) {
+    return null;
+  }
+
+  const [priceIn, priceOut, ethPriceUsd] = await Promise.all([
+    getTokenPriceUsd(tokenIn),
+    getTokenPriceUsd(tokenOut),
+    getTokenPriceUsd(SWAP_TOKENS.WETH),
+  ]);
+
+  return {
+    tokenIn,
+    tokenOut,
+    amountIn,
+    amountInRaw: parseUnits(amountIn.toFixed(tokenIn.decimals), tokenIn.decimals).toString(),
+    priceIn,
+    priceOut,
+    ethPriceUsd,
+  };
+}
+
+function gasCostUsd(gasUnits: number, ethPriceUsd: number): number {
+  return (gasUnits * ROUTING_GAS_PRICE_GWEI * ethPriceUsd) / 1e9;
+}
+
+/**
+ * Build a costed quote from a venue's raw output amount
+ * Cost not explained by the venue fee is attributed to price impact
+ */
+function buildSwapQuote(params: {
+  venue: string;
+  ctx: VenueContext;
+  leg: SwapLeg;
+  amountOutRaw: string;
+  gasUnits: number;
+  feeBps: number;
+  source: string;
+}): VenueQuote {
+  const { venue, ctx, leg, amountOutRaw, gasUnits, feeBps, source } = params;
+  const expectedOut = Number(amountOutRaw) / 10 ** leg.tokenOut.decimals;
+  const inputUsd = leg.amountIn * leg.priceIn;
+  const expectedOutUsd = expectedOut * leg.priceOut;
+  const feeUsd = (inputUsd * feeBps) / 10000;
+
+  return {
+    venue,
+    chain: ctx.chain,
+    inputUsd,
+    expectedOut: expectedOut.toString(),
+    expectedOutUsd,
+    feeUsd,
+    priceImpactUsd: Math.max(0, inputUsd - expectedOutUsd - feeUsd),
+    gasUsd: gasCostUsd(gasUnits, leg.ethPriceUsd),
+    source,
+  };
+}
+
+/**
+ * Deterministic demo DEX quote
+ * Valued in USD terms so it competes fairly with live venues
+ */
+export async function quoteDemoDex(parsed: ParsedIntent, ctx: VenueContext): Promise<VenueQuote | null> {
+  const leg = await resolveSwapLeg(parsed);
+  if (!leg) return null;
+
+  const outUsd = leg.amountIn * leg.priceIn * (1 - DEMO_DEX_FEE_BPS / 10000);
+  const amountOut = (outUsd / leg.priceOut).toFixed(leg.tokenOut.decimals);
+  return buildSwapQuote({
+    venue: 'demo_dex',
+    ctx,
+    leg,
+    amountOutRaw: parseUnits(amountOut, leg.tokenOut.decimals).toString(),
+    gasUnits: DEMO_DEX_GAS_UNITS,
+    feeBps: DEMO_DEX_FEE_BPS,
+    source: 'deterministic',
+  });
+}
+
+/**
+ * Quote-only live swap venue
+ * Live DEXes have no settlement adapter on the demo router, so their quotes are
+ * reference prices shown beside demo_dex, never a routing choice. Naming one
+ * explicitly still settles through the demo DEX, and says so on the route.
+ */
+function createLiveSwapVenue(
+  id: string,
+  isAvailable: () => boolean,
+  quote: (leg: SwapLeg, ctx: VenueContext) => Promise<VenueQuote | null>
+): VenueAdapter {
+  return {
+    id,
+    capabilities: { chains: ['ethereum'], kinds: ['swap'], quoteOnly: true },
+    isAvailable,
+    async quote(parsed, ctx) {
+      const leg = await resolveSwapLeg(parsed);
+      if (!leg?.tokenIn.address || !leg.tokenOut.address) return null;
+      return quote(leg, ctx);
+    },
+    buildPlan(parsed, ctx) {
+      const route = getVenueAdapter('demo_dex')!.buildPlan(parsed, ctx);
+      return {
+        ...route,
+        warnings: [...(route.warnings || []), `${id} is quote-only; settling through the demo DEX on ${ctx.network}.`],
+      };
+    },
+    execute(intentId, parsed, route) {
+      return getVenueAdapter('demo_dex')!.execute(intentId, parsed, route);
+    },
+  };
+}
+
+registerVenueAdapter(createLiveSwapVenue(
+  'uniswap',
+  () => isUniswapQuoterAvailable(),
+  async (leg, ctx) => {
+    const result = await getUniswapV3Quote({
+      tokenIn: leg.tokenIn.address!,
+      tokenOut: leg.tokenOut.address!,
+      amountIn: leg.amountInRaw,
+      fee: UNISWAP_FEE_TIER,
+    });
+    if (!result) return null;
+    return buildSwapQuote({
+      venue: 'uniswap',
+      ctx,
+      leg,
+      amountOutRaw: result.amountOut,
+      gasUnits: Number(result.gasEstimate || 0),
+      feeBps: UNISWAP_FEE_TIER / 100,
+      source: 'uniswap_quoter',
+    });
+  }
+));
+
+registerVenueAdapter(createLiveSwapVenue(
+  '1inch',
+  () => ROUTING_MODE === 'hybrid' && isOneInchAvailable(),
+  async (leg, ctx) => {
+    const result = await getOneInchQuote({
+      chainId: ETH_TESTNET_CHAIN_ID,
+      tokenIn: leg.tokenIn.address!,
+      tokenOut: leg.tokenOut.address!,
+      amountIn: leg.amountInRaw,
+    });
+    if (!result) return null;
+    return {
+      ...buildSwapQuote({
+        venue: '1inch',
+        ctx,
+        leg,
+        amountOutRaw: result.toTokenAmount,
+        gasUnits: Number(result.estimatedGas || 0),
+        feeBps: 0,
+        source: '1inch',
+      }),
+      warnings: result.warnings,
+    };
+  }
+));
+
+registerVenueAdapter(createLiveSwapVenue(
+  'dflow',
+  () => ROUTING_MODE !== 'deterministic' && isDflowCapabilityAvailable('swapsQuotes'),
+  async (leg, ctx) => {
+    const result = await getDflowSwapQuote({
+      tokenIn: leg.tokenIn.address!,
+      tokenOut: leg.tokenOut.address!,
+      amountIn: leg.amountInRaw,
+      chainId: ETH_TESTNET_CHAIN_ID,
+    });
+    if (!result.ok || !result.data) return null;
+    return buildSwapQuote({
+      venue: 'dflow',
+      ctx,
+      leg,
+      amountOutRaw: result.data.amountOut,
+      gasUnits: Number(result.data.gas || 0),
+      feeBps: 0,
+      source: 'dflow',
+    });
+  }
+));
@@ -159,6 +159,24 @@ export async function registerWallet(params: {
   return db.registerWallet(params);
 }
 
+/**
+ * Record or update a session (status from on-chain session events)
+ */
+export async function upsertLedgerSession(params: {
+  chain: Chain;
+  network: Network;
+  userAddress: string;
+  sessionId: string;
+  relayerAddress?: string;
+  status: 'preparing' | 'active' | 'revoked' | 'expired';
+  expiresAt?: number;
+  createdTx?: string;
+  blockNumber?: number;
+}) {
+  const db = await getLedgerDb();
+  return db.upsertSession(params);
+}
+
 /**
  * Build explorer URL based on chain/network
  */
@@ -238,6 +256,7 @@ export interface PositionParams {
   open_explorer_url?: string;
   user_address: string;
   on_chain_position_id?: string;
+  open_block_number?: number;
   intent_id?: string;
   execution_id?: string;
 }
@@ -262,8 +281,12 @@ export interface Position {
   close_tx_hash?: string;
   close_explorer_url?: string;
   pnl?: string;
+  funding_usd?: number;
+  funding_updated_at?: number;
   user_address: string;
   on_chain_position_id?: string;
+  open_block_number?: number;
+  close_block_number?: number;
   intent_id?: string;
   execution_id?: string;
 }
@@ -316,18 +339,19 @@ export async function closePosition(
   txHash: string,
   explorerUrl: string,
   pnl: string,
-  status: 'closed' | 'liquidated' = 'closed'
+  status: 'closed' | 'liquidated' = 'closed',
+  blockNumber?: number
 ): Promise<void> {
   const db = await getLedgerDb();
-  db.closePosition(id, txHash, explorerUrl, pnl, status);
+  db.closePosition(id, txHash, explorerUrl, pnl, status, blockNumber);
 }
 
 /**
  * Get all open positions
  */
-export async function getOpenPositions(chain?: Chain, network?: Network): Promise<Position[]> {
+export async function getOpenPositions(chain?: Chain, network?: Network, venue?: string): Promise<Position[]> {
   const db = await getLedgerDb();
-  return db.getOpenPositions(chain, network) as Position[];
+  return db.getOpenPositions({ chain, network, venue }) as Position[];
 }
 
 /**
@@ -384,6 +408,92 @@ export async function upsertIndexerState(
   db.upsertIndexerState(chain, network, contractAddress, lastIndexedBlock);
 }
 
+/**
+ * List indexer cursors (one per indexed contract)
+ */
+export async function listIndexerStates(chain?: Chain, network?: Network): Promise<IndexerState[]> {
+  const db = await getLedgerDb();
+  return db.listIndexerStates(chain, network) as IndexerState[];
+}
+
+/**
+ * Record a decoded contract event (no-op if the log was already indexed)
+ */
+export async function recordIndexedEvent(params: {
+  chain: Chain;
+  network: Network;
+  contractName: string;
+  contractAddress: string;
+  eventName: string;
+  blockNumber: number;
+  txHash: string;
+  logIndex: number;
+  args: Record<string, unknown>;
+}): Promise<boolean> {
+  const db = await getLedgerDb();
+  return db.recordIndexedEvent(params);
+}
+
+export interface IndexerBlock {
+  chain: Chain;
+  network: Network;
+  block_number: number;
+  block_hash: string;
+  recorded_at: number;
+}
+
+/**
+ * Remember the hash of an unconfirmed block the indexer has read
+ */
+export async function recordIndexerBlock(
+  chain: Chain,
+  network: Network,
+  blockNumber: number,
+  blockHash: string
+): Promise<void> {
+  const db = await getLedgerDb();
+  db.recordIndexerBlock(chain, network, blockNumber, blockHash);
+}
+
+/**
+ * List remembered block hashes from a block upwards (ascending)
+ */
+export async function listIndexerBlocks(
+  chain: Chain,
+  network: Network,
+  fromBlock: number
+): Promise<IndexerBlock[]> {
+  const db = await getLedgerDb();
+  return db.listIndexerBlocks(chain, network, fromBlock) as IndexerBlock[];
+}
+
+/**
+ * Drop remembered hashes below a block (past confirmation depth)
+ */
+export async function pruneIndexerBlocks(chain: Chain, network: Network, belowBlock: number): Promise<number> {
+  const db = await getLedgerDb();
+  return db.pruneIndexerBlocks(chain, network, belowBlock);
+}
+
+/**
+ * Roll back indexed events, positions, sessions and cursors to a common ancestor block
+ */
+export async function rollbackIndexerToBlock(
+  chain: Chain,
+  network: Network,
+  ancestorBlock: number
+): Promise<{
+  eventsRemoved: number;
+  positionsRemoved: number;
+  positionsReopened: number;
+  sessionsUnrevoked: number;
+  sessionsReverted: number;
+  cursorsRewound: number;
+}> {
+  const db = await getLedgerDb();
+  return db.rollbackIndexerToBlock(chain, network, ancestorBlock);
+}
+
 // ============================================================================
 // Execution Steps
 // ============================================================================
@@ -5,6 +5,7 @@
 
 import { v4 as uuidv4 } from 'uuid';
 import { DefiPosition, DefiState } from './types';
+import { getSimState, setSimState, markSimDirty } from '../../services/simStore';
 
 // Available vaults with APRs
 const VAULTS = {
@@ -13,17 +14,23 @@ const VAULTS = {
   Jet: { apr: 7.2, asset: 'USDC' },
 } as const;
 
-let defiState: DefiState = {
-  positions: [],
-};
+function createInitialState(): DefiState {
+  return {
+    positions: [],
+  };
+}
+
+function getState(userId: string): DefiState {
+  return getSimState('defi', userId, createInitialState);
+}
 
 // Reference to perps account for balance updates
-let getUsdcBalance: () => number;
-let updateUsdcBalance: (delta: number) => void;
+let getUsdcBalance: (userId: string) => number;
+let updateUsdcBalance: (userId: string, delta: number) => void;
 
 export function setBalanceCallbacks(
-  getBalance: () => number,
-  updateBalance: (delta: number) => void
+  getBalance: (userId: string) => number,
+  updateBalance: (userId: string, delta: number) => void
 ): void {
   getUsdcBalance = getBalance;
   updateUsdcBalance = updateBalance;
@@ -33,6 +40,7 @@ export function setBalanceCallbacks(
  * Open a DeFi position
  */
 export function openDefiPosition(
+  userId: string,
   protocol: 'Kamino' | 'RootsFi' | 'Jet',
   asset: string,
   amountUsd: number
@@ -43,14 +51,14 @@ export function openDefiPosition(
   }
 
   // Check USDC balance
-  const currentBalance = getUsdcBalance ? getUsdcBalance() : 0;
+  const currentBalance = getUsdcBalance ? getUsdcBalance(userId) : 0;
   if (currentBalance < amountUsd) {
     throw new Error(`Insufficient USDC balance. Need $${amountUsd.toFixed(2)}, have $${currentBalance.toFixed(2)}`);
   }
 
   // Deduct from USDC
   if (updateUsdcBalance) {
-    updateUsdcBalance(-amountUsd);
+    updateUsdcBalance(userId, -amountUsd);
   }
 
   // Create position
@@ -64,15 +72,16 @@ export function openDefiPosition(
     isClosed: false,
   };
 
-  defiState.positions.push(position);
+  getState(userId).positions.push(position);
+  markSimDirty(userId);
   return position;
 }
 
 /**
  * Close a DeFi position
  */
-export function closeDefiPosition(id: string): { position: DefiPosition; yieldEarned: number } {
-  const position = defiState.positions.find(p => p.id === id && !p.isClosed);
+export function closeDefiPosition(userId: string, id: string): { position: DefiPosition; yieldEarned: number } {
+  const position = getState(userId).positions.find(p => p.id === id && !p.isClosed);
   if (!position) {
     throw new Error(`Position ${id} not found or already closed`);
   }
@@ -90,8 +99,9 @@ export function closeDefiPosition(id: string): { position: DefiPosition; yieldEa
   // Credit USDC with deposit + yield
   const totalReturn = position.depositUsd + yieldEarnedUsd;
   if (updateUsdcBalance) {
-    updateUsdcBalance(totalReturn);
+    updateUsdcBalance(userId, totalReturn);
   }
+  markSimDirty(userId);
 
   return { position, yieldEarned: yieldEarnedUsd };
 }
@@ -99,18 +109,16 @@ export function closeDefiPosition(id: string): { position: DefiPosition; yieldEa
 /**
  * Get DeFi snapshot
  */
-export function getDefiSnapshot(): DefiState {
+export function getDefiSnapshot(userId: string): DefiState {
   return {
-    positions: [...defiState.positions],
+    positions: [...getState(userId).positions],
   };
 }
 
 /**
- * Reset DeFi state (for testing)
+ * Reset a user's DeFi state
  */
-export function resetDefiState(): void {
-  defiState = {
-    positions: [],
-  };
+export function resetDefiState(userId: string): void {
+  setSimState('defi', userId, createInitialState());
 }
 
@@ -4,8 +4,9 @@
  */
 
 import { v4 as uuidv4 } from 'uuid';
-import { EventMarket, EventPosition, EventState } from './types';
+import { EventMarket, EventPosition, EventPositionsState, EventState } from './types';
 import { fetchKalshiMarkets, fetchPolymarketMarkets, RawPredictionMarket } from '../../services/predictionData';
+import { getSimState, setSimState, markSimDirty } from '../../services/simStore';
 
 // Seed markets matching FALLBACK_MARKETS (harmonized IDs)
 const SEEDED_MARKETS: EventMarket[] = [
@@ -47,18 +48,26 @@ const SEEDED_MARKETS: EventMarket[] = [
   },
 ];
 
-let eventState: EventState = {
-  markets: [...SEEDED_MARKETS],
-  positions: [],
-};
+// Market catalog is shared across users; positions are per user
+const eventMarkets: EventMarket[] = [...SEEDED_MARKETS];
+
+function createInitialPositions(): EventPositionsState {
+  return {
+    positions: [],
+  };
+}
+
+function getPositionsState(userId: string): EventPositionsState {
+  return getSimState('event', userId, createInitialPositions);
+}
 
 // Reference to perps account for balance updates
-let getUsdcBalance: () => number;
-let updateUsdcBalance: (delta: number) => void;
+let getUsdcBalance: (userId: string) => number;
+let updateUsdcBalance: (userId: string, delta: number) => void;
 
 export function setBalanceCallbacks(
-  getBalance: () => number,
-  updateBalance: (delta: number) => void
+  getBalance: (userId: string) => number,
+  updateBalance: (userId: string, delta: number) => void
 ): void {
   getUsdcBalance = getBalance;
   updateUsdcBalance = updateBalance;
@@ -68,12 +77,13 @@ export function setBalanceCallbacks(
  * Open an event position
  */
 export async function openEventPosition(
+  userId: string,
   eventKey: string,
   side: 'YES' | 'NO',
   stakeUsd: number,
   label?: string // Optional label for live markets
 ): Promise<EventPosition> {
-  let market = eventState.markets.find(m => m.key === eventKey);
+  let market = eventMarkets.find(m => m.key === eventKey);
   
   // If market not found in seeded markets, try to find it in live markets
   if (!market) {
@@ -97,8 +107,8 @@ export async function openEventPosition(
           payoutMultiple,
         };
         
-        // Add to state temporarily (won't persist across resets, but that's fine)
-        eventState.markets.push(market);
+        // Add to the shared catalog (in-memory only, not persisted)
+        eventMarkets.push(market);
         console.log(`[EventSim] Created temporary market entry for live market: ${market.label}`);
       }
     } catch (error) {
@@ -111,7 +121,7 @@ export async function openEventPosition(
   }
 
   // Check USDC balance
-  const currentBalance = getUsdcBalance ? getUsdcBalance() : 0;
+  const currentBalance = getUsdcBalance ? getUsdcBalance(userId) : 0;
   if (currentBalance < stakeUsd) {
     throw new Error(`Insufficient USDC balance. Need $${stakeUsd.toFixed(2)}, have $${currentBalance.toFixed(2)}`);
   }
@@ -142,7 +152,7 @@ export async function openEventPosition(
 
   // Deduct stake from USDC
   if (updateUsdcBalance) {
-    updateUsdcBalance(-stakeUsd);
+    updateUsdcBalance(userId, -stakeUsd);
   }
 
   // Calculate max payout and loss
@@ -163,7 +173,8 @@ export async function openEventPosition(
     externalMarketId,
   };
 
-  eventState.positions.push(position);
+  getPositionsState(userId).positions.push(position);
+  markSimDirty(userId);
   return position;
 }
 
@@ -194,18 +205,18 @@ export async function getLiveEventPrice(position: EventPosition): Promise<number
 /**
  * Update an event position's stake
  */
-export async function updateEventStake(params: {
+export async function updateEventStake(userId: string, params: {
   positionId: string;
   newStakeUsd: number;
   overrideRiskCap: boolean;
   requestedStakeUsd?: number;
 }): Promise<EventPosition> {
-  const position = eventState.positions.find(p => p.id === params.positionId && !p.isClosed);
+  const position = getPositionsState(userId).positions.find(p => p.id === params.positionId && !p.isClosed);
   if (!position) {
     throw new Error(`Event position ${params.positionId} not found or already closed`);
   }
 
-  const currentBalance = getUsdcBalance ? getUsdcBalance() : 0;
+  const currentBalance = getUsdcBalance ? getUsdcBalance(userId) : 0;
   const stakeDelta = params.newStakeUsd - position.stakeUsd;
 
   // Check if we have enough balance for the increase
@@ -214,14 +225,14 @@ export async function updateEventStake(params: {
   }
 
   // Find the market to recalculate payout
-  const market = eventState.markets.find(m => m.key === position.eventKey);
+  const market = eventMarkets.find(m => m.key === position.eventKey);
   if (!market) {
     throw new Error(`Market ${position.eventKey} not found`);
   }
 
   // Update USDC balance
   if (updateUsdcBalance) {
-    updateUsdcBalance(-stakeDelta);
+    updateUsdcBalance(userId, -stakeDelta);
   }
 
   // Recalculate max payout and loss
@@ -236,6 +247,7 @@ export async function updateEventStake(params: {
   if (params.requestedStakeUsd !== undefined) {
     position.requestedStakeUsd = params.requestedStakeUsd;
   }
+  markSimDirty(userId);
 
   return position;
 }
@@ -243,13 +255,13 @@ export async function updateEventStake(params: {
 /**
  * Close an event position
  */
-export async function closeEventPosition(id: string): Promise<{ position: EventPosition; pnl: number; liveMarkToMarketUsd?: number }> {
-  const position = eventState.positions.find(p => p.id === id && !p.isClosed);
+export async function closeEventPosition(userId: string, id: string): Promise<{ position: EventPosition; pnl: number; liveMarkToMarketUsd?: number }> {
+  const position = getPositionsState(userId).positions.find(p => p.id === id && !p.isClosed);
   if (!position) {
     throw new Error(`Position ${id} not found or already closed`);
   }
 
-  const market = eventState.markets.find(m => m.key === position.eventKey);
+  const market = eventMarkets.find(m => m.key === position.eventKey);
   if (!market) {
     throw new Error(`Market ${position.eventKey} not found`);
   }
@@ -286,7 +298,7 @@ export async function closeEventPosition(id: string): Promise<{ position: EventP
     realizedPnlUsd = position.maxPayoutUsd - position.stakeUsd; // Profit
     // Credit max payout to USDC
     if (updateUsdcBalance) {
-      updateUsdcBalance(position.maxPayoutUsd);
+      updateUsdcBalance(userId, position.maxPayoutUsd);
     }
   } else {
     realizedPnlUsd = -position.stakeUsd; // Loss (stake already deducted)
@@ -298,6 +310,7 @@ export async function closeEventPosition(id: string): Promise<{ position: EventP
   position.closedAt = Date.now();
   position.outcome = outcome;
   position.realizedPnlUsd = realizedPnlUsd;
+  markSimDirty(userId);
 
   return { position, pnl: realizedPnlUsd, liveMarkToMarketUsd };
 }
@@ -305,31 +318,34 @@ export async function closeEventPosition(id: string): Promise<{ position: EventP
 /**
  * Get event snapshot
  */
-export function getEventSnapshot(): EventState {
-  const openPositions = eventState.positions.filter(p => !p.isClosed);
-  const eventExposureUsd = openPositions.reduce((sum, p) => sum + p.stakeUsd, 0);
+export function getEventSnapshot(userId: string): EventState {
+  const { positions } = getPositionsState(userId);
 
   return {
-    markets: [...eventState.markets],
-    positions: [...eventState.positions],
+    markets: [...eventMarkets],
+    positions: [...positions],
   };
 }
 
+/**
+ * Get the shared event market catalog (seeded + live entries)
+ */
+export function getEventMarkets(): EventMarket[] {
+  return [...eventMarkets];
+}
+
 /**
  * Get total event exposure
  */
-export function getEventExposureUsd(): number {
-  const openPositions = eventState.positions.filter(p => !p.isClosed);
+export function getEventExposureUsd(userId: string): number {
+  const openPositions = getPositionsState(userId).positions.filter(p => !p.isClosed);
   return openPositions.reduce((sum, p) => sum + p.stakeUsd, 0);
 }
 
 /**
- * Reset event state (for testing)
+ * Reset a user's event positions
  */
-export function resetEventState(): void {
-  eventState = {
-    markets: [...SEEDED_MARKETS],
-    positions: [],
-  };
+export function resetEventState(userId: string): void {
+  setSimState('event', userId, createInitialPositions());
 }
 
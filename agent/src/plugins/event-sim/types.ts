@@ -34,3 +34,6 @@ export interface EventState {
   positions: EventPosition[];
 }
 
+export interface EventPositionsState {
+  positions: EventPosition[];
+}
@@ -4,11 +4,13 @@
  */
 
 import { v4 as uuidv4 } from 'uuid';
-import { PerpPosition, PerpsAccountState } from './types';
+import { PerpCloseEvent, PerpCloseReason, PerpPosition, PerpsAccountState } from './types';
 import { getPrice, PriceSymbol } from '../../services/prices';
+import { getSimState, setSimState, markSimDirty, setSimRetention } from '../../services/simStore';
+import { computeFundingPaymentUsd } from '../../services/funding';
 
 // Helper to extract base symbol from market
-function getBaseSymbolFromMarket(market: string): PriceSymbol {
+export function getBaseSymbolFromMarket(market: string): PriceSymbol {
   const base = market.split('-')[0];
   if (base === 'ETH') return 'ETH';
   if (base === 'BTC') return 'BTC';
@@ -23,24 +25,36 @@ const INITIAL_BALANCES = [
   { symbol: 'SOL', balanceUsd: 3000 },
 ];
 
-let accountState: PerpsAccountState = {
-  accountValueUsd: 10000,
-  balances: [...INITIAL_BALANCES],
-  positions: [],
-};
+function createInitialAccount(): PerpsAccountState {
+  return {
+    accountValueUsd: 10000,
+    balances: INITIAL_BALANCES.map(b => ({ ...b })),
+    positions: [],
+  };
+}
+
+// Accounts with open positions stay loaded so the mark and funding engines keep reaching them
+setSimRetention('perps', state => (state as PerpsAccountState).positions.some(position => !position.isClosed));
+
+function getAccount(userId: string): PerpsAccountState {
+  return getSimState('perps', userId, createInitialAccount);
+}
 
 /**
  * Open a perp position
  */
-export async function openPerp(spec: {
+export async function openPerp(userId: string, spec: {
   market: string;
   side: 'long' | 'short';
   riskPct: number;
   entry?: number;
   takeProfit?: number;
   stopLoss?: number;
+  leverage?: number;
 }): Promise<PerpPosition> {
   const { market, side, riskPct, entry, takeProfit, stopLoss } = spec;
+  const leverage = spec.leverage && spec.leverage > 0 ? spec.leverage : 1;
+  const accountState = getAccount(userId);
 
   // Calculate size based on risk
   const sizeUsd = accountState.accountValueUsd * (riskPct / 100);
@@ -74,68 +88,211 @@ export async function openPerp(spec: {
     market,
     side,
     sizeUsd,
+    leverage,
     entryPrice,
     takeProfit: calculatedTP,
     stopLoss: calculatedSL,
     unrealizedPnlUsd: 0,
+    markPrice: entryPrice,
+    lastMarkedAt: Date.now(),
+    fundingPaidUsd: 0,
+    lastFundingAt: Date.now(),
     isClosed: false,
   };
 
   accountState.positions.push(position);
   accountState.accountValueUsd = accountState.balances.reduce((sum, b) => sum + b.balanceUsd, 0);
+  markSimDirty(userId);
 
   return position;
 }
 
 /**
- * Close a perp position
+ * Compute unrealized PnL for a position at a given mark price
  */
-export async function closePerp(id: string): Promise<{ position: PerpPosition; pnl: number }> {
-  const position = accountState.positions.find(p => p.id === id && !p.isClosed);
-  if (!position) {
-    throw new Error(`Position ${id} not found or already closed`);
+export function computePerpPnlUsd(position: PerpPosition, markPrice: number): number {
+  if (!position.entryPrice || position.entryPrice <= 0) {
+    return 0;
   }
+  const notionalUsd = position.sizeUsd * (position.leverage || 1);
+  const move = (markPrice - position.entryPrice) / position.entryPrice;
+  return notionalUsd * (position.side === 'long' ? move : -move);
+}
 
-  // Calculate PnL based on current price vs entry
-  const baseSymbol = getBaseSymbolFromMarket(position.market);
-  const currentPriceSnapshot = await getPrice(baseSymbol);
-  const currentPrice = currentPriceSnapshot.priceUsd;
-  
-  // Calculate PnL based on price movement
-  let pnlPct: number;
-  if (position.side === 'long') {
-    pnlPct = ((currentPrice - position.entryPrice) / position.entryPrice) * 100;
-  } else {
-    pnlPct = ((position.entryPrice - currentPrice) / position.entryPrice) * 100;
-  }
-  
-  // Clamp to reasonable range for demo (between -2% and +2%)
-  pnlPct = Math.max(-2, Math.min(2, pnlPct));
-  
-  const realizedPnlUsd = (position.sizeUsd * pnlPct) / 100;
+/**
+ * Realize a position at exitPrice and return margin + PnL to USDC
+ * Liquidations forfeit the full margin
+ */
+function settlePosition(
+  accountState: PerpsAccountState,
+  position: PerpPosition,
+  exitPrice: number,
+  reason: PerpCloseReason
+): number {
+  // Accrued funding is settled against the price PnL
+  const realizedPnlUsd = reason === 'liquidation'
+    ? -position.sizeUsd
+    : computePerpPnlUsd(position, exitPrice) - (position.fundingPaidUsd || 0);
 
-  // Update position
   position.isClosed = true;
   position.closedAt = Date.now();
+  position.exitPrice = exitPrice;
+  position.markPrice = exitPrice;
+  position.closeReason = reason;
   position.realizedPnlUsd = realizedPnlUsd;
+  position.realizedPnlPct = position.sizeUsd > 0 ? (realizedPnlUsd / position.sizeUsd) * 100 : 0;
   position.unrealizedPnlUsd = 0;
 
-  // Credit USDC with size + PnL
+  // Credit USDC with margin + PnL (never below zero)
   const usdcBalance = accountState.balances.find(b => b.symbol === 'USDC');
   if (usdcBalance) {
-    usdcBalance.balanceUsd += position.sizeUsd + realizedPnlUsd;
+    usdcBalance.balanceUsd += Math.max(0, position.sizeUsd + realizedPnlUsd);
   }
 
-  // Recalculate account value
   accountState.accountValueUsd = accountState.balances.reduce((sum, b) => sum + b.balanceUsd, 0);
+  return realizedPnlUsd;
+}
+
+/**
+ * Close a perp position
+ */
+export async function closePerp(userId: string, id: string): Promise<{ position: PerpPosition; pnl: number }> {
+  const accountState = getAccount(userId);
+  const position = accountState.positions.find(p => p.id === id && !p.isClosed);
+  if (!position) {
+    throw new Error(`Position ${id} not found or already closed`);
+  }
+
+  // Realize PnL at the current market price
+  const baseSymbol = getBaseSymbolFromMarket(position.market);
+  const currentPriceSnapshot = await getPrice(baseSymbol);
+  const realizedPnlUsd = settlePosition(accountState, position, currentPriceSnapshot.priceUsd, 'manual');
+  markSimDirty(userId);
 
   return { position, pnl: realizedPnlUsd };
 }
 
+/**
+ * Revalue a user's open positions against mark prices
+ * Auto-closes on take-profit / stop-loss and liquidates when equity
+ * (margin + unrealized PnL) falls to the maintenance margin of notional.
+ * Returns the closures triggered by this pass.
+ */
+export function markPositionsToMarket(
+  userId: string,
+  marks: Partial<Record<PriceSymbol, number>>,
+  maintenanceMarginBps: number
+): PerpCloseEvent[] {
+  const accountState = getAccount(userId);
+  const closures: PerpCloseEvent[] = [];
+  let touched = false;
+
+  for (const position of accountState.positions) {
+    if (position.isClosed) continue;
+
+    const markPrice = marks[getBaseSymbolFromMarket(position.market)];
+    if (!markPrice || markPrice <= 0) continue;
+
+    touched = true;
+    position.markPrice = markPrice;
+    position.lastMarkedAt = Date.now();
+    position.unrealizedPnlUsd = computePerpPnlUsd(position, markPrice) - (position.fundingPaidUsd || 0);
+
+    const notionalUsd = position.sizeUsd * (position.leverage || 1);
+    const equityUsd = position.sizeUsd + position.unrealizedPnlUsd;
+    const maintenanceUsd = (notionalUsd * maintenanceMarginBps) / 10000;
+
+    let reason: PerpCloseReason | null = null;
+    let exitPrice = markPrice;
+    if (equityUsd <= maintenanceUsd) {
+      reason = 'liquidation';
+    } else if (position.takeProfit && (position.side === 'long' ? markPrice >= position.takeProfit : markPrice <= position.takeProfit)) {
+      reason = 'take_profit';
+      exitPrice = position.takeProfit;
+    } else if (position.stopLoss && (position.side === 'long' ? markPrice <= position.stopLoss : markPrice >= position.stopLoss)) {
+      reason = 'stop_loss';
+      exitPrice = position.stopLoss;
+    }
+
+    if (reason) {
+      const realizedPnlUsd = settlePosition(accountState, position, exitPrice, reason);
+      closures.push({
+        positionId: position.id,
+        market: position.market,
+        side: position.side,
+        reason,
+        exitPrice,
+        realizedPnlUsd,
+        closedAt: position.closedAt!,
+      });
+    }
+  }
+
+  if (touched) {
+    markSimDirty(userId);
+  }
+
+  return closures;
+}
+
+/**
+ * Accrue funding on a user's open positions
+ * rates are hourly fractions per base symbol (positive = longs pay shorts).
+ * Funding accrues pro rata since each position's last accrual and is folded
+ * into unrealized PnL. Returns the net funding paid by the user this pass.
+ */
+export function accrueFunding(
+  userId: string,
+  rates: Partial<Record<PriceSymbol, number>>,
+  now: number = Date.now()
+): number {
+  const accountState = getAccount(userId);
+  let netPaidUsd = 0;
+  let touched = false;
+
+  for (const position of accountState.positions) {
+    if (position.isClosed) continue;
+
+    const ratePerHour = rates[getBaseSymbolFromMarket(position.market)];
+    const since = position.lastFundingAt ?? position.lastMarkedAt ?? now;
+    if (ratePerHour === undefined || now <= since) continue;
+
+    const notionalUsd = position.sizeUsd * (position.leverage || 1);
+    const paymentUsd = computeFundingPaymentUsd(notionalUsd, position.side, ratePerHour, now - since);
+
+    touched = true;
+    position.fundingPaidUsd = (position.fundingPaidUsd || 0) + paymentUsd;
+    position.lastFundingAt = now;
+    position.unrealizedPnlUsd -= paymentUsd;
+    netPaidUsd += paymentUsd;
+  }
+
+  if (touched) {
+    markSimDirty(userId);
+  }
+
+  return netPaidUsd;
+}
+
+/**
+ * Get the base symbols a user has open perp exposure in
+ */
+export function getOpenPerpSymbols(userId: string): PriceSymbol[] {
+  const symbols = new Set<PriceSymbol>();
+  for (const position of getAccount(userId).positions) {
+    if (!position.isClosed) {
+      symbols.add(getBaseSymbolFromMarket(position.market));
+    }
+  }
+  return Array.from(symbols);
+}
+
 /**
  * Get perps account snapshot
  */
-export function getPerpsSnapshot(): PerpsAccountState {
+export function getPerpsSnapshot(userId: string): PerpsAccountState {
+  const accountState = getAccount(userId);
+
   // Calculate open exposure
   const openPositions = accountState.positions.filter(p => !p.isClosed);
   const openPerpExposureUsd = openPositions.reduce((sum, p) => sum + p.sizeUsd, 0);
@@ -149,30 +306,28 @@ export function getPerpsSnapshot(): PerpsAccountState {
 /**
  * Update USDC balance (for DeFi/Event sims to sync)
  */
-export function updateUsdcBalance(delta: number): void {
+export function updateUsdcBalance(userId: string, delta: number): void {
+  const accountState = getAccount(userId);
   const usdc = accountState.balances.find(b => b.symbol === 'USDC');
   if (usdc) {
     usdc.balanceUsd += delta;
     accountState.accountValueUsd = accountState.balances.reduce((sum, b) => sum + b.balanceUsd, 0);
+    markSimDirty(userId);
   }
 }
 
 /**
  * Get current USDC balance
  */
-export function getUsdcBalance(): number {
-  const usdc = accountState.balances.find(b => b.symbol === 'USDC');
+export function getUsdcBalance(userId: string): number {
+  const usdc = getAccount(userId).balances.find(b => b.symbol === 'USDC');
   return usdc?.balanceUsd || 0;
 }
 
 /**
- * Reset account to initial state (for testing)
+ * Reset a user's account to initial state
  */
-export function resetPerpsAccount(): void {
-  accountState = {
-    accountValueUsd: 10000,
-    balances: [...INITIAL_BALANCES],
-    positions: [],
-  };
+export function resetPerpsAccount(userId: string): void {
+  setSimState('perps', userId, createInitialAccount());
 }
 
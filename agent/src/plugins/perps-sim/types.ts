@@ -2,18 +2,38 @@
  * Perps Simulation Types
  */
 
+export type PerpCloseReason = 'manual' | 'take_profit' | 'stop_loss' | 'liquidation';
+
 export interface PerpPosition {
   id: string;
   market: string;
   side: 'long' | 'short';
-  sizeUsd: number;
+  sizeUsd: number; // margin posted
+  leverage?: number; // default 1x (notional = sizeUsd * leverage)
   entryPrice: number;
   takeProfit?: number;
   stopLoss?: number;
   unrealizedPnlUsd: number;
+  markPrice?: number;
+  lastMarkedAt?: number;
   isClosed: boolean;
   closedAt?: number;
+  exitPrice?: number;
+  closeReason?: PerpCloseReason;
   realizedPnlUsd?: number;
+  realizedPnlPct?: number; // of margin
+  fundingPaidUsd?: number; // cumulative funding (positive = paid, negative = received)
+  lastFundingAt?: number;
+}
+
+export interface PerpCloseEvent {
+  positionId: string;
+  market: string;
+  side: 'long' | 'short';
+  reason: PerpCloseReason;
+  exitPrice: number;
+  realizedPnlUsd: number;
+  closedAt: number;
 }
 
 export interface PerpsAccountState {
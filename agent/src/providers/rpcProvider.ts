@@ -459,8 +459,8 @@ function ensureInitialized(): void {
     fallbacks.push(process.env.INFURA_RPC_URL);
   }
 
-  // Add public RPCs as last resort (multiple for redundancy)
-  const publicRpcs = [
+  // Add public RPCs as last resort (multiple for redundancy), unless pointed at a local chain
+  const publicRpcs = process.env.ETH_RPC_PUBLIC_FALLBACKS === 'false' ? [] : [
     'https://ethereum-sepolia-rpc.publicnode.com',
     'https://1rpc.io/sepolia',
     'https://rpc.sepolia.org',
@@ -0,0 +1,135 @@
+/**
+ * Plan Spend Estimate Tests
+ * Action payloads are ABI-encoded by hand; prices and token metadata are mocked.
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { encodeAbiParameters, parseEther, parseUnits } from 'viem';
+
+const tokens = vi.hoisted(() => ({
+  USDC: '0x00000000000000000000000000000000000000c1',
+  WETH: '0x00000000000000000000000000000000000000e1',
+  MYSTERY: '0x00000000000000000000000000000000000000d1',
+  ROUTER: '0x00000000000000000000000000000000000000f1',
+}));
+
+vi.mock('../../config', () => ({
+  EXECUTION_ROUTER_ADDRESS: tokens.ROUTER,
+  WETH_ADDRESS_SEPOLIA: tokens.WETH,
+}));
+vi.mock('../../services/prices', () => ({
+  getPrice: async (symbol: string) => symbol === 'USDC'
+    ? { symbol, priceUsd: 1, source: 'chainlink', confidence: 'medium' }
+    : { symbol, priceUsd: 3000, source: 'median', confidence: 'high' },
+}));
+vi.mock('../../services/priceOracle', () => {
+  const rank = (confidence: string) => ['low', 'medium', 'high'].indexOf(confidence);
+  return {
+    lowestConfidence: (values: string[]) => [...values].sort((a, b) => rank(a) - rank(b))[0],
+  };
+});
+vi.mock('../../services/tokenMetadata', () => ({
+  NATIVE_ETH: 'eth',
+  getTokenMetadata: async (token: string) => {
+    switch (token.toLowerCase()) {
+      case tokens.USDC: return { address: tokens.USDC, symbol: 'USDC', decimals: 6, priceSymbol: 'USDC' };
+      case tokens.WETH: return { address: tokens.WETH, symbol: 'WETH', decimals: 18, priceSymbol: 'ETH' };
+      case 'eth': return { address: 'eth', symbol: 'ETH', decimals: 18, priceSymbol: 'ETH' };
+      default: return { address: token, symbol: 'MYST', decimals: 8 };
+    }
+  },
+}));
+
+import {
+  estimatePlanSpend,
+  SWAP_LAYOUT,
+  PULL_LAYOUT,
+  LEND_LAYOUT,
+  WRAP_LAYOUT,
+} from '../sessionPolicy';
+
+const USER = '0x00000000000000000000000000000000000000a1';
+const ADAPTER = '0x00000000000000000000000000000000000000aa';
+const VAULT = '0x00000000000000000000000000000000000000b1';
+
+const pull = (token: string, amount: bigint) => ({
+  actionType: 2,
+  adapter: ADAPTER,
+  data: encodeAbiParameters(PULL_LAYOUT, [token, USER, amount]),
+});
+
+const swap = (tokenIn: string, tokenOut: string, amountIn: bigint, amountOutMin: bigint, recipient: string) => ({
+  actionType: 0,
+  adapter: ADAPTER,
+  data: encodeAbiParameters(SWAP_LAYOUT, [tokenIn, tokenOut, 3000, amountIn, amountOutMin, recipient, 0n]),
+});
+
+const supply = (asset: string, amount: bigint) => ({
+  actionType: 3,
+  adapter: ADAPTER,
+  data: encodeAbiParameters(LEND_LAYOUT, [asset, VAULT, amount, USER]),
+});
+
+// Session mode wraps the payload as (maxSpendUnits, bytes)
+const sessionWrapped = (action: { actionType: number; adapter: string; data: `0x${string}` }) => ({
+  ...action,
+  data: encodeAbiParameters([{ type: 'uint256' }, { type: 'bytes' }], [1n, action.data]),
+});
+
+describe('estimatePlanSpend', () => {
+  it('values pulled tokens with their own decimals and does not charge router-held balance again', async () => {
+    const estimate = await estimatePlanSpend({
+      actions: [
+        pull(tokens.USDC, parseUnits('250', 6)),
+        swap(tokens.USDC, tokens.WETH, parseUnits('250', 6), parseEther('0.08'), tokens.ROUTER),
+        supply(tokens.WETH, parseEther('0.08')),
+      ],
+    });
+
+    expect(estimate).toMatchObject({ determinable: true, instrumentType: 'defi', spendUsd: 250, ethPriceUsd: 3000 });
+    expect(estimate.breakdown).toEqual([{
+      actionIndex: 0,
+      actionType: 2,
+      token: tokens.USDC,
+      symbol: 'USDC',
+      decimals: 6,
+      amountUnits: '250000000',
+      amount: 250,
+      priceUsd: 1,
+      priceSource: 'chainlink',
+      priceConfidence: 'medium',
+      usd: 250,
+    }]);
+    // Session caps are in wei: 250 USD at 3000 USD/ETH
+    expect(Number(estimate.spendWei) / 1e18).toBeCloseTo(250 / 3000, 12);
+    // The USDC feed is the weakest price used
+    expect(estimate.priceConfidence).toBe('medium');
+  });
+
+  it('charges the plan value and any shortfall in router balance', async () => {
+    const estimate = await estimatePlanSpend({
+      value: `0x${parseEther('0.01').toString(16)}`,
+      actions: [
+        { actionType: 1, adapter: ADAPTER, data: encodeAbiParameters(WRAP_LAYOUT, [tokens.ROUTER]) },
+        // Wrapped 0.01 WETH covers part of the swap; the other 0.02 comes from the wallet
+        sessionWrapped(swap(tokens.WETH, tokens.USDC, parseEther('0.03'), parseUnits('85', 6), USER)),
+      ],
+    });
+
+    expect(estimate).toMatchObject({ determinable: true, instrumentType: 'swap', priceConfidence: 'high' });
+    expect(estimate.breakdown.map(line => [line.actionIndex, line.symbol, line.amountUnits, line.usd])).toEqual([
+      [undefined, 'ETH', parseEther('0.01').toString(), 30],
+      [1, 'WETH', parseEther('0.02').toString(), 60],
+    ]);
+    expect(estimate.spendUsd).toBeCloseTo(90);
+  });
+
+  it('is not determinable without a price feed or for unknown actions', async () => {
+    const unpriced = await estimatePlanSpend({ actions: [pull(tokens.MYSTERY, 10n ** 8n)] });
+    expect(unpriced).toMatchObject({ determinable: false, spendWei: 0n });
+    expect(unpriced.reason).toMatch(/No price feed for MYST/);
+
+    const unknown = await estimatePlanSpend({ actions: [pull(tokens.USDC, 1n), { actionType: 9, adapter: ADAPTER, data: '0x' }] });
+    expect(unknown).toMatchObject({ determinable: false, reason: 'Unsupported action type 9 at index 1' });
+  });
+});
@@ -0,0 +1,249 @@
+/**
+ * Session Policy Tests
+ * Policy documents are evaluated against hand-built plan facts; prices, token
+ * metadata and the ledger are mocked.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+import { privateKeyToAccount } from 'viem/accounts';
+
+const mocks = vi.hoisted(() => ({
+  getSessionPolicy: vi.fn(),
+}));
+
+vi.mock('../../config', () => ({
+  PRICE_POLICY_MIN_CONFIDENCE: 'medium',
+  EXECUTION_ROUTER_ADDRESS: '0x00000000000000000000000000000000000000f1',
+  WETH_ADDRESS_SEPOLIA: undefined,
+}));
+vi.mock('../../services/prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: 3000, source: 'median', confidence: 'high' }),
+}));
+vi.mock('../../services/priceOracle', () => {
+  const rank = (confidence: string) => ['low', 'medium', 'high'].indexOf(confidence);
+  return {
+    meetsConfidence: (actual: string, required: string) => rank(actual) >= rank(required),
+    lowestConfidence: (values: string[]) => [...values].sort((a, b) => rank(a) - rank(b))[0],
+  };
+});
+vi.mock('../../services/tokenMetadata', () => ({
+  NATIVE_ETH: 'eth',
+  getTokenMetadata: async (token: string) => ({ address: token, symbol: 'ETH', decimals: 18, priceSymbol: 'ETH' }),
+}));
+vi.mock('../../../execution-ledger/db', () => ({
+  getSessionPolicy: mocks.getSessionPolicy,
+  getSessionPolicyUsageSince: () => [],
+  getLosingPositionsSince: () => [],
+}));
+
+import {
+  validateSessionPolicyDocument,
+  evaluatePolicyDocument,
+  evaluateSessionPolicy,
+  buildSessionPolicyMessage,
+  verifySessionPolicySignature,
+  type PlanFacts,
+  type PolicyHistory,
+  type SessionStatus,
+} from '../sessionPolicy';
+
+const NOW = 1_750_000_000;
+const ADAPTER = '0x00000000000000000000000000000000000000aa';
+const NO_HISTORY: PolicyHistory = { usage: [], losses: [] };
+
+function facts(overrides: Partial<PlanFacts> = {}): PlanFacts {
+  return { adapters: [ADAPTER], venues: ['uniswap_v3'], tokenSpendUsd: { USDC: 100 }, perps: [], events: [], ...overrides };
+}
+
+describe('validateSessionPolicyDocument', () => {
+  it('accepts a complete document', () => {
+    expect(validateSessionPolicyDocument({
+      maxPerTxUsd: 500,
+      dailyLimitUsd: 2000,
+      allowedTokens: ['USDC', 'WETH'],
+      tokenCaps: { USDC: { maxPerTxUsd: 250, dailyLimitUsd: 1000 } },
+      allowedVenues: ['uniswap_v3'],
+      perps: { maxLeverage: 5, allowedMarkets: ['ETH-USD'] },
+      events: { allowedMarkets: ['fed-cut'] },
+      maxTradesPerHour: 10,
+      lossCooldown: { cooldownSeconds: 600, minLossUsd: 0 },
+    })).toEqual([]);
+  });
+
+  it('reports unknown fields and malformed rules', () => {
+    const errors = validateSessionPolicyDocument({
+      maxPerTxUsd: -1,
+      allowedTokens: 'USDC',
+      tokenCaps: { USDC: { dailyLimitUsd: 0 } },
+      perps: { maxLeverage: '5x' },
+      lossCooldown: { cooldownSeconds: 60, minLossUsd: -5 },
+      sneaky: true,
+    });
+    expect(errors).toEqual(expect.arrayContaining([
+      'Unknown policy field: sneaky',
+      'maxPerTxUsd must be a positive number',
+      'allowedTokens must be an array of strings',
+      'tokenCaps.USDC.dailyLimitUsd must be a positive number',
+      'perps.maxLeverage must be a positive number',
+      'lossCooldown.minLossUsd must be a non-negative number',
+    ]));
+    expect(validateSessionPolicyDocument([])).toEqual(['Policy must be an object']);
+  });
+});
+
+describe('evaluatePolicyDocument', () => {
+  it('allows a plan inside every rule', () => {
+    const result = evaluatePolicyDocument(
+      { allowedTokens: ['usdc'], allowedVenues: ['uniswap_v3'], maxPerTxUsd: 500, maxTradesPerHour: 3 },
+      facts(),
+      100,
+      NO_HISTORY,
+      NOW
+    );
+    expect(result).toEqual({ allowed: true });
+  });
+
+  it('denies tokens and venues outside the allowlists', () => {
+    expect(evaluatePolicyDocument({ allowedTokens: ['WETH'] }, facts(), 100, NO_HISTORY, NOW))
+      .toMatchObject({ allowed: false, code: 'POLICY_TOKEN_NOT_ALLOWED', details: { token: 'USDC' } });
+    expect(evaluatePolicyDocument({ allowedVenues: ['aave'] }, facts(), 100, NO_HISTORY, NOW))
+      .toMatchObject({ allowed: false, code: 'POLICY_VENUE_NOT_ALLOWED', details: { venue: 'uniswap_v3' } });
+  });
+
+  it('applies per-token caps on top of the plan limit', () => {
+    const policy = { maxPerTxUsd: 500, tokenCaps: { usdc: { maxPerTxUsd: 50 } } };
+    expect(evaluatePolicyDocument(policy, facts(), 100, NO_HISTORY, NOW))
+      .toMatchObject({ code: 'POLICY_TOKEN_CAP_EXCEEDED', details: { token: 'USDC', limitUsd: 50 } });
+  });
+
+  it('counts the last 24h of spend against the daily limit', () => {
+    const history: PolicyHistory = {
+      usage: [
+        { spend_usd: 900, created_at: NOW - 3600 },
+        { spend_usd: 5000, created_at: NOW - 2 * 86400 },  // Outside the window
+      ],
+      losses: [],
+    };
+    expect(evaluatePolicyDocument({ dailyLimitUsd: 1000 }, facts(), 50, history, NOW)).toEqual({ allowed: true });
+    expect(evaluatePolicyDocument({ dailyLimitUsd: 1000 }, facts(), 150, history, NOW))
+      .toMatchObject({ code: 'POLICY_DAILY_LIMIT_EXCEEDED', details: { spent24hUsd: 900 } });
+  });
+
+  it('limits perp leverage and markets, and denies unreadable perp plans', () => {
+    const policy = { perps: { maxLeverage: 5, allowedMarkets: ['ETH-USD'] } };
+    const perp = (market: string | undefined, leverage: number | undefined) =>
+      facts({ venues: ['perps'], perps: [{ market, side: 'long', leverage }] });
+
+    expect(evaluatePolicyDocument(policy, perp('ETH', 3), 100, NO_HISTORY, NOW)).toEqual({ allowed: true });
+    expect(evaluatePolicyDocument(policy, perp('ETH', 10), 100, NO_HISTORY, NOW))
+      .toMatchObject({ code: 'POLICY_LEVERAGE_EXCEEDED', details: { leverage: 10, maxLeverage: 5 } });
+    expect(evaluatePolicyDocument(policy, perp('BTC', 2), 100, NO_HISTORY, NOW))
+      .toMatchObject({ code: 'POLICY_MARKET_NOT_ALLOWED', details: { market: 'BTC' } });
+    expect(evaluatePolicyDocument(policy, perp(undefined, undefined), 100, NO_HISTORY, NOW))
+      .toMatchObject({ code: 'POLICY_PERP_PARAMS_UNKNOWN' });
+  });
+
+  it('rate limits executions per hour with a retry hint', () => {
+    const history: PolicyHistory = {
+      usage: [
+        { spend_usd: 10, created_at: NOW - 1800 },
+        { spend_usd: 10, created_at: NOW - 600 },
+        { spend_usd: 10, created_at: NOW - 7200 },
+      ],
+      losses: [],
+    };
+    expect(evaluatePolicyDocument({ maxTradesPerHour: 3 }, facts(), 10, history, NOW)).toEqual({ allowed: true });
+    expect(evaluatePolicyDocument({ maxTradesPerHour: 2 }, facts(), 10, history, NOW))
+      .toMatchObject({ code: 'POLICY_RATE_LIMITED', details: { tradesLastHour: 2, retryAfterSeconds: 1800 } });
+  });
+
+  it('pauses trading after a losing close until the cooldown ends', () => {
+    const policy = { lossCooldown: { cooldownSeconds: 900, minLossUsd: 20 } };
+    const closed = (pnlUsd: number, ago: number, status = 'closed') => ({
+      closed_at: NOW - ago,
+      pnl: String(pnlUsd * 1e6),
+      status,
+      margin_units: String(100 * 1e6),
+    });
+
+    expect(evaluatePolicyDocument(policy, facts(), 10, { usage: [], losses: [closed(-50, 300)] }, NOW))
+      .toMatchObject({ code: 'POLICY_LOSS_COOLDOWN', details: { retryAfterSeconds: 600 } });
+    // Below the loss threshold, or past the cooldown
+    expect(evaluatePolicyDocument(policy, facts(), 10, { usage: [], losses: [closed(-5, 300)] }, NOW)).toEqual({ allowed: true });
+    expect(evaluatePolicyDocument(policy, facts(), 10, { usage: [], losses: [closed(-50, 1200)] }, NOW)).toEqual({ allowed: true });
+    // A liquidation loses the margin
+    expect(evaluatePolicyDocument(policy, facts(), 10, { usage: [], losses: [{ ...closed(0, 60, 'liquidated'), pnl: undefined }] }, NOW))
+      .toMatchObject({ code: 'POLICY_LOSS_COOLDOWN' });
+  });
+});
+
+describe('evaluateSessionPolicy', () => {
+  const session: SessionStatus = {
+    active: true,
+    owner: '0x00000000000000000000000000000000000000b2',
+    executor: '0x00000000000000000000000000000000000000a1',
+    expiresAt: BigInt(Math.floor(Date.now() / 1000) + 3600),
+    maxSpend: 10n ** 18n,
+    spent: 0n,
+    status: 'active',
+  };
+
+  beforeEach(() => {
+    mocks.getSessionPolicy.mockReset();
+  });
+
+  it('denies when the policy document cannot be loaded', async () => {
+    mocks.getSessionPolicy.mockImplementation(() => {
+      throw new Error('database is locked');
+    });
+
+    const result = await evaluateSessionPolicy(
+      '0x01',
+      session.owner,
+      { actions: [], value: '0x2386f26fc10000' },  // 0.01 ETH
+      new Set(),
+      async () => session
+    );
+
+    expect(result).toMatchObject({ allowed: false, code: 'POLICY_UNAVAILABLE' });
+  });
+
+  it('passes with only the base checks when the session has no document', async () => {
+    mocks.getSessionPolicy.mockReturnValue(undefined);
+
+    const result = await evaluateSessionPolicy('0x01', session.owner, { actions: [], value: '0x2386f26fc10000' }, new Set(), async () => session);
+
+    expect(result).toEqual({ allowed: true });
+  });
+});
+
+describe('verifySessionPolicySignature', () => {
+  const owner = privateKeyToAccount('0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d');
+  const sessionId = `0x${'11'.repeat(32)}`;
+  const policy = { maxPerTxUsd: 100 };
+
+  let nonceCounter = 0;
+
+  async function signed(issuedAt = NOW) {
+    const nonce = `policy-nonce-${++nonceCounter}`;
+    const signature = await owner.signMessage({
+      message: buildSessionPolicyMessage(sessionId, owner.address, policy, nonce, issuedAt),
+    });
+    return { sessionId, userAddress: owner.address, policy, nonce, issuedAt, signature };
+  }
+
+  it('accepts the owner signature over this exact policy once', async () => {
+    const request = await signed();
+    expect(await verifySessionPolicySignature(request, NOW + 30)).toBeNull();
+    expect(await verifySessionPolicySignature(request, NOW + 60)).toBe('Nonce already used');
+  });
+
+  it('rejects another signer, a different policy and stale signatures', async () => {
+    const request = await signed();
+    expect(await verifySessionPolicySignature({ ...request, userAddress: '0x00000000000000000000000000000000000000b2' }, NOW))
+      .toMatch(/does not match/);
+    expect(await verifySessionPolicySignature({ ...request, policy: { maxPerTxUsd: 1_000_000 } }, NOW))
+      .toMatch(/does not match/);
+    expect(await verifySessionPolicySignature(await signed(), NOW + 3600)).toMatch(/expired/);
+  });
+});
@@ -0,0 +1,89 @@
+/**
+ * Session Policy Usage Reservation Tests
+ * The ledger runs against an in-memory SQLite database; spend estimates are
+ * built by hand.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+});
+
+import { reserveSessionPolicyUsage, type PlanSpendEstimate } from '../sessionPolicy';
+import {
+  getDatabase,
+  upsertSessionPolicy,
+  getSessionPolicyUsageSince,
+  attachSessionPolicyUsageTx,
+  releaseSessionPolicyUsage,
+} from '../../../execution-ledger/db';
+
+const SESSION_ID = `0x${'22'.repeat(32)}`;
+const USER = '0x00000000000000000000000000000000000000a1';
+const PULL_ADAPTER = '0x00000000000000000000000000000000000000aa';
+
+// A plain PULL: no venue lookup, so only the spend estimate matters
+const plan = { actions: [{ actionType: 2, adapter: PULL_ADAPTER, data: '0x' }] };
+
+function estimate(usd: number): PlanSpendEstimate {
+  return {
+    spendWei: 0n,
+    spendUsd: usd,
+    determinable: true,
+    breakdown: [{
+      token: '0x00000000000000000000000000000000000000c1',
+      symbol: 'USDC',
+      decimals: 6,
+      amountUnits: String(usd * 1e6),
+      amount: usd,
+      priceUsd: 1,
+      priceSource: 'chainlink',
+      priceConfidence: 'high',
+      usd,
+    }],
+  };
+}
+
+function reserve(usd: number) {
+  return reserveSessionPolicyUsage({ sessionId: SESSION_ID, userAddress: USER, plan, spendEstimate: estimate(usd), instrumentType: 'swap' });
+}
+
+const usageRows = () => getSessionPolicyUsageSince(SESSION_ID, 0);
+
+describe('reserveSessionPolicyUsage', () => {
+  beforeEach(() => {
+    const db = getDatabase();
+    db.prepare('DELETE FROM session_policy_usage').run();
+    db.prepare('DELETE FROM session_policies').run();
+    upsertSessionPolicy(SESSION_ID, USER, { dailyLimitUsd: 150 });
+  });
+
+  it('admits only one of two concurrent relays that each fit the daily limit alone', async () => {
+    const results = await Promise.all([reserve(100), reserve(100)]);
+
+    expect(results.map(r => r.result.allowed).sort()).toEqual([false, true]);
+    expect(results.find(r => !r.result.allowed)!.result.code).toBe('POLICY_DAILY_LIMIT_EXCEEDED');
+    expect(usageRows()).toHaveLength(1);
+  });
+
+  it('attaches the tx hash once sent and frees the limit when a send is released', async () => {
+    const sent = await reserve(100);
+    attachSessionPolicyUsageTx(sent.usageId!, '0xabc');
+    expect(usageRows()).toMatchObject([{ id: sent.usageId, tx_hash: '0xabc', spend_usd: 100 }]);
+
+    const failed = await reserve(50);
+    expect(failed.result.allowed).toBe(true);
+    releaseSessionPolicyUsage(failed.usageId!);
+
+    expect((await reserve(50)).result.allowed).toBe(true);
+    expect((await reserve(1)).result.allowed).toBe(false);
+  });
+
+  it('records usage for sessions without a policy document', async () => {
+    getDatabase().prepare('DELETE FROM session_policies').run();
+
+    expect(await reserve(1000)).toMatchObject({ result: { allowed: true }, usageId: expect.any(String) });
+    expect(usageRows()).toMatchObject([{ token_spend_json: JSON.stringify({ USDC: 1000 }), instrument_type: 'swap' }]);
+  });
+});
@@ -0,0 +1,96 @@
+/**
+ * Chat Streaming (Server-Sent Events)
+ *
+ * POST /api/chat streams when the client sends `Accept: text/event-stream`
+ * (or `stream: true` in the body). Events, in order:
+ *
+ *   start              { correlationId }
+ *   token              { text }                         assistant text as the model writes it (0..n)
+ *   message            { assistantMessage }             final, validated assistant text
+ *   actions            { actions, executionResults }
+ *   execution_request  { executionRequest, draftId }
+ *   done               full ChatResponse (same body as the JSON endpoint)
+ *
+ * On failure a single `error` event ({ error, errorCode? }) ends the stream.
+ * Tokens are a preview: deterministic fallbacks can replace the model's text,
+ * so clients should render `message` once it arrives.
+ */
+
+import type { Request, Response } from 'express';
+
+const HEARTBEAT_MS = 15000;
+
+export interface ChatStream {
+  send(event: string, data: unknown): void;
+  token(text: string): void;
+  // Emit message/actions/execution_request/done for a complete response and close
+  finish(response: Record<string, any>): void;
+  fail(error: string, errorCode?: string): void;
+  readonly closed: boolean;
+}
+
+export function wantsChatStream(req: Request): boolean {
+  return req.body?.stream === true || (req.headers.accept || '').includes('text/event-stream');
+}
+
+export function openChatStream(req: Request, res: Response, correlationId?: string): ChatStream {
+  res.status(200);
+  res.setHeader('Content-Type', 'text/event-stream; charset=utf-8');
+  res.setHeader('Cache-Control', 'no-cache, no-transform');
+  res.setHeader('Connection', 'keep-alive');
+  res.setHeader('X-Accel-Buffering', 'no'); // Disable proxy buffering (nginx)
+  res.flushHeaders();
+
+  let closed = false;
+  let streamedText = '';
+
+  // Comment lines keep idle connections open through proxies
+  const heartbeat = setInterval(() => {
+    if (!closed) res.write(': ping\n\n');
+  }, HEARTBEAT_MS);
+
+  const close = () => {
+    if (closed) return;
+    closed = true;
+    clearInterval(heartbeat);
+    res.end();
+  };
+  req.on('close', () => {
+    closed = true;
+    clearInterval(heartbeat);
+  });
+
+  const send = (event: string, data: unknown) => {
+    if (closed) return;
+    res.write(`event: ${event}\ndata: ${JSON.stringify(data)}\n\n`);
+  };
+
+  send('start', { correlationId });
+
+  return {
+    send,
+    token(text: string) {
+      if (!text) return;
+      streamedText += text;
+      send('token', { text });
+    },
+    finish(response: Record<string, any>) {
+      // Responses that never went through the model still reach the client as text first
+      if (!streamedText && response.assistantMessage) {
+        send('token', { text: response.assistantMessage });
+      }
+      send('message', { assistantMessage: response.assistantMessage });
+      send('actions', { actions: response.actions ?? [], executionResults: response.executionResults ?? [] });
+      send('execution_request', { executionRequest: response.executionRequest ?? null, draftId: response.draftId });
+      send('done', response);
+      close();
+    },
+    fail(error: string, errorCode?: string) {
+      send('error', { error, errorCode });
+      close();
+    },
+    get closed() {
+      return closed;
+    },
+  };
+}
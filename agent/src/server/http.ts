@@ -45,7 +45,8 @@ import cors from 'cors';
 import cookieParser from 'cookie-parser';
 import { BlossomAction, BlossomPortfolioSnapshot, BlossomExecutionRequest, ExecutionResult } from '../types/blossom';
 import { validateActions, buildBlossomPrompts } from '../utils/actionParser';
-import { callLlm } from '../services/llmClient';
+import { callLlm, LlmBudgetExceededError } from '../services/llmClient';
+import { wantsChatStream, openChatStream, type ChatStream } from './chatStream';
 import * as perpsSim from '../plugins/perps-sim';
 import * as defiSim from '../plugins/defi-sim';
 
@@ -60,6 +61,7 @@ const ALLOWED_ORIGINS = [
 ];
 import * as eventSim from '../plugins/event-sim';
 import { resetAllSims, getPortfolioSnapshot } from '../services/state';
+import { resolveSimUserId, loadSimAccount, newSimSessionId, ANONYMOUS_SIM_USER, SIM_SESSION_COOKIE } from '../services/simStore';
 import { getOnchainTicker, getEventMarketsTicker } from '../services/ticker';
 import { logExecutionArtifact, getExecutionArtifacts, dumpExecutionArtifacts } from '../utils/executionLogger';
 import { validateAccessCode, hasAccess, initializeAccessGate, getAllAccessCodes, createAccessCode, revokeAccessCode, checkAccess } from '../utils/accessGate';
@@ -106,7 +108,7 @@ app.use(cors({
   },
   credentials: true,
   methods: ['GET', 'POST', 'PUT', 'DELETE', 'OPTIONS'],
-  allowedHeaders: ['Content-Type', 'X-Ledger-Secret', 'X-Access-Code', 'X-Wallet-Address', 'x-correlation-id'],
+  allowedHeaders: ['Content-Type', 'X-Ledger-Secret', 'X-Access-Code', 'X-Wallet-Address', 'X-Session-Id', 'Authorization', 'x-correlation-id'],
 }));
 app.use(express.json());
 app.use(cookieParser());
@@ -123,6 +125,7 @@ const TELEMETRY_ALLOWLIST = [
   'GET /api/rpc/health',
   'GET /api/telemetry/summary',
   'GET /api/telemetry/devnet-stats',
+  'GET /api/telemetry/llm-usage',
   'GET /api/telemetry/users',
   'GET /api/telemetry/executions',
   'GET /api/telemetry/runs',
@@ -361,37 +364,74 @@ const maybeCheckAccess = ACCESS_GATE_ENABLED ? checkAccess : (req: any, res: any
 initializeAccessGate();
 
 // Set up balance callbacks for DeFi and Event sims
-// Use perps sim as the source of truth for USDC balance
-const getUsdcBalance = () => {
-  return perpsSim.getUsdcBalance();
+// Use the user's perps account as the source of truth for USDC balance
+const getUsdcBalance = (userId: string) => {
+  return perpsSim.getUsdcBalance(userId);
 };
 
-const updateUsdcBalance = (delta: number) => {
-  perpsSim.updateUsdcBalance(delta);
+const updateUsdcBalance = (userId: string, delta: number) => {
+  perpsSim.updateUsdcBalance(userId, delta);
 };
 
 defiSim.setBalanceCallbacks(getUsdcBalance, updateUsdcBalance);
 eventSim.setBalanceCallbacks(getUsdcBalance, updateUsdcBalance);
 
 /**
- * Build portfolio snapshot from all sims
+ * Resolve the sim account for a request and hydrate it from the ledger
+ * Signed-out clients get a sim session cookie on first use so their book is
+ * their own instead of the shared anonymous account
+ */
+async function getSimUserId(req: express.Request, res: express.Response): Promise<string> {
+  const { getAuthenticatedWallet } = await import('../utils/walletAuth');
+  let simUserId = resolveSimUserId(req, getAuthenticatedWallet(req));
+  if (simUserId === ANONYMOUS_SIM_USER) {
+    const sessionId = newSimSessionId();
+    res.cookie(SIM_SESSION_COOKIE, sessionId, {
+      httpOnly: true,
+      secure: process.env.NODE_ENV === 'production',
+      sameSite: 'lax',
+      maxAge: 30 * 24 * 60 * 60 * 1000, // 30 days
+    });
+    simUserId = resolveSimUserId({ cookies: { [SIM_SESSION_COOKIE]: sessionId } }, null);
+  }
+  await loadSimAccount(simUserId);
+  return simUserId;
+}
+
+/**
+ * Identity an LLM token budget is charged to: the signed-in wallet, else the
+ * access code when the gate is on, else the client IP (with its own budget)
+ */
+function resolveLlmBudgetIdentity(req: express.Request, wallet: string | null): { userId: string; budgetScope: 'user' | 'ip' } {
+  if (wallet) {
+    return { userId: `wallet:${wallet}`, budgetScope: 'user' };
+  }
+  const accessCode = req.headers['x-access-code'] || req.body?.accessCode;
+  if (ACCESS_GATE_ENABLED && typeof accessCode === 'string' && accessCode) {
+    return { userId: `access:${createHash('sha256').update(accessCode).digest('hex')}`, budgetScope: 'user' };
+  }
+  return { userId: `ip:${req.ip || req.socket?.remoteAddress || 'unknown'}`, budgetScope: 'ip' };
+}
+
+/**
+ * Build portfolio snapshot from a user's sims
  * (Now uses centralized helper)
  */
-function buildPortfolioSnapshot(): BlossomPortfolioSnapshot {
-  return getPortfolioSnapshot();
+function buildPortfolioSnapshot(simUserId: string): BlossomPortfolioSnapshot {
+  return getPortfolioSnapshot(simUserId);
 }
 
 /**
  * Apply action to appropriate sim and return unified ExecutionResult
  */
-async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
-  const portfolioBefore = buildPortfolioSnapshot();
+async function applyAction(action: BlossomAction, simUserId: string): Promise<ExecutionResult> {
+  const portfolioBefore = buildPortfolioSnapshot(simUserId);
   const { v4: uuidv4 } = await import('uuid');
   const simulatedTxId = `sim_${uuidv4()}`;
 
   try {
     if (action.type === 'perp' && action.action === 'open') {
-      const position = await perpsSim.openPerp({
+      const position = await perpsSim.openPerp(simUserId, {
         market: action.market,
         side: action.side,
         riskPct: action.riskPct,
@@ -400,7 +440,7 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
         stopLoss: action.stopLoss,
       });
 
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       const accountValueDelta = portfolioAfter.accountValueUsd - portfolioBefore.accountValueUsd;
       const balanceDeltas = portfolioAfter.balances.map(b => {
         const before = portfolioBefore.balances.find(b2 => b2.symbol === b.symbol);
@@ -430,12 +470,13 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
       };
     } else if (action.type === 'defi' && action.action === 'deposit') {
       const position = defiSim.openDefiPosition(
+        simUserId,
         action.protocol as 'Kamino' | 'RootsFi' | 'Jet',
         action.asset,
         action.amountUsd
       );
 
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       const accountValueDelta = portfolioAfter.accountValueUsd - portfolioBefore.accountValueUsd;
       const balanceDeltas = portfolioAfter.balances.map(b => {
         const before = portfolioBefore.balances.find(b2 => b2.symbol === b.symbol);
@@ -490,13 +531,14 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
       }
       
       const position = await eventSim.openEventPosition(
+        simUserId,
         action.eventKey,
         action.side,
         action.stakeUsd,
         action.label // Pass label for live markets
       );
 
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       const accountValueDelta = portfolioAfter.accountValueUsd - portfolioBefore.accountValueUsd;
       const balanceDeltas = portfolioAfter.balances.map(b => {
         const before = portfolioBefore.balances.find(b2 => b2.symbol === b.symbol);
@@ -529,14 +571,14 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
         throw new Error('positionId is required for event update action');
       }
       
-      await eventSim.updateEventStake({
+      await eventSim.updateEventStake(simUserId, {
         positionId: action.positionId,
         newStakeUsd: action.stakeUsd,
         overrideRiskCap: action.overrideRiskCap || false,
         requestedStakeUsd: action.requestedStakeUsd,
       });
 
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       return {
         success: true,
         status: 'success',
@@ -546,7 +588,7 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
     }
 
     // Unknown action type
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
     return {
       success: false,
       status: 'failed',
@@ -554,7 +596,7 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
       portfolio: portfolioAfter,
     };
   } catch (error: any) {
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
     return {
       success: false,
       status: 'failed',
@@ -565,7 +607,8 @@ async function applyAction(action: BlossomAction): Promise<ExecutionResult> {
 }
 
 /**
- * Parse LLM JSON response into assistant message and actions
+ * Parse LLM response into assistant message and actions
+ * Accepts the raw JSON text or, for tool-calling providers, the structured tool input
  */
 interface ModelResponse {
   assistantMessage: string;
@@ -575,7 +618,7 @@ interface ModelResponse {
 }
 
 async function parseModelResponse(
-  rawJson: string, 
+  rawJson: string | Record<string, any>, 
   isSwapPrompt: boolean = false, 
   isDefiPrompt: boolean = false, 
   userMessage?: string,
@@ -583,7 +626,7 @@ async function parseModelResponse(
   isEventPrompt: boolean = false
 ): Promise<ModelResponse> {
   try {
-    const parsed = JSON.parse(rawJson);
+    const parsed = typeof rawJson === 'string' ? JSON.parse(rawJson) : rawJson;
     
     if (typeof parsed !== 'object' || parsed === null) {
       throw new Error('Response is not an object');
@@ -720,6 +763,41 @@ function normalizeUserInput(userMessage: string | undefined): string {
   return normalized;
 }
 
+/**
+ * Compile a compound prompt into a single multi-leg execution request
+ * Returns null unless the prompt has two or more legs that compile into one
+ * plan; anything else ("swap ... and open a perp") is left to the LLM
+ */
+async function buildCompoundExecution(
+  userMessage: string
+): Promise<{ assistantMessage: string; actions: BlossomAction[]; executionRequest: BlossomExecutionRequest } | null> {
+  const { parseCompoundIntent, compileCompoundIntent, describePlanLegs } = await import('../intent/compoundIntent');
+  const compound = parseCompoundIntent(userMessage);
+  if (!compound) {
+    return null;
+  }
+
+  const compiled = compileCompoundIntent(compound);
+  if ('error' in compiled) {
+    console.log('[api/chat] Compound prompt not composable, falling through:', compiled.error);
+    return null;
+  }
+
+  const { legs } = compiled.executionRequest as Extract<BlossomExecutionRequest, { kind: 'multi' }>;
+  const { checkCompositePlanSupport } = await import('../executors/compositePlan');
+  const unsupported = checkCompositePlanSupport(legs);
+  if (unsupported) {
+    console.log('[api/chat] Compound plan cannot be prepared, falling through:', unsupported);
+    return null;
+  }
+
+  return {
+    assistantMessage: `I'll execute these ${legs.length} steps atomically on Sepolia with a single signature:\n${describePlanLegs(legs)}`,
+    actions: [],
+    executionRequest: compiled.executionRequest,
+  };
+}
+
 /**
  * Deterministic fallback for when LLM fails
  */
@@ -733,6 +811,12 @@ async function applyDeterministicFallback(
   // Normalize input before parsing
   const normalizedMessage = normalizeUserInput(userMessage);
   const lowerMessage = normalizedMessage.toLowerCase();
+
+  // Compound prompts compile to one multi-leg plan
+  const compound = await buildCompoundExecution(normalizedMessage);
+  if (compound) {
+    return compound;
+  }
   
   if (isEventPrompt) {
     // Extract event details
@@ -872,6 +956,9 @@ interface ChatRequest {
   userMessage: string;
   venue: 'hyperliquid' | 'event_demo';
   clientPortfolio?: Partial<BlossomPortfolioSnapshot>;
+  stream?: boolean; // Respond with server-sent events (also via Accept: text/event-stream)
+  threadId?: string; // Continue this conversation (defaults to the user's recent thread)
+  newThread?: boolean; // Start a fresh conversation
 }
 
 /**
@@ -909,26 +996,59 @@ interface ChatResponse {
   executionResults?: ExecutionResult[]; // Unified execution results
   errorCode?: 'INSUFFICIENT_BALANCE' | 'SESSION_EXPIRED' | 'RELAYER_FAILED' | 'SLIPPAGE_FAILURE' | 'LLM_REFUSAL' | 'UNKNOWN_ERROR';
   draftId?: string; // Task A: Server-created draft strategy ID (for UI to set msg.type + msg.draftId)
+  threadId?: string; // Conversation thread this turn was recorded on
 }
 
 /**
  * POST /api/chat
+ * JSON by default; streams server-sent events when requested (see chatStream.ts)
  */
 app.post('/api/chat', maybeCheckAccess, async (req, res) => {
   const chatStartTime = Date.now();
+  let stream: ChatStream | null = null;
   try {
-    const { userMessage, venue, clientPortfolio }: ChatRequest = req.body;
+    const { userMessage: sentMessage, venue, clientPortfolio, threadId, newThread }: ChatRequest = req.body;
+    const simUserId = await getSimUserId(req, res);
 
     // Telemetry: log chat request
     logEvent('chat_request', {
       venue,
-      notes: [userMessage ? (userMessage.substring(0, 50) + (userMessage.length > 50 ? '...' : '')) : 'undefined'],
+      notes: [sentMessage ? (sentMessage.substring(0, 50) + (sentMessage.length > 50 ? '...' : '')) : 'undefined'],
     });
 
-    if (!userMessage) {
+    if (!sentMessage) {
       return res.status(400).json({ error: 'userMessage is required' });
     }
 
+    // SSE clients get assistant tokens as they arrive and the response as structured events
+    if (wantsChatStream(req)) {
+      stream = openChatStream(req, res, req.correlationId);
+    }
+
+    // Conversation memory: prior turns go into the prompt and every reply is recorded on the thread
+    // Threads belong to the signed-in wallet, never to the spoofable X-Wallet-Address
+    const { resolveChatThread, loadConversationContext, recordChatTurn, resolveFollowUp, ANONYMOUS_CHAT_USER } = await import('../services/conversation');
+    const { getAuthenticatedWallet } = await import('../utils/walletAuth');
+    const chatUserId = getAuthenticatedWallet(req) ?? ANONYMOUS_CHAT_USER;
+    const thread = await resolveChatThread(chatUserId, { threadId, newThread: newThread === true });
+    const conversation = thread ? await loadConversationContext(thread) : null;
+
+    // "same thing but on SOL" → the previous request with the asset swapped
+    const userMessage = resolveFollowUp(sentMessage, conversation);
+    if (userMessage !== sentMessage) {
+      console.log('[api/chat] Resolved follow-up:', { sent: sentMessage, resolved: userMessage });
+    }
+
+    const reply = (body: Record<string, any>) => {
+      const payload = thread ? { ...body, threadId: thread.id } : body;
+      if (thread) {
+        void recordChatTurn(thread, sentMessage, payload);
+      }
+      return stream ? stream.finish(payload) : res.json(payload);
+    };
+    const onToken = stream ? (text: string) => stream!.token(text) : undefined;
+    const llmOptions = { onToken, ...resolveLlmBudgetIdentity(req, getAuthenticatedWallet(req)), correlationId: req.correlationId };
+
     // Log incoming request for debugging
     console.log('[api/chat] Received request:', { 
       userMessage: userMessage ? userMessage.substring(0, 100) : 'undefined', 
@@ -937,7 +1057,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
     });
 
     // Get current portfolio snapshot before applying new actions
-    const portfolioBefore = buildPortfolioSnapshot();
+    const portfolioBefore = buildPortfolioSnapshot(simUserId);
     const portfolioForPrompt = clientPortfolio ? { ...portfolioBefore, ...clientPortfolio } : portfolioBefore;
 
     // Normalize user input first (handle edge cases like "5weth" → "5 weth")
@@ -966,8 +1086,8 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
         const protocols = await getTopProtocolsByTVL(requestedCount);
 
         // Return response with protocol list (frontend will render with quick action buttons)
-        const portfolioAfter = buildPortfolioSnapshot();
-        return res.json({
+        const portfolioAfter = buildPortfolioSnapshot(simUserId);
+        return reply({
           ok: true,
           assistantMessage: `Here are the top ${protocols.length} DeFi protocol${protocols.length !== 1 ? 's' : ''} by TVL right now:`,
           actions: [],
@@ -980,8 +1100,8 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       } catch (error: any) {
         console.error('[api/chat] Failed to fetch DeFi protocols:', error.message);
         // Return error response
-        const portfolioAfter = buildPortfolioSnapshot();
-        return res.json({
+        const portfolioAfter = buildPortfolioSnapshot(simUserId);
+        return reply({
           ok: false,
           assistantMessage: "I couldn't fetch the DeFi protocols right now. Please try again later.",
           actions: [],
@@ -1017,8 +1137,8 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
         const result = await getEventMarketsWithRouting(requestedCount);
 
         // Return response with event market list (frontend will render with quick action buttons)
-        const portfolioAfter = buildPortfolioSnapshot();
-        return res.json({
+        const portfolioAfter = buildPortfolioSnapshot(simUserId);
+        return reply({
           ok: true,
           assistantMessage: `Here are the top ${result.markets.length} prediction market${result.markets.length !== 1 ? 's' : ''} by volume right now:`,
           actions: [],
@@ -1032,9 +1152,9 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       } catch (error: any) {
         console.error('[api/chat] Failed to fetch event markets:', error.message, error.stack);
         // Return error response with fallback routing metadata
-        const portfolioAfter = buildPortfolioSnapshot();
+        const portfolioAfter = buildPortfolioSnapshot(simUserId);
         const correlationId = req.correlationId || makeCorrelationId('error');
-        return res.json({
+        return reply({
           ok: false,
           assistantMessage: "I couldn't fetch the prediction markets right now. Please try again later.",
           actions: [],
@@ -1114,8 +1234,8 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
             const price = outcome === 'YES' ? matchedMarket.yesPrice : matchedMarket.noPrice;
             const maxPayout = stakeUsd / price;
 
-            const portfolioAfter = buildPortfolioSnapshot();
-            return res.json({
+            const portfolioAfter = buildPortfolioSnapshot(simUserId);
+            return reply({
               ok: true,
               assistantMessage: `I'll place a ${outcome} bet on "${matchedMarket.title}" with $${stakeUsd.toFixed(0)} stake. At ${(price * 100).toFixed(1)}¢ odds, your max payout is $${maxPayout.toFixed(0)}. Confirm to execute?`,
               actions: [],
@@ -1148,6 +1268,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       userMessage: normalizedUserMessage,
       portfolio: portfolioForPrompt,
       venue: venue || 'hyperliquid',
+      conversation: conversation?.prompt,
     });
 
     let assistantMessage = '';
@@ -1200,6 +1321,9 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       userMessage: userMessage.substring(0, 100)
     });
 
+    // Compound intents ("swap ... and deposit the rest ...") don't fit the single-action LLM schema
+    const compoundResponse = isPredictionMarketQuery ? null : await buildCompoundExecution(normalizeUserInput(userMessage));
+
     if (isStubMode && isPredictionMarketQuery) {
       // Short-circuit: build deterministic response for prediction markets in stub mode
       console.log('[api/chat] ✅ STUB SHORT-CIRCUIT: Building deterministic prediction market response');
@@ -1228,11 +1352,21 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       } catch (error: any) {
         console.error('[api/chat] ❌ Failed to build stub prediction market response:', error.message);
         // Fall through to normal stub LLM call
-        const llmOutput = await callLlm({ systemPrompt, userPrompt });
-        modelResponse = await parseModelResponse(llmOutput.rawJson, isSwapPrompt);
+        const llmOutput = await callLlm({ systemPrompt, userPrompt }, llmOptions);
+        modelResponse = await parseModelResponse(llmOutput.toolCall?.input ?? llmOutput.rawJson, isSwapPrompt);
         assistantMessage = modelResponse.assistantMessage;
         actions = modelResponse.actions;
       }
+    } else if (compoundResponse) {
+      console.log('[api/chat] → Compound intent compiled deterministically');
+      assistantMessage = compoundResponse.assistantMessage;
+      actions = compoundResponse.actions;
+      modelResponse = {
+        assistantMessage,
+        actions,
+        executionRequest: compoundResponse.executionRequest,
+        modelOk: true,
+      };
     } else {
       // Normal flow: call LLM (stub or real)
       console.log('[api/chat] → Normal LLM flow (stub or real)');
@@ -1266,10 +1400,13 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       
       try {
         // Call LLM with normalized prompt
-        const llmOutput = await callLlm({ systemPrompt, userPrompt: normalizedUserPrompt });
+        const llmOutput = await callLlm({ systemPrompt, userPrompt: normalizedUserPrompt }, {
+          ...llmOptions,
+          cacheKey: { prompt: normalizedUserMessage, portfolio: portfolioForPrompt, scope: `${venue}\n${conversation?.prompt ?? ''}` },
+        });
 
         // Parse JSON response with normalized message for fallback
-        modelResponse = await parseModelResponse(llmOutput.rawJson, normalizedIsSwapPrompt, normalizedIsDefiPrompt, normalizedUserMessage, normalizedIsPerpPrompt, normalizedIsEventPrompt);
+        modelResponse = await parseModelResponse(llmOutput.toolCall?.input ?? llmOutput.rawJson, normalizedIsSwapPrompt, normalizedIsDefiPrompt, normalizedUserMessage, normalizedIsPerpPrompt, normalizedIsEventPrompt);
         assistantMessage = modelResponse.assistantMessage;
         actions = modelResponse.actions;
         
@@ -1293,8 +1430,17 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
         }
       } catch (error: any) {
         console.error('LLM call or parsing error:', error.message);
-        // Try deterministic fallback before giving up
-        if (normalizedIsSwapPrompt || normalizedIsDefiPrompt || normalizedIsPerpPrompt || normalizedIsEventPrompt) {
+        // Try deterministic fallback before giving up (it costs no tokens, so it also serves over-budget users)
+        if (error instanceof LlmBudgetExceededError && !(normalizedIsSwapPrompt || normalizedIsDefiPrompt || normalizedIsPerpPrompt || normalizedIsEventPrompt)) {
+          assistantMessage = "You've reached your AI usage limit for now. Direct commands like \"swap 10 USDC to WETH\" still work, and the limit resets within 24 hours.";
+          actions = [];
+          modelResponse = {
+            assistantMessage,
+            actions: [],
+            executionRequest: null,
+            modelOk: false,
+          };
+        } else if (normalizedIsSwapPrompt || normalizedIsDefiPrompt || normalizedIsPerpPrompt || normalizedIsEventPrompt) {
           const fallback = await applyDeterministicFallback(normalizedUserMessage, normalizedIsSwapPrompt, normalizedIsDefiPrompt, normalizedIsPerpPrompt, normalizedIsEventPrompt);
           if (fallback) {
             modelResponse = {
@@ -1334,7 +1480,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
     const executionResults: ExecutionResult[] = [];
     for (const action of actions) {
       try {
-        const result = await applyAction(action);
+        const result = await applyAction(action, simUserId);
         executionResults.push(result);
         // If execution failed, remove action from array
         if (!result.success) {
@@ -1351,7 +1497,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
           actions.splice(index, 1);
         }
         // Add failed result
-        const portfolioAfter = buildPortfolioSnapshot();
+        const portfolioAfter = buildPortfolioSnapshot(simUserId);
         executionResults.push({
           success: false,
           status: 'failed',
@@ -1362,7 +1508,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
     }
 
     // Build updated portfolio snapshot after applying actions
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
 
     // Get executionRequest from modelResponse if available
     const executionRequest = modelResponse?.executionRequest ?? null;
@@ -1387,7 +1533,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
         });
       }
       
-      return res.status(200).json({
+      return reply({
         ok: false,
         assistantMessage: "I couldn't generate a valid execution plan. Please try rephrasing your request.",
         actions: [],
@@ -1626,7 +1772,7 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       }
     }
 
-    res.json(response);
+    reply(response);
   } catch (error: any) {
     console.error('Chat error:', error);
     logEvent('chat_response', {
@@ -1634,7 +1780,116 @@ app.post('/api/chat', maybeCheckAccess, async (req, res) => {
       error: error.message,
       latencyMs: Date.now() - chatStartTime,
     });
-    res.status(500).json({ error: error.message || 'Internal server error' });
+    if (stream) {
+      stream.fail(error.message || 'Internal server error');
+    } else {
+      res.status(500).json({ error: error.message || 'Internal server error' });
+    }
+  }
+});
+
+/**
+ * POST /api/auth/wallet/challenge
+ * Start a wallet sign-in: returns the message to sign with a one-time nonce
+ */
+app.post('/api/auth/wallet/challenge', async (req, res) => {
+  try {
+    const { createWalletChallenge } = await import('../utils/walletAuth');
+    const challenge = createWalletChallenge(req.body?.address);
+    res.json({ ok: true, ...challenge });
+  } catch (error: any) {
+    res.status(400).json({ ok: false, error: error.message });
+  }
+});
+
+/**
+ * POST /api/auth/wallet/verify
+ * Exchange the signed challenge for a bearer token (Authorization: Bearer <token>)
+ */
+app.post('/api/auth/wallet/verify', async (req, res) => {
+  try {
+    const { address, nonce, signature } = req.body || {};
+    if (!address || !nonce || !signature) {
+      return res.status(400).json({ ok: false, error: 'address, nonce and signature are required' });
+    }
+    const { verifyWalletChallenge } = await import('../utils/walletAuth');
+    const session = await verifyWalletChallenge({ address, nonce, signature });
+    if (typeof session === 'string') {
+      return res.status(401).json({ ok: false, error: session });
+    }
+    res.json({ ok: true, ...session });
+  } catch (error: any) {
+    console.error('[api/auth/wallet/verify] Error:', error);
+    res.status(500).json({ ok: false, error: 'Wallet sign-in failed', message: error.message });
+  }
+});
+
+/**
+ * Signed-in wallet for the thread endpoints, or a 401
+ */
+async function requireChatUser(req: any, res: any): Promise<string | null> {
+  const { getAuthenticatedWallet } = await import('../utils/walletAuth');
+  const wallet = getAuthenticatedWallet(req);
+  if (!wallet) {
+    res.status(401).json({ ok: false, error: 'Wallet sign-in required', code: 'WALLET_AUTH_REQUIRED' });
+  }
+  return wallet;
+}
+
+/**
+ * GET /api/chat/threads
+ * Recent conversation threads for the signed-in wallet
+ */
+app.get('/api/chat/threads', maybeCheckAccess, async (req, res) => {
+  try {
+    const chatUserId = await requireChatUser(req, res);
+    if (!chatUserId) return;
+    const limit = Math.min(parseInt(String(req.query.limit || '20'), 10) || 20, 100);
+    const { listUserThreads } = await import('../services/conversation');
+    const threads = await listUserThreads(chatUserId, limit);
+    res.json({ ok: true, threads });
+  } catch (error: any) {
+    console.error('[api/chat/threads] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to list chat threads', message: error.message });
+  }
+});
+
+/**
+ * GET /api/chat/threads/:id
+ * One thread with its messages and the proposals attached to them
+ */
+app.get('/api/chat/threads/:id', maybeCheckAccess, async (req, res) => {
+  try {
+    const chatUserId = await requireChatUser(req, res);
+    if (!chatUserId) return;
+    const { getUserThread } = await import('../services/conversation');
+    const view = await getUserThread(chatUserId, req.params.id);
+    if (!view) {
+      return res.status(404).json({ ok: false, error: 'Chat thread not found' });
+    }
+    res.json({ ok: true, ...view });
+  } catch (error: any) {
+    console.error('[api/chat/threads] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to fetch chat thread', message: error.message });
+  }
+});
+
+/**
+ * DELETE /api/chat/threads/:id
+ */
+app.delete('/api/chat/threads/:id', maybeCheckAccess, async (req, res) => {
+  try {
+    const chatUserId = await requireChatUser(req, res);
+    if (!chatUserId) return;
+    const { deleteUserThread } = await import('../services/conversation');
+    const deleted = await deleteUserThread(chatUserId, req.params.id);
+    if (!deleted) {
+      return res.status(404).json({ ok: false, error: 'Chat thread not found' });
+    }
+    res.json({ ok: true });
+  } catch (error: any) {
+    console.error('[api/chat/threads] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to delete chat thread', message: error.message });
   }
 });
 
@@ -1655,6 +1910,7 @@ interface CloseResponse {
 app.post('/api/strategy/close', async (req, res) => {
   try {
     const { strategyId, type }: CloseRequest = req.body;
+    const simUserId = await getSimUserId(req, res);
 
     if (!strategyId || !type) {
       return res.status(400).json({ error: 'strategyId and type are required' });
@@ -1665,11 +1921,11 @@ app.post('/api/strategy/close', async (req, res) => {
     let eventResult: { liveMarkToMarketUsd?: number } | undefined;
 
     if (type === 'perp') {
-      const result = await perpsSim.closePerp(strategyId);
+      const result = await perpsSim.closePerp(simUserId, strategyId);
       pnl = result.pnl;
       summaryMessage = `Closed ${result.position.market} ${result.position.side} position. Realized PnL: ${pnl >= 0 ? '+' : ''}$${pnl.toFixed(2)}`;
     } else if (type === 'event') {
-      const result = await eventSim.closeEventPosition(strategyId);
+      const result = await eventSim.closeEventPosition(simUserId, strategyId);
       pnl = result.pnl;
       const outcome = result.position.outcome === 'won' ? 'Won' : 'Lost';
       let pnlMessage = `Realized PnL: ${pnl >= 0 ? '+' : ''}$${pnl.toFixed(2)}`;
@@ -1679,7 +1935,7 @@ app.post('/api/strategy/close', async (req, res) => {
       }
       summaryMessage = `Settled event position "${result.position.label}" (${outcome}). ${pnlMessage}`;
     } else if (type === 'defi') {
-      const result = defiSim.closeDefiPosition(strategyId);
+      const result = defiSim.closeDefiPosition(simUserId, strategyId);
       pnl = result.yieldEarned;
       summaryMessage = `Closed ${result.position.protocol} position. Yield earned: $${pnl.toFixed(2)}`;
     } else {
@@ -1687,7 +1943,7 @@ app.post('/api/strategy/close', async (req, res) => {
     }
 
     // Build updated portfolio snapshot
-    const portfolio = buildPortfolioSnapshot();
+    const portfolio = buildPortfolioSnapshot(simUserId);
 
     // If this was an event close with liveMarkToMarketUsd, attach it to the strategy in the portfolio
     if (type === 'event' && eventResult?.liveMarkToMarketUsd !== undefined) {
@@ -1715,7 +1971,7 @@ app.post('/api/strategy/close', async (req, res) => {
 /**
  * POST /api/reset
  * V1/V1.1: Only resets chat state (no portfolio reset)
- * SIM mode: Resets simulation state (only if ALLOW_SIM_MODE=true)
+ * SIM mode: Resets the caller's simulation state (only if ALLOW_SIM_MODE=true)
  */
 app.post('/api/reset', async (req, res) => {
   try {
@@ -1724,8 +1980,9 @@ app.post('/api/reset', async (req, res) => {
     
     // In SIM mode with explicit permission, reset simulation state
     if (EXECUTION_MODE === 'sim' && ALLOW_SIM_MODE) {
-      resetAllSims();
-      const snapshot = getPortfolioSnapshot();
+      const simUserId = await getSimUserId(req, res);
+      resetAllSims(simUserId);
+      const snapshot = getPortfolioSnapshot(simUserId);
       res.json({ 
         portfolio: snapshot, 
         message: 'Simulation state reset.' 
@@ -1997,8 +2254,31 @@ app.post('/api/setup/approve', maybeCheckAccess, async (req, res) => {
  */
 app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
   const submitStartTime = Date.now();
+  const simUserId = await getSimUserId(req, res);
   try {
-    const { draftId, txHash, userAddress, strategy, executionRequest } = req.body;
+    const { draftId, txHash, userAddress, strategy, executionRequest, bridgeTransferId } = req.body;
+
+    // User-signed LI.FI bridge source tx: record it and let the bridge tracker follow the transfer
+    if (bridgeTransferId) {
+      if (!txHash || !userAddress) {
+        return res.status(400).json({ error: 'bridgeTransferId, txHash and userAddress are required' });
+      }
+      const { submitLiFiBridge } = await import('../bridge/lifiExecutor');
+      const submitted = await submitLiFiBridge({ transferId: bridgeTransferId, txHash, fromAddress: userAddress });
+      if (!submitted.ok) {
+        const statusCode = submitted.error!.code === 'TRANSFER_NOT_FOUND' ? 404 : 400;
+        return res.status(statusCode).json({ success: false, error: submitted.error });
+      }
+      logEvent('submit_tx', { txHash, userHash: hashAddress(userAddress), notes: [`bridge: ${bridgeTransferId}`] });
+      const { getBridgeTransferView } = await import('../bridge/bridgeTracker');
+      return res.json({
+        success: true,
+        status: 'submitted',
+        txHash,
+        explorerUrl: submitted.explorerUrl,
+        bridge: await getBridgeTransferView(bridgeTransferId),
+      });
+    }
 
     if (!draftId || !txHash) {
       return res.status(400).json({
@@ -2014,7 +2294,7 @@ app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
     });
 
     // Get portfolio before
-    const portfolioBefore = buildPortfolioSnapshot();
+    const portfolioBefore = buildPortfolioSnapshot(simUserId);
     
     const { EXECUTION_MODE, ETH_TESTNET_RPC_URL } = await import('../config');
     
@@ -2071,18 +2351,20 @@ app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
 
       if (isPerp) {
         // Add perp position to sim state
-        await perpsSim.openPerp({
+        await perpsSim.openPerp(simUserId, {
           market: strategy?.market || executionRequest?.market || 'BTC-USD',
           side: strategy?.side || strategy?.direction || executionRequest?.side || 'long',
           riskPct: strategy?.riskPercent || strategy?.riskPct || executionRequest?.riskPct || 2,
           entry: strategy?.entry || executionRequest?.entryPrice || 0,
           takeProfit: strategy?.takeProfit || executionRequest?.takeProfitPrice || 0,
           stopLoss: strategy?.stopLoss || executionRequest?.stopLossPrice || 0,
+          leverage: strategy?.leverage || executionRequest?.leverage,
         });
         console.log('[api/execute/submit] Updated perpsSim with new position');
       } else if (isEvent) {
         // Add event position to sim state
         await eventSim.openEventPosition(
+          simUserId,
           strategy?.market || executionRequest?.marketId || 'unknown-event',
           strategy?.outcome || strategy?.side || executionRequest?.outcome || 'YES',
           strategy?.stakeUsd || executionRequest?.stakeUsd || 10
@@ -2091,6 +2373,7 @@ app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
       } else if (isDefi) {
         // Add DeFi position to sim state
         await defiSim.openDefiPosition(
+          simUserId,
           strategy?.protocol || 'DemoLend',
           strategy?.depositUsd || executionRequest?.amountUsd || 100
         );
@@ -2098,7 +2381,7 @@ app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
       }
     }
 
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
     
     // Build response based on receipt status
     // Map receipt status to ExecutionResult status (which only supports 'success' | 'failed')
@@ -2135,7 +2418,7 @@ app.post('/api/execute/submit', maybeCheckAccess, async (req, res) => {
       error: error.message,
       notes: ['submit_tx_error'],
     });
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
     const result: ExecutionResult = {
       success: false,
       status: 'failed',
@@ -2754,11 +3037,13 @@ app.post('/api/session/prepare', async (req, res) => {
     console.log('[session/prepare] SessionIds match:', sessionId.toLowerCase() === encodedSessionId.toLowerCase());
 
     // V1: Return capability snapshot (caps, allowlists, approvals, expiresAt)
+    const { getPrice } = await import('../services/prices');
+    const ethPrice = await getPrice('ETH');
     const capabilitySnapshot = {
       sessionId,
       caps: {
         maxSpend: maxSpend.toString(),
-        maxSpendUsd: '10000', // Approximate USD value of 10 ETH
+        maxSpendUsd: (10 * ethPrice.priceUsd).toFixed(2), // Session policy enforces the cap in USD at the live ETH price
         expiresAt: expiresAt.toString(),
         expiresAtIso: new Date(Number(expiresAt) * 1000).toISOString(),
       },
@@ -2884,6 +3169,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
   console.log('[api/execute/relayed] Handler invoked - DEBUG MARKER V2');
   const correlationId = req.correlationId || generateCorrelationId();
   const relayedStartTime = Date.now();
+  const simUserId = await getSimUserId(req, res);
   
   // Trace log: relayed start (no secrets)
   const { sessionId, plan, userAddress } = req.body || {};
@@ -2955,7 +3241,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
 
     // If session is disabled, force direct execution path (return success with notes)
     if (!sessionEnabled) {
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       return res.json({
         success: true,
         status: 'success',
@@ -2969,7 +3255,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
 
     if (!draftId || !userAddress || !plan || !sessionId) {
       // Missing required fields - fall back to direct mode
-      const portfolioAfter = buildPortfolioSnapshot();
+      const portfolioAfter = buildPortfolioSnapshot(simUserId);
       return res.json({
         success: true,
         status: 'success',
@@ -3005,35 +3291,9 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
       });
     }
 
-    // Guard 2: Validate allowed adapters only (reuse UNISWAP_V3_ADAPTER_ADDRESS, WETH_WRAP_ADAPTER_ADDRESS, MOCK_SWAP_ADAPTER_ADDRESS from above)
-    const adapterConfig = await import('../config');
-    const PROOF_ADAPTER_ADDRESS = adapterConfig.PROOF_ADAPTER_ADDRESS;
-    const ERC20_PULL_ADAPTER_ADDRESS = adapterConfig.ERC20_PULL_ADAPTER_ADDRESS;
-    const DEMO_LEND_ADAPTER_ADDRESS = adapterConfig.DEMO_LEND_ADAPTER_ADDRESS;
-    const AAVE_ADAPTER_ADDRESS_RELAYED = adapterConfig.AAVE_ADAPTER_ADDRESS;
-    
-    const allowedAdapters = new Set<string>();
-    if (UNISWAP_V3_ADAPTER_ADDRESS) {
-      allowedAdapters.add(UNISWAP_V3_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (WETH_WRAP_ADAPTER_ADDRESS) {
-      allowedAdapters.add(WETH_WRAP_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (MOCK_SWAP_ADAPTER_ADDRESS) {
-      allowedAdapters.add(MOCK_SWAP_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (PROOF_ADAPTER_ADDRESS) {
-      allowedAdapters.add(PROOF_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (ERC20_PULL_ADAPTER_ADDRESS) {
-      allowedAdapters.add(ERC20_PULL_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (DEMO_LEND_ADAPTER_ADDRESS) {
-      allowedAdapters.add(DEMO_LEND_ADAPTER_ADDRESS.toLowerCase());
-    }
-    if (AAVE_ADAPTER_ADDRESS_RELAYED) {
-      allowedAdapters.add(AAVE_ADAPTER_ADDRESS_RELAYED.toLowerCase());
-    }
+    // Guard 2: Validate allowed adapters only (the allowlist sessions are created with)
+    const { getSessionAdapterAllowlist } = await import('./sessionPolicy');
+    const allowedAdapters = await getSessionAdapterAllowlist();
 
     for (const action of plan.actions) {
       const adapter = action.adapter?.toLowerCase();
@@ -3159,89 +3419,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
     const validateOnly = req.query?.validateOnly === 'true' || req.body?.validateOnly === true;
     
     // Helper to get session status from on-chain
-    const getSessionStatusFromChain = async (sessionId: string): Promise<{
-      active: boolean;
-      owner: string;
-      executor: string;
-      expiresAt: bigint;
-      maxSpend: bigint;
-      spent: bigint;
-      status: 'active' | 'expired' | 'revoked' | 'not_created';
-    } | null> => {
-      try {
-        const { ETH_TESTNET_RPC_URL, EXECUTION_ROUTER_ADDRESS } = await import('../config');
-        if (!ETH_TESTNET_RPC_URL || !EXECUTION_ROUTER_ADDRESS) {
-          return null;
-        }
-
-        const { createPublicClient, http } = await import('viem');
-        const { sepolia } = await import('viem/chains');
-        const publicClient = createPublicClient({
-          chain: sepolia,
-          transport: http(ETH_TESTNET_RPC_URL),
-        });
-
-        const normalizedSessionId = sessionId.startsWith('0x') ? sessionId : `0x${sessionId}`;
-        const sessionAbi = [
-          {
-            name: 'sessions',
-            type: 'function',
-            stateMutability: 'view',
-            inputs: [{ name: '', type: 'bytes32' }],
-            outputs: [
-              { name: 'owner', type: 'address' },
-              { name: 'executor', type: 'address' },
-              { name: 'expiresAt', type: 'uint64' },
-              { name: 'maxSpend', type: 'uint256' },
-              { name: 'spent', type: 'uint256' },
-              { name: 'active', type: 'bool' },
-            ],
-          },
-        ] as const;
-
-        const sessionResult = await Promise.race([
-          publicClient.readContract({
-            address: EXECUTION_ROUTER_ADDRESS as `0x${string}`,
-            abi: sessionAbi,
-            functionName: 'sessions',
-            args: [normalizedSessionId as `0x${string}`],
-          }),
-          new Promise((_, reject) => setTimeout(() => reject(new Error('timeout')), 2000)),
-        ]) as any;
-
-        const owner = sessionResult[0];
-        const executor = sessionResult[1];
-        const expiresAt = sessionResult[2];
-        const maxSpend = sessionResult[3];
-        const spent = sessionResult[4];
-        const active = sessionResult[5];
-
-        const now = BigInt(Math.floor(Date.now() / 1000));
-        let status: 'active' | 'expired' | 'revoked' | 'not_created' = 'not_created';
-
-        if (active) {
-          if (expiresAt > now) {
-            status = 'active';
-          } else {
-            status = 'expired';
-          }
-        } else if (owner !== '0x0000000000000000000000000000000000000000') {
-          status = 'revoked';
-        }
-
-        return {
-          active: status === 'active',
-          owner,
-          executor,
-          expiresAt,
-          maxSpend,
-          spent,
-          status,
-        };
-      } catch (error) {
-        return null;
-      }
-    };
+    const { readSessionStatusFromChain: getSessionStatusFromChain } = await import('./sessionPolicy');
 
     // Evaluate SessionPolicy
     const { evaluateSessionPolicy, estimatePlanSpend } = await import('./sessionPolicy');
@@ -3283,6 +3461,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
         allowed: policyResult.allowed,
         code: policyResult.code,
         spendWei: spendEstimate.spendWei.toString(),
+        spendUsd: spendEstimate.spendUsd,
         determinable: spendEstimate.determinable,
         instrumentType,
       });
@@ -3496,7 +3675,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
     }
 
     // Get portfolio before execution
-    const portfolioBefore = buildPortfolioSnapshot();
+    const portfolioBefore = buildPortfolioSnapshot(simUserId);
 
     // V1: Compute planHash server-side (keccak256(abi.encode(plan)))
     const { keccak256, encodeAbiParameters } = await import('viem');
@@ -3536,12 +3715,46 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
       throw hashErr;
     }
 
+    // Reserve usage for rolling session policy limits (daily spend, trades per hour) before sending,
+    // re-checking them atomically so concurrent relays cannot overrun a limit together
+    const { reserveSessionPolicyUsage } = await import('./sessionPolicy');
+    const { attachSessionPolicyUsageTx, releaseSessionPolicyUsage } = await import('../../execution-ledger/db');
+    let reservation: Awaited<ReturnType<typeof reserveSessionPolicyUsage>>;
+    try {
+      reservation = await reserveSessionPolicyUsage({ sessionId, userAddress, plan, spendEstimate, instrumentType });
+    } catch (usageError: any) {
+      // Fail closed: an unrecorded relay would not count against the limits
+      console.warn('[api/execute/relayed] Failed to reserve session policy usage:', usageError.message);
+      reservation = {
+        result: { allowed: false, code: 'POLICY_UNAVAILABLE', message: 'Session policy usage could not be recorded. Try again shortly.' },
+      };
+    }
+    if (!reservation.result.allowed) {
+      res.setHeader('x-correlation-id', correlationId);
+      return res.status(400).json({
+        ok: false,
+        correlationId,
+        error: {
+          code: reservation.result.code || 'POLICY_FAILED',
+          message: reservation.result.message || 'Session policy check failed',
+          ...(reservation.result.details || {}),
+        },
+      });
+    }
+
     // Send relayed transaction
-    const txHash = await sendRelayedTx({
-      to: EXECUTION_ROUTER_ADDRESS!,
-      data,
-      value: req.body.value || '0x0',
-    });
+    let txHash: string;
+    try {
+      txHash = await sendRelayedTx({
+        to: EXECUTION_ROUTER_ADDRESS!,
+        data,
+        value: req.body.value || '0x0',
+      });
+    } catch (sendError) {
+      releaseSessionPolicyUsage(reservation.usageId!);
+      throw sendError;
+    }
+    attachSessionPolicyUsageTx(reservation.usageId!, txHash);
 
     // Log successful attempt (before receipt confirmation)
     if (process.env.NODE_ENV !== 'production') {
@@ -3578,7 +3791,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
 
     // V1: Only update portfolio if receipt.status === 1 (confirmed)
     const portfolioAfter = receiptStatus === 'confirmed' 
-      ? buildPortfolioSnapshot()
+      ? buildPortfolioSnapshot(simUserId)
       : portfolioBefore;
 
     const result: ExecutionResult & { receiptStatus?: string; blockNumber?: number; planHash?: string } = {
@@ -3681,7 +3894,7 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
       }
     }
 
-    const portfolioAfter = buildPortfolioSnapshot();
+    const portfolioAfter = buildPortfolioSnapshot(simUserId);
     const result: ExecutionResult = {
       success: false,
       status: 'failed',
@@ -3708,6 +3921,165 @@ app.post('/api/execute/relayed', maybeCheckAccess, async (req, res) => {
   }
 });
 
+/**
+ * POST /api/execute/simulate
+ * Dry-run a plan before anything is signed: per-action balance deltas, revert reason and policy verdict
+ * Body: { userAddress, plan, value?, sessionId? } (sessionId simulates the relayed path)
+ */
+app.post('/api/execute/simulate', maybeCheckAccess, async (req, res) => {
+  const correlationId = req.correlationId || generateCorrelationId();
+  try {
+    const { userAddress, plan, sessionId } = req.body || {};
+    const value = req.body?.value ?? plan?.value;
+    if (!userAddress || !plan || !Array.isArray(plan.actions) || plan.actions.length === 0) {
+      return res.status(400).json({ ok: false, error: 'userAddress and a plan with actions are required', correlationId });
+    }
+    if (plan.user && plan.user.toLowerCase() !== userAddress.toLowerCase()) {
+      return res.status(400).json({ ok: false, error: 'plan.user does not match userAddress', correlationId });
+    }
+
+    const { EXECUTION_ROUTER_ADDRESS, RELAYER_PRIVATE_KEY } = await import('../config');
+    if (!EXECUTION_ROUTER_ADDRESS) {
+      return res.status(503).json({ ok: false, error: 'EXECUTION_ROUTER_ADDRESS not configured', correlationId });
+    }
+
+    let relayerAddress: string | undefined;
+    if (sessionId) {
+      if (!RELAYER_PRIVATE_KEY) {
+        return res.status(503).json({ ok: false, error: 'Session simulation requires RELAYER_PRIVATE_KEY', correlationId });
+      }
+      const { privateKeyToAccount } = await import('viem/accounts');
+      relayerAddress = privateKeyToAccount(RELAYER_PRIVATE_KEY as `0x${string}`).address;
+    }
+
+    const {
+      evaluateSessionPolicy,
+      estimatePlanSpend,
+      getSessionAdapterAllowlist,
+      readSessionStatusFromChain,
+    } = await import('./sessionPolicy');
+    const { simulatePlan } = await import('../executors/planSimulator');
+
+    const planWithUser = { ...plan, user: plan.user || userAddress };
+    const [simulation, spendEstimate, policy] = await Promise.all([
+      simulatePlan({ plan: planWithUser, value, sessionId, relayerAddress }),
+      estimatePlanSpend({ actions: plan.actions, value }),
+      sessionId
+        ? evaluateSessionPolicy(
+            sessionId,
+            userAddress,
+            { actions: plan.actions, value },
+            await getSessionAdapterAllowlist(),
+            readSessionStatusFromChain
+          )
+        : Promise.resolve(null),
+    ]);
+
+    logExecuteTrace(correlationId, 'simulate:done', {
+      mode: sessionId ? 'session' : 'direct',
+      method: simulation.method,
+      success: simulation.success,
+      policyAllowed: policy?.allowed,
+      spendUsd: spendEstimate.spendUsd,
+    });
+
+    res.json({
+      ok: true,
+      mode: sessionId ? 'session' : 'direct',
+      wouldSucceed: simulation.success && (policy ? policy.allowed : true),
+      simulation,
+      policy,
+      spendEstimate: { ...spendEstimate, spendWei: spendEstimate.spendWei.toString() },
+      correlationId,
+    });
+  } catch (error: any) {
+    console.error('[api/execute/simulate] Error:', error.message);
+    res.status(500).json({ ok: false, error: error.message || 'Simulation failed', correlationId });
+  }
+});
+
+/**
+ * GET /api/session/policy?sessionId=0x...
+ * Returns the policy document attached to a session (null if none)
+ */
+app.get('/api/session/policy', maybeCheckAccess, async (req, res) => {
+  try {
+    const sessionId = req.query.sessionId as string;
+    if (!sessionId) {
+      return res.status(400).json({ ok: false, error: 'sessionId is required' });
+    }
+    const { getSessionPolicy } = await import('../../execution-ledger/db');
+    const record = getSessionPolicy(sessionId);
+    res.json({
+      ok: true,
+      sessionId,
+      policy: record ? JSON.parse(record.policy_json) : null,
+      updatedAt: record?.updated_at,
+    });
+  } catch (error: any) {
+    res.status(500).json({ ok: false, error: error.message });
+  }
+});
+
+/**
+ * PUT /api/session/policy
+ * Attach or replace a session's policy document
+ * Body: { sessionId, userAddress, policy, nonce, issuedAt, signature } (policy: null removes it)
+ * signature: EIP-191 signature by userAddress over buildSessionPolicyMessage(sessionId, userAddress, policy, nonce, issuedAt)
+ * Fails closed: the session must exist on-chain and be owned by userAddress
+ */
+app.put('/api/session/policy', maybeCheckAccess, async (req, res) => {
+  try {
+    const { sessionId, userAddress, policy, nonce, issuedAt, signature } = req.body || {};
+    if (!sessionId || !userAddress) {
+      return res.status(400).json({ ok: false, error: 'sessionId and userAddress are required' });
+    }
+    if (typeof signature !== 'string' || typeof nonce !== 'string' || issuedAt === undefined) {
+      return res.status(401).json({ ok: false, error: 'nonce, issuedAt and an owner signature are required', errorCode: 'SIGNATURE_REQUIRED' });
+    }
+
+    const { verifySessionPolicySignature, readSessionStatusFromChain } = await import('./sessionPolicy');
+    const signatureError = await verifySessionPolicySignature({ sessionId, userAddress, policy: policy ?? null, nonce, issuedAt: Number(issuedAt), signature });
+    if (signatureError) {
+      return res.status(401).json({ ok: false, error: signatureError, errorCode: 'INVALID_SIGNATURE' });
+    }
+
+    // The signer must also own the session on-chain; without a readable session there is no owner to check
+    const onChain = await readSessionStatusFromChain(sessionId);
+    if (!onChain) {
+      return res.status(503).json({ ok: false, error: 'Cannot verify session ownership on-chain', errorCode: 'OWNERSHIP_UNVERIFIED' });
+    }
+    if (onChain.status === 'not_created') {
+      return res.status(404).json({ ok: false, error: 'Session does not exist on-chain', errorCode: 'SESSION_NOT_FOUND' });
+    }
+    if (onChain.owner.toLowerCase() !== userAddress.toLowerCase()) {
+      return res.status(403).json({ ok: false, error: 'userAddress does not own this session' });
+    }
+
+    const { getSessionPolicy, upsertSessionPolicy, deleteSessionPolicy } = await import('../../execution-ledger/db');
+    const existing = getSessionPolicy(sessionId);
+    if (existing && existing.user_address !== userAddress.toLowerCase()) {
+      return res.status(403).json({ ok: false, error: 'Session policy belongs to a different owner' });
+    }
+
+    if (policy === null) {
+      const removed = deleteSessionPolicy(sessionId);
+      return res.json({ ok: true, sessionId, policy: null, removed });
+    }
+
+    const { validateSessionPolicyDocument } = await import('./sessionPolicy');
+    const errors = validateSessionPolicyDocument(policy);
+    if (errors.length > 0) {
+      return res.status(400).json({ ok: false, error: 'Invalid session policy', errorCode: 'INVALID_POLICY', details: errors });
+    }
+
+    upsertSessionPolicy(sessionId, userAddress, policy);
+    res.json({ ok: true, sessionId, policy });
+  } catch (error: any) {
+    res.status(500).json({ ok: false, error: error.message });
+  }
+});
+
 /**
  * GET /api/session/status
  * Get session status (for feature detection and direct mode compatibility)
@@ -4602,6 +4974,189 @@ app.get('/api/portfolio/eth_testnet', maybeCheckAccess, async (req, res) => {
   }
 });
 
+/**
+ * GET /api/portfolio
+ * Unified portfolio: Sepolia holdings of userAddress plus every linked wallet
+ * (Solana devnet SOL + SPL tokens), valued in USD
+ * Optional solanaAddress includes an unlinked Solana wallet for this request only
+ */
+app.get('/api/portfolio', maybeCheckAccess, async (req, res) => {
+  try {
+    const userAddress = typeof req.query.userAddress === 'string' ? req.query.userAddress : '';
+    const solanaAddress = typeof req.query.solanaAddress === 'string' ? req.query.solanaAddress : '';
+
+    if (!/^0x[a-fA-F0-9]{40}$/.test(userAddress)) {
+      return res.status(400).json({ ok: false, error: 'Valid userAddress query parameter is required' });
+    }
+
+    const { getUnifiedPortfolio, isSolanaAddress } = await import('../services/portfolio');
+    if (solanaAddress && !isSolanaAddress(solanaAddress)) {
+      return res.status(400).json({ ok: false, error: 'Invalid solanaAddress format' });
+    }
+
+    const portfolio = await getUnifiedPortfolio(
+      userAddress,
+      solanaAddress ? [{ chain: 'solana', network: 'devnet', address: solanaAddress }] : []
+    );
+    res.json({ ok: true, ...portfolio });
+  } catch (error: any) {
+    console.error('[api/portfolio] Error:', error);
+    res.status(500).json({
+      ok: false,
+      error: 'Failed to fetch portfolio',
+      message: error.message,
+    });
+  }
+});
+
+/**
+ * GET /api/wallet/linked
+ * Wallets linked to a user. With ?solanaAddress=... (and optional action=unlink)
+ * also returns a fresh message to sign, with its nonce and issuedAt
+ */
+app.get('/api/wallet/linked', maybeCheckAccess, async (req, res) => {
+  try {
+    const userAddress = typeof req.query.userAddress === 'string' ? req.query.userAddress : '';
+    if (!/^0x[a-fA-F0-9]{40}$/.test(userAddress)) {
+      return res.status(400).json({ ok: false, error: 'Valid userAddress query parameter is required' });
+    }
+
+    const { listLinkedWallets } = await import('../../execution-ledger/db');
+    const { buildWalletLinkMessage } = await import('../services/portfolio');
+    const solanaAddress = typeof req.query.solanaAddress === 'string' ? req.query.solanaAddress : '';
+    const action = req.query.action === 'unlink' ? 'unlink' : 'link';
+
+    let linkChallenge;
+    if (solanaAddress) {
+      const { randomBytes } = await import('crypto');
+      const nonce = randomBytes(16).toString('hex');
+      const issuedAt = Math.floor(Date.now() / 1000);
+      linkChallenge = {
+        linkMessage: buildWalletLinkMessage(action, userAddress, solanaAddress, nonce, issuedAt),
+        nonce,
+        issuedAt,
+      };
+    }
+
+    res.json({
+      ok: true,
+      userAddress: userAddress.toLowerCase(),
+      wallets: listLinkedWallets(userAddress),
+      ...linkChallenge,
+    });
+  } catch (error: any) {
+    console.error('[api/wallet/linked] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to list linked wallets' });
+  }
+});
+
+/**
+ * POST /api/wallet/link
+ * Link a Solana wallet to a user. Both wallets sign the link message
+ * (see GET /api/wallet/linked?solanaAddress=...): the EVM user proves they own
+ * userAddress, the Solana wallet that it agrees to be linked
+ * Body: { userAddress, solanaAddress, nonce, issuedAt, evmSignature, solanaSignature (base58 or base64), label? }
+ */
+app.post('/api/wallet/link', maybeCheckAccess, async (req, res) => {
+  try {
+    const { userAddress, solanaAddress, nonce, issuedAt, evmSignature, solanaSignature, label } = req.body || {};
+
+    if (typeof userAddress !== 'string' || !/^0x[a-fA-F0-9]{40}$/.test(userAddress)) {
+      return res.status(400).json({ ok: false, error: 'Valid userAddress is required' });
+    }
+
+    const { isSolanaAddress, verifyWalletLinkSignature } = await import('../services/portfolio');
+    if (typeof solanaAddress !== 'string' || !isSolanaAddress(solanaAddress)) {
+      return res.status(400).json({ ok: false, error: 'Valid solanaAddress is required' });
+    }
+    if (typeof evmSignature !== 'string' || typeof solanaSignature !== 'string') {
+      return res.status(401).json({ ok: false, error: 'evmSignature and solanaSignature are required', code: 'SIGNATURE_REQUIRED' });
+    }
+    const signatureError = await verifyWalletLinkSignature({
+      action: 'link',
+      userAddress,
+      solanaAddress,
+      nonce,
+      issuedAt: Number(issuedAt),
+      evmSignature,
+      solanaSignature,
+    });
+    if (signatureError) {
+      return res.status(401).json({ ok: false, error: signatureError, code: 'INVALID_SIGNATURE' });
+    }
+
+    const { linkWallet } = await import('../../execution-ledger/db');
+    const wallet = linkWallet({
+      userAddress,
+      chain: 'solana',
+      network: 'devnet',
+      address: solanaAddress,
+      label: typeof label === 'string' ? label.slice(0, 64) : undefined,
+    });
+    res.json({ ok: true, wallet });
+  } catch (error: any) {
+    console.error('[api/wallet/link] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to link wallet' });
+  }
+});
+
+/**
+ * DELETE /api/wallet/link
+ * Unlink a Solana wallet from a user; the EVM user signs the unlink message
+ * (see GET /api/wallet/linked?solanaAddress=...&action=unlink)
+ * Body: { userAddress, address, nonce, issuedAt, evmSignature }
+ */
+app.delete('/api/wallet/link', maybeCheckAccess, async (req, res) => {
+  try {
+    const { userAddress, address, nonce, issuedAt, evmSignature } = req.body || {};
+
+    if (typeof userAddress !== 'string' || !/^0x[a-fA-F0-9]{40}$/.test(userAddress) || typeof address !== 'string') {
+      return res.status(400).json({ ok: false, error: 'userAddress and address are required' });
+    }
+    if (typeof evmSignature !== 'string') {
+      return res.status(401).json({ ok: false, error: 'evmSignature is required', code: 'SIGNATURE_REQUIRED' });
+    }
+
+    const { verifyWalletLinkSignature } = await import('../services/portfolio');
+    const signatureError = await verifyWalletLinkSignature({
+      action: 'unlink',
+      userAddress,
+      solanaAddress: address,
+      nonce,
+      issuedAt: Number(issuedAt),
+      evmSignature,
+    });
+    if (signatureError) {
+      return res.status(401).json({ ok: false, error: signatureError, code: 'INVALID_SIGNATURE' });
+    }
+
+    const { unlinkWallet } = await import('../../execution-ledger/db');
+    res.json({ ok: true, removed: unlinkWallet(userAddress, 'solana', 'devnet', address) });
+  } catch (error: any) {
+    console.error('[api/wallet/link] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to unlink wallet' });
+  }
+});
+
+/**
+ * GET /api/bridge/:id
+ * Progress of a cross-chain transfer (by transfer id or source tx hash)
+ * States: source_pending -> source_confirmed -> in_flight -> dest_confirmed | refunded | failed (stuck if overdue)
+ */
+app.get('/api/bridge/:id', maybeCheckAccess, async (req, res) => {
+  try {
+    const { getBridgeTransferView } = await import('../bridge/bridgeTracker');
+    const view = await getBridgeTransferView(req.params.id);
+    if (!view) {
+      return res.status(404).json({ ok: false, error: 'Bridge transfer not found' });
+    }
+    res.json({ ok: true, ...view });
+  } catch (error: any) {
+    console.error('[api/bridge] Error:', error);
+    res.status(500).json({ ok: false, error: 'Failed to fetch bridge transfer', message: error.message });
+  }
+});
+
 /**
  * GET /api/defi/aave/positions
  * Read Aave positions (aToken balances) for a user
@@ -4677,6 +5232,34 @@ app.get('/api/wallet/balances', maybeCheckAccess, async (req, res) => {
       });
     }
 
+    // Solana wallets: native SOL plus every SPL token account
+    const { isSolanaAddress } = await import('../services/portfolio');
+    if (!address.startsWith('0x') && isSolanaAddress(address)) {
+      const { getWalletHoldings } = await import('../services/portfolio');
+      const holdings = await getWalletHoldings({ chain: 'solana', network: 'devnet', address });
+      const [native, ...tokens] = holdings;
+      return res.json({
+        chain: 'solana',
+        network: 'devnet',
+        address,
+        native: {
+          symbol: 'SOL',
+          lamports: native.raw,
+          formatted: native.formatted,
+          valueUsd: native.valueUsd,
+        },
+        tokens: tokens.map(token => ({
+          mint: token.token,
+          symbol: token.symbol,
+          decimals: token.decimals,
+          raw: token.raw,
+          formatted: token.formatted,
+          valueUsd: token.valueUsd,
+        })),
+        timestamp: Date.now(),
+      });
+    }
+
     // Validate address format
     if (!/^0x[a-fA-F0-9]{40}$/.test(address)) {
       return res.status(400).json({
@@ -5089,6 +5672,21 @@ app.get('/api/telemetry/summary', async (req, res) => {
   }
 });
 
+/**
+ * LLM usage by provider/model: calls, failures, cache hits, tokens, estimated cost
+ */
+app.get('/api/telemetry/llm-usage', async (req, res) => {
+  try {
+    const hours = Math.min(Math.max(parseInt(String(req.query.hours || '24'), 10) || 24, 1), 24 * 30);
+    const { getLlmUsageSummary } = await import('../../telemetry/db');
+    const providers = getLlmUsageSummary(hours);
+    const totalCostUsd = providers.reduce((sum, row) => sum + row.costUsd, 0);
+    res.json({ ok: true, data: { windowHours: hours, totalCostUsd, providers } });
+  } catch (error) {
+    res.json({ ok: false, error: 'Telemetry DB not available', data: { windowHours: 24, totalCostUsd: 0, providers: [] } });
+  }
+});
+
 /**
  * Devnet Statistics endpoint for landing page
  * Returns comprehensive stats:
@@ -5469,6 +6067,11 @@ if (!process.env.VERCEL) {
   console.log(`   Health check: http://127.0.0.1:${PORT}/health`);
   console.log(`   API endpoints:`);
   console.log(`   - POST /api/chat`);
+  console.log(`   - POST /api/auth/wallet/challenge`);
+  console.log(`   - POST /api/auth/wallet/verify`);
+  console.log(`   - GET  /api/chat/threads`);
+  console.log(`   - GET  /api/chat/threads/:id`);
+  console.log(`   - DELETE /api/chat/threads/:id`);
   console.log(`   - POST /api/strategy/close`);
   console.log(`   - POST /api/reset`);
   console.log(`   - GET  /api/ticker`);
@@ -5481,30 +6084,95 @@ if (!process.env.VERCEL) {
   console.log(`   - POST /api/token/approve/prepare`);
   console.log(`   - POST /api/token/weth/wrap/prepare`);
   console.log(`   - GET  /api/portfolio/eth_testnet`);
+  console.log(`   - GET  /api/portfolio`);
+  console.log(`   - GET  /api/bridge/:id`);
   console.log(`   - GET  /health`);
   console.log(`   - GET  /api/debug/executions`);
   console.log(`   - POST /api/access/validate`);
   console.log(`   - GET  /api/ledger/positions`);
   console.log(`   - GET  /api/ledger/positions/recent`);
 
-  // Start perp indexer if configured
-  try {
-    const { startPerpIndexer } = await import('../indexer/perpIndexer');
-    const rpcUrl = process.env.ETH_TESTNET_RPC_URL;
-    const perpEngineAddress = process.env.DEMO_PERP_ENGINE_ADDRESS;
+  // Start contract event indexer (router, sessions, perp engine, adapters, vaults)
+  if (process.env.INDEXER_DISABLED !== 'true') {
+    try {
+      const { registerConfiguredContracts } = await import('../indexer/contracts');
+      const { startEventIndexer } = await import('../indexer/eventIndexer');
 
-    if (rpcUrl && perpEngineAddress) {
-      startPerpIndexer(rpcUrl, perpEngineAddress);
-    } else {
-      console.log('   [indexer] Perp indexer disabled (config missing)');
+      if (process.env.ETH_TESTNET_RPC_URL && registerConfiguredContracts().length > 0) {
+        await startEventIndexer();
+      } else {
+        console.log('   [indexer] Event indexer disabled (config missing)');
+      }
+    } catch (err: any) {
+      console.log('   [indexer] Failed to start:', err.message);
+    }
+  }
+
+  // Start sim perps mark-to-market engine (TP/SL + liquidation)
+  if (process.env.PERPS_MARK_ENGINE_DISABLED !== 'true') {
+    try {
+      const { startPerpsMarkEngine } = await import('../services/perpsMarkEngine');
+      startPerpsMarkEngine();
+    } catch (err: any) {
+      console.log('   [markEngine] Failed to start:', err.message);
+    }
+  }
+
+  // Start price history recorder (ticks + OHLC candles in the ledger)
+  if (process.env.PRICE_HISTORY_DISABLED !== 'true') {
+    try {
+      const { startPriceHistoryRecorder } = await import('../services/priceHistory');
+      startPriceHistoryRecorder();
+    } catch (err: any) {
+      console.log('   [priceHistory] Failed to start:', err.message);
+    }
+  }
+
+  // Start perps funding accrual (sim perps + ledger demo_perp positions)
+  if (process.env.PERPS_FUNDING_ENGINE_DISABLED !== 'true') {
+    try {
+      const { startPerpsFundingEngine } = await import('../services/perpsFundingEngine');
+      startPerpsFundingEngine();
+    } catch (err: any) {
+      console.log('   [funding] Failed to start:', err.message);
+    }
+  }
+
+  // Start execution reconciler (fills stuck receipts, flags reorged/dropped txs)
+  if (process.env.RECONCILER_DISABLED !== 'true') {
+    try {
+      const { startExecutionReconciler } = await import('../services/executionReconciler');
+      startExecutionReconciler();
+    } catch (err: any) {
+      console.log('   [reconciler] Failed to start:', err.message);
+    }
+  }
+
+  // Start bridge transfer tracker (follows in-flight transfers, alerts on stuck ones)
+  if (process.env.BRIDGE_TRACKER_DISABLED !== 'true') {
+    try {
+      const { startBridgeTracker } = await import('../bridge/bridgeTracker');
+      startBridgeTracker();
+    } catch (err: any) {
+      console.log('   [bridgeTracker] Failed to start:', err.message);
+    }
+  }
+
+  // Start intent job queue worker (resumes orphaned and interrupted intents)
+  if (process.env.INTENT_QUEUE_DISABLED !== 'true') {
+    try {
+      const { startIntentQueue } = await import('../intent/intentQueue');
+      await startIntentQueue();
+    } catch (err: any) {
+      console.log('   [intentQueue] Failed to start:', err.message);
     }
-  } catch (err: any) {
-    console.log('   [indexer] Failed to start:', err.message);
   }
   console.log(`   - POST /api/access/check`);
   console.log(`   - GET  /api/access/codes (admin)`);
   console.log(`   - POST /api/access/codes/generate (admin)`);
   console.log(`   - GET  /api/prices/eth`);
+  console.log(`   - GET  /api/prices/history`);
+  console.log(`   - GET  /api/prices/:symbol`);
   });
 } else {
   console.log('🌸 Blossom Agent (Vercel serverless mode - app exported, not listening)');
@@ -5640,6 +6308,9 @@ app.get('/api/prices/eth', async (req, res) => {
       symbol: 'ETH',
       priceUsd: priceSnapshot.priceUsd,
       source: priceSnapshot.source || 'coingecko',
+      confidence: priceSnapshot.confidence,
+      stale: priceSnapshot.stale,
+      sources: priceSnapshot.sources,
     });
   } catch (error: any) {
     console.error('[api/prices/eth] Error:', error);
@@ -5648,10 +6319,90 @@ app.get('/api/prices/eth', async (req, res) => {
       symbol: 'ETH',
       priceUsd: 3000,
       source: 'fallback',
+      confidence: 'low',
     });
   }
 });
 
+/**
+ * GET /api/prices/history?symbol=ETH&interval=1h&from=&to=&limit=
+ * OHLC candles recorded from the price oracle, oldest first (from/to are unix seconds)
+ * Registered before /api/prices/:symbol so 'history' is not taken as a symbol
+ */
+app.get('/api/prices/history', async (req, res) => {
+  try {
+    const {
+      getPriceCandles,
+      computeLogReturnVolatility,
+      annualizeVolatility,
+      isCandlePeriod,
+      PRICE_CANDLE_PERIOD_SECONDS,
+    } = await import('../services/priceHistory');
+
+    const symbol = typeof req.query.symbol === 'string' ? req.query.symbol.trim().toUpperCase() : '';
+    if (!symbol) {
+      return res.status(400).json({ ok: false, error: 'symbol is required' });
+    }
+    const interval = req.query.interval ?? '1h';
+    if (!isCandlePeriod(interval)) {
+      return res.status(400).json({
+        ok: false,
+        error: `interval must be one of ${Object.keys(PRICE_CANDLE_PERIOD_SECONDS).join(', ')}`,
+      });
+    }
+
+    const from = req.query.from !== undefined ? parseInt(String(req.query.from), 10) : undefined;
+    const to = req.query.to !== undefined ? parseInt(String(req.query.to), 10) : undefined;
+    if ((from !== undefined && Number.isNaN(from)) || (to !== undefined && Number.isNaN(to))) {
+      return res.status(400).json({ ok: false, error: 'from and to must be unix seconds' });
+    }
+    const limit = Math.min(Math.max(parseInt(String(req.query.limit ?? '200'), 10) || 200, 1), 1000);
+
+    const candles = await getPriceCandles(symbol, interval, { from, to, limit });
+    const volatility = computeLogReturnVolatility(candles.map(candle => candle.close));
+
+    res.json({
+      ok: true,
+      symbol,
+      interval,
+      candles,
+      // Over the returned candles
+      realizedVolatility: volatility
+        ? {
+            samples: volatility.samples,
+            periodVolatility: volatility.stdev,
+            annualized: annualizeVolatility(volatility.stdev, interval),
+          }
+        : null,
+    });
+  } catch (error: any) {
+    console.error('[api/prices/history] Error:', error);
+    res.status(500).json({ ok: false, error: error.message || 'Failed to load price history' });
+  }
+});
+
+/**
+ * GET /api/prices/:symbol
+ * Oracle price for any supported symbol, with per-source detail
+ */
+app.get('/api/prices/:symbol', async (req, res) => {
+  try {
+    const { getPrice, PriceUnavailableError } = await import('../services/prices');
+    try {
+      const priceSnapshot = await getPrice(req.params.symbol);
+      res.json({ ok: true, ...priceSnapshot });
+    } catch (error: any) {
+      if (error instanceof PriceUnavailableError) {
+        return res.status(404).json({ ok: false, code: error.code, error: error.message });
+      }
+      throw error;
+    }
+  } catch (error: any) {
+    console.error('[api/prices/:symbol] Error:', error);
+    res.status(500).json({ ok: false, error: error.message || 'Failed to fetch price' });
+  }
+});
+
 /**
  * GET /api/debug/executions
  * Dump execution artifacts for debugging
@@ -6220,6 +6971,137 @@ app.get('/api/ledger/executions', checkLedgerSecret, async (req, res) => {
   }
 });
 
+/**
+ * GET /api/ledger/indexer
+ * Event indexer status: registered contracts, per-contract cursors, the last poll and last reorg
+ */
+app.get('/api/ledger/indexer', checkLedgerSecret, async (req, res) => {
+  try {
+    const { listIndexerStates } = await import('../../execution-ledger/db');
+    const { getIndexedContracts, isEventIndexerRunning, getLastIndexerPoll, getLastIndexerReorg } = await import('../indexer/eventIndexer');
+
+    const cursors = listIndexerStates();
+    res.json({
+      ok: true,
+      data: {
+        running: isEventIndexerRunning(),
+        lastPoll: getLastIndexerPoll(),
+        lastReorg: getLastIndexerReorg(),
+        contracts: getIndexedContracts().map(contract => ({
+          name: contract.name,
+          address: contract.address,
+          events: contract.events.map(event => event.name),
+          lastIndexedBlock: cursors.find(c => c.contract_address === contract.address)?.last_indexed_block ?? null,
+        })),
+        cursors,
+      },
+    });
+  } catch (error: any) {
+    console.error('[ledger] Failed to fetch indexer status:', error);
+    res.json({ ok: false, error: 'Failed to fetch indexer status', data: null });
+  }
+});
+
+/**
+ * GET /api/ledger/indexer/events
+ * Recently indexed contract events (filter by contract name, event name, fromBlock)
+ */
+app.get('/api/ledger/indexer/events', checkLedgerSecret, async (req, res) => {
+  try {
+    const { listIndexedEvents } = await import('../../execution-ledger/db');
+    const limit = parseInt(req.query.limit as string) || 100;
+    const fromBlock = parseInt(req.query.fromBlock as string);
+
+    const events = listIndexedEvents({
+      contractName: req.query.contract as string | undefined,
+      eventName: req.query.event as string | undefined,
+      fromBlock: Number.isNaN(fromBlock) ? undefined : fromBlock,
+      limit: Math.min(limit, 500),
+    });
+    res.json({
+      ok: true,
+      data: events.map(({ args_json, ...event }) => ({ ...event, args: JSON.parse(args_json) })),
+    });
+  } catch (error: any) {
+    console.error('[ledger] Failed to fetch indexed events:', error);
+    res.json({ ok: false, error: 'Failed to fetch indexed events', data: [] });
+  }
+});
+
+/**
+ * GET /api/ledger/bridge-transfers
+ * Bridge transfers with tracker status (?state=in_flight,stuck&intentId=&limit=)
+ */
+app.get('/api/ledger/bridge-transfers', checkLedgerSecret, async (req, res) => {
+  try {
+    const { listBridgeTransfers } = await import('../../execution-ledger/db');
+    const { isBridgeTrackerRunning, getLastBridgeTrackerCycle } = await import('../bridge/bridgeTracker');
+    const limit = parseInt(req.query.limit as string) || 50;
+    const states = typeof req.query.state === 'string' ? req.query.state.split(',') as any[] : undefined;
+    const intentId = req.query.intentId as string | undefined;
+
+    res.json({
+      ok: true,
+      data: {
+        tracker: {
+          running: isBridgeTrackerRunning(),
+          lastCycle: getLastBridgeTrackerCycle(),
+        },
+        transfers: listBridgeTransfers({ states, intentId, limit: Math.min(limit, 500) }),
+      },
+    });
+  } catch (error: any) {
+    console.error('[ledger] Failed to fetch bridge transfers:', error);
+    res.json({ ok: false, error: 'Failed to fetch bridge transfers', data: null });
+  }
+});
+
+/**
+ * GET /api/ledger/reconcile/report
+ * Lists executions whose ledger state disagreed with on-chain receipts
+ * (?kind=dropped|reorged|reverted_after_confirm|missing_tx_hash&chain=&since=&limit=)
+ */
+app.get('/api/ledger/reconcile/report', checkLedgerSecret, async (req, res) => {
+  try {
+    const { listExecutionDiscrepancies, getReconciliationSummary } = await import('../../execution-ledger/db');
+    const { isReconcilerRunning, getLastReconcileCycle } = await import('../services/executionReconciler');
+    const limit = parseInt(req.query.limit as string) || 100;
+    const since = parseInt(req.query.since as string) || undefined;
+    const kind = req.query.kind as any;
+    const chain = req.query.chain as any;
+
+    res.json({
+      ok: true,
+      data: {
+        summary: getReconciliationSummary(),
+        reconciler: {
+          running: isReconcilerRunning(),
+          lastCycle: getLastReconcileCycle(),
+        },
+        discrepancies: listExecutionDiscrepancies({ kind, chain, since, limit: Math.min(limit, 500) }),
+      },
+    });
+  } catch (error: any) {
+    console.error('[ledger] Failed to build reconciliation report:', error);
+    res.json({ ok: false, error: 'Failed to build reconciliation report', data: null });
+  }
+});
+
+/**
+ * POST /api/ledger/reconcile/run
+ * Runs one reconciliation pass immediately
+ */
+app.post('/api/ledger/reconcile/run', checkLedgerSecret, async (req, res) => {
+  try {
+    const { runReconcileCycle } = await import('../services/executionReconciler');
+    const limit = parseInt(req.body?.limit) || undefined;
+    const result = await runReconcileCycle(limit);
+    res.json({ ok: true, data: result });
+  } catch (error: any) {
+    res.status(500).json({ ok: false, error: error.message });
+  }
+});
+
 /**
  * GET /api/ledger/sessions
  * Returns list of sessions across chains
@@ -6388,27 +7270,63 @@ app.get('/api/ledger/intents/recent', checkLedgerSecret, async (req, res) => {
   }
 });
 
+/**
+ * GET /api/ledger/intents/jobs
+ * Lists intent queue jobs (?status=pending|leased|done|dead)
+ */
+app.get('/api/ledger/intents/jobs', checkLedgerSecret, async (req, res) => {
+  try {
+    const { listIntentJobs } = await import('../../execution-ledger/db');
+    const status = req.query.status as any;
+    const limit = parseInt(req.query.limit as string) || 50;
+    const jobs = listIntentJobs({ status, limit: Math.min(limit, 200) });
+    res.json({ ok: true, data: jobs });
+  } catch (error: any) {
+    console.error('[ledger] Failed to fetch intent jobs:', error);
+    res.json({ ok: false, error: 'Failed to fetch intent jobs', data: [] });
+  }
+});
+
+/**
+ * POST /api/ledger/intents/:id/job/retry
+ * Re-queue a dead-lettered intent job with a fresh attempt budget
+ */
+app.post('/api/ledger/intents/:id/job/retry', checkLedgerSecret, async (req, res) => {
+  try {
+    const { retryDeadIntentJob } = await import('../intent/intentQueue');
+    const requeued = await retryDeadIntentJob(req.params.id);
+    if (!requeued) {
+      return res.status(404).json({ ok: false, error: 'No dead-lettered job for this intent' });
+    }
+    res.json({ ok: true, intentId: req.params.id, status: 'queued' });
+  } catch (error: any) {
+    res.status(500).json({ ok: false, error: error.message });
+  }
+});
+
 /**
  * GET /api/ledger/intents/:id
  * Returns a single intent by ID
  */
 app.get('/api/ledger/intents/:id', checkLedgerSecret, async (req, res) => {
   try {
-    const { getIntentAsync, getExecutionsForIntentAsync } = await import('../../execution-ledger/db');
+    const { getIntentAsync, getExecutionsForIntentAsync, getIntentJob } = await import('../../execution-ledger/db');
     const intent = await getIntentAsync(req.params.id);
 
     if (!intent) {
       return res.status(404).json({ ok: false, error: 'Intent not found', data: null });
     }
 
-    // Include linked executions
+    // Include linked executions and queue job (if queued)
     const executions = await getExecutionsForIntentAsync(req.params.id);
+    const job = getIntentJob(req.params.id) ?? null;
 
     res.json({
       ok: true,
       data: {
         ...intent,
         executions,
+        job,
       },
     });
   } catch (error) {
@@ -6475,18 +7393,35 @@ app.get('/api/ledger/intents/:id/executions', checkLedgerSecret, async (req, res
  * Options:
  * - planOnly: true → Returns plan without executing (for confirm mode)
  * - intentId: string → Execute a previously planned intent (skip parse/route)
+ * - queue: true → Enqueue on the durable intent job queue and return 202 immediately
  *
  * Returns execution result with explorer links
  */
 app.post('/api/ledger/intents/execute', checkLedgerSecret, async (req, res) => {
   try {
-    const { intentText, chain = 'ethereum', planOnly = false, intentId, metadata } = req.body;
+    const { intentText, chain = 'ethereum', planOnly = false, intentId, metadata, queue = false } = req.body;
 
     // Import the intent runner functions
     const { runIntent, executeIntentById, recordFailedIntent } = await import('../intent/intentRunner');
+    const useQueue = Boolean(queue) && !planOnly;
 
     // If intentId is provided, execute the existing planned intent
     if (intentId && typeof intentId === 'string') {
+      if (useQueue) {
+        const { getIntent } = await import('../../execution-ledger/db');
+        const intent = getIntent(intentId);
+        if (!intent || intent.status !== 'planned') {
+          return res.status(400).json({
+            ok: false,
+            intentId,
+            status: 'failed',
+            error: { stage: 'execute', code: 'INVALID_STATUS', message: `Intent ${intentId} is not planned` },
+          });
+        }
+        const { enqueuePlannedIntent } = await import('../intent/intentQueue');
+        const queued = await enqueuePlannedIntent(intentId);
+        return res.status(202).json({ ok: true, ...queued, status: 'queued' });
+      }
       const result = await executeIntentById(intentId);
       return res.json(result);
     }
@@ -6520,6 +7455,16 @@ app.post('/api/ledger/intents/execute', checkLedgerSecret, async (req, res) => {
       return res.status(400).json(failedResult);
     }
 
+    // Queue mode: the worker drives route → execute → confirm; poll GET /api/ledger/intents/:id
+    if (useQueue) {
+      const { enqueueIntent } = await import('../intent/intentQueue');
+      const queued = await enqueueIntent(intentText, {
+        chain: chain as 'ethereum' | 'solana' | 'both',
+        metadata: enrichedMetadata,
+      });
+      return res.status(202).json({ ok: true, ...queued, status: 'queued' });
+    }
+
     // Run the intent through the pipeline
     const result = await runIntent(intentText, {
       chain: chain as 'ethereum' | 'solana' | 'both',
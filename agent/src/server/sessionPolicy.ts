@@ -4,7 +4,7 @@
  * Server-side enforcement for relayed execution
  */
 
-import { parseUnits } from 'viem';
+import { createHash } from 'crypto';
 
 export interface SessionPolicyResult {
   allowed: boolean;
@@ -23,138 +23,746 @@ export interface SessionStatus {
   status: 'active' | 'expired' | 'revoked' | 'not_created';
 }
 
+export interface PlanSpendLine {
+  actionIndex?: number;          // Undefined for the plan's native ETH value
+  actionType?: number;
+  token: string;                 // Token address, or 'eth' for native value
+  symbol: string;
+  decimals: number;
+  amountUnits: string;           // Base units charged to the session
+  amount: number;                // Human-readable amount
+  priceUsd: number;
+  priceSource: string;
+  priceConfidence: 'high' | 'medium' | 'low';
+  usd: number;
+}
+
 export interface PlanSpendEstimate {
-  spendWei: bigint;
+  spendWei: bigint;              // ETH-equivalent of spendUsd (session caps are denominated in wei)
   spendUsd?: number;
+  ethPriceUsd?: number;
+  priceConfidence?: 'high' | 'medium' | 'low'; // Lowest confidence of any price used
+  breakdown: PlanSpendLine[];
   determinable: boolean;
+  reason?: string;               // Why spend could not be determined
   instrumentType?: 'swap' | 'perp' | 'defi' | 'event';
 }
 
+// Direct action payload layouts (session mode wraps these as (maxSpendUnits, bytes))
+export const SWAP_LAYOUT = [
+  { type: 'address' },  // tokenIn
+  { type: 'address' },  // tokenOut
+  { type: 'uint24' },   // fee
+  { type: 'uint256' },  // amountIn
+  { type: 'uint256' },  // amountOutMin
+  { type: 'address' },  // recipient
+  { type: 'uint256' },  // deadline
+] as const;
+export const PULL_LAYOUT = [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }] as const; // token, from, amount
+export const LEND_LAYOUT = [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }, { type: 'address' }] as const; // asset, vault, amount, onBehalfOf
+export const WRAP_LAYOUT = [{ type: 'address' }] as const; // recipient
+export const PROOF_LAYOUT = [{ type: 'address' }, { type: 'uint8' }, { type: 'bytes32' }, { type: 'string' }] as const; // user, venueType, intentHash, summary
+
 /**
- * Estimate spend from plan actions (best effort)
- * Returns spend in wei and whether it could be determined
+ * Decode an action payload, unwrapping the session-mode envelope first
+ * A direct payload never decodes as (uint256, bytes): its second word is an address, not a 0x40 offset
+ */
+export async function decodeActionData(layout: readonly { type: string }[], data: string): Promise<readonly any[]> {
+  const { decodeAbiParameters } = await import('viem');
+  try {
+    const [, innerData] = decodeAbiParameters(
+      [{ type: 'uint256' }, { type: 'bytes' }],
+      data as `0x${string}`
+    );
+    return decodeAbiParameters(layout as any, innerData);
+  } catch {
+    return decodeAbiParameters(layout as any, data as `0x${string}`);
+  }
+}
+
+/**
+ * Estimate USD spend from plan actions
+ *
+ * Spend is value leaving the user's wallet: the plan's ETH value plus every PULL.
+ * Later actions that consume balance the router already holds (pulled, wrapped
+ * or swapped to the router earlier in the plan) are not charged again; any
+ * shortfall is charged conservatively. Each token is valued with its real
+ * decimals and the price oracle.
  */
 export async function estimatePlanSpend(plan: {
   actions: Array<{ actionType: number; adapter: string; data: string }>;
   value?: string;
 }): Promise<PlanSpendEstimate> {
-  let totalSpendWei = BigInt(plan.value || '0x0');
-  let determinable = true;
+  const { formatUnits, parseUnits } = await import('viem');
+  const { getTokenMetadata, NATIVE_ETH } = await import('../services/tokenMetadata');
+  const { getPrice } = await import('../services/prices');
+  const { lowestConfidence } = await import('../services/priceOracle');
+  const { EXECUTION_ROUTER_ADDRESS, WETH_ADDRESS_SEPOLIA } = await import('../config');
+
+  const router = EXECUTION_ROUTER_ADDRESS?.toLowerCase();
+  const breakdown: PlanSpendLine[] = [];
+  const routerBalances = new Map<string, bigint>();
   let instrumentType: 'swap' | 'perp' | 'defi' | 'event' | undefined;
 
-  // Decode actions to estimate spend
-  const { decodeAbiParameters } = await import('viem');
+  const undetermined = (reason: string): PlanSpendEstimate => ({
+    spendWei: 0n,
+    breakdown,
+    determinable: false,
+    reason,
+    instrumentType,
+  });
 
-  for (const action of plan.actions) {
-    try {
+  const charge = async (token: string, amountUnits: bigint, actionIndex?: number, actionType?: number) => {
+    if (amountUnits <= 0n) return;
+    const metadata = await getTokenMetadata(token);
+    if (!metadata.priceSymbol) {
+      throw new Error(`No price feed for ${metadata.symbol} (${token})`);
+    }
+    const price = await getPrice(metadata.priceSymbol);
+    const amount = Number(formatUnits(amountUnits, metadata.decimals));
+    breakdown.push({
+      actionIndex,
+      actionType,
+      token: metadata.address,
+      symbol: metadata.symbol,
+      decimals: metadata.decimals,
+      amountUnits: amountUnits.toString(),
+      amount,
+      priceUsd: price.priceUsd,
+      priceSource: price.source,
+      priceConfidence: price.confidence,
+      usd: amount * price.priceUsd,
+    });
+  };
+
+  const credit = (token: string, amountUnits: bigint) => {
+    const key = token.toLowerCase();
+    routerBalances.set(key, (routerBalances.get(key) || 0n) + amountUnits);
+  };
+
+  // Spend router-held balance first; whatever is not covered is charged
+  const consume = async (token: string, amountUnits: bigint, actionIndex: number, actionType: number) => {
+    const key = token.toLowerCase();
+    const held = routerBalances.get(key) || 0n;
+    const covered = held < amountUnits ? held : amountUnits;
+    routerBalances.set(key, held - covered);
+    await charge(key, amountUnits - covered, actionIndex, actionType);
+  };
+
+  const value = BigInt(plan.value || '0x0');
+
+  try {
+    await charge(NATIVE_ETH, value);
+
+    for (const [i, action] of plan.actions.entries()) {
       if (action.actionType === 0) {
-        // SWAP action
+        // SWAP: tokenIn from router balance; output stays in the router if sent there
         instrumentType = 'swap';
-        try {
-          // Try to decode as direct swap (tokenIn, tokenOut, fee, amountIn, ...)
-          const decoded = decodeAbiParameters(
-            [
-              { type: 'address' },
-              { type: 'address' },
-              { type: 'uint24' },
-              { type: 'uint256' },
-              { type: 'uint256' },
-              { type: 'address' },
-              { type: 'uint256' },
-            ],
-            action.data as `0x${string}`
-          );
-          const amountIn = decoded[3];
-          // For swaps, spend is the amountIn (in token units, not ETH)
-          // We can't convert to USD without price oracle, so mark as determinable but don't add to totalSpendWei
-          // For now, we'll use a conservative estimate: assume 1 ETH max per swap
-          totalSpendWei += BigInt(parseUnits('1', 18)); // Conservative: 1 ETH per swap
-        } catch {
-          // Session mode: wrapped as (maxSpendUnits, innerData)
-          try {
-            const decoded = decodeAbiParameters(
-              [{ type: 'uint256' }, { type: 'bytes' }],
-              action.data as `0x${string}`
-            );
-            const maxSpendUnits = decoded[0];
-            // maxSpendUnits is in token units (e.g., USDC has 6 decimals)
-            // Convert to wei-equivalent for comparison (rough estimate: 1 unit = 1e-12 ETH)
-            // Actually, for policy, we should compare against session's maxSpend which is in wei
-            // So we'll use maxSpendUnits directly as a conservative estimate
-            totalSpendWei += maxSpendUnits * BigInt(1e12); // Rough conversion (assumes 6-decimal token)
-          } catch {
-            determinable = false;
-          }
+        const [tokenIn, tokenOut, , amountIn, amountOutMin, recipient] = await decodeActionData(SWAP_LAYOUT, action.data);
+        await consume(tokenIn, amountIn, i, action.actionType);
+        if (router && recipient.toLowerCase() === router) {
+          credit(tokenOut, amountOutMin);
         }
-      } else if (action.actionType === 2) {
-        // PULL action (token transfer)
-        try {
-          const decoded = decodeAbiParameters(
-            [{ type: 'address' }, { type: 'address' }, { type: 'uint256' }],
-            action.data as `0x${string}`
-          );
-          const amount = decoded[2];
-          // PULL doesn't spend ETH, but transfers tokens
-          // For policy, we'll count it conservatively
-          totalSpendWei += amount * BigInt(1e12); // Rough conversion
-        } catch {
-          determinable = false;
+      } else if (action.actionType === 1) {
+        // WRAP: converts the plan's ETH value, already charged above
+        const [recipient] = await decodeActionData(WRAP_LAYOUT, action.data);
+        if (router && WETH_ADDRESS_SEPOLIA && recipient.toLowerCase() === router) {
+          credit(WETH_ADDRESS_SEPOLIA, value);
         }
+      } else if (action.actionType === 2) {
+        // PULL: tokens leave the user's wallet for the router
+        const [token, , amount] = await decodeActionData(PULL_LAYOUT, action.data);
+        await charge(token, amount, i, action.actionType);
+        credit(token, amount);
       } else if (action.actionType === 3) {
-        // LEND_SUPPLY action (Aave supply)
+        // LEND_SUPPLY: supplies router-held balance
         instrumentType = 'defi';
-        try {
-          // Try to decode as direct supply (asset, vault, amount, onBehalfOf)
-          const decoded = decodeAbiParameters(
-            [
-              { type: 'address' },
-              { type: 'address' },
-              { type: 'uint256' },
-              { type: 'address' },
-            ],
-            action.data as `0x${string}`
-          );
-          const amount = decoded[2];
-          // For Aave supply, spend is the amount supplied (in token units)
-          // Convert to wei-equivalent for policy comparison
-          // Assume 6 decimals for USDC (most common)
-          totalSpendWei += amount * BigInt(1e12); // Convert 6-decimal token to wei-equivalent
-        } catch {
-          // Session mode: wrapped as (maxSpendUnits, innerData)
-          try {
-            const decoded = decodeAbiParameters(
-              [{ type: 'uint256' }, { type: 'bytes' }],
-              action.data as `0x${string}`
-            );
-            const maxSpendUnits = decoded[0];
-            // maxSpendUnits is in token units (e.g., USDC has 6 decimals)
-            totalSpendWei += maxSpendUnits * BigInt(1e12); // Rough conversion (assumes 6-decimal token)
-          } catch {
-            determinable = false;
-          }
-        }
+        const [asset, , amount] = await decodeActionData(LEND_LAYOUT, action.data);
+        await consume(asset, amount, i, action.actionType);
       } else if (action.actionType === 6) {
-        // PROOF action (perps/events)
-        instrumentType = 'perp'; // Default to perp, could be event
-        // PROOF actions don't spend tokens directly, they're proof-of-execution
-        // For policy, we'll use a conservative estimate
-        totalSpendWei += BigInt(parseUnits('0.1', 18)); // Conservative: 0.1 ETH equivalent
+        // PROOF (perps/events): records the intent on-chain, moves no assets
+        instrumentType = instrumentType || 'perp';
       } else {
-        // Unknown action type
-        determinable = false;
+        return undetermined(`Unsupported action type ${action.actionType} at index ${i}`);
       }
-    } catch (error) {
-      determinable = false;
     }
+  } catch (error: any) {
+    return undetermined(error.message);
   }
 
+  const spendUsd = breakdown.reduce((sum, line) => sum + line.usd, 0);
+  const ethPrice = await getPrice('ETH');
+  const ethPriceUsd = ethPrice.priceUsd;
+
   return {
-    spendWei: totalSpendWei,
-    determinable,
+    spendWei: parseUnits((spendUsd / ethPriceUsd).toFixed(18), 18),
+    spendUsd,
+    ethPriceUsd,
+    priceConfidence: lowestConfidence([ethPrice.confidence, ...breakdown.map(line => line.priceConfidence)]),
+    breakdown,
+    determinable: true,
     instrumentType,
   };
 }
 
+/**
+ * Convert a wei-denominated session cap to USD at the given ETH price
+ */
+function weiToUsd(wei: bigint, ethPriceUsd: number): number {
+  return (Number(wei) / 1e18) * ethPriceUsd;
+}
+
+/**
+ * Adapters a relayer session may call (mirrors the allowlist passed to createSession)
+ */
+export async function getSessionAdapterAllowlist(): Promise<Set<string>> {
+  const config = await import('../config');
+  const adapters = [
+    config.UNISWAP_V3_ADAPTER_ADDRESS,
+    config.WETH_WRAP_ADAPTER_ADDRESS,
+    config.MOCK_SWAP_ADAPTER_ADDRESS,
+    config.PROOF_ADAPTER_ADDRESS,
+    config.ERC20_PULL_ADAPTER_ADDRESS,
+    config.DEMO_LEND_ADAPTER_ADDRESS,
+    config.AAVE_ADAPTER_ADDRESS,
+  ];
+  return new Set(adapters.filter(Boolean).map(address => address!.toLowerCase()));
+}
+
+/**
+ * Read a session from ExecutionRouter.sessions (null if the RPC is unavailable)
+ */
+export async function readSessionStatusFromChain(sessionId: string): Promise<SessionStatus | null> {
+  try {
+    const { ETH_TESTNET_RPC_URL, EXECUTION_ROUTER_ADDRESS } = await import('../config');
+    if (!ETH_TESTNET_RPC_URL || !EXECUTION_ROUTER_ADDRESS) {
+      return null;
+    }
+
+    const { createPublicClient, http } = await import('viem');
+    const { sepolia } = await import('viem/chains');
+    const publicClient = createPublicClient({
+      chain: sepolia,
+      transport: http(ETH_TESTNET_RPC_URL),
+    });
+
+    const normalizedSessionId = sessionId.startsWith('0x') ? sessionId : `0x${sessionId}`;
+    const sessionAbi = [
+      {
+        name: 'sessions',
+        type: 'function',
+        stateMutability: 'view',
+        inputs: [{ name: '', type: 'bytes32' }],
+        outputs: [
+          { name: 'owner', type: 'address' },
+          { name: 'executor', type: 'address' },
+          { name: 'expiresAt', type: 'uint64' },
+          { name: 'maxSpend', type: 'uint256' },
+          { name: 'spent', type: 'uint256' },
+          { name: 'active', type: 'bool' },
+        ],
+      },
+    ] as const;
+
+    const sessionResult = await Promise.race([
+      publicClient.readContract({
+        address: EXECUTION_ROUTER_ADDRESS as `0x${string}`,
+        abi: sessionAbi,
+        functionName: 'sessions',
+        args: [normalizedSessionId as `0x${string}`],
+      }),
+      new Promise((_, reject) => setTimeout(() => reject(new Error('timeout')), 2000)),
+    ]) as any;
+
+    const [owner, executor, expiresAt, maxSpend, spent, active] = sessionResult;
+
+    const now = BigInt(Math.floor(Date.now() / 1000));
+    let status: SessionStatus['status'] = 'not_created';
+    if (active) {
+      status = expiresAt > now ? 'active' : 'expired';
+    } else if (owner !== '0x0000000000000000000000000000000000000000') {
+      status = 'revoked';
+    }
+
+    return {
+      active: status === 'active',
+      owner,
+      executor,
+      expiresAt,
+      maxSpend,
+      spent,
+      status,
+    };
+  } catch (error) {
+    return null;
+  }
+}
+
+// ============================================
+// Policy documents
+// ============================================
+
+/**
+ * Policy document attached to a session (stored in the ledger's session_policies)
+ * Every rule is optional; a session without a document only gets the base checks
+ */
+export interface SessionPolicyDocument {
+  maxPerTxUsd?: number;                  // Total plan spend per execution
+  dailyLimitUsd?: number;                // Rolling 24h spend across executions
+  allowedTokens?: string[];              // Symbols the session may spend (e.g. ['USDC', 'WETH'])
+  tokenCaps?: Record<string, { maxPerTxUsd?: number; dailyLimitUsd?: number }>; // Keyed by symbol
+  allowedAdapters?: string[];            // Subset of the server adapter allowlist
+  allowedVenues?: string[];              // Venue ids, see adapterVenue()
+  perps?: { maxLeverage?: number; allowedMarkets?: string[] };
+  events?: { allowedMarkets?: string[] };
+  maxTradesPerHour?: number;
+  lossCooldown?: { cooldownSeconds: number; minLossUsd?: number }; // Pause after a losing close
+}
+
+// Signed policy updates older than this are rejected
+export const SESSION_POLICY_SIGNATURE_MAX_AGE_SECONDS = 5 * 60;
+
+// Nonces seen within the signature window; a signed update is accepted once
+const usedPolicyNonces = new Map<string, number>();
+
+/**
+ * Message the session owner signs (EIP-191) to attach, replace or remove a policy
+ * The policy is bound by the SHA-256 of its JSON as sent in the request body;
+ * the nonce and timestamp make each signature single use
+ */
+export function buildSessionPolicyMessage(
+  sessionId: string,
+  userAddress: string,
+  policy: SessionPolicyDocument | null,
+  nonce: string,
+  issuedAt: number
+): string {
+  const digest = createHash('sha256').update(JSON.stringify(policy ?? null)).digest('hex');
+  return [
+    'Blossom session policy update',
+    `Session: ${sessionId.toLowerCase()}`,
+    `Owner: ${userAddress.toLowerCase()}`,
+    `Policy SHA-256: ${digest}`,
+    `Nonce: ${nonce}`,
+    `Issued at: ${issuedAt}`,
+  ].join('\n');
+}
+
+/**
+ * Check a signed policy update; returns the reason it is rejected, or null
+ * A valid update consumes its nonce
+ */
+export async function verifySessionPolicySignature(
+  params: {
+    sessionId: string;
+    userAddress: string;
+    policy: SessionPolicyDocument | null;
+    nonce: string;
+    issuedAt: number;
+    signature: string;
+  },
+  now: number = Math.floor(Date.now() / 1000)
+): Promise<string | null> {
+  if (!Number.isInteger(params.issuedAt) || Math.abs(now - params.issuedAt) > SESSION_POLICY_SIGNATURE_MAX_AGE_SECONDS) {
+    return 'Signature expired; sign a fresh policy update';
+  }
+  if (typeof params.nonce !== 'string' || !/^[0-9a-zA-Z-]{8,64}$/.test(params.nonce)) {
+    return 'nonce must be 8-64 alphanumeric characters';
+  }
+  for (const [nonce, seenAt] of usedPolicyNonces) {
+    if (now - seenAt > 2 * SESSION_POLICY_SIGNATURE_MAX_AGE_SECONDS) usedPolicyNonces.delete(nonce);
+  }
+  if (usedPolicyNonces.has(params.nonce)) {
+    return 'Nonce already used';
+  }
+
+  const { verifyMessage } = await import('viem');
+  try {
+    const valid = await verifyMessage({
+      address: params.userAddress as `0x${string}`,
+      message: buildSessionPolicyMessage(params.sessionId, params.userAddress, params.policy, params.nonce, params.issuedAt),
+      signature: params.signature as `0x${string}`,
+    });
+    if (!valid) return 'Signature does not match userAddress';
+  } catch {
+    return 'Invalid signature';
+  }
+
+  usedPolicyNonces.set(params.nonce, now);
+  return null;
+}
+
+const POLICY_KEYS = new Set([
+  'maxPerTxUsd', 'dailyLimitUsd', 'allowedTokens', 'tokenCaps', 'allowedAdapters',
+  'allowedVenues', 'perps', 'events', 'maxTradesPerHour', 'lossCooldown',
+]);
+
+/**
+ * Validate a policy document; returns a list of problems (empty when valid)
+ */
+export function validateSessionPolicyDocument(doc: any): string[] {
+  const errors: string[] = [];
+  if (!doc || typeof doc !== 'object' || Array.isArray(doc)) {
+    return ['Policy must be an object'];
+  }
+
+  const positive = (value: any, path: string) => {
+    if (value !== undefined && !(typeof value === 'number' && value > 0)) {
+      errors.push(`${path} must be a positive number`);
+    }
+  };
+  const stringList = (value: any, path: string) => {
+    if (value !== undefined && !(Array.isArray(value) && value.every(v => typeof v === 'string'))) {
+      errors.push(`${path} must be an array of strings`);
+    }
+  };
+
+  for (const key of Object.keys(doc)) {
+    if (!POLICY_KEYS.has(key)) errors.push(`Unknown policy field: ${key}`);
+  }
+  positive(doc.maxPerTxUsd, 'maxPerTxUsd');
+  positive(doc.dailyLimitUsd, 'dailyLimitUsd');
+  positive(doc.maxTradesPerHour, 'maxTradesPerHour');
+  stringList(doc.allowedTokens, 'allowedTokens');
+  stringList(doc.allowedAdapters, 'allowedAdapters');
+  stringList(doc.allowedVenues, 'allowedVenues');
+  for (const [symbol, cap] of Object.entries(doc.tokenCaps || {})) {
+    positive((cap as any)?.maxPerTxUsd, `tokenCaps.${symbol}.maxPerTxUsd`);
+    positive((cap as any)?.dailyLimitUsd, `tokenCaps.${symbol}.dailyLimitUsd`);
+  }
+  positive(doc.perps?.maxLeverage, 'perps.maxLeverage');
+  stringList(doc.perps?.allowedMarkets, 'perps.allowedMarkets');
+  stringList(doc.events?.allowedMarkets, 'events.allowedMarkets');
+  if (doc.lossCooldown !== undefined) {
+    positive(doc.lossCooldown?.cooldownSeconds, 'lossCooldown.cooldownSeconds');
+    if (doc.lossCooldown?.minLossUsd !== undefined && !(doc.lossCooldown.minLossUsd >= 0)) {
+      errors.push('lossCooldown.minLossUsd must be a non-negative number');
+    }
+  }
+
+  return errors;
+}
+
+export interface PlanFacts {
+  adapters: string[];
+  venues: string[];
+  tokenSpendUsd: Record<string, number>;                       // By symbol, from the spend estimate
+  perps: Array<{ market?: string; side?: string; leverage?: number }>;
+  events: Array<{ marketId?: string }>;
+}
+
+/**
+ * Venue id for an allowlisted adapter address
+ */
+async function adapterVenue(adapter: string): Promise<string> {
+  const config = await import('../config');
+  const venues: Array<[string | undefined, string]> = [
+    [config.MOCK_SWAP_ADAPTER_ADDRESS, 'mock_swap'],
+    [config.UNISWAP_V3_ADAPTER_ADDRESS, 'uniswap_v3'],
+    [config.UNISWAP_ADAPTER_ADDRESS, 'demo_dex'],
+    [config.WETH_WRAP_ADAPTER_ADDRESS, 'weth_wrap'],
+    [config.ERC20_PULL_ADAPTER_ADDRESS, 'pull'],
+    [config.DEMO_LEND_ADAPTER_ADDRESS, 'demo_vault'],
+    [config.AAVE_ADAPTER_ADDRESS, 'aave'],
+    [config.DEMO_PERP_ADAPTER_ADDRESS, 'demo_perp'],
+    [config.PROOF_ADAPTER_ADDRESS, 'proof'],
+  ];
+  const match = venues.find(([address]) => address?.toLowerCase() === adapter.toLowerCase());
+  return match ? match[1] : 'unknown';
+}
+
+function normalizeMarket(market: string): string {
+  return market.toUpperCase().replace(/[-/]?USDC?$/, '');
+}
+
+/**
+ * Extract what a plan does: adapters, venues, per-token spend, perp and event parameters
+ * Perp/event parameters come from the PROOF summary ("PERP:ETH-USD-LONG-5x-3%", "EVENT:<id>-YES-5USD")
+ */
+export async function describePlan(
+  plan: { actions: Array<{ actionType: number; adapter: string; data: string }> },
+  spendEstimate: PlanSpendEstimate
+): Promise<PlanFacts> {
+  const facts: PlanFacts = { adapters: [], venues: [], tokenSpendUsd: {}, perps: [], events: [] };
+
+  for (const line of spendEstimate.breakdown) {
+    const symbol = line.symbol.toUpperCase();
+    facts.tokenSpendUsd[symbol] = (facts.tokenSpendUsd[symbol] || 0) + line.usd;
+  }
+
+  for (const action of plan.actions) {
+    const adapter = action.adapter.toLowerCase();
+    facts.adapters.push(adapter);
+
+    if (action.actionType !== 6) {
+      // PULL and WRAP are plumbing, not venues
+      if (action.actionType !== 1 && action.actionType !== 2) {
+        facts.venues.push(await adapterVenue(adapter));
+      }
+      continue;
+    }
+
+    let summary = '';
+    let venueType = 0;
+    try {
+      const decoded = await decodeActionData(PROOF_LAYOUT, action.data);
+      venueType = Number(decoded[1]);
+      summary = decoded[3];
+    } catch {
+      // Leave parameters unknown; rules that need them deny
+    }
+
+    if (venueType === 2 || summary.startsWith('EVENT:')) {
+      facts.venues.push('events');
+      const match = summary.match(/^EVENT:(.+)-([^-]+)-([\d.]+)USD$/);
+      facts.events.push({ marketId: match?.[1] });
+    } else {
+      facts.venues.push('perps');
+      const match = summary.match(/^PERP:(.+)-(LONG|SHORT)-(\d+(?:\.\d+)?)x/i);
+      facts.perps.push({
+        market: match ? normalizeMarket(match[1]) : undefined,
+        side: match?.[2].toLowerCase(),
+        leverage: match ? parseFloat(match[3]) : undefined,
+      });
+    }
+  }
+
+  return facts;
+}
+
+export interface PolicyHistory {
+  usage: Array<{ spend_usd: number; token_spend_json?: string; created_at: number }>; // Last 24h
+  losses: Array<{ closed_at?: number; pnl?: string; status: string; margin_units?: string }>;
+}
+
+const DAY_SECONDS = 24 * 60 * 60;
+
+/**
+ * Evaluate a policy document against a plan and the session's recent history
+ */
+export function evaluatePolicyDocument(
+  policy: SessionPolicyDocument,
+  facts: PlanFacts,
+  spendUsd: number,
+  history: PolicyHistory,
+  now: number = Math.floor(Date.now() / 1000)
+): SessionPolicyResult {
+  const deny = (code: string, message: string, details?: any): SessionPolicyResult => ({
+    allowed: false,
+    code,
+    message,
+    details,
+  });
+
+  if (policy.allowedAdapters) {
+    const allowed = new Set(policy.allowedAdapters.map(a => a.toLowerCase()));
+    const blocked = facts.adapters.find(a => !allowed.has(a));
+    if (blocked) {
+      return deny('POLICY_ADAPTER_NOT_ALLOWED', `Adapter ${blocked} is not allowed by the session policy`, { adapter: blocked });
+    }
+  }
+
+  if (policy.allowedVenues) {
+    const allowed = new Set(policy.allowedVenues.map(v => v.toLowerCase()));
+    const blocked = facts.venues.find(v => !allowed.has(v));
+    if (blocked) {
+      return deny('POLICY_VENUE_NOT_ALLOWED', `Venue ${blocked} is not allowed by the session policy`, { venue: blocked });
+    }
+  }
+
+  if (policy.allowedTokens) {
+    const allowed = new Set(policy.allowedTokens.map(t => t.toUpperCase()));
+    const blocked = Object.keys(facts.tokenSpendUsd).find(symbol => !allowed.has(symbol));
+    if (blocked) {
+      return deny('POLICY_TOKEN_NOT_ALLOWED', `Spending ${blocked} is not allowed by the session policy`, { token: blocked });
+    }
+  }
+
+  if (policy.maxPerTxUsd !== undefined && spendUsd > policy.maxPerTxUsd) {
+    return deny('POLICY_TX_LIMIT_EXCEEDED', `Plan spend ($${spendUsd.toFixed(2)}) exceeds the per-execution limit ($${policy.maxPerTxUsd})`, {
+      spendUsd,
+      limitUsd: policy.maxPerTxUsd,
+    });
+  }
+
+  const tokenCaps = Object.fromEntries(
+    Object.entries(policy.tokenCaps || {}).map(([symbol, cap]) => [symbol.toUpperCase(), cap])
+  );
+  for (const [symbol, usd] of Object.entries(facts.tokenSpendUsd)) {
+    const cap = tokenCaps[symbol];
+    if (cap?.maxPerTxUsd !== undefined && usd > cap.maxPerTxUsd) {
+      return deny('POLICY_TOKEN_CAP_EXCEEDED', `${symbol} spend ($${usd.toFixed(2)}) exceeds its per-execution cap ($${cap.maxPerTxUsd})`, {
+        token: symbol,
+        spendUsd: usd,
+        limitUsd: cap.maxPerTxUsd,
+      });
+    }
+  }
+
+  const dayUsage = history.usage.filter(u => u.created_at > now - DAY_SECONDS);
+  if (policy.dailyLimitUsd !== undefined) {
+    const spentUsd = dayUsage.reduce((sum, u) => sum + u.spend_usd, 0);
+    if (spentUsd + spendUsd > policy.dailyLimitUsd) {
+      return deny('POLICY_DAILY_LIMIT_EXCEEDED', `Plan would bring 24h spend to $${(spentUsd + spendUsd).toFixed(2)} (limit $${policy.dailyLimitUsd})`, {
+        spendUsd,
+        spent24hUsd: spentUsd,
+        limitUsd: policy.dailyLimitUsd,
+      });
+    }
+  }
+
+  for (const [symbol, usd] of Object.entries(facts.tokenSpendUsd)) {
+    const cap = tokenCaps[symbol];
+    if (cap?.dailyLimitUsd === undefined) continue;
+    const spentUsd = dayUsage.reduce((sum, u) => {
+      const byToken = u.token_spend_json ? JSON.parse(u.token_spend_json) : {};
+      return sum + (byToken[symbol] || 0);
+    }, 0);
+    if (spentUsd + usd > cap.dailyLimitUsd) {
+      return deny('POLICY_TOKEN_DAILY_LIMIT_EXCEEDED', `Plan would bring 24h ${symbol} spend to $${(spentUsd + usd).toFixed(2)} (limit $${cap.dailyLimitUsd})`, {
+        token: symbol,
+        spendUsd: usd,
+        spent24hUsd: spentUsd,
+        limitUsd: cap.dailyLimitUsd,
+      });
+    }
+  }
+
+  for (const perp of facts.perps) {
+    if (policy.perps?.allowedMarkets || policy.perps?.maxLeverage !== undefined) {
+      if (!perp.market || perp.leverage === undefined) {
+        return deny('POLICY_PERP_PARAMS_UNKNOWN', 'Cannot read perp market and leverage from the plan');
+      }
+    }
+    if (policy.perps?.allowedMarkets) {
+      const allowed = new Set(policy.perps.allowedMarkets.map(normalizeMarket));
+      if (!allowed.has(perp.market!)) {
+        return deny('POLICY_MARKET_NOT_ALLOWED', `Perp market ${perp.market} is not allowed by the session policy`, {
+          market: perp.market,
+          allowedMarkets: policy.perps.allowedMarkets,
+        });
+      }
+    }
+    if (policy.perps?.maxLeverage !== undefined && perp.leverage! > policy.perps.maxLeverage) {
+      return deny('POLICY_LEVERAGE_EXCEEDED', `Leverage ${perp.leverage}x exceeds the session maximum (${policy.perps.maxLeverage}x)`, {
+        leverage: perp.leverage,
+        maxLeverage: policy.perps.maxLeverage,
+      });
+    }
+  }
+
+  if (policy.events?.allowedMarkets) {
+    const allowed = new Set(policy.events.allowedMarkets.map(m => m.toLowerCase()));
+    const blocked = facts.events.find(e => !e.marketId || !allowed.has(e.marketId.toLowerCase()));
+    if (blocked) {
+      return deny('POLICY_MARKET_NOT_ALLOWED', `Event market ${blocked.marketId || '(unknown)'} is not allowed by the session policy`, {
+        market: blocked.marketId,
+        allowedMarkets: policy.events.allowedMarkets,
+      });
+    }
+  }
+
+  if (policy.maxTradesPerHour !== undefined) {
+    const lastHour = history.usage.filter(u => u.created_at > now - 3600);
+    if (lastHour.length >= policy.maxTradesPerHour) {
+      const oldest = Math.min(...lastHour.map(u => u.created_at));
+      return deny('POLICY_RATE_LIMITED', `Session allows ${policy.maxTradesPerHour} executions per hour`, {
+        tradesLastHour: lastHour.length,
+        limit: policy.maxTradesPerHour,
+        retryAfterSeconds: oldest + 3600 - now,
+      });
+    }
+  }
+
+  if (policy.lossCooldown) {
+    const { cooldownSeconds, minLossUsd = 0 } = policy.lossCooldown;
+    const loss = history.losses.find(position => {
+      if ((position.closed_at || 0) <= now - cooldownSeconds) return false;
+      // PnL and margin are USD with 6 decimals; a liquidation loses the margin
+      const lossUnits = position.status === 'liquidated'
+        ? Math.abs(Number(position.pnl || position.margin_units || 0))
+        : -Number(position.pnl || 0);
+      return lossUnits / 1e6 >= minLossUsd;
+    });
+    if (loss) {
+      return deny('POLICY_LOSS_COOLDOWN', `Trading is paused for ${cooldownSeconds}s after a losing close`, {
+        closedAt: loss.closed_at,
+        retryAfterSeconds: loss.closed_at! + cooldownSeconds - now,
+      });
+    }
+  }
+
+  return { allowed: true };
+}
+
+/**
+ * Load a session's policy document and recent history from the ledger
+ * Returns null when the session has no document
+ */
+export async function loadSessionPolicyContext(
+  sessionId: string,
+  userAddress: string
+): Promise<{ policy: SessionPolicyDocument; history: PolicyHistory } | null> {
+  const { getSessionPolicy, getSessionPolicyUsageSince, getLosingPositionsSince } = await import('../../execution-ledger/db');
+  const record = getSessionPolicy(sessionId);
+  if (!record) {
+    return null;
+  }
+
+  const policy = JSON.parse(record.policy_json) as SessionPolicyDocument;
+  const now = Math.floor(Date.now() / 1000);
+  return {
+    policy,
+    history: {
+      usage: getSessionPolicyUsageSince(sessionId, now - DAY_SECONDS),
+      losses: policy.lossCooldown
+        ? getLosingPositionsSince(userAddress, now - policy.lossCooldown.cooldownSeconds)
+        : [],
+    },
+  };
+}
+
+/**
+ * Reserve a relay's spend and trade against the session's rolling limits
+ * Re-evaluates the policy document with the ledger's usage and records the
+ * usage in one ledger transaction; release the reservation if the relay is
+ * not sent. Sessions without a document are recorded unconditionally.
+ */
+export async function reserveSessionPolicyUsage(params: {
+  sessionId: string;
+  userAddress: string;
+  plan: { actions: Array<{ actionType: number; adapter: string; data: string }> };
+  spendEstimate: PlanSpendEstimate;
+  instrumentType?: string;
+}): Promise<{ result: SessionPolicyResult; usageId?: string }> {
+  const db = await import('../../execution-ledger/db');
+  const facts = await describePlan(params.plan, params.spendEstimate);
+  const spendUsd = params.spendEstimate.spendUsd || 0;
+  const now = Math.floor(Date.now() / 1000);
+
+  return db.reserveSessionPolicyUsage(
+    {
+      sessionId: params.sessionId,
+      userAddress: params.userAddress,
+      spendUsd,
+      tokenSpend: facts.tokenSpendUsd,
+      instrumentType: params.instrumentType,
+      since: now - DAY_SECONDS,
+    },
+    (usage): SessionPolicyResult => {
+      const record = db.getSessionPolicy(params.sessionId);
+      if (!record) {
+        return { allowed: true };
+      }
+      const policy = JSON.parse(record.policy_json) as SessionPolicyDocument;
+      const losses = policy.lossCooldown
+        ? db.getLosingPositionsSince(params.userAddress, now - policy.lossCooldown.cooldownSeconds)
+        : [];
+      return evaluatePolicyDocument(policy, facts, spendUsd, { usage, losses }, now);
+    }
+  );
+}
+
 /**
  * Evaluate SessionPolicy for a relayed execution
  */
@@ -234,12 +842,29 @@ export async function evaluateSessionPolicy(
       code: 'POLICY_UNDETERMINED_SPEND',
       message: 'Cannot determine plan spend from actions. Policy cannot be evaluated.',
       details: {
+        reason: spendEstimate.reason,
         actionCount: plan.actions.length,
         actionTypes: plan.actions.map(a => a.actionType),
       },
     };
   }
 
+  // Spend is only as good as the prices behind it
+  const { PRICE_POLICY_MIN_CONFIDENCE } = await import('../config');
+  const { meetsConfidence } = await import('../services/priceOracle');
+  if (spendEstimate.priceConfidence && !meetsConfidence(spendEstimate.priceConfidence, PRICE_POLICY_MIN_CONFIDENCE)) {
+    return {
+      allowed: false,
+      code: 'POLICY_PRICE_UNRELIABLE',
+      message: `Prices used to value this plan are ${spendEstimate.priceConfidence} confidence (policy requires ${PRICE_POLICY_MIN_CONFIDENCE}). Try again shortly.`,
+      details: {
+        priceConfidence: spendEstimate.priceConfidence,
+        requiredConfidence: PRICE_POLICY_MIN_CONFIDENCE,
+        breakdown: spendEstimate.breakdown,
+      },
+    };
+  }
+
   // Check if spend exceeds session's maxSpend
   // DEV-ONLY: Allow policyOverride for testing (only in validateOnly mode, checked by caller)
   let effectiveMaxSpend: bigint;
@@ -255,22 +880,50 @@ export async function evaluateSessionPolicy(
     effectiveSpent = sessionStatus.spent;
   }
   
+  // Session caps are stored in wei; enforce them in USD at the current ETH price
   const remainingSpend = effectiveMaxSpend - effectiveSpent;
-  if (spendEstimate.spendWei > remainingSpend) {
+  const remainingUsd = weiToUsd(remainingSpend, spendEstimate.ethPriceUsd!);
+  if (spendEstimate.spendUsd! > remainingUsd) {
     return {
       allowed: false,
       code: 'POLICY_EXCEEDED',
-      message: `Plan spend (${spendEstimate.spendWei.toString()}) exceeds remaining session spend limit (${remainingSpend.toString()})`,
+      message: `Plan spend ($${spendEstimate.spendUsd!.toFixed(2)}) exceeds remaining session spend limit ($${remainingUsd.toFixed(2)})`,
       details: {
         spendAttempted: spendEstimate.spendWei.toString(),
+        spendUsd: spendEstimate.spendUsd,
         maxSpend: effectiveMaxSpend.toString(),
+        maxSpendUsd: weiToUsd(effectiveMaxSpend, spendEstimate.ethPriceUsd!),
         spent: effectiveSpent.toString(),
         remaining: remainingSpend.toString(),
+        remainingUsd,
+        breakdown: spendEstimate.breakdown,
         ...(policyOverride?.maxSpendUnits ? { policyOverride: true } : {}),
       },
     };
   }
 
+  // Check 4: Session policy document (per-token, per-venue, leverage, rate and loss rules)
+  let policyContext: Awaited<ReturnType<typeof loadSessionPolicyContext>> = null;
+  try {
+    policyContext = await loadSessionPolicyContext(sessionId, userAddress);
+  } catch (error: any) {
+    // Fail closed: the session may have a document we can't see
+    console.warn('[sessionPolicy] Could not load policy document:', error.message);
+    return {
+      allowed: false,
+      code: 'POLICY_UNAVAILABLE',
+      message: 'Session policy could not be loaded. Try again shortly.',
+    };
+  }
+
+  if (policyContext) {
+    const facts = await describePlan(plan, spendEstimate);
+    const documentResult = evaluatePolicyDocument(policyContext.policy, facts, spendEstimate.spendUsd!, policyContext.history);
+    if (!documentResult.allowed) {
+      return documentResult;
+    }
+  }
+
   // All checks passed
   return {
     allowed: true,
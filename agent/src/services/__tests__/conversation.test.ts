@@ -0,0 +1,172 @@
+/**
+ * Conversation Memory Tests
+ * The ledger runs against an in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+});
+
+vi.mock('../../config', () => ({
+  CHAT_HISTORY_MESSAGES: 4,
+  CHAT_SUMMARIZE_AFTER_MESSAGES: 6,
+  CHAT_THREAD_IDLE_SECONDS: 1800,
+}));
+
+import {
+  resolveChatThread,
+  loadConversationContext,
+  recordChatTurn,
+  resolveFollowUp,
+  getUserThread,
+  deleteUserThread,
+  listUserThreads,
+  ANONYMOUS_CHAT_USER,
+  type ChatThread,
+} from '../conversation';
+import { getDatabase, getChatThread } from '../../../execution-ledger/db';
+
+const ETH_LONG = {
+  assistantMessage: 'Opening a 3% risk ETH long.',
+  actions: [{ type: 'perp', action: 'open', market: 'ETH-PERP', side: 'long', riskPct: 3, reasoning: ['trend'] }],
+  executionRequest: { kind: 'perp', chain: 'sepolia', market: 'ETH-PERP', side: 'long', leverage: 5 },
+  executionResults: [{ positionDelta: { type: 'perp', positionId: 'pos-eth-1', side: 'long', sizeUsd: 500 } }],
+  draftId: 'draft-1',
+};
+
+async function newThread(userId: string): Promise<ChatThread> {
+  const thread = await resolveChatThread(userId, { newThread: true });
+  if (!thread) throw new Error('thread not created');
+  return thread;
+}
+
+describe('conversation memory', () => {
+  beforeEach(() => {
+    const db = getDatabase();
+    db.prepare('DELETE FROM chat_messages').run();
+    db.prepare('DELETE FROM chat_threads').run();
+  });
+
+  describe('resolveChatThread', () => {
+    it('continues the latest thread while it is active', async () => {
+      const first = await newThread('user-a');
+      const again = await resolveChatThread('user-a');
+      expect(again?.id).toBe(first.id);
+    });
+
+    it('starts a new thread once the latest one has gone idle', async () => {
+      const first = await newThread('user-a');
+      getDatabase().prepare('UPDATE chat_threads SET updated_at = updated_at - 3600 WHERE id = ?').run(first.id);
+
+      const next = await resolveChatThread('user-a');
+      expect(next?.id).not.toBe(first.id);
+    });
+
+    it('never auto-resumes a thread for anonymous visitors', async () => {
+      const first = await newThread(ANONYMOUS_CHAT_USER);
+      const next = await resolveChatThread(ANONYMOUS_CHAT_USER);
+      expect(next?.id).not.toBe(first.id);
+
+      // Only the visitor holding the threadId continues it
+      const again = await resolveChatThread(ANONYMOUS_CHAT_USER, { threadId: first.id });
+      expect(again?.id).toBe(first.id);
+      expect(await listUserThreads(ANONYMOUS_CHAT_USER)).toEqual([]);
+      expect(await getUserThread(ANONYMOUS_CHAT_USER, first.id)).toBeNull();
+      expect(await deleteUserThread(ANONYMOUS_CHAT_USER, first.id)).toBe(false);
+    });
+
+    it("does not continue another user's thread", async () => {
+      const theirs = await newThread('user-a');
+      const mine = await resolveChatThread('user-b', { threadId: theirs.id });
+      expect(mine?.id).not.toBe(theirs.id);
+      expect(mine?.user_id).toBe('user-b');
+    });
+  });
+
+  describe('loadConversationContext', () => {
+    it('is empty for a new thread', async () => {
+      const thread = await newThread('user-a');
+      const context = await loadConversationContext(thread);
+      expect(context.prompt).toBe('');
+      expect(context.lastProposal).toBeNull();
+    });
+
+    it('includes prior turns and the most recent proposal with its ids', async () => {
+      const thread = await newThread('user-a');
+      await recordChatTurn(thread, 'long ETH with 3% risk, 5x', ETH_LONG);
+
+      const context = await loadConversationContext(getChatThread(thread.id) as ChatThread);
+      expect(context.prompt).toContain('User: long ETH with 3% risk, 5x');
+      expect(context.prompt).toContain('Blossom: Opening a 3% risk ETH long.');
+      expect(context.prompt).toContain('**Most Recent Proposal:**');
+      expect(context.prompt).toContain('pos-eth-1');
+      expect(context.lastProposal?.draftId).toBe('draft-1');
+      expect(context.lastProposalRequest).toBe('long ETH with 3% risk, 5x');
+    });
+
+    it('sets the thread title from the first message', async () => {
+      const thread = await newThread('user-a');
+      await recordChatTurn(thread, 'long ETH with 3% risk, 5x', ETH_LONG);
+      expect(getChatThread(thread.id)?.title).toBe('long ETH with 3% risk, 5x');
+    });
+  });
+
+  describe('summarization', () => {
+    it('folds older messages into the summary and keeps the recent ones verbatim', async () => {
+      const thread = await newThread('user-a');
+      for (let i = 1; i <= 4; i++) {
+        await recordChatTurn(thread, `question ${i}`, { assistantMessage: `answer ${i}`, actions: [] });
+      }
+
+      const row = getChatThread(thread.id) as ChatThread;
+      expect(row.message_count).toBe(8);
+      expect(row.summarized_seq).toBe(4);
+      expect(row.summary).toContain('- User: question 1');
+      expect(row.summary).toContain('- Blossom: answer 2');
+
+      const context = await loadConversationContext(row);
+      expect(context.prompt).toContain('Earlier:');
+      expect(context.prompt).toContain('User: question 3');
+      expect(context.prompt).toContain('Blossom: answer 4');
+    });
+  });
+
+  describe('resolveFollowUp', () => {
+    it('rewrites "same thing but on SOL" against the previous request', async () => {
+      const thread = await newThread('user-a');
+      await recordChatTurn(thread, 'long ETH with 3% risk, 5x', ETH_LONG);
+      const context = await loadConversationContext(getChatThread(thread.id) as ChatThread);
+
+      expect(resolveFollowUp('same thing but on SOL', context)).toBe('long SOL with 3% risk, 5x');
+      expect(resolveFollowUp('Same trade for btc', context)).toBe('long BTC with 3% risk, 5x');
+    });
+
+    it('leaves other messages and empty context alone', async () => {
+      const thread = await newThread('user-a');
+      await recordChatTurn(thread, 'long ETH with 3% risk, 5x', ETH_LONG);
+      const context = await loadConversationContext(getChatThread(thread.id) as ChatThread);
+
+      expect(resolveFollowUp('close half of that', context)).toBe('close half of that');
+      expect(resolveFollowUp('same thing but on SOL', null)).toBe('same thing but on SOL');
+    });
+  });
+
+  describe('thread views', () => {
+    it('returns messages with parsed proposals to the owner only', async () => {
+      const thread = await newThread('user-a');
+      await recordChatTurn(thread, 'long ETH with 3% risk, 5x', ETH_LONG);
+
+      const view = await getUserThread('user-a', thread.id);
+      expect(view?.messages.map(m => m.role)).toEqual(['user', 'assistant']);
+      expect(view?.messages[1].executionRequest).toMatchObject({ kind: 'perp', market: 'ETH-PERP' });
+      expect(view?.messages[1].positions[0].positionId).toBe('pos-eth-1');
+
+      expect(await getUserThread('user-b', thread.id)).toBeNull();
+      expect(await deleteUserThread('user-b', thread.id)).toBe(false);
+      expect(await deleteUserThread('user-a', thread.id)).toBe(true);
+      expect(getChatThread(thread.id)).toBeUndefined();
+    });
+  });
+});
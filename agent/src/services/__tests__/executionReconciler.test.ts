@@ -0,0 +1,185 @@
+/**
+ * Execution Reconciler Tests
+ * Tx receipts and the RPC head are mocked; the ledger runs against an
+ * in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+const mocks = vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  return {
+    lookupTxReceipt: vi.fn(),
+    head: 1000n,
+  };
+});
+
+vi.mock('../../config', () => ({
+  RECONCILER_INTERVAL_MS: 60000,
+  RECONCILER_BATCH_SIZE: 25,
+  RECONCILER_MIN_AGE_SECONDS: 0,
+  RECONCILER_DROP_AFTER_SECONDS: 1800,
+  RECONCILER_REORG_WINDOW_SECONDS: 3600,
+  RECONCILER_EVM_FINALITY_BLOCKS: 64,
+}));
+vi.mock('../../executors/txReceipts', () => ({ lookupTxReceipt: mocks.lookupTxReceipt }));
+vi.mock('../../providers/rpcProvider', () => ({
+  createFailoverPublicClient: () => ({ getBlockNumber: async () => mocks.head }),
+}));
+vi.mock('../../telemetry/logger', () => ({ logEvent: vi.fn() }));
+
+import { runReconcileCycle } from '../executionReconciler';
+import {
+  getDatabase,
+  createExecution,
+  getExecution,
+  updateExecution,
+  listExecutionDiscrepancies,
+  getReconciliationSummary,
+  Chain,
+  Network,
+  ExecutionStatus,
+} from '../../../execution-ledger/db';
+
+const TX_HASH = `0x${'ab'.repeat(32)}`;
+
+function newExecution(params: {
+  status: ExecutionStatus;
+  txHash?: string;
+  blockNumber?: number;
+  ageSeconds?: number;
+  chain?: Chain;
+  network?: Network;
+}) {
+  const execution = createExecution({
+    chain: params.chain ?? 'ethereum',
+    network: params.network ?? 'sepolia',
+    intent: 'swap 10 usdc to weth',
+    action: 'swap',
+    fromAddress: '0x00000000000000000000000000000000000000a1',
+  });
+  updateExecution(execution.id, {
+    status: params.status,
+    txHash: params.txHash,
+    blockNumber: params.blockNumber,
+  });
+  if (params.ageSeconds) {
+    getDatabase().prepare('UPDATE executions SET created_at = created_at - ? WHERE id = ?').run(params.ageSeconds, execution.id);
+  }
+  return execution;
+}
+
+function discrepancyKinds() {
+  return listExecutionDiscrepancies().map(d => d.kind);
+}
+
+describe('runReconcileCycle', () => {
+  beforeEach(() => {
+    const db = getDatabase();
+    db.prepare('DELETE FROM execution_discrepancies').run();
+    db.prepare('DELETE FROM executions').run();
+    mocks.lookupTxReceipt.mockReset();
+    mocks.head = 1000n;
+  });
+
+  it('fills in the receipt for a submitted tx and finalizes it past the finality depth', async () => {
+    const deep = newExecution({ status: 'submitted', txHash: TX_HASH });
+    mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'success', blockNumber: 900, gasUsed: '21000' });
+
+    expect(await runReconcileCycle()).toMatchObject({ checked: 1, updated: 1, discrepancies: 0, errors: 0 });
+    expect(getExecution(deep.id)).toMatchObject({ status: 'finalized', block_number: 900, gas_used: '21000' });
+    expect(discrepancyKinds()).toEqual([]);
+  });
+
+  it('keeps a recent receipt confirmed and uses the Solana finalized flag', async () => {
+    const evm = newExecution({ status: 'pending', txHash: TX_HASH });
+    const solana = newExecution({ status: 'submitted', txHash: 'sig-1', chain: 'solana', network: 'devnet' });
+    mocks.lookupTxReceipt.mockImplementation(async (chain: Chain) =>
+      chain === 'solana'
+        ? { status: 'success', blockNumber: 5, finalized: false }
+        : { status: 'success', blockNumber: 990, gasUsed: '21000' }
+    );
+
+    await runReconcileCycle();
+
+    expect(getExecution(evm.id)).toMatchObject({ status: 'confirmed', block_number: 990 });
+    expect(getExecution(solana.id)).toMatchObject({ status: 'confirmed', block_number: 5 });
+  });
+
+  it('flags a confirmed tx that was re-included at another block as reorged', async () => {
+    const execution = newExecution({ status: 'confirmed', txHash: TX_HASH, blockNumber: 980 });
+    mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'success', blockNumber: 985, gasUsed: '21000' });
+
+    expect(await runReconcileCycle()).toMatchObject({ updated: 1, discrepancies: 1 });
+    expect(getExecution(execution.id)).toMatchObject({ status: 'confirmed', block_number: 985 });
+    expect(listExecutionDiscrepancies()).toMatchObject([
+      { execution_id: execution.id, kind: 'reorged', ledger_status: 'confirmed', chain_status: 'success' },
+    ]);
+  });
+
+  it('fails a confirmed tx whose receipt now reports reverted', async () => {
+    const execution = newExecution({ status: 'confirmed', txHash: TX_HASH, blockNumber: 980 });
+    mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'reverted', blockNumber: 981, error: 'execution reverted' });
+
+    await runReconcileCycle();
+
+    expect(getExecution(execution.id)).toMatchObject({ status: 'failed', error_code: 'TX_REVERTED', block_number: 981 });
+    expect(listExecutionDiscrepancies()).toMatchObject([{ kind: 'reverted_after_confirm', detail: 'execution reverted' }]);
+  });
+
+  it('does not flag a revert the ledger had not yet confirmed', async () => {
+    const execution = newExecution({ status: 'submitted', txHash: TX_HASH });
+    mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'reverted', blockNumber: 981 });
+
+    expect(await runReconcileCycle()).toMatchObject({ updated: 1, discrepancies: 0 });
+    expect(getExecution(execution.id)).toMatchObject({ status: 'failed', error_code: 'TX_REVERTED' });
+  });
+
+  it('moves a confirmed tx whose receipt vanished back to submitted', async () => {
+    const execution = newExecution({ status: 'confirmed', txHash: TX_HASH, blockNumber: 980 });
+    mocks.lookupTxReceipt.mockResolvedValueOnce({ status: 'not_found' });
+
+    await runReconcileCycle();
+
+    expect(getExecution(execution.id)).toMatchObject({ status: 'submitted' });
+    expect(listExecutionDiscrepancies()).toMatchObject([{ kind: 'reorged', chain_status: 'not_found' }]);
+  });
+
+  it('drops an unknown tx only after the drop window', async () => {
+    const recent = newExecution({ status: 'submitted', txHash: TX_HASH, ageSeconds: 600 });
+    const stale = newExecution({ status: 'submitted', txHash: `0x${'cd'.repeat(32)}`, ageSeconds: 2400 });
+    mocks.lookupTxReceipt.mockResolvedValue({ status: 'not_found' });
+
+    expect(await runReconcileCycle()).toMatchObject({ checked: 2, updated: 1, discrepancies: 1 });
+    expect(getExecution(recent.id)).toMatchObject({ status: 'submitted' });
+    expect(getExecution(stale.id)).toMatchObject({ status: 'failed', error_code: 'TX_DROPPED' });
+    expect(discrepancyKinds()).toEqual(['dropped']);
+  });
+
+  it('fails executions that never recorded a tx hash once abandoned', async () => {
+    const fresh = newExecution({ status: 'pending' });
+    const abandoned = newExecution({ status: 'pending', ageSeconds: 2400 });
+
+    await runReconcileCycle();
+
+    expect(mocks.lookupTxReceipt).not.toHaveBeenCalled();
+    expect(getExecution(fresh.id)).toMatchObject({ status: 'pending' });
+    expect(getExecution(abandoned.id)).toMatchObject({ status: 'failed', error_code: 'TX_NOT_SUBMITTED' });
+    expect(getReconciliationSummary()).toEqual({ discrepancies: { missing_tx_hash: 1 }, unresolved: 1 });
+  });
+
+  it('skips networks it cannot see and counts lookup errors without failing the row', async () => {
+    const mainnet = newExecution({ status: 'submitted', txHash: TX_HASH, network: 'mainnet' });
+    const flaky = newExecution({ status: 'submitted', txHash: `0x${'cd'.repeat(32)}` });
+    mocks.lookupTxReceipt.mockRejectedValueOnce(new Error('rpc down'));
+
+    expect(await runReconcileCycle()).toMatchObject({ checked: 2, updated: 0, discrepancies: 0, errors: 1 });
+    expect(mocks.lookupTxReceipt).toHaveBeenCalledTimes(1);
+    expect(getExecution(mainnet.id)).toMatchObject({ status: 'submitted' });
+    expect(getExecution(flaky.id)).toMatchObject({ status: 'submitted' });
+    expect(getExecution(flaky.id)!.reconciled_at).toBeGreaterThan(0);
+
+    // Both rows wait for the next recheck interval
+    expect(await runReconcileCycle()).toMatchObject({ checked: 0 });
+  });
+});
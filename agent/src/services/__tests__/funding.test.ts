@@ -0,0 +1,130 @@
+/**
+ * Perp Funding Tests
+ * Sim accounts stay in memory; ledger positions use an in-memory SQLite database.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+const funding = vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  process.env.SIM_PERSIST_DISABLED = 'true';
+  return { mode: 'fixed', bpsPerHour: 1 };
+});
+
+vi.mock('../../config', () => ({
+  get PERPS_FUNDING_MODE() {
+    return funding.mode;
+  },
+  get PERPS_FUNDING_RATE_BPS_PER_HOUR() {
+    return funding.bpsPerHour;
+  },
+  PERPS_FUNDING_PREMIUM_SOURCE: 'mock',
+  PERPS_FUNDING_INTERVAL_MS: 3600000,
+}));
+vi.mock('../prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: 3000, source: 'median', confidence: 'high' }),
+}));
+
+import {
+  computeFundingPaymentUsd,
+  fundingRateFromPremium,
+  getFundingRate,
+  isFundingEnabled,
+} from '../funding';
+import { accrueSimFunding, accrueLedgerFunding } from '../perpsFundingEngine';
+import * as perpsSim from '../../plugins/perps-sim';
+import { getDatabase, createPosition, getPosition } from '../../../execution-ledger/db';
+
+const HOUR_MS = 60 * 60 * 1000;
+
+beforeEach(() => {
+  funding.mode = 'fixed';
+  funding.bpsPerHour = 1;
+});
+
+describe('funding rates', () => {
+  it('charges longs and pays shorts pro rata', () => {
+    expect(computeFundingPaymentUsd(10_000, 'long', 0.0001, HOUR_MS)).toBeCloseTo(1);
+    expect(computeFundingPaymentUsd(10_000, 'short', 0.0001, HOUR_MS / 2)).toBeCloseTo(-0.5);
+    // Negative rates flip the direction
+    expect(computeFundingPaymentUsd(10_000, 'long', -0.0001, HOUR_MS)).toBeCloseTo(-1);
+    expect(computeFundingPaymentUsd(0, 'long', 0.0001, HOUR_MS)).toBe(0);
+    expect(computeFundingPaymentUsd(10_000, 'long', 0.0001, -1)).toBe(0);
+  });
+
+  it('clamps the premium adjustment around the interest rate', () => {
+    // At the interest rate, funding is the interest rate spread over 8h
+    expect(fundingRateFromPremium(0)).toBeCloseTo(0.0001 / 8);
+    // Large premiums pass through, less the clamp
+    expect(fundingRateFromPremium(0.002)).toBeCloseTo((0.002 - 0.0005) / 8);
+    expect(fundingRateFromPremium(-0.002)).toBeCloseTo((-0.002 + 0.0005) / 8);
+  });
+
+  it('uses the fixed rate, or none when funding is off', async () => {
+    expect(await getFundingRate('ETH')).toMatchObject({ ratePerHour: 0.0001, source: 'fixed' });
+
+    funding.mode = 'off';
+    expect(isFundingEnabled()).toBe(false);
+    expect(await getFundingRate('ETH')).toMatchObject({ ratePerHour: 0 });
+  });
+});
+
+describe('sim funding accrual', () => {
+  it('folds funding into unrealized PnL and settles it on close', async () => {
+    const user = 'funding-user';
+    const long = await perpsSim.openPerp(user, { market: 'ETH-PERP', side: 'long', riskPct: 10, entry: 3000, leverage: 5 });
+    const short = await perpsSim.openPerp(user, { market: 'ETH-PERP', side: 'short', riskPct: 10, entry: 3000, leverage: 2 });
+    const now = long.lastFundingAt! + 2 * HOUR_MS;
+    short.lastFundingAt = long.lastFundingAt;
+
+    // 5000 notional pays 1bp/h for 2h; the short (900 margin x2) receives it
+    const netPaid = perpsSim.accrueFunding(user, { ETH: 0.0001 }, now);
+
+    expect(netPaid).toBeCloseTo(1 - 0.36);
+    expect(long).toMatchObject({ lastFundingAt: now });
+    expect(long.fundingPaidUsd).toBeCloseTo(1);
+    expect(long.unrealizedPnlUsd).toBeCloseTo(-1);
+    expect(short.fundingPaidUsd).toBeCloseTo(-0.36);
+
+    // Accruing again at the same instant is a no-op
+    expect(perpsSim.accrueFunding(user, { ETH: 0.0001 }, now)).toBe(0);
+
+    const { pnl } = await perpsSim.closePerp(user, long.id);
+    expect(pnl).toBeCloseTo(-1);
+  });
+
+  it('accrues every loaded account through the engine', async () => {
+    const user = 'funding-engine-user';
+    const position = await perpsSim.openPerp(user, { market: 'BTC-PERP', side: 'long', riskPct: 10, entry: 60000 });
+
+    await accrueSimFunding(position.lastFundingAt! + HOUR_MS);
+
+    // 1000 notional at 1bp/h
+    expect(position.fundingPaidUsd).toBeCloseTo(0.1);
+  });
+});
+
+describe('ledger funding accrual', () => {
+  beforeEach(() => {
+    getDatabase().prepare('DELETE FROM positions').run();
+  });
+
+  it('accrues cumulative funding on open demo_perp positions since the last update', async () => {
+    const position = createPosition({
+      chain: 'ethereum',
+      network: 'sepolia',
+      venue: 'demo_perp',
+      market: 'ETH',
+      side: 'short',
+      size_units: String(20_000 * 1e6),
+      user_address: '0x00000000000000000000000000000000000000a1',
+    });
+    const openedMs = position.opened_at * 1000;
+
+    expect(await accrueLedgerFunding(openedMs + HOUR_MS)).toBeCloseTo(-2);
+    expect(getPosition(position.id)).toMatchObject({ funding_updated_at: position.opened_at + 3600 });
+
+    await accrueLedgerFunding(openedMs + 3 * HOUR_MS);
+    expect(getPosition(position.id)!.funding_usd).toBeCloseTo(-6);
+  });
+});
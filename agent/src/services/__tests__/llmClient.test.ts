@@ -0,0 +1,327 @@
+/**
+ * LLM Client Tests
+ * Exercises assistantMessage extraction from chunked JSON, tool-calling
+ * request/response handling, and the provider chain (failover, cache, budgets).
+ * The provider SDKs and telemetry are mocked - no model access
+ */
+
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
+
+const mocks = vi.hoisted(() => ({
+  openaiCreate: vi.fn(),
+  anthropicCreate: vi.fn(),
+  logEvent: vi.fn(),
+  trackLlmUsage: vi.fn(),
+  getLlmTokenUsage: vi.fn(),
+}));
+
+vi.mock('openai', () => ({
+  default: class {
+    chat = { completions: { create: mocks.openaiCreate } };
+  },
+}));
+vi.mock('@anthropic-ai/sdk', () => ({
+  default: class {
+    messages = { create: mocks.anthropicCreate };
+  },
+}));
+vi.mock('../../telemetry/logger', () => ({
+  logEvent: mocks.logEvent,
+  trackLlmUsage: mocks.trackLlmUsage,
+  getLlmTokenUsage: mocks.getLlmTokenUsage,
+  hashAddress: (address: string) => `hash:${address}`,
+}));
+
+import { AssistantMessageStream, callLlm, LlmBudgetExceededError } from '../llmClient';
+import { BLOSSOM_RESPONSE_TOOL_NAME } from '../llmTools';
+import { resetLlmUsageState, estimateCostUsd } from '../llmUsage';
+
+function streamAll(chunks: string[]): string[] {
+  const stream = new AssistantMessageStream();
+  return chunks.map(chunk => stream.push(chunk));
+}
+
+describe('AssistantMessageStream', () => {
+  it('emits the assistantMessage value as it arrives', () => {
+    const out = streamAll(['{"assist', 'antMessage": "Open', 'ing a long', ' on BTC", "actions": [', ']}']);
+    expect(out).toEqual(['', 'Open', 'ing a long', ' on BTC', '']);
+  });
+
+  it('ignores fields before the message and text after it', () => {
+    const out = streamAll(['{"actions": [{"type": "perp"}], ', '"assistantMessage": "Done"', ', "note": "x"}']);
+    expect(out.join('')).toBe('Done');
+  });
+
+  it('decodes escapes split across chunks', () => {
+    const out = streamAll(['{"assistantMessage": "line one\\', 'nline \\"two\\" \\u00', 'e9', '"}']);
+    expect(out.join('')).toBe('line one\nline "two" é');
+    expect(out[0]).toBe('line one');
+    expect(out[1]).toBe('\nline "two" ');
+  });
+
+  it('keeps the raw JSON for parsing', () => {
+    const stream = new AssistantMessageStream();
+    stream.push('{"assistantMessage": "hi", ');
+    stream.push('"actions": []}');
+    expect(JSON.parse(stream.raw)).toEqual({ assistantMessage: 'hi', actions: [] });
+  });
+});
+
+const INPUT = { systemPrompt: 'You are Blossom.', userPrompt: 'Swap 10 USDC to WETH' };
+const RESPONSE = {
+  assistantMessage: 'Swapping 10 USDC to WETH.',
+  actions: [],
+  executionRequest: { kind: 'swap', chain: 'sepolia', tokenIn: 'USDC', tokenOut: 'WETH', amountIn: '10' },
+};
+
+async function* iterate<T>(items: T[]): AsyncGenerator<T> {
+  for (const item of items) yield item;
+}
+
+function split(text: string, size: number): string[] {
+  const parts: string[] = [];
+  for (let i = 0; i < text.length; i += size) parts.push(text.slice(i, i + size));
+  return parts;
+}
+
+const env = { ...process.env };
+
+function resetMocks() {
+  Object.values(mocks).forEach(mock => mock.mockReset());
+  resetLlmUsageState();
+  process.env.BLOSSOM_OPENAI_API_KEY = 'sk-test';
+  process.env.BLOSSOM_ANTHROPIC_API_KEY = 'sk-ant-test';
+  delete process.env.BLOSSOM_MODEL_PROVIDERS;
+  delete process.env.BLOSSOM_LLM_TOOLS;
+}
+
+function openAIToolResponse(response: object, usage = { prompt_tokens: 1200, completion_tokens: 300 }) {
+  return {
+    choices: [{
+      message: {
+        content: null,
+        tool_calls: [{ type: 'function', function: { name: BLOSSOM_RESPONSE_TOOL_NAME, arguments: JSON.stringify(response) } }],
+      },
+    }],
+    usage,
+  };
+}
+
+describe('callLlm tool calling', () => {
+  beforeEach(resetMocks);
+
+  afterEach(() => {
+    process.env = { ...env };
+  });
+
+  it('forces the response tool on OpenAI and returns its arguments', async () => {
+    process.env.BLOSSOM_MODEL_PROVIDER = 'openai';
+    mocks.openaiCreate.mockResolvedValue({
+      choices: [{
+        message: {
+          content: null,
+          tool_calls: [{ type: 'function', function: { name: BLOSSOM_RESPONSE_TOOL_NAME, arguments: JSON.stringify(RESPONSE) } }],
+        },
+      }],
+    });
+
+    const output = await callLlm(INPUT);
+
+    const request = mocks.openaiCreate.mock.calls[0][0];
+    expect(request.tools[0].function.name).toBe(BLOSSOM_RESPONSE_TOOL_NAME);
+    expect(request.tool_choice).toEqual({ type: 'function', function: { name: BLOSSOM_RESPONSE_TOOL_NAME } });
+    expect(request.response_format).toBeUndefined();
+    expect(output.toolCall).toEqual({ name: BLOSSOM_RESPONSE_TOOL_NAME, input: RESPONSE });
+    expect(output.assistantMessage).toBe(RESPONSE.assistantMessage);
+    expect(JSON.parse(output.rawJson)).toEqual(RESPONSE);
+  });
+
+  it('streams assistantMessage out of OpenAI tool argument deltas', async () => {
+    process.env.BLOSSOM_MODEL_PROVIDER = 'openai';
+    const chunks = split(JSON.stringify(RESPONSE), 7).map((args, i) => ({
+      choices: [{
+        delta: {
+          tool_calls: [{ index: 0, function: { ...(i === 0 ? { name: BLOSSOM_RESPONSE_TOOL_NAME } : {}), arguments: args } }],
+        },
+      }],
+    }));
+    mocks.openaiCreate.mockResolvedValue(iterate(chunks));
+    const tokens: string[] = [];
+
+    const output = await callLlm(INPUT, { onToken: text => tokens.push(text) });
+
+    expect(mocks.openaiCreate.mock.calls[0][0].stream).toBe(true);
+    expect(tokens.length).toBeGreaterThan(1);
+    expect(tokens.join('')).toBe(RESPONSE.assistantMessage);
+    expect(output.toolCall?.input).toEqual(RESPONSE);
+  });
+
+  it('reads the tool_use block from Anthropic', async () => {
+    process.env.BLOSSOM_MODEL_PROVIDER = 'anthropic';
+    mocks.anthropicCreate.mockResolvedValue({
+      content: [{ type: 'tool_use', id: 'toolu_1', name: BLOSSOM_RESPONSE_TOOL_NAME, input: RESPONSE }],
+    });
+
+    const output = await callLlm(INPUT);
+
+    const request = mocks.anthropicCreate.mock.calls[0][0];
+    expect(request.tools[0].name).toBe(BLOSSOM_RESPONSE_TOOL_NAME);
+    expect(request.tools[0].input_schema.required).toContain('assistantMessage');
+    expect(request.tool_choice).toEqual({ type: 'tool', name: BLOSSOM_RESPONSE_TOOL_NAME });
+    expect(request.system).toBe(INPUT.systemPrompt);
+    expect(output.toolCall?.input).toEqual(RESPONSE);
+  });
+
+  it('streams assistantMessage out of Anthropic input_json deltas', async () => {
+    process.env.BLOSSOM_MODEL_PROVIDER = 'anthropic';
+    mocks.anthropicCreate.mockResolvedValue(iterate([
+      { type: 'message_start' },
+      { type: 'content_block_start', index: 0, content_block: { type: 'tool_use', id: 'toolu_1', name: BLOSSOM_RESPONSE_TOOL_NAME, input: {} } },
+      ...split(JSON.stringify(RESPONSE), 9).map(partial_json => ({
+        type: 'content_block_delta',
+        index: 0,
+        delta: { type: 'input_json_delta', partial_json },
+      })),
+      { type: 'content_block_stop', index: 0 },
+    ]));
+    const tokens: string[] = [];
+
+    const output = await callLlm(INPUT, { onToken: text => tokens.push(text) });
+
+    expect(tokens.join('')).toBe(RESPONSE.assistantMessage);
+    expect(output.toolCall?.input).toEqual(RESPONSE);
+  });
+
+  it('falls back to JSON text when tools are disabled', async () => {
+    process.env.BLOSSOM_MODEL_PROVIDER = 'openai';
+    process.env.BLOSSOM_LLM_TOOLS = 'false';
+    mocks.openaiCreate.mockResolvedValue({ choices: [{ message: { content: JSON.stringify(RESPONSE) } }] });
+
+    const output = await callLlm(INPUT);
+
+    const request = mocks.openaiCreate.mock.calls[0][0];
+    expect(request.tools).toBeUndefined();
+    expect(request.response_format).toEqual({ type: 'json_object' });
+    expect(output.toolCall).toBeUndefined();
+    expect(JSON.parse(output.rawJson)).toEqual(RESPONSE);
+  });
+});
+
+describe('callLlm provider chain', () => {
+  const anthropicReply = {
+    content: [{ type: 'tool_use', id: 'toolu_1', name: BLOSSOM_RESPONSE_TOOL_NAME, input: RESPONSE }],
+    usage: { input_tokens: 1000, output_tokens: 200 },
+  };
+
+  beforeEach(() => {
+    resetMocks();
+    process.env.BLOSSOM_MODEL_PROVIDERS = 'openai,anthropic';
+  });
+
+  afterEach(() => {
+    process.env = { ...env };
+  });
+
+  it('fails over to the next provider on an error and records both attempts', async () => {
+    mocks.openaiCreate.mockRejectedValue(Object.assign(new Error('upstream 500'), { status: 500 }));
+    mocks.anthropicCreate.mockResolvedValue(anthropicReply);
+
+    const output = await callLlm(INPUT, { userId: '0xabc' });
+
+    expect(output.provider).toBe('anthropic');
+    expect(output.usage).toEqual({ inputTokens: 1000, outputTokens: 200 });
+    expect(mocks.trackLlmUsage).toHaveBeenCalledWith(expect.objectContaining({ provider: 'openai', status: 'error' }));
+    expect(mocks.trackLlmUsage).toHaveBeenCalledWith(expect.objectContaining({
+      provider: 'anthropic',
+      status: 'ok',
+      userHash: 'hash:0xabc',
+      inputTokens: 1000,
+      outputTokens: 200,
+      costUsd: expect.any(Number),
+    }));
+    expect(mocks.logEvent).toHaveBeenCalledWith('llm_call', expect.objectContaining({ provider: 'anthropic', success: true }));
+  });
+
+  it('parks a rate-limited provider until its cooldown ends', async () => {
+    mocks.openaiCreate.mockRejectedValue(Object.assign(new Error('rate limited'), { status: 429, headers: { 'retry-after': '30' } }));
+    mocks.anthropicCreate.mockResolvedValue(anthropicReply);
+
+    await callLlm(INPUT);
+    await callLlm(INPUT);
+
+    expect(mocks.openaiCreate).toHaveBeenCalledTimes(1);
+    expect(mocks.anthropicCreate).toHaveBeenCalledTimes(2);
+    expect(mocks.trackLlmUsage).toHaveBeenCalledWith(expect.objectContaining({ provider: 'openai', status: 'rate_limited' }));
+  });
+
+  it('times out a slow provider and moves on', async () => {
+    process.env.BLOSSOM_OPENAI_TIMEOUT_MS = '20';
+    mocks.openaiCreate.mockImplementation((_request: unknown, { signal }: { signal: AbortSignal }) =>
+      new Promise((_, reject) => signal.addEventListener('abort', () => reject(new Error('aborted')))));
+    mocks.anthropicCreate.mockResolvedValue(anthropicReply);
+
+    const output = await callLlm(INPUT);
+
+    expect(output.provider).toBe('anthropic');
+    expect(mocks.trackLlmUsage).toHaveBeenCalledWith(expect.objectContaining({
+      provider: 'openai',
+      status: 'timeout',
+      errorMessage: 'openai timed out after 20ms',
+    }));
+  });
+
+  it('skips providers without an API key and throws when every provider fails', async () => {
+    delete process.env.BLOSSOM_OPENAI_API_KEY;
+    mocks.anthropicCreate.mockRejectedValue(Object.assign(new Error('overloaded'), { status: 529 }));
+
+    await expect(callLlm(INPUT)).rejects.toThrow('Anthropic API error: overloaded');
+    expect(mocks.openaiCreate).not.toHaveBeenCalled();
+  });
+
+  it('serves repeat prompts for the same portfolio from the cache', async () => {
+    mocks.openaiCreate.mockResolvedValue(openAIToolResponse(RESPONSE));
+    const portfolio = { accountValueUsd: 10000, balances: [{ symbol: 'USDC', balanceUsd: 5000 }] };
+    const tokens: string[] = [];
+
+    await callLlm(INPUT, { cacheKey: { prompt: 'Swap 10 USDC to WETH', portfolio } });
+    const cached = await callLlm(INPUT, {
+      cacheKey: { prompt: '  swap 10 usdc   to weth ', portfolio: { balances: portfolio.balances, accountValueUsd: 10000 } },
+      onToken: text => tokens.push(text),
+    });
+    await callLlm(INPUT, { cacheKey: { prompt: 'Swap 10 USDC to WETH', portfolio: { ...portfolio, accountValueUsd: 9000 } } });
+
+    expect(cached.cached).toBe(true);
+    expect(cached.toolCall?.input).toEqual(RESPONSE);
+    expect(tokens.join('')).toBe(RESPONSE.assistantMessage);
+    expect(mocks.openaiCreate).toHaveBeenCalledTimes(2);
+  });
+
+  it('refuses users over their token budget before calling a provider', async () => {
+    process.env.BLOSSOM_LLM_USER_TOKEN_BUDGET = '50000';
+    mocks.getLlmTokenUsage.mockResolvedValue(50000);
+
+    await expect(callLlm(INPUT, { userId: '0xabc' })).rejects.toBeInstanceOf(LlmBudgetExceededError);
+    expect(mocks.getLlmTokenUsage).toHaveBeenCalledWith('hash:0xabc', expect.any(Number));
+    expect(mocks.openaiCreate).not.toHaveBeenCalled();
+
+    mocks.getLlmTokenUsage.mockResolvedValue(1000);
+    mocks.openaiCreate.mockResolvedValue(openAIToolResponse(RESPONSE));
+    await expect(callLlm(INPUT, { userId: '0xabc' })).resolves.toMatchObject({ provider: 'openai' });
+  });
+
+  it('applies the IP budget to unauthenticated callers', async () => {
+    process.env.BLOSSOM_LLM_USER_TOKEN_BUDGET = '50000';
+    process.env.BLOSSOM_LLM_IP_TOKEN_BUDGET = '5000';
+    mocks.getLlmTokenUsage.mockResolvedValue(10000);
+    mocks.openaiCreate.mockResolvedValue(openAIToolResponse(RESPONSE));
+
+    await expect(callLlm(INPUT, { userId: 'ip:203.0.113.7', budgetScope: 'ip' })).rejects.toBeInstanceOf(LlmBudgetExceededError);
+    await expect(callLlm(INPUT, { userId: 'wallet:0xabc' })).resolves.toMatchObject({ provider: 'openai' });
+  });
+
+  it('prices calls by model prefix', () => {
+    expect(estimateCostUsd('gpt-4o-mini-2024-07-18', { inputTokens: 1_000_000, outputTokens: 1_000_000 })).toBeCloseTo(0.75);
+    expect(estimateCostUsd('gpt-4o', { inputTokens: 1_000_000, outputTokens: 0 })).toBeCloseTo(2.5);
+    expect(estimateCostUsd('some-local-model', { inputTokens: 1000, outputTokens: 1000 })).toBe(0);
+  });
+});
@@ -0,0 +1,128 @@
+/**
+ * Perps Mark-to-Market Tests
+ * Sim accounts stay in memory; prices are set per test.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+const price = vi.hoisted(() => {
+  process.env.SIM_PERSIST_DISABLED = 'true';
+  return { usd: 3000, confidence: 'high' };
+});
+
+vi.mock('../../config', () => ({
+  PERPS_MARK_INTERVAL_MS: 15000,
+  PERPS_MAINTENANCE_MARGIN_BPS: 500,
+  PERPS_FUNDING_MODE: 'off',
+  PERPS_FUNDING_RATE_BPS_PER_HOUR: 0,
+  PERPS_FUNDING_PREMIUM_SOURCE: 'mock',
+}));
+vi.mock('../prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: price.usd, source: 'median', confidence: price.confidence }),
+}));
+vi.mock('../../telemetry/logger', () => ({ logEvent: vi.fn(), hashAddress: (value: string) => value }));
+
+import * as perpsSim from '../../plugins/perps-sim';
+import { runMarkCycle } from '../perpsMarkEngine';
+
+const MAINTENANCE_BPS = 500;
+
+let userCounter = 0;
+let user: string;
+
+// 1000 USDC margin (10% of the 10k account) at 3000
+function open(side: 'long' | 'short', leverage: number) {
+  return perpsSim.openPerp(user, { market: 'ETH-PERP', side, riskPct: 10, entry: 3000, leverage });
+}
+
+describe('markPositionsToMarket', () => {
+  beforeEach(() => {
+    user = `mark-user-${++userCounter}`;
+    price.usd = 3000;
+    price.confidence = 'high';
+  });
+
+  it('revalues open positions without closing them inside their range', async () => {
+    const position = await open('long', 5);
+
+    expect(perpsSim.markPositionsToMarket(user, { ETH: 3060 }, MAINTENANCE_BPS)).toEqual([]);
+    expect(position.markPrice).toBe(3060);
+    expect(position.unrealizedPnlUsd).toBeCloseTo(100);   // 5000 notional * 2%
+
+    // No mark for the symbol leaves the position alone
+    perpsSim.markPositionsToMarket(user, { BTC: 50000 }, MAINTENANCE_BPS);
+    expect(position.markPrice).toBe(3060);
+  });
+
+  it('closes a long at its take-profit price', async () => {
+    const position = await open('long', 5);   // TP 3120
+
+    const [closure] = perpsSim.markPositionsToMarket(user, { ETH: 3150 }, MAINTENANCE_BPS);
+
+    expect(closure).toMatchObject({ positionId: position.id, reason: 'take_profit', exitPrice: 3120 });
+    expect(closure.realizedPnlUsd).toBeCloseTo(200);
+    expect(position).toMatchObject({ isClosed: true, closeReason: 'take_profit', unrealizedPnlUsd: 0 });
+    expect(perpsSim.getUsdcBalance(user)).toBeCloseTo(4200);
+  });
+
+  it('closes a short at its stop-loss price', async () => {
+    await open('short', 5);   // SL 3090
+
+    const [closure] = perpsSim.markPositionsToMarket(user, { ETH: 3100 }, MAINTENANCE_BPS);
+
+    expect(closure).toMatchObject({ reason: 'stop_loss', exitPrice: 3090 });
+    expect(closure.realizedPnlUsd).toBeCloseTo(-150);
+    expect(perpsSim.getUsdcBalance(user)).toBeCloseTo(3850);
+  });
+
+  it('liquidates at the maintenance margin and forfeits the margin', async () => {
+    const position = await open('long', 10);
+
+    // Equity 1000 - 833 = 167 is below 5% of the 10k notional
+    const [closure] = perpsSim.markPositionsToMarket(user, { ETH: 2750 }, MAINTENANCE_BPS);
+
+    expect(closure).toMatchObject({ reason: 'liquidation', exitPrice: 2750, realizedPnlUsd: -1000 });
+    expect(position.realizedPnlPct).toBe(-100);
+    expect(perpsSim.getUsdcBalance(user)).toBe(3000);
+  });
+});
+
+describe('closePerp', () => {
+  beforeEach(() => {
+    user = `mark-user-${++userCounter}`;
+    price.usd = 3000;
+  });
+
+  it('settles at the market price and returns margin plus PnL', async () => {
+    const position = await open('short', 2);
+    price.usd = 2970;
+
+    const { pnl } = await perpsSim.closePerp(user, position.id);
+
+    expect(pnl).toBeCloseTo(20);   // 2000 notional * 1%
+    expect(position).toMatchObject({ isClosed: true, closeReason: 'manual', exitPrice: 2970 });
+    expect(perpsSim.getUsdcBalance(user)).toBeCloseTo(4020);
+    await expect(perpsSim.closePerp(user, position.id)).rejects.toThrow(/already closed/);
+  });
+});
+
+describe('runMarkCycle', () => {
+  beforeEach(() => {
+    user = `mark-user-${++userCounter}`;
+    price.usd = 3000;
+    price.confidence = 'high';
+  });
+
+  it('does not act on low-confidence marks', async () => {
+    const position = await open('long', 10);
+    price.usd = 2750;
+    price.confidence = 'low';
+
+    expect(await runMarkCycle()).toEqual([]);
+    expect(position.isClosed).toBe(false);
+
+    price.confidence = 'medium';
+    const closures = await runMarkCycle();
+    expect(closures.filter(c => c.positionId === position.id)).toMatchObject([{ reason: 'liquidation' }]);
+  });
+});
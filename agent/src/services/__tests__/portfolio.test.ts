@@ -0,0 +1,153 @@
+/**
+ * Unified Portfolio Tests
+ * Wallet-link signatures use real EVM and ed25519 keys; the Solana RPC client
+ * and prices are mocked.
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { privateKeyToAccount } from 'viem/accounts';
+import { generateKeypair, signMessage, base58Encode } from '../../solana/solanaClient';
+
+const USDC_MINT = '4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU';
+const BONK_MINT = 'DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263';
+const UNKNOWN_MINT = '7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU';
+
+const solana = vi.hoisted(() => ({
+  lamports: 0,
+  accounts: [] as { mint: string; amount: string; decimals: number }[],
+}));
+
+vi.mock('../../config', () => ({
+  SOLANA_USDC_MINT: '4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU',
+  SOLANA_EXTRA_TOKENS: 'DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263:bonk:5',
+}));
+vi.mock('../tokenMetadata', () => ({
+  NATIVE_ETH: 'eth',
+  listConfiguredTokens: () => [],
+  priceSymbolFor: (symbol: string) => (['SOL', 'WSOL', 'USDC'].includes(symbol) ? symbol.replace('WSOL', 'SOL') : undefined),
+}));
+vi.mock('../prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: symbol === 'SOL' ? 150 : 1, source: 'median' }),
+}));
+vi.mock('../../solana/solanaClient', async (importOriginal) => {
+  const actual = await importOriginal<typeof import('../../solana/solanaClient')>();
+  return {
+    ...actual,
+    SolanaClient: class {
+      async getBalance() {
+        return { lamports: solana.lamports, sol: solana.lamports / 1e9 };
+      }
+      async getTokenAccountsByOwner() {
+        return solana.accounts;
+      }
+    },
+  };
+});
+
+import {
+  buildWalletLinkMessage,
+  verifyWalletLinkSignature,
+  getSolanaHoldings,
+  valueHoldings,
+  type WalletLinkRequest,
+} from '../portfolio';
+
+const NOW = 1_750_000_000;
+const user = privateKeyToAccount('0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d');
+const stranger = privateKeyToAccount('0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a');
+const solanaWallet = generateKeypair();
+
+let nonceCounter = 0;
+
+async function linkRequest(overrides: Partial<WalletLinkRequest> = {}): Promise<WalletLinkRequest> {
+  const action = overrides.action ?? 'link';
+  const nonce = overrides.nonce ?? `nonce${String(++nonceCounter).padStart(6, '0')}`;
+  const issuedAt = overrides.issuedAt ?? NOW;
+  const message = buildWalletLinkMessage(action, user.address, solanaWallet.publicKey, nonce, issuedAt);
+  return {
+    action,
+    userAddress: user.address,
+    solanaAddress: solanaWallet.publicKey,
+    nonce,
+    issuedAt,
+    evmSignature: await user.signMessage({ message }),
+    solanaSignature: base58Encode(signMessage(Buffer.from(message, 'utf8'), solanaWallet.secretKey)),
+    ...overrides,
+  };
+}
+
+describe('verifyWalletLinkSignature', () => {
+  it('accepts a link signed by both wallets, base58 or base64', async () => {
+    expect(await verifyWalletLinkSignature(await linkRequest(), NOW + 30)).toBeNull();
+
+    const request = await linkRequest();
+    const message = buildWalletLinkMessage('link', user.address, solanaWallet.publicKey, request.nonce, NOW);
+    const base64 = signMessage(Buffer.from(message, 'utf8'), solanaWallet.secretKey).toString('base64');
+    expect(await verifyWalletLinkSignature({ ...request, solanaSignature: base64 }, NOW)).toBeNull();
+  });
+
+  it('requires the EVM user signature', async () => {
+    const request = await linkRequest();
+    const message = buildWalletLinkMessage('link', user.address, solanaWallet.publicKey, request.nonce, NOW);
+    const forged = await stranger.signMessage({ message });
+
+    expect(await verifyWalletLinkSignature({ ...request, evmSignature: forged }, NOW)).toMatch(/userAddress/);
+  });
+
+  it('requires the Solana wallet signature to link but not to unlink', async () => {
+    const request = await linkRequest();
+    expect(await verifyWalletLinkSignature({ ...request, solanaSignature: undefined }, NOW)).toMatch(/solanaAddress/);
+    expect(await verifyWalletLinkSignature({ ...request, solanaSignature: 'not-a-signature' }, NOW)).toMatch(/solanaAddress/);
+
+    const unlink = await linkRequest({ action: 'unlink' });
+    expect(await verifyWalletLinkSignature({ ...unlink, solanaSignature: undefined }, NOW)).toBeNull();
+    // A link signature cannot be replayed as an unlink
+    const link = await linkRequest();
+    expect(await verifyWalletLinkSignature({ ...link, action: 'unlink' }, NOW)).toMatch(/userAddress/);
+  });
+
+  it('rejects stale signatures and reused nonces', async () => {
+    expect(await verifyWalletLinkSignature(await linkRequest(), NOW + 3600)).toMatch(/expired/);
+
+    const request = await linkRequest();
+    expect(await verifyWalletLinkSignature(request, NOW)).toBeNull();
+    expect(await verifyWalletLinkSignature(request, NOW + 1)).toMatch(/already used/);
+  });
+});
+
+describe('Solana holdings', () => {
+  it('lists SOL and sums token accounts per mint, skipping empty ones', async () => {
+    solana.lamports = 2_500_000_000;
+    solana.accounts = [
+      { mint: USDC_MINT, amount: '1500000', decimals: 6 },
+      { mint: USDC_MINT, amount: '500000', decimals: 6 },
+      { mint: BONK_MINT, amount: '0', decimals: 5 },
+      { mint: UNKNOWN_MINT, amount: '42', decimals: 2 },
+    ];
+
+    const holdings = await getSolanaHoldings('Wallet1111111111111111111111111111111111111');
+
+    expect(holdings.map(h => [h.symbol, h.raw, h.decimals])).toEqual([
+      ['SOL', '2500000000', 9],
+      ['USDC', '2000000', 6],
+      [`${UNKNOWN_MINT.slice(0, 4)}…${UNKNOWN_MINT.slice(-4)}`, '42', 2],
+    ]);
+  });
+
+  it('values holdings with a feed and leaves the rest unvalued', async () => {
+    solana.lamports = 2_000_000_000;
+    solana.accounts = [
+      { mint: USDC_MINT, amount: '12500000', decimals: 6 },
+      { mint: UNKNOWN_MINT, amount: '42', decimals: 2 },
+    ];
+
+    const valued = await valueHoldings(await getSolanaHoldings('Wallet1111111111111111111111111111111111111'));
+
+    expect(valued.map(h => [h.symbol, h.formatted, h.valueUsd])).toEqual([
+      ['SOL', '2', 300],
+      ['USDC', '12.5', 12.5],
+      [expect.any(String), '0.42', undefined],
+    ]);
+    expect(valued[0]).not.toHaveProperty('priceSymbol');
+  });
+});
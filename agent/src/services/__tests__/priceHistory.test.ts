@@ -0,0 +1,140 @@
+/**
+ * Price History Tests
+ * The ledger runs against an in-memory SQLite database.
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+
+vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+});
+
+vi.mock('../../config', () => ({
+  PRICE_HISTORY_SYMBOLS: 'ETH',
+  PRICE_HISTORY_SAMPLE_MS: 15000,
+  PRICE_HISTORY_TICK_RETENTION_HOURS: 48,
+  PRICE_HISTORY_1M_RETENTION_DAYS: 7,
+  PRICE_HISTORY_1H_RETENTION_DAYS: 180,
+}));
+
+vi.mock('../prices', () => ({
+  getPrice: vi.fn(),
+  onPriceUpdate: vi.fn(() => () => {}),
+}));
+
+import {
+  recordPriceSnapshot,
+  getPriceCandles,
+  getRealizedVolatility,
+  computeLogReturnVolatility,
+  annualizeVolatility,
+  prunePriceHistoryNow,
+} from '../priceHistory';
+import type { PriceSnapshot } from '../prices';
+import { listPriceTicks } from '../../../execution-ledger/db';
+
+const DAY_START = 19700 * 86400; // Midnight UTC
+
+function tick(symbol: string, priceUsd: number, ts: number, extra: Partial<PriceSnapshot> = {}): PriceSnapshot {
+  return { symbol, priceUsd, source: 'median', confidence: 'high', fetchedAt: ts * 1000, ...extra };
+}
+
+describe('recordPriceSnapshot', () => {
+  it('folds ticks into 1m, 1h and 1d candles', async () => {
+    await recordPriceSnapshot(tick('CANDLE', 100, DAY_START));
+    await recordPriceSnapshot(tick('CANDLE', 110, DAY_START + 30));
+    await recordPriceSnapshot(tick('CANDLE', 95, DAY_START + 45));
+    await recordPriceSnapshot(tick('CANDLE', 105, DAY_START + 70));
+
+    const minutes = await getPriceCandles('CANDLE', '1m');
+    expect(minutes).toEqual([
+      { time: DAY_START, open: 100, high: 110, low: 95, close: 95, ticks: 3 },
+      { time: DAY_START + 60, open: 105, high: 105, low: 105, close: 105, ticks: 1 },
+    ]);
+
+    const [hour] = await getPriceCandles('CANDLE', '1h');
+    expect(hour).toEqual({ time: DAY_START, open: 100, high: 110, low: 95, close: 105, ticks: 4 });
+
+    const [day] = await getPriceCandles('candle', '1d');
+    expect(day.close).toBe(105);
+  });
+
+  it('keeps open and close when a tick arrives out of order', async () => {
+    await recordPriceSnapshot(tick('LATE', 100, DAY_START + 10));
+    await recordPriceSnapshot(tick('LATE', 102, DAY_START + 50));
+    await recordPriceSnapshot(tick('LATE', 120, DAY_START + 30));
+    await recordPriceSnapshot(tick('LATE', 90, DAY_START + 5));
+
+    const [minute] = await getPriceCandles('LATE', '1m');
+    expect(minute).toMatchObject({ open: 90, high: 120, low: 90, close: 102, ticks: 4 });
+  });
+
+  it('skips static and stale prices', async () => {
+    expect(await recordPriceSnapshot(tick('SKIP', 100, DAY_START, { source: 'static', confidence: 'low' }))).toBe(false);
+    expect(await recordPriceSnapshot(tick('SKIP', 100, DAY_START, { stale: true, confidence: 'low' }))).toBe(false);
+    expect(await getPriceCandles('SKIP', '1m')).toEqual([]);
+  });
+
+  it('limits to the most recent candles, oldest first', async () => {
+    for (let i = 0; i < 5; i++) {
+      await recordPriceSnapshot(tick('LIMIT', 100 + i, DAY_START + i * 60));
+    }
+    const candles = await getPriceCandles('LIMIT', '1m', { limit: 2 });
+    expect(candles.map(c => c.close)).toEqual([103, 104]);
+
+    const ranged = await getPriceCandles('LIMIT', '1m', { from: DAY_START + 60, to: DAY_START + 120 });
+    expect(ranged.map(c => c.close)).toEqual([101, 102]);
+  });
+});
+
+describe('realized volatility', () => {
+  it('computes the sample stdev of log returns', () => {
+    const closes = [100, 110, 99, 108.9];
+    const returns = [Math.log(1.1), Math.log(0.9), Math.log(1.1)];
+    const mean = returns.reduce((a, b) => a + b, 0) / 3;
+    const expected = Math.sqrt(returns.reduce((sum, r) => sum + (r - mean) ** 2, 0) / 2);
+
+    const result = computeLogReturnVolatility(closes);
+    expect(result?.samples).toBe(3);
+    expect(result?.stdev).toBeCloseTo(expected, 10);
+    expect(computeLogReturnVolatility([100, 101])).toBeNull();
+  });
+
+  it('annualizes hourly candles over a 24/7 year', () => {
+    expect(annualizeVolatility(0.01, '1h')).toBeCloseTo(0.01 * Math.sqrt(8760), 10);
+    expect(annualizeVolatility(0.01, '1d')).toBeCloseTo(0.01 * Math.sqrt(365), 10);
+  });
+
+  it('uses the last lookback candles of recorded history', async () => {
+    expect(await getRealizedVolatility('VOL')).toBeNull();
+
+    const closes = [100, 102, 99, 101, 104, 103];
+    for (const [i, close] of closes.entries()) {
+      await recordPriceSnapshot(tick('VOL', close, DAY_START + i * 3600));
+    }
+
+    const vol = await getRealizedVolatility('VOL', { period: '1h', lookback: 3 });
+    const expected = computeLogReturnVolatility(closes.slice(-4));
+    expect(vol?.samples).toBe(3);
+    expect(vol?.periodVolatility).toBeCloseTo(expected!.stdev, 10);
+    expect(vol?.annualized).toBeCloseTo(annualizeVolatility(expected!.stdev, '1h'), 10);
+    expect(vol?.from).toBe(DAY_START + 2 * 3600);
+    expect(vol?.to).toBe(DAY_START + 5 * 3600);
+  });
+});
+
+describe('prunePriceHistoryNow', () => {
+  it('drops old ticks and 1m candles but keeps daily candles', async () => {
+    const old = DAY_START - 30 * 86400;
+    await recordPriceSnapshot(tick('PRUNE', 50, old));
+    await recordPriceSnapshot(tick('PRUNE', 60, DAY_START));
+
+    const pruned = await prunePriceHistoryNow(DAY_START * 1000);
+    expect(pruned.ticks).toBeGreaterThanOrEqual(1);
+
+    expect(listPriceTicks('PRUNE').map(t => t.price_usd)).toEqual([60]);
+    expect((await getPriceCandles('PRUNE', '1m')).map(c => c.close)).toEqual([60]);
+    expect((await getPriceCandles('PRUNE', '1h')).map(c => c.close)).toEqual([50, 60]);
+    expect((await getPriceCandles('PRUNE', '1d')).map(c => c.close)).toEqual([50, 60]);
+  });
+});
@@ -0,0 +1,213 @@
+/**
+ * Price Oracle Tests
+ * Aggregation runs against fake sources; no network.
+ */
+
+import { describe, it, expect, beforeEach, vi } from 'vitest';
+
+vi.mock('../../config', () => ({
+  PRICE_ORACLE_SOURCES: '',
+  PRICE_CACHE_TTL_MS: 12000,
+  PRICE_SOURCE_TIMEOUT_MS: 50,
+  PRICE_MAX_AGE_SECONDS: 600,
+  PRICE_SOURCE_MAX_AGE_SECONDS: '',
+  PRICE_MAX_DEVIATION_BPS: 200,
+}));
+
+vi.mock('../priceSources/coingecko', () => ({ createCoinGeckoSource: () => null }));
+vi.mock('../priceSources/chainlink', () => ({ createChainlinkSource: () => null }));
+vi.mock('../priceSources/uniswapTwap', () => ({ createUniswapTwapSource: () => null }));
+vi.mock('../priceSources/dflow', () => ({ createDflowSource: () => null }));
+
+import {
+  PriceOracle,
+  PriceUnavailableError,
+  meetsConfidence,
+  lowestConfidence,
+  parseSymbolList,
+  type PriceSource,
+} from '../priceOracle';
+import { getPrice, registerPriceSource, hasPriceFeed, resetPriceOracle, parseSourceMaxAges } from '../prices';
+
+function fakeSource(
+  id: string,
+  priceUsd: number,
+  options: { ageSeconds?: number; maxAgeSeconds?: number; symbols?: string[] } = {}
+): PriceSource {
+  const symbols = options.symbols ?? ['ETH'];
+  return {
+    id,
+    supports: symbol => symbols.includes(symbol),
+    fetchPrice: async () => ({
+      priceUsd,
+      observedAt: Date.now() - (options.ageSeconds ?? 0) * 1000,
+      maxAgeSeconds: options.maxAgeSeconds,
+    }),
+  };
+}
+
+function failingSource(id: string, fetchPrice: PriceSource['fetchPrice']): PriceSource {
+  return { id, supports: () => true, fetchPrice };
+}
+
+function newOracle(...sources: PriceSource[]): PriceOracle {
+  const oracle = new PriceOracle({ timeoutMs: 50, maxAgeSeconds: 600, maxDeviationBps: 200 });
+  sources.forEach(source => oracle.addSource(source));
+  return oracle;
+}
+
+describe('PriceOracle.aggregate', () => {
+  it('takes the median of agreeing sources with high confidence', async () => {
+    const price = await newOracle(
+      fakeSource('a', 3000),
+      fakeSource('b', 3010),
+      fakeSource('c', 3004),
+    ).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(3004);
+    expect(price?.source).toBe('median');
+    expect(price?.confidence).toBe('high');
+    expect(price?.stale).toBe(false);
+    expect(price?.sources.every(o => o.status === 'used')).toBe(true);
+  });
+
+  it('drops an outlier when three or more sources report', async () => {
+    const price = await newOracle(
+      fakeSource('a', 3000),
+      fakeSource('b', 3006),
+      fakeSource('c', 4500),
+    ).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(3003);
+    expect(price?.confidence).toBe('high');
+    expect(price?.sources.find(o => o.source === 'c')?.status).toBe('outlier');
+  });
+
+  it('reports low confidence when two sources disagree', async () => {
+    const price = await newOracle(fakeSource('a', 3000), fakeSource('b', 3300)).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(3150);
+    expect(price?.confidence).toBe('low');
+    expect(price?.deviationBps).toBeGreaterThan(200);
+  });
+
+  it('gives a single fresh source medium confidence', async () => {
+    const price = await newOracle(fakeSource('a', 3000)).aggregate('ETH');
+
+    expect(price?.source).toBe('a');
+    expect(price?.confidence).toBe('medium');
+  });
+
+  it('prefers fresh observations and ignores stale ones', async () => {
+    const price = await newOracle(
+      fakeSource('fresh', 3000),
+      fakeSource('old', 2500, { ageSeconds: 3600 }),
+    ).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(3000);
+    expect(price?.confidence).toBe('medium');
+    expect(price?.sources.find(o => o.source === 'old')?.status).toBe('stale');
+  });
+
+  it('falls back to stale observations with low confidence', async () => {
+    const price = await newOracle(fakeSource('old', 2500, { ageSeconds: 3600 })).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(2500);
+    expect(price?.stale).toBe(true);
+    expect(price?.confidence).toBe('low');
+  });
+
+  it('judges staleness by the feed, then the source, then the oracle max age', async () => {
+    const oracle = new PriceOracle({
+      timeoutMs: 50,
+      maxAgeSeconds: 600,
+      sourceMaxAgeSeconds: { coingecko: 120 },
+      maxDeviationBps: 200,
+    });
+    // A 1h heartbeat feed updated 50 minutes ago is still fresh
+    oracle.addSource(fakeSource('chainlink', 3000, { ageSeconds: 3000, maxAgeSeconds: 4200 }));
+    oracle.addSource(fakeSource('coingecko', 3005, { ageSeconds: 300 }));
+    oracle.addSource(fakeSource('uniswap_twap', 3002, { ageSeconds: 300 }));
+
+    const price = await oracle.aggregate('ETH');
+
+    expect(price?.sources.map(o => [o.source, o.status])).toEqual([
+      ['chainlink', 'used'],
+      ['coingecko', 'stale'],
+      ['uniswap_twap', 'used'],
+    ]);
+    expect(price?.confidence).toBe('high');
+  });
+
+  it('records errors and timeouts without failing the aggregate', async () => {
+    const price = await newOracle(
+      fakeSource('ok', 3000),
+      failingSource('down', async () => { throw new Error('503'); }),
+      failingSource('slow', () => new Promise(() => {})),
+    ).aggregate('ETH');
+
+    expect(price?.priceUsd).toBe(3000);
+    expect(price?.sources.find(o => o.source === 'down')).toMatchObject({ status: 'error', error: '503' });
+    expect(price?.sources.find(o => o.source === 'slow')?.error).toMatch(/timed out/);
+  });
+
+  it('returns null when no source covers the symbol or none answers', async () => {
+    expect(await newOracle(fakeSource('a', 3000)).aggregate('BTC')).toBeNull();
+    expect(await newOracle(failingSource('down', async () => { throw new Error('503'); })).aggregate('ETH')).toBeNull();
+  });
+});
+
+describe('helpers', () => {
+  it('ranks confidence levels', () => {
+    expect(meetsConfidence('high', 'medium')).toBe(true);
+    expect(meetsConfidence('low', 'medium')).toBe(false);
+    expect(lowestConfidence(['high', 'low', 'medium'])).toBe('low');
+    expect(lowestConfidence([])).toBeUndefined();
+  });
+
+  it('parses symbol lists and skips malformed entries', () => {
+    const parsed = parseSymbolList('jup:JUPyi:6, bad, wif:abc');
+    expect(parsed.get('JUP')).toEqual(['JUPyi', '6']);
+    expect(parsed.get('WIF')).toEqual(['abc']);
+    expect(parsed.has('BAD')).toBe(false);
+  });
+
+  it('parses per-source max ages and skips malformed entries', () => {
+    expect(parseSourceMaxAges('CoinGecko:300, chainlink:abc, dflow:0, uniswap_twap:900')).toEqual({
+      coingecko: 300,
+      uniswap_twap: 900,
+    });
+    expect(parseSourceMaxAges('')).toEqual({});
+  });
+});
+
+describe('getPrice', () => {
+  beforeEach(() => {
+    resetPriceOracle();
+  });
+
+  it('uses registered sources and normalizes the symbol', async () => {
+    registerPriceSource(fakeSource('a', 3100));
+    registerPriceSource(fakeSource('b', 3102));
+
+    const snapshot = await getPrice('eth');
+    expect(snapshot.symbol).toBe('ETH');
+    expect(snapshot.source).toBe('median');
+    expect(snapshot.confidence).toBe('high');
+  });
+
+  it('falls back to the static table with low confidence', async () => {
+    const snapshot = await getPrice('BTC');
+    expect(snapshot.source).toBe('static');
+    expect(snapshot.confidence).toBe('low');
+  });
+
+  it('throws PriceUnavailableError for unknown symbols', async () => {
+    expect(hasPriceFeed('DOGE')).toBe(false);
+    await expect(getPrice('DOGE')).rejects.toBeInstanceOf(PriceUnavailableError);
+
+    registerPriceSource(fakeSource('a', 0.12, { symbols: ['DOGE'] }));
+    expect(hasPriceFeed('doge')).toBe(true);
+    expect((await getPrice('DOGE')).priceUsd).toBe(0.12);
+  });
+});
@@ -0,0 +1,156 @@
+/**
+ * Sim Account Store Tests
+ * The ledger runs against an in-memory SQLite database; prices are fixed.
+ */
+
+import { describe, it, expect, afterEach, vi } from 'vitest';
+
+vi.hoisted(() => {
+  process.env.EXECUTION_LEDGER_DB_PATH = ':memory:';
+  process.env.SIM_MAX_LOADED_ACCOUNTS = '3';
+});
+
+vi.mock('../prices', () => ({
+  getPrice: async (symbol: string) => ({ symbol, priceUsd: symbol === 'BTC' ? 60000 : 3000, source: 'static' }),
+}));
+vi.mock('../funding', () => ({
+  computeFundingPaymentUsd: () => 0,
+}));
+
+import {
+  ANONYMOUS_SIM_USER,
+  SIM_SESSION_COOKIE,
+  normalizeSimUserId,
+  resolveSimUserId,
+  getSimState,
+  loadSimAccount,
+  persistSimAccount,
+  markSimDirty,
+  listLoadedSimUsers,
+  hydrateRetainedSimAccounts,
+} from '../simStore';
+import * as perpsSim from '../../plugins/perps-sim';
+import { getSimAccount, upsertSimAccount } from '../../../execution-ledger/db';
+
+const WALLET = '0x00000000000000000000000000000000000000AA';
+
+const SESSION = '0b5e4c1a-7d2f-4e3b-9a61-2c8f0d4e5a7b';
+
+describe('resolveSimUserId', () => {
+  it('uses the signed-in wallet, then the server-issued session cookie', () => {
+    const cookies = { [SIM_SESSION_COOKIE]: SESSION };
+    expect(resolveSimUserId({ cookies }, WALLET)).toBe(WALLET.toLowerCase());
+    expect(resolveSimUserId({ cookies }, null)).toBe(`session:${SESSION}`);
+  });
+
+  it('ignores client-supplied identities and malformed session cookies', () => {
+    const req = {
+      headers: { 'x-wallet-address': WALLET, 'x-session-id': 'session-1' },
+      body: { userAddress: WALLET },
+      cookies: { [SIM_SESSION_COOKIE]: WALLET },
+    };
+    expect(resolveSimUserId(req, null)).toBe(ANONYMOUS_SIM_USER);
+    expect(normalizeSimUserId('x'.repeat(300))).toHaveLength(128);
+  });
+});
+
+describe('per-user sim state', () => {
+  it('keeps each user on their own perps book', async () => {
+    await perpsSim.openPerp('alice', { market: 'ETH-PERP', side: 'long', riskPct: 10 });
+
+    expect(perpsSim.getUsdcBalance('alice')).toBe(3000);
+    expect(perpsSim.getUsdcBalance('bob')).toBe(4000);
+    expect(perpsSim.getPerpsSnapshot('bob').positions).toHaveLength(0);
+
+    perpsSim.resetPerpsAccount('alice');
+    expect(perpsSim.getUsdcBalance('alice')).toBe(4000);
+  });
+
+  it('persists state to the ledger and hydrates it for a new process', async () => {
+    getSimState('defi', 'carol', () => ({ positions: ['vault'] }));
+    await persistSimAccount('carol');
+    expect(JSON.parse(getSimAccount('carol')!.state_json)).toEqual({ defi: { positions: ['vault'] } });
+
+    upsertSimAccount('dave', JSON.stringify({ event: { stakes: 2 } }));
+    await loadSimAccount('dave');
+    expect(getSimState('event', 'dave', () => ({ stakes: 0 }))).toEqual({ stakes: 2 });
+  });
+
+  it('keeps in-memory state touched before hydration finished', async () => {
+    upsertSimAccount('erin', JSON.stringify({ defi: { positions: ['stale'] } }));
+    getSimState('defi', 'erin', () => ({ positions: ['fresh'] }));
+
+    await loadSimAccount('erin');
+
+    expect(getSimState('defi', 'erin', () => ({ positions: [] }))).toEqual({ positions: ['fresh'] });
+  });
+});
+
+describe('loaded account eviction', () => {
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it('drops the least recently used account beyond the cap', async () => {
+    for (const userId of ['lru-1', 'lru-2', 'lru-3']) {
+      await loadSimAccount(userId);
+      getSimState('defi', userId, () => ({ positions: [userId] }));
+    }
+    // Touching lru-1 again leaves lru-2 as the oldest
+    await loadSimAccount('lru-1');
+
+    await loadSimAccount('lru-4');
+
+    expect(listLoadedSimUsers()).not.toContain('lru-2');
+    expect(listLoadedSimUsers()).toEqual(expect.arrayContaining(['lru-1', 'lru-3', 'lru-4']));
+  });
+
+  it('writes pending state before dropping an idle account, then hydrates it again', async () => {
+    vi.useFakeTimers({ toFake: ['Date'] });
+    await loadSimAccount('idle');
+    getSimState('defi', 'idle', () => ({ positions: ['kept'] }));
+    markSimDirty('idle');
+
+    vi.setSystemTime(Date.now() + 31 * 60 * 1000);
+    await loadSimAccount('active');
+
+    expect(listLoadedSimUsers()).not.toContain('idle');
+    await vi.waitFor(() => expect(getSimAccount('idle')).toBeTruthy());
+    await loadSimAccount('idle');
+    expect(getSimState('defi', 'idle', () => ({ positions: [] }))).toEqual({ positions: ['kept'] });
+  });
+});
+
+describe('accounts with open perps', () => {
+  const openBook = (isClosed: boolean) => JSON.stringify({
+    perps: { accountValueUsd: 10000, balances: [], positions: [{ id: 'p1', market: 'ETH-PERP', isClosed }] },
+  });
+
+  afterEach(() => {
+    vi.useRealTimers();
+  });
+
+  it('hydrates persisted accounts with open positions at boot', async () => {
+    upsertSimAccount('boot-open', openBook(false));
+    upsertSimAccount('boot-closed', openBook(true));
+
+    const hydrated = await hydrateRetainedSimAccounts();
+
+    expect(hydrated).toContain('boot-open');
+    expect(hydrated).not.toContain('boot-closed');
+    expect(listLoadedSimUsers()).toContain('boot-open');
+    expect(listLoadedSimUsers()).not.toContain('boot-closed');
+    expect(perpsSim.getOpenPerpSymbols('boot-open')).toEqual(['ETH']);
+  });
+
+  it('keeps them loaded while idle', async () => {
+    vi.useFakeTimers({ toFake: ['Date'] });
+    upsertSimAccount('idle-open', openBook(false));
+    await loadSimAccount('idle-open');
+
+    vi.setSystemTime(Date.now() + 31 * 60 * 1000);
+    await loadSimAccount('someone-else');
+
+    expect(listLoadedSimUsers()).toContain('idle-open');
+  });
+});
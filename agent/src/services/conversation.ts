@@ -0,0 +1,380 @@
+/**
+ * Conversation Memory
+ * Per-user chat threads for /api/chat, persisted in the execution ledger
+ * (chat_threads / chat_messages).
+ *
+ * Each request continues a thread: the one named by threadId, or the user's
+ * latest thread if it was active within CHAT_THREAD_IDLE_SECONDS. Threads are
+ * owned by the signed-in wallet (utils/walletAuth.ts); everyone else shares
+ * ANONYMOUS_CHAT_USER and only keeps a thread by sending its threadId. The last
+ * CHAT_HISTORY_MESSAGES messages are sent to the model verbatim together with
+ * what the assistant proposed (actions, execution request, draft/position ids),
+ * so follow-ups like "close half of that" can be resolved. Once a thread has
+ * more than CHAT_SUMMARIZE_AFTER_MESSAGES unsummarized messages, older ones are
+ * folded into a rolling summary.
+ *
+ * Uses dynamic imports for the ledger (same as simStore.ts); without it chat
+ * works as before, just without memory.
+ */
+
+import {
+  CHAT_HISTORY_MESSAGES,
+  CHAT_SUMMARIZE_AFTER_MESSAGES,
+  CHAT_THREAD_IDLE_SECONDS,
+} from '../config';
+import { ANONYMOUS_SIM_USER } from './simStore';
+
+// Row shapes mirror execution-ledger/db.ts (kept local to avoid rootDir issues)
+export interface ChatThread {
+  id: string;
+  user_id: string;
+  title: string | null;
+  summary: string | null;
+  summarized_seq: number;
+  message_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+interface ChatMessage {
+  seq: number;
+  role: 'user' | 'assistant';
+  content: string;
+  actions_json: string | null;
+  execution_request_json: string | null;
+  refs_json: string | null;
+}
+
+export interface ChatProposal {
+  actions: any[];
+  executionRequest: any | null;
+  draftId?: string;
+  positions: { type?: string; positionId?: string; side?: string; sizeUsd?: number }[];
+}
+
+export interface ConversationContext {
+  threadId: string;
+  prompt: string;                    // Block for the model; empty for a new thread
+  lastProposal: ChatProposal | null; // Most recent assistant turn that proposed something
+  lastProposalRequest: string | null; // The user message that led to it
+}
+
+// Chat response fields recorded for a turn
+export interface ChatTurn {
+  assistantMessage?: string;
+  actions?: any[];
+  executionRequest?: any | null;
+  executionResults?: { positionDelta?: ChatProposal['positions'][number] }[];
+  draftId?: string;
+}
+
+// Owner of threads started without a signed-in wallet
+export const ANONYMOUS_CHAT_USER = ANONYMOUS_SIM_USER;
+
+const SUMMARY_MAX_LINES = 40;
+const SUMMARY_LINE_CHARS = 160;
+const VERBATIM_MESSAGE_CHARS = 1200;
+
+// Lazy-loaded ledger module (use any to avoid rootDir issues with typeof import)
+let ledgerDb: any = null;
+
+async function getLedgerDb() {
+  if (!ledgerDb) {
+    ledgerDb = await import('../../execution-ledger/db');
+  }
+  return ledgerDb;
+}
+
+function truncate(text: string, max: number): string {
+  const flat = text.replace(/\s+/g, ' ').trim();
+  return flat.length > max ? `${flat.slice(0, max - 1)}…` : flat;
+}
+
+function parseJson<T>(raw: string | null): T | null {
+  if (!raw) return null;
+  try {
+    return JSON.parse(raw) as T;
+  } catch {
+    return null;
+  }
+}
+
+function proposalOf(message: ChatMessage): ChatProposal | null {
+  const actions = parseJson<any[]>(message.actions_json) ?? [];
+  const executionRequest = parseJson<any>(message.execution_request_json);
+  const refs = parseJson<{ draftId?: string; positions?: ChatProposal['positions'] }>(message.refs_json);
+  if (actions.length === 0 && !executionRequest && !refs?.draftId && !refs?.positions?.length) {
+    return null;
+  }
+  return { actions, executionRequest, draftId: refs?.draftId, positions: refs?.positions ?? [] };
+}
+
+/**
+ * One-line description of a proposal, e.g. "perp open ETH-PERP long 3% risk"
+ */
+export function describeProposal(proposal: ChatProposal): string {
+  const parts: string[] = [];
+  for (const action of proposal.actions) {
+    if (action.type === 'perp') {
+      parts.push(`perp ${action.action} ${action.market} ${action.side} ${action.riskPct}% risk`);
+    } else if (action.type === 'defi') {
+      parts.push(`defi ${action.action} $${action.amountUsd} ${action.asset} into ${action.protocol}`);
+    } else if (action.type === 'event') {
+      parts.push(`event ${action.action} ${action.side} on "${action.label}" $${action.stakeUsd}`);
+    }
+  }
+  const req = proposal.executionRequest;
+  if (req) {
+    if (req.kind === 'swap') {
+      parts.push(`execution: swap ${req.amountIn} ${req.tokenIn} → ${req.tokenOut}`);
+    } else if (req.kind === 'perp') {
+      parts.push(`execution: perp ${req.market} ${req.side}${req.leverage ? ` ${req.leverage}x` : ''}`);
+    } else if (req.kind === 'lend' || req.kind === 'lend_supply') {
+      parts.push(`execution: lend ${req.amount} ${req.asset}${req.vault ? ` in ${req.vault}` : ''}`);
+    } else if (req.kind === 'event') {
+      parts.push(`execution: event ${req.marketId} ${req.outcome} $${req.stakeUsd}`);
+    } else {
+      parts.push(`execution: ${req.kind}`);
+    }
+  }
+  if (proposal.draftId) {
+    parts.push(`draft ${proposal.draftId}`);
+  }
+  for (const position of proposal.positions) {
+    if (position.positionId) {
+      parts.push(`position ${position.positionId} (${[position.type, position.side, position.sizeUsd ? `$${position.sizeUsd}` : ''].filter(Boolean).join(' ')})`);
+    }
+  }
+  return parts.join('; ');
+}
+
+function summaryLine(message: ChatMessage): string {
+  const speaker = message.role === 'user' ? 'User' : 'Blossom';
+  const proposal = message.role === 'assistant' ? proposalOf(message) : null;
+  const text = truncate(message.content, SUMMARY_LINE_CHARS);
+  return proposal ? `- ${speaker}: ${text} [proposed: ${describeProposal(proposal)}]` : `- ${speaker}: ${text}`;
+}
+
+/**
+ * Find or start the thread for a request
+ * A threadId that is unknown or belongs to another user starts a new thread.
+ * Anonymous visitors all share ANONYMOUS_CHAT_USER, so their threads are only
+ * continued by explicit threadId (the random id is the capability) and never
+ * auto-resumed or listed.
+ */
+export async function resolveChatThread(
+  userId: string,
+  options: { threadId?: string; newThread?: boolean } = {}
+): Promise<ChatThread | null> {
+  try {
+    const db = await getLedgerDb();
+
+    if (options.threadId && !options.newThread) {
+      const thread = db.getChatThread(options.threadId) as ChatThread | undefined;
+      if (thread && thread.user_id === userId) {
+        return thread;
+      }
+    } else if (!options.newThread && userId !== ANONYMOUS_CHAT_USER) {
+      const latest = db.getLatestChatThread(userId) as ChatThread | undefined;
+      const now = Math.floor(Date.now() / 1000);
+      if (latest && now - latest.updated_at <= CHAT_THREAD_IDLE_SECONDS) {
+        return latest;
+      }
+    }
+
+    return db.createChatThread({ userId }) as ChatThread;
+  } catch (error: any) {
+    console.warn('[conversation] Thread unavailable, continuing without memory:', error.message);
+    return null;
+  }
+}
+
+/**
+ * Build the conversation block for the prompt and find the last proposal
+ */
+export async function loadConversationContext(thread: ChatThread): Promise<ConversationContext> {
+  const context: ConversationContext = { threadId: thread.id, prompt: '', lastProposal: null, lastProposalRequest: null };
+
+  let messages: ChatMessage[];
+  try {
+    const db = await getLedgerDb();
+    messages = db.listChatMessages(thread.id, { afterSeq: thread.summarized_seq });
+  } catch (error: any) {
+    console.warn('[conversation] Failed to load messages:', error.message);
+    return context;
+  }
+  if (messages.length === 0 && !thread.summary) {
+    return context;
+  }
+
+  for (let i = messages.length - 1; i >= 0; i--) {
+    if (messages[i].role !== 'assistant') continue;
+    const proposal = proposalOf(messages[i]);
+    if (!proposal) continue;
+    context.lastProposal = proposal;
+    const request = messages.slice(0, i).reverse().find(m => m.role === 'user');
+    context.lastProposalRequest = request?.content ?? null;
+    break;
+  }
+
+  const verbatim = messages.slice(-CHAT_HISTORY_MESSAGES);
+  const earlier = [
+    ...(thread.summary ? thread.summary.split('\n') : []),
+    ...messages.slice(0, messages.length - verbatim.length).map(summaryLine),
+  ];
+
+  let prompt = `**Conversation So Far** (oldest first - resolve "that", "it", "same thing", "half of that" etc. against it):\n`;
+  if (earlier.length > 0) {
+    prompt += `Earlier:\n${earlier.join('\n')}\n`;
+  }
+  for (const message of verbatim) {
+    const speaker = message.role === 'user' ? 'User' : 'Blossom';
+    prompt += `${speaker}: ${truncate(message.content, VERBATIM_MESSAGE_CHARS)}\n`;
+    const proposal = message.role === 'assistant' ? proposalOf(message) : null;
+    if (proposal) {
+      prompt += `  (proposed: ${describeProposal(proposal)})\n`;
+    }
+  }
+  if (context.lastProposal) {
+    prompt += `\n**Most Recent Proposal:**\n${JSON.stringify({
+      actions: context.lastProposal.actions,
+      executionRequest: context.lastProposal.executionRequest,
+      draftId: context.lastProposal.draftId,
+      positions: context.lastProposal.positions,
+    })}\n`;
+  }
+  context.prompt = `${prompt}\n`;
+  return context;
+}
+
+const ASSET_RE = /^[a-z][a-z0-9]{1,9}$/i;
+const SAME_BUT_RE = /^\s*(?:(?:do|try)\s+)?(?:the\s+)?same(?:\s+(?:thing|trade|again))?(?:\s+again)?\s*,?\s*(?:but\s+)?(?:on|for|with|in|using)\s+\$?([a-z][a-z0-9]*)(?:-perp)?\s*[.!?]*\s*$/i;
+
+/**
+ * Expand "same thing but on SOL" into the previous request with the asset swapped,
+ * so the deterministic planners see a complete request. Anything else is returned unchanged.
+ */
+export function resolveFollowUp(message: string, context: ConversationContext | null): string {
+  if (!context?.lastProposal || !context.lastProposalRequest) return message;
+
+  const match = SAME_BUT_RE.exec(message);
+  if (!match || !ASSET_RE.test(match[1])) return message;
+  const target = match[1].toUpperCase();
+
+  // Assets the previous proposal was about, most specific first
+  const { actions, executionRequest } = context.lastProposal;
+  const candidates = [
+    ...actions.map(a => (a.market ? String(a.market).split('-')[0] : a.asset)),
+    executionRequest?.market ? String(executionRequest.market).split('-')[0] : undefined,
+    executionRequest?.tokenOut,
+    executionRequest?.tokenIn,
+    executionRequest?.asset,
+  ].filter((symbol): symbol is string => typeof symbol === 'string' && symbol.length > 0);
+
+  for (const symbol of candidates) {
+    const word = `\\b${symbol.replace(/[^a-z0-9]/gi, '')}\\b`;
+    if (new RegExp(word, 'i').test(context.lastProposalRequest)) {
+      return context.lastProposalRequest.replace(new RegExp(word, 'gi'), target);
+    }
+  }
+  return message;
+}
+
+/**
+ * Persist the user message and the assistant's response, then summarize if long
+ * Fail open: memory problems never break the chat response
+ */
+export async function recordChatTurn(thread: ChatThread, userMessage: string, turn: ChatTurn): Promise<void> {
+  try {
+    const db = await getLedgerDb();
+    db.appendChatMessage({ threadId: thread.id, role: 'user', content: userMessage });
+
+    const positions = (turn.executionResults ?? [])
+      .map(result => result.positionDelta)
+      .filter((delta): delta is NonNullable<typeof delta> => !!delta?.positionId)
+      .map(delta => ({ type: delta.type, positionId: delta.positionId, side: delta.side, sizeUsd: delta.sizeUsd }));
+    db.appendChatMessage({
+      threadId: thread.id,
+      role: 'assistant',
+      content: turn.assistantMessage || '',
+      actions: turn.actions ?? [],
+      executionRequest: turn.executionRequest ?? undefined,
+      refs: turn.draftId || positions.length > 0 ? { draftId: turn.draftId, positions } : undefined,
+    });
+
+    if (!db.getChatThread(thread.id)?.title) {
+      db.updateChatThread(thread.id, { title: truncate(userMessage, 80) });
+    }
+    await summarizeThread(thread.id);
+  } catch (error: any) {
+    console.warn('[conversation] Failed to record turn:', error.message);
+  }
+}
+
+/**
+ * Fold messages older than the verbatim window into the thread summary
+ */
+export async function summarizeThread(threadId: string): Promise<boolean> {
+  const db = await getLedgerDb();
+  const thread = db.getChatThread(threadId) as ChatThread | undefined;
+  if (!thread || thread.message_count - thread.summarized_seq <= CHAT_SUMMARIZE_AFTER_MESSAGES) {
+    return false;
+  }
+
+  const foldThrough = thread.message_count - CHAT_HISTORY_MESSAGES;
+  const messages = (db.listChatMessages(threadId, { afterSeq: thread.summarized_seq }) as ChatMessage[])
+    .filter(message => message.seq <= foldThrough);
+  if (messages.length === 0) return false;
+
+  const lines = [...(thread.summary ? thread.summary.split('\n') : []), ...messages.map(summaryLine)];
+  db.updateChatThread(threadId, {
+    summary: lines.slice(-SUMMARY_MAX_LINES).join('\n'),
+    summarized_seq: foldThrough,
+  });
+  return true;
+}
+
+// ============================================
+// Thread views for /api/chat/threads
+// ============================================
+
+export async function listUserThreads(userId: string, limit = 20): Promise<ChatThread[]> {
+  if (userId === ANONYMOUS_CHAT_USER) return [];
+  const db = await getLedgerDb();
+  return db.listChatThreads(userId, limit);
+}
+
+/**
+ * Thread with its messages, or null if it does not exist or belongs to another user
+ */
+export async function getUserThread(userId: string, threadId: string) {
+  if (userId === ANONYMOUS_CHAT_USER) return null;
+  const db = await getLedgerDb();
+  const thread = db.getChatThread(threadId) as ChatThread | undefined;
+  if (!thread || thread.user_id !== userId) return null;
+
+  const messages = (db.listChatMessages(threadId) as (ChatMessage & { id: string; created_at: number })[]).map(message => {
+    const refs = parseJson<{ draftId?: string; positions?: ChatProposal['positions'] }>(message.refs_json);
+    return {
+      id: message.id,
+      seq: message.seq,
+      role: message.role,
+      content: message.content,
+      actions: parseJson<any[]>(message.actions_json) ?? [],
+      executionRequest: parseJson<any>(message.execution_request_json),
+      draftId: refs?.draftId,
+      positions: refs?.positions ?? [],
+      createdAt: message.created_at,
+    };
+  });
+  return { thread, messages };
+}
+
+export async function deleteUserThread(userId: string, threadId: string): Promise<boolean> {
+  if (userId === ANONYMOUS_CHAT_USER) return false;
+  const db = await getLedgerDb();
+  const thread = db.getChatThread(threadId) as ChatThread | undefined;
+  if (!thread || thread.user_id !== userId) return false;
+  db.deleteChatThread(threadId);
+  return true;
+}
@@ -0,0 +1,254 @@
+/**
+ * Execution Reconciler
+ * Background loop that re-checks ledger executions against on-chain receipts.
+ *
+ * Rows left in pending/submitted (e.g. the executor's receipt wait timed out)
+ * get their final status, gas_used and block_number filled in. Confirmed rows
+ * are re-checked until they are final, so a tx that disappears, moves block or
+ * flips to reverted is caught. Anything where the ledger disagreed with the
+ * chain is recorded in execution_discrepancies for the report endpoint.
+ */
+
+import { lookupTxReceipt, TxLookupResult } from '../executors/txReceipts';
+import { logEvent } from '../telemetry/logger';
+import {
+  RECONCILER_INTERVAL_MS,
+  RECONCILER_BATCH_SIZE,
+  RECONCILER_MIN_AGE_SECONDS,
+  RECONCILER_DROP_AFTER_SECONDS,
+  RECONCILER_REORG_WINDOW_SECONDS,
+  RECONCILER_EVM_FINALITY_BLOCKS,
+} from '../config';
+
+// Networks the configured RPC clients can see
+const RECONCILABLE_NETWORKS = new Set(['sepolia', 'devnet']);
+
+export interface ReconcileCycleResult {
+  checked: number;
+  updated: number;
+  discrepancies: number;
+  errors: number;
+  ranAt: number;
+}
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+let lastCycle: ReconcileCycleResult | null = null;
+
+async function getLedgerDb() {
+  return import('../../execution-ledger/db');
+}
+
+async function getEvmHead(): Promise<bigint> {
+  const { createFailoverPublicClient } = await import('../providers/rpcProvider');
+  return createFailoverPublicClient().getBlockNumber();
+}
+
+/**
+ * Reconcile one execution row
+ * Returns whether the row's status or receipt fields changed and any discrepancy raised
+ */
+async function reconcileExecution(
+  execution: any,
+  now: number,
+  evmHead: () => Promise<bigint>
+): Promise<{ updated: boolean; discrepancy?: string }> {
+  const { updateExecution, recordExecutionDiscrepancy } = await getLedgerDb();
+  const age = now - execution.created_at;
+
+  const flag = (kind: any, chainStatus: string, detail: string) => {
+    recordExecutionDiscrepancy({
+      executionId: execution.id,
+      chain: execution.chain,
+      txHash: execution.tx_hash,
+      kind,
+      ledgerStatus: execution.status,
+      chainStatus,
+      detail,
+    });
+    logEvent('execution_discrepancy', {
+      txHash: execution.tx_hash,
+      executionKind: execution.kind,
+      venue: execution.venue,
+      notes: [`execution: ${execution.id}`, `discrepancy: ${kind}`, `ledger: ${execution.status}`, `chain: ${chainStatus}`],
+    });
+    console.warn(`[reconciler] ${kind}: execution ${execution.id.slice(0, 8)} (${detail})`);
+    return kind;
+  };
+
+  if (!RECONCILABLE_NETWORKS.has(execution.network)) {
+    updateExecution(execution.id, { reconciledAt: now });
+    return { updated: false };
+  }
+
+  // Never broadcast: nothing to look up, fail once it is clearly abandoned
+  if (!execution.tx_hash) {
+    if (age < RECONCILER_DROP_AFTER_SECONDS) {
+      updateExecution(execution.id, { reconciledAt: now });
+      return { updated: false };
+    }
+    updateExecution(execution.id, {
+      status: 'failed',
+      errorCode: 'TX_NOT_SUBMITTED',
+      errorMessage: `No transaction recorded after ${Math.round(age / 60)} minutes`,
+      reconciledAt: now,
+    });
+    return { updated: true, discrepancy: flag('missing_tx_hash', 'not_found', 'no tx hash recorded') };
+  }
+
+  const lookup: TxLookupResult = await lookupTxReceipt(execution.chain, execution.tx_hash);
+  const wasConfirmed = execution.status === 'confirmed';
+
+  switch (lookup.status) {
+    case 'success': {
+      let discrepancy: string | undefined;
+      if (wasConfirmed && execution.block_number && lookup.blockNumber && execution.block_number !== lookup.blockNumber) {
+        discrepancy = flag('reorged', 'success', `re-included at block ${lookup.blockNumber} (was ${execution.block_number})`);
+      }
+      const finalized = execution.chain === 'solana'
+        ? lookup.finalized === true
+        : lookup.blockNumber !== undefined && (await evmHead()) - BigInt(lookup.blockNumber) >= BigInt(RECONCILER_EVM_FINALITY_BLOCKS);
+      const status = finalized ? 'finalized' : 'confirmed';
+      const changed = status !== execution.status
+        || lookup.blockNumber !== execution.block_number
+        || (lookup.gasUsed !== undefined && lookup.gasUsed !== execution.gas_used);
+      updateExecution(execution.id, {
+        status,
+        gasUsed: lookup.gasUsed,
+        blockNumber: lookup.blockNumber,
+        reconciledAt: now,
+      });
+      return { updated: changed, discrepancy };
+    }
+
+    case 'reverted': {
+      const discrepancy = wasConfirmed
+        ? flag('reverted_after_confirm', 'reverted', lookup.error || 'receipt now reports reverted')
+        : undefined;
+      updateExecution(execution.id, {
+        status: 'failed',
+        errorCode: 'TX_REVERTED',
+        errorMessage: lookup.error || 'Transaction reverted on-chain',
+        gasUsed: lookup.gasUsed,
+        blockNumber: lookup.blockNumber,
+        reconciledAt: now,
+      });
+      return { updated: true, discrepancy };
+    }
+
+    case 'pending':
+    case 'not_found': {
+      if (wasConfirmed) {
+        // Receipt vanished: back to submitted; a later pass confirms it or flags it dropped
+        const discrepancy = flag('reorged', lookup.status, lookup.status === 'pending' ? 'receipt gone, tx back in mempool' : 'receipt and tx gone');
+        updateExecution(execution.id, { status: 'submitted', reconciledAt: now });
+        return { updated: true, discrepancy };
+      }
+      if (lookup.status === 'not_found' && age >= RECONCILER_DROP_AFTER_SECONDS) {
+        const discrepancy = flag('dropped', 'not_found', `unknown to the node after ${Math.round(age / 60)} minutes`);
+        updateExecution(execution.id, {
+          status: 'failed',
+          errorCode: 'TX_DROPPED',
+          errorMessage: 'Transaction was dropped and never mined',
+          reconciledAt: now,
+        });
+        return { updated: true, discrepancy };
+      }
+      updateExecution(execution.id, { reconciledAt: now });
+      return { updated: false };
+    }
+  }
+}
+
+/**
+ * Run a single reconciliation pass over unresolved and recently confirmed executions
+ */
+export async function runReconcileCycle(limit: number = RECONCILER_BATCH_SIZE): Promise<ReconcileCycleResult> {
+  const { listExecutionsToReconcile, updateExecution } = await getLedgerDb();
+  const now = Math.floor(Date.now() / 1000);
+  const executions = listExecutionsToReconcile({
+    minAgeSeconds: RECONCILER_MIN_AGE_SECONDS,
+    recheckAfterSeconds: Math.floor(RECONCILER_INTERVAL_MS / 1000),
+    reorgWindowSeconds: RECONCILER_REORG_WINDOW_SECONDS,
+    limit,
+  });
+
+  // Fetch the EVM head at most once per pass
+  let head: Promise<bigint> | null = null;
+  const evmHead = () => (head ??= getEvmHead());
+
+  const result: ReconcileCycleResult = { checked: 0, updated: 0, discrepancies: 0, errors: 0, ranAt: now };
+  for (const execution of executions) {
+    result.checked++;
+    try {
+      const outcome = await reconcileExecution(execution, now, evmHead);
+      if (outcome.updated) result.updated++;
+      if (outcome.discrepancy) result.discrepancies++;
+    } catch (error: any) {
+      // RPC trouble: skip this row until the next recheck instead of blocking the batch
+      result.errors++;
+      updateExecution(execution.id, { reconciledAt: now });
+      console.warn(`[reconciler] Lookup failed for ${execution.id.slice(0, 8)}:`, error.message?.slice(0, 100));
+    }
+  }
+
+  lastCycle = result;
+  if (result.updated > 0 || result.discrepancies > 0) {
+    console.log(`[reconciler] Checked ${result.checked}, updated ${result.updated}, discrepancies ${result.discrepancies}`);
+  }
+  return result;
+}
+
+/**
+ * Start the reconciliation loop
+ */
+export function startExecutionReconciler(intervalMs: number = RECONCILER_INTERVAL_MS): void {
+  if (isRunning) {
+    console.log('[reconciler] Already running');
+    return;
+  }
+
+  console.log(`[reconciler] Starting execution reconciler (every ${intervalMs}ms)`);
+  isRunning = true;
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runReconcileCycle();
+    } catch (error: any) {
+      console.error('[reconciler] Cycle error:', error.message?.slice(0, 100));
+    }
+
+    // Schedule next pass
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  pollTimeout = setTimeout(poll, intervalMs);
+}
+
+/**
+ * Stop the reconciliation loop
+ */
+export function stopExecutionReconciler(): void {
+  console.log('[reconciler] Stopping execution reconciler');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the reconciler is running
+ */
+export function isReconcilerRunning(): boolean {
+  return isRunning;
+}
+
+/**
+ * Result of the most recent pass (null before the first one)
+ */
+export function getLastReconcileCycle(): ReconcileCycleResult | null {
+  return lastCycle;
+}
@@ -0,0 +1,173 @@
+/**
+ * Perp Funding Rates
+ * Hourly funding rate model for sim perps and DemoPerpEngine ledger positions
+ *
+ * Modes (PERPS_FUNDING_MODE):
+ * - fixed:   constant PERPS_FUNDING_RATE_BPS_PER_HOUR for every market
+ * - premium: rate derived from a premium index, either a deterministic mock
+ *            or Hyperliquid's live asset contexts (falls back to mock)
+ * - off:     no funding accrues
+ *
+ * Rates are hourly fractions of notional; positive means longs pay shorts.
+ */
+
+import { PriceSymbol } from './prices';
+import {
+  PERPS_FUNDING_MODE,
+  PERPS_FUNDING_RATE_BPS_PER_HOUR,
+  PERPS_FUNDING_PREMIUM_SOURCE,
+} from '../config';
+
+export interface FundingRateSnapshot {
+  symbol: PriceSymbol;
+  ratePerHour: number;
+  source: 'fixed' | 'mock_premium' | 'hyperliquid';
+  premiumIndex?: number;
+  fetchedAt: number;
+}
+
+const MS_PER_HOUR = 60 * 60 * 1000;
+
+// Standard 8h interest component and premium clamp (0.01% / ±0.05%)
+const INTEREST_RATE_8H = 0.0001;
+const PREMIUM_CLAMP_8H = 0.0005;
+
+// Mock premium index oscillates over a day with a per-symbol phase
+const MOCK_PREMIUM_AMPLITUDE = 0.0008;
+const MOCK_PREMIUM_PERIOD_MS = 24 * MS_PER_HOUR;
+const MOCK_PREMIUM_PHASE: Partial<Record<PriceSymbol, number>> = {
+  BTC: 0,
+  ETH: Math.PI / 3,
+  SOL: (2 * Math.PI) / 3,
+};
+
+// Cache TTL for live funding: 60 seconds
+const CACHE_TTL_MS = 60 * 1000;
+const fundingCache = new Map<PriceSymbol, FundingRateSnapshot>();
+
+const HYPERLIQUID_INFO_URL = 'https://api.hyperliquid.xyz/info';
+
+/**
+ * Check whether funding accrual is enabled
+ */
+export function isFundingEnabled(): boolean {
+  return PERPS_FUNDING_MODE !== 'off';
+}
+
+/**
+ * Convert an 8h premium index into an hourly funding rate
+ */
+export function fundingRateFromPremium(premiumIndex: number): number {
+  const clamped = Math.max(
+    -PREMIUM_CLAMP_8H,
+    Math.min(PREMIUM_CLAMP_8H, INTEREST_RATE_8H - premiumIndex)
+  );
+  return (premiumIndex + clamped) / 8;
+}
+
+/**
+ * Deterministic mock premium index for a symbol at a point in time
+ */
+export function getMockPremiumIndex(symbol: PriceSymbol, now: number = Date.now()): number {
+  const phase = MOCK_PREMIUM_PHASE[symbol] ?? 0;
+  return MOCK_PREMIUM_AMPLITUDE * Math.sin((2 * Math.PI * now) / MOCK_PREMIUM_PERIOD_MS + phase);
+}
+
+/**
+ * Get the current hourly funding rate for a symbol
+ */
+export async function getFundingRate(symbol: PriceSymbol): Promise<FundingRateSnapshot> {
+  const now = Date.now();
+
+  if (PERPS_FUNDING_MODE !== 'premium') {
+    return {
+      symbol,
+      ratePerHour: PERPS_FUNDING_MODE === 'off' ? 0 : PERPS_FUNDING_RATE_BPS_PER_HOUR / 10000,
+      source: 'fixed',
+      fetchedAt: now,
+    };
+  }
+
+  if (PERPS_FUNDING_PREMIUM_SOURCE === 'hyperliquid') {
+    const cached = fundingCache.get(symbol);
+    if (cached && now - cached.fetchedAt < CACHE_TTL_MS) {
+      return cached;
+    }
+
+    try {
+      const snapshot = await fetchFromHyperliquid(symbol);
+      fundingCache.set(symbol, snapshot);
+      return snapshot;
+    } catch (error: any) {
+      console.warn(`[funding] Hyperliquid funding fetch failed for ${symbol}, using mock premium:`, error.message);
+    }
+  }
+
+  const premiumIndex = getMockPremiumIndex(symbol, now);
+  return {
+    symbol,
+    ratePerHour: fundingRateFromPremium(premiumIndex),
+    source: 'mock_premium',
+    premiumIndex,
+    fetchedAt: now,
+  };
+}
+
+/**
+ * Fetch the live hourly funding rate and premium from Hyperliquid
+ */
+async function fetchFromHyperliquid(symbol: PriceSymbol): Promise<FundingRateSnapshot> {
+  const controller = new AbortController();
+  const timeoutId = setTimeout(() => controller.abort(), 5000);
+
+  try {
+    const response = await fetch(HYPERLIQUID_INFO_URL, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ type: 'metaAndAssetCtxs' }),
+      signal: controller.signal,
+    });
+
+    if (!response.ok) {
+      throw new Error(`Hyperliquid API returned ${response.status}`);
+    }
+
+    const [meta, assetCtxs] = await response.json() as [
+      { universe: { name: string }[] },
+      { funding?: string; premium?: string | null }[]
+    ];
+    const index = meta?.universe?.findIndex(asset => asset.name === symbol) ?? -1;
+    const ctx = index >= 0 ? assetCtxs?.[index] : undefined;
+    const ratePerHour = ctx?.funding !== undefined ? parseFloat(ctx.funding) : NaN;
+    if (!Number.isFinite(ratePerHour)) {
+      throw new Error(`No funding data for ${symbol}`);
+    }
+
+    return {
+      symbol,
+      ratePerHour,
+      source: 'hyperliquid',
+      premiumIndex: ctx?.premium ? parseFloat(ctx.premium) : undefined,
+      fetchedAt: Date.now(),
+    };
+  } finally {
+    clearTimeout(timeoutId);
+  }
+}
+
+/**
+ * Funding owed on a position over an elapsed window
+ * Positive = paid by the position, negative = received
+ */
+export function computeFundingPaymentUsd(
+  notionalUsd: number,
+  side: 'long' | 'short',
+  ratePerHour: number,
+  elapsedMs: number
+): number {
+  if (notionalUsd <= 0 || elapsedMs <= 0) {
+    return 0;
+  }
+  const paymentUsd = notionalUsd * ratePerHour * (elapsedMs / MS_PER_HOUR);
+  return side === 'long' ? paymentUsd : -paymentUsd;
+}
@@ -1,10 +1,48 @@
 /**
  * LLM Client Service
- * Supports OpenAI, Anthropic, or stub mode
+ * Supports OpenAI, Anthropic, Gemini, or stub mode
+ *
+ * Providers are tried in the order of BLOSSOM_MODEL_PROVIDERS: a provider that
+ * errors, times out or is rate limited hands over to the next one. Responses are
+ * cached per prompt + portfolio, per-user token budgets are enforced, and every
+ * call's tokens and estimated cost go to telemetry (see llmUsage.ts).
+ *
+ * OpenAI and Anthropic are called with the Blossom response declared as a tool
+ * (see llmTools.ts) and return its arguments as toolCall. Gemini, stub, and
+ * BLOSSOM_LLM_TOOLS=false use the JSON-in-text path. Either way rawJson holds
+ * the response object as a JSON string.
+ *
+ * Pass onToken to stream: OpenAI and Anthropic responses are read as they are
+ * generated and the assistantMessage text inside the JSON is forwarded as it
+ * arrives. Gemini and stub responses are forwarded in one piece.
  */
 
 import OpenAI from 'openai';
 import Anthropic from '@anthropic-ai/sdk';
+import {
+  BLOSSOM_RESPONSE_TOOL_NAME,
+  openAIResponseTool,
+  anthropicResponseTool,
+  type BlossomToolCall,
+} from './llmTools';
+import {
+  getProviderChain,
+  providerTimeoutMs,
+  isCoolingDown,
+  startCooldown,
+  assertWithinBudget,
+  buildCacheKey,
+  getCachedResponse,
+  setCachedResponse,
+  estimateCostUsd,
+  type ModelProvider,
+  type LlmUsage,
+  type LlmCacheKeyInput,
+  type LlmBudgetScope,
+} from './llmUsage';
+import { logEvent, trackLlmUsage, hashAddress } from '../telemetry/logger';
+
+export { LlmBudgetExceededError } from './llmUsage';
 
 export interface LlmChatInput {
   systemPrompt: string;
@@ -14,78 +52,375 @@ export interface LlmChatInput {
 export interface LlmChatOutput {
   assistantMessage: string;
   rawJson: string; // the JSON the model returned for actions
+  toolCall?: BlossomToolCall; // Structured arguments when the provider called the response tool
+  provider?: ModelProvider;
+  model?: string;
+  usage?: LlmUsage;
+  cached?: boolean;
+}
+
+export interface LlmCallOptions {
+  onToken?: (text: string) => void; // Decoded assistantMessage text, in order
+  userId?: string;                  // Caller identity; enables the token budget and usage attribution
+  budgetScope?: LlmBudgetScope;     // 'ip' for unauthenticated callers (default 'user')
+  correlationId?: string;
+  cacheKey?: LlmCacheKeyInput;      // Responses are only cached when set
+}
+
+// Passed to each provider call; the signal aborts it at the provider timeout
+interface ProviderCallOptions extends LlmCallOptions {
+  signal?: AbortSignal;
+}
+
+/**
+ * Provider failure that keeps the HTTP status for failover decisions
+ */
+class LlmProviderError extends Error {
+  constructor(message: string, public readonly status?: number, public readonly retryAfterSeconds?: number) {
+    super(message);
+    this.name = 'LlmProviderError';
+  }
+}
+
+function providerError(label: string, error: any): LlmProviderError {
+  const headers = error?.headers;
+  const retryAfter = typeof headers?.get === 'function' ? headers.get('retry-after') : headers?.['retry-after'];
+  return new LlmProviderError(
+    `${label} API error: ${error?.message}`,
+    typeof error?.status === 'number' ? error.status : undefined,
+    retryAfter ? parseInt(retryAfter, 10) || undefined : undefined
+  );
+}
+
+/**
+ * Pulls the assistantMessage string out of a JSON object that arrives in chunks
+ * push() returns the newly decoded text (possibly empty); escapes split across
+ * chunks are held back until complete
+ */
+export class AssistantMessageStream {
+  private buffer = '';
+  private start = -1;      // Index just after the opening quote of the value
+  private cursor = 0;      // Next unread index in buffer
+  private done = false;
+
+  push(chunk: string): string {
+    this.buffer += chunk;
+    if (this.done) return '';
+
+    if (this.start === -1) {
+      const match = /"assistantMessage"\s*:\s*"/.exec(this.buffer);
+      if (!match) return '';
+      this.start = match.index + match[0].length;
+      this.cursor = this.start;
+    }
+
+    let out = '';
+    while (this.cursor < this.buffer.length) {
+      const ch = this.buffer[this.cursor];
+      if (ch === '"') {
+        this.done = true;
+        break;
+      }
+      if (ch !== '\\') {
+        out += ch;
+        this.cursor++;
+        continue;
+      }
+
+      const next = this.buffer[this.cursor + 1];
+      if (next === undefined) break;
+      if (next === 'u') {
+        const hex = this.buffer.slice(this.cursor + 2, this.cursor + 6);
+        if (hex.length < 4) break;
+        out += String.fromCharCode(parseInt(hex, 16));
+        this.cursor += 6;
+        continue;
+      }
+      const escapes: Record<string, string> = { n: '\n', t: '\t', r: '\r', b: '\b', f: '\f' };
+      out += escapes[next] ?? next;
+      this.cursor += 2;
+    }
+    return out;
+  }
+
+  get raw(): string {
+    return this.buffer;
+  }
 }
 
-type ModelProvider = 'openai' | 'anthropic' | 'gemini' | 'stub';
+const API_KEY_ENV: Record<Exclude<ModelProvider, 'stub'>, string> = {
+  openai: 'BLOSSOM_OPENAI_API_KEY',
+  anthropic: 'BLOSSOM_ANTHROPIC_API_KEY',
+  gemini: 'BLOSSOM_GEMINI_API_KEY',
+};
 
-function getProvider(): ModelProvider {
-  const provider = process.env.BLOSSOM_MODEL_PROVIDER as ModelProvider;
-  if (provider === 'openai' || provider === 'anthropic' || provider === 'gemini') {
-    return provider;
+function isConfigured(provider: ModelProvider): boolean {
+  return provider === 'stub' || !!process.env[API_KEY_ENV[provider]];
+}
+
+/**
+ * Native tool calling is used for providers that support it unless disabled
+ */
+function useTools(provider: ModelProvider): boolean {
+  if (process.env.BLOSSOM_LLM_TOOLS === 'false') return false;
+  return provider === 'openai' || provider === 'anthropic';
+}
+
+/**
+ * Wrap parsed tool arguments as a chat output
+ */
+function toolOutput(name: string, args: string | Record<string, any>): LlmChatOutput {
+  const input = typeof args === 'string' ? JSON.parse(args || '{}') : args;
+  if (!input || typeof input !== 'object' || Array.isArray(input)) {
+    throw new Error(`Tool ${name} returned non-object arguments`);
   }
-  return 'stub';
+  return {
+    assistantMessage: typeof input.assistantMessage === 'string' ? input.assistantMessage : '',
+    rawJson: JSON.stringify(input),
+    toolCall: { name, input },
+  };
 }
 
+const STUB_OUTPUT: LlmChatOutput = {
+  assistantMessage: "This is a stubbed Blossom response. No real AI model is configured. Set BLOSSOM_MODEL_PROVIDER and API keys to enable real AI.",
+  rawJson: JSON.stringify({
+    assistantMessage: "This is a stubbed Blossom response. No real AI model is configured.",
+    actions: []
+  }),
+  provider: 'stub',
+};
+
 /**
  * Call LLM with structured JSON output
+ * Throws LlmBudgetExceededError when the user is over budget, or the last
+ * provider error when every provider in the chain failed
  */
-export async function callLlm(input: LlmChatInput): Promise<LlmChatOutput> {
-  const provider = getProvider();
-  console.log('[llmClient] Using provider:', provider);
+export async function callLlm(input: LlmChatInput, options: LlmCallOptions = {}): Promise<LlmChatOutput> {
+  const chain = getProviderChain();
+  console.log('[llmClient] Provider chain:', chain.join(' → '));
 
-  if (provider === 'stub') {
-    return {
-      assistantMessage: "This is a stubbed Blossom response. No real AI model is configured. Set BLOSSOM_MODEL_PROVIDER and API keys to enable real AI.",
-      rawJson: JSON.stringify({
-        assistantMessage: "This is a stubbed Blossom response. No real AI model is configured.",
-        actions: []
-      })
-    };
+  const cacheKey = options.cacheKey ? buildCacheKey(chain, input.systemPrompt, options.cacheKey) : null;
+  if (cacheKey) {
+    const hit = getCachedResponse<LlmChatOutput>(cacheKey);
+    if (hit) {
+      recordCall(options, { provider: hit.provider ?? 'stub', model: hit.model, cached: true, latencyMs: 0 });
+      return emitWhole({ ...hit, cached: true }, options);
+    }
   }
 
-  if (provider === 'openai') {
-    return callOpenAI(input);
+  const userHash = options.userId ? hashAddress(options.userId) : undefined;
+  if (userHash) {
+    await assertWithinBudget(userHash, options.budgetScope);
+  }
+
+  let lastError: Error | null = null;
+  for (const provider of chain) {
+    if (provider === 'stub') {
+      return emitWhole(STUB_OUTPUT, options);
+    }
+    if (!isConfigured(provider)) {
+      console.warn(`[llmClient] ${API_KEY_ENV[provider]} is not set, skipping ${provider}`);
+      continue;
+    }
+    if (isCoolingDown(provider)) {
+      console.warn(`[llmClient] ${provider} is rate limited, skipping`);
+      continue;
+    }
+
+    const timeoutMs = providerTimeoutMs(provider);
+    const controller = new AbortController();
+    const timer = setTimeout(() => controller.abort(), timeoutMs);
+    const startedAt = Date.now();
+
+    try {
+      // A provider that fails mid-stream has already sent tokens; the next one
+      // streams a fresh message and clients replace the preview on `message`
+      const output = await callProvider(provider, input, { ...options, signal: controller.signal });
+      recordCall(options, {
+        provider,
+        model: output.model,
+        usage: output.usage,
+        latencyMs: Date.now() - startedAt,
+      });
+      if (cacheKey) {
+        setCachedResponse(cacheKey, output);
+      }
+      return output;
+    } catch (error: any) {
+      const timedOut = controller.signal.aborted;
+      const rateLimited = error?.status === 429;
+      lastError = timedOut ? new Error(`${provider} timed out after ${timeoutMs}ms`) : error;
+
+      if (rateLimited) {
+        const cooldownMs = startCooldown(provider, error.retryAfterSeconds);
+        console.warn(`[llmClient] ${provider} rate limited, cooling down for ${cooldownMs}ms`);
+      }
+      recordCall(options, {
+        provider,
+        model: modelFor(provider),
+        latencyMs: Date.now() - startedAt,
+        status: timedOut ? 'timeout' : rateLimited ? 'rate_limited' : 'error',
+        error: lastError!.message,
+      });
+      console.warn(`[llmClient] ${provider} failed (${lastError!.message}), trying next provider`);
+    } finally {
+      clearTimeout(timer);
+    }
   }
 
-  if (provider === 'anthropic') {
-    return callAnthropic(input);
+  if (lastError) {
+    throw lastError;
   }
 
-  if (provider === 'gemini') {
-    return callGemini(input);
+  // Nothing in the chain is configured
+  return emitWhole(STUB_OUTPUT, options);
+}
+
+function callProvider(provider: ModelProvider, input: LlmChatInput, options: ProviderCallOptions): Promise<LlmChatOutput> {
+  if (provider === 'openai') return callOpenAI(input, options);
+  if (provider === 'anthropic') return callAnthropic(input, options);
+  return callGemini(input, options).then(output => emitWhole(output, options));
+}
+
+function modelFor(provider: ModelProvider): string | undefined {
+  if (provider === 'openai') return process.env.BLOSSOM_OPENAI_MODEL || 'gpt-4o-mini';
+  if (provider === 'anthropic') return process.env.BLOSSOM_ANTHROPIC_MODEL || 'claude-3-5-sonnet-20241022';
+  if (provider === 'gemini') return process.env.BLOSSOM_GEMINI_MODEL || 'gemini-1.5-pro';
+  return undefined;
+}
+
+/**
+ * Log one provider attempt (or cache hit) with its tokens and estimated cost
+ */
+function recordCall(
+  options: LlmCallOptions,
+  call: {
+    provider: ModelProvider;
+    model?: string;
+    usage?: LlmUsage;
+    latencyMs: number;
+    cached?: boolean;
+    status?: 'ok' | 'error' | 'timeout' | 'rate_limited';
+    error?: string;
   }
+): void {
+  const costUsd = call.cached ? 0 : estimateCostUsd(call.model, call.usage);
+  const userHash = options.userId ? hashAddress(options.userId) : undefined;
+  const status = call.status ?? 'ok';
 
-  // Fallback to stub
-  return {
-    assistantMessage: "LLM provider not configured correctly.",
-    rawJson: JSON.stringify({ assistantMessage: "Error", actions: [] })
-  };
+  logEvent('llm_call', {
+    userHash,
+    provider: call.provider,
+    model: call.model,
+    inputTokens: call.cached ? 0 : call.usage?.inputTokens,
+    outputTokens: call.cached ? 0 : call.usage?.outputTokens,
+    costUsd,
+    cached: call.cached,
+    latencyMs: call.latencyMs,
+    success: status === 'ok',
+    error: call.error,
+  });
+
+  if (call.provider === 'stub') return;
+  void trackLlmUsage({
+    userHash,
+    correlationId: options.correlationId,
+    provider: call.provider,
+    model: call.model,
+    inputTokens: call.cached ? 0 : call.usage?.inputTokens,
+    outputTokens: call.cached ? 0 : call.usage?.outputTokens,
+    costUsd,
+    latencyMs: call.latencyMs,
+    cached: call.cached,
+    status,
+    errorMessage: call.error,
+  });
+}
+
+/**
+ * Forward a non-streamed response's assistantMessage as a single token
+ */
+function emitWhole(output: LlmChatOutput, options: LlmCallOptions): LlmChatOutput {
+  if (options.onToken) {
+    const text = new AssistantMessageStream().push(output.rawJson);
+    if (text) options.onToken(text);
+  }
+  return output;
 }
 
 /**
  * Call OpenAI API
  */
-async function callOpenAI(input: LlmChatInput): Promise<LlmChatOutput> {
+async function callOpenAI(input: LlmChatInput, options: ProviderCallOptions = {}): Promise<LlmChatOutput> {
   const apiKey = process.env.BLOSSOM_OPENAI_API_KEY;
   if (!apiKey) {
     throw new Error('BLOSSOM_OPENAI_API_KEY is not set');
   }
 
-  const model = process.env.BLOSSOM_OPENAI_MODEL || 'gpt-4o-mini';
+  const model = modelFor('openai')!;
   const client = new OpenAI({ apiKey });
 
+  const tools = useTools('openai');
+
   try {
-    const response = await client.chat.completions.create({
+    const request = {
       model,
       messages: [
-        { role: 'system', content: input.systemPrompt },
-        { role: 'user', content: input.userPrompt }
+        { role: 'system' as const, content: input.systemPrompt },
+        { role: 'user' as const, content: input.userPrompt }
       ],
-      response_format: { type: 'json_object' },
+      ...(tools
+        ? {
+            tools: [openAIResponseTool()],
+            tool_choice: { type: 'function' as const, function: { name: BLOSSOM_RESPONSE_TOOL_NAME } },
+          }
+        : { response_format: { type: 'json_object' as const } }),
       temperature: 0.7,
+    };
+
+    const meta = (usage?: { prompt_tokens?: number; completion_tokens?: number } | null) => ({
+      provider: 'openai' as const,
+      model,
+      usage: usage ? { inputTokens: usage.prompt_tokens ?? 0, outputTokens: usage.completion_tokens ?? 0 } : undefined,
     });
 
-    const content = response.choices[0]?.message?.content;
+    if (options.onToken) {
+      const stream = await client.chat.completions.create(
+        { ...request, stream: true, stream_options: { include_usage: true } },
+        { signal: options.signal }
+      );
+      const message = new AssistantMessageStream();
+      let toolName = '';
+      let usage: { prompt_tokens?: number; completion_tokens?: number } | null = null;
+      for await (const chunk of stream) {
+        if (chunk.usage) usage = chunk.usage;
+        const delta = chunk.choices[0]?.delta;
+        const call = delta?.tool_calls?.[0];
+        if (call?.function?.name) toolName = call.function.name;
+        const piece = call?.function?.arguments ?? delta?.content;
+        if (!piece) continue;
+        const text = message.push(piece);
+        if (text) options.onToken(text);
+      }
+      if (!message.raw) {
+        throw new Error('No content in OpenAI response');
+      }
+      if (toolName) {
+        return { ...toolOutput(toolName, message.raw), ...meta(usage) };
+      }
+      return { assistantMessage: '', rawJson: message.raw, ...meta(usage) };
+    }
+
+    const response = await client.chat.completions.create(request, { signal: options.signal });
+
+    const choice = response.choices[0]?.message;
+    const call = choice?.tool_calls?.find(c => c.type === 'function');
+    if (call) {
+      return { ...toolOutput(call.function.name, call.function.arguments), ...meta(response.usage) };
+    }
+
+    // Providers may still answer in text; treat it as the JSON response
+    const content = choice?.content;
     if (!content) {
       throw new Error('No content in OpenAI response');
     }
@@ -93,44 +428,98 @@ async function callOpenAI(input: LlmChatInput): Promise<LlmChatOutput> {
     return {
       assistantMessage: '', // Will be extracted from JSON
       rawJson: content,
+      ...meta(response.usage),
     };
   } catch (error: any) {
     console.error('OpenAI API error:', error.message);
-    throw new Error(`OpenAI API error: ${error.message}`);
+    throw providerError('OpenAI', error);
   }
 }
 
 /**
  * Call Anthropic API
  */
-async function callAnthropic(input: LlmChatInput): Promise<LlmChatOutput> {
+async function callAnthropic(input: LlmChatInput, options: ProviderCallOptions = {}): Promise<LlmChatOutput> {
   const apiKey = process.env.BLOSSOM_ANTHROPIC_API_KEY;
   if (!apiKey) {
     throw new Error('BLOSSOM_ANTHROPIC_API_KEY is not set');
   }
 
-  const model = process.env.BLOSSOM_ANTHROPIC_MODEL || 'claude-3-5-sonnet-20241022';
+  const model = modelFor('anthropic')!;
   const client = new Anthropic({ apiKey });
 
+  const tools = useTools('anthropic');
+
   try {
-    // Anthropic requires JSON in the system prompt or user message
-    const enhancedSystemPrompt = `${input.systemPrompt}\n\nYou MUST respond with ONLY a valid JSON object, no other text before or after.`;
+    // Without tools Anthropic requires JSON in the system prompt or user message
+    const enhancedSystemPrompt = tools
+      ? input.systemPrompt
+      : `${input.systemPrompt}\n\nYou MUST respond with ONLY a valid JSON object, no other text before or after.`;
 
-    const response = await client.messages.create({
+    const request = {
       model,
       max_tokens: 4096,
       system: enhancedSystemPrompt,
       messages: [
-        { role: 'user', content: input.userPrompt }
+        { role: 'user' as const, content: input.userPrompt }
       ],
-    });
+      ...(tools
+        ? {
+            tools: [anthropicResponseTool()],
+            tool_choice: { type: 'tool' as const, name: BLOSSOM_RESPONSE_TOOL_NAME },
+          }
+        : {}),
+    };
 
-    const content = response.content[0];
-    if (content.type !== 'text') {
-      throw new Error('Unexpected content type from Anthropic');
-    }
+    const usage: LlmUsage = { inputTokens: 0, outputTokens: 0 };
+    const meta = () => ({ provider: 'anthropic' as const, model, usage });
+
+    let text: string;
+    if (options.onToken) {
+      const stream = await client.messages.create({ ...request, stream: true }, { signal: options.signal });
+      const message = new AssistantMessageStream();
+      let toolName = '';
+      for await (const event of stream) {
+        if (event.type === 'message_start') {
+          usage.inputTokens = event.message.usage?.input_tokens ?? 0;
+          continue;
+        }
+        if (event.type === 'message_delta') {
+          usage.outputTokens = event.usage?.output_tokens ?? usage.outputTokens;
+          continue;
+        }
+        if (event.type === 'content_block_start' && event.content_block.type === 'tool_use') {
+          toolName = event.content_block.name;
+          continue;
+        }
+        if (event.type !== 'content_block_delta') continue;
+        const piece = event.delta.type === 'input_json_delta'
+          ? event.delta.partial_json
+          : event.delta.type === 'text_delta' ? event.delta.text : '';
+        if (!piece) continue;
+        const delta = message.push(piece);
+        if (delta) options.onToken(delta);
+      }
+      if (toolName) {
+        return { ...toolOutput(toolName, message.raw), ...meta() };
+      }
+      text = message.raw.trim();
+    } else {
+      const response = await client.messages.create(request, { signal: options.signal });
+      usage.inputTokens = response.usage?.input_tokens ?? 0;
+      usage.outputTokens = response.usage?.output_tokens ?? 0;
 
-    const text = content.text.trim();
+      const toolUse = response.content.find(block => block.type === 'tool_use');
+      if (toolUse && toolUse.type === 'tool_use') {
+        return { ...toolOutput(toolUse.name, toolUse.input as Record<string, any>), ...meta() };
+      }
+
+      const content = response.content.find(block => block.type === 'text');
+      if (!content || content.type !== 'text') {
+        throw new Error('Unexpected content type from Anthropic');
+      }
+      text = content.text.trim();
+    }
     
     // Extract JSON if wrapped in markdown code blocks
     let jsonText = text;
@@ -143,45 +532,42 @@ async function callAnthropic(input: LlmChatInput): Promise<LlmChatOutput> {
     return {
       assistantMessage: '', // Will be extracted from JSON
       rawJson: jsonText,
+      ...meta(),
     };
   } catch (error: any) {
     console.error('Anthropic API error:', error.message);
-    throw new Error(`Anthropic API error: ${error.message}`);
+    throw providerError('Anthropic', error);
   }
 }
 
 /**
  * Call Google Gemini API
+ * JSON mode only (no tool calling); the system prompt goes in systemInstruction
  */
-async function callGemini(input: LlmChatInput): Promise<LlmChatOutput> {
+async function callGemini(input: LlmChatInput, options: ProviderCallOptions = {}): Promise<LlmChatOutput> {
   const apiKey = process.env.BLOSSOM_GEMINI_API_KEY;
   if (!apiKey) {
-    console.warn('[llmClient] Gemini key missing, falling back to stub');
-    // Fall back to stub instead of throwing error
-    return {
-      assistantMessage: "This is a stubbed Blossom response. Gemini API key not configured. Set BLOSSOM_GEMINI_API_KEY to enable Gemini.",
-      rawJson: JSON.stringify({
-        assistantMessage: "This is a stubbed Blossom response. Gemini API key not configured.",
-        actions: []
-      })
-    };
+    throw new Error('BLOSSOM_GEMINI_API_KEY is not set');
   }
 
-  const model = process.env.BLOSSOM_GEMINI_MODEL || 'gemini-1.5-pro';
+  const model = modelFor('gemini')!;
   
   try {
     // Gemini API endpoint
-    const url = `https://generativelanguage.googleapis.com/v1beta/models/${model}:generateContent?key=${apiKey}`;
+    const url = `https://generativelanguage.googleapis.com/v1beta/models/${model}:generateContent`;
     
     const response = await fetch(url, {
       method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
+      headers: { 'Content-Type': 'application/json', 'x-goog-api-key': apiKey },
+      signal: options.signal,
       body: JSON.stringify({
+        systemInstruction: {
+          parts: [{ text: `${input.systemPrompt}\n\nYou MUST respond with ONLY a valid JSON object, no other text before or after.` }],
+        },
         contents: [
           {
-            parts: [
-              { text: `${input.systemPrompt}\n\n${input.userPrompt}\n\nYou MUST respond with ONLY a valid JSON object, no other text before or after.` }
-            ]
+            role: 'user',
+            parts: [{ text: input.userPrompt }],
           }
         ],
         generationConfig: {
@@ -193,14 +579,20 @@ async function callGemini(input: LlmChatInput): Promise<LlmChatOutput> {
 
     if (!response.ok) {
       const errorText = await response.text();
-      throw new Error(`Gemini API error: ${response.status} ${errorText}`);
+      throw Object.assign(new Error(`${response.status} ${errorText}`), {
+        status: response.status,
+        headers: response.headers,
+      });
     }
 
     const result = await response.json();
-    const content = result.candidates?.[0]?.content?.parts?.[0]?.text;
+    const candidate = result.candidates?.[0];
+    const content = candidate?.content?.parts
+      ?.map((part: { text?: string }) => part.text ?? '')
+      .join('');
     
     if (!content) {
-      throw new Error('No content in Gemini response');
+      throw new Error(`No content in Gemini response${candidate?.finishReason ? ` (${candidate.finishReason})` : ''}`);
     }
 
     // Extract JSON if wrapped in markdown code blocks
@@ -214,10 +606,17 @@ async function callGemini(input: LlmChatInput): Promise<LlmChatOutput> {
     return {
       assistantMessage: '', // Will be extracted from JSON
       rawJson: jsonText,
+      provider: 'gemini',
+      model,
+      usage: result.usageMetadata
+        ? {
+            inputTokens: result.usageMetadata.promptTokenCount ?? 0,
+            outputTokens: result.usageMetadata.candidatesTokenCount ?? 0,
+          }
+        : undefined,
     };
   } catch (error: any) {
     console.error('Gemini API error:', error.message);
-    throw new Error(`Gemini API error: ${error.message}`);
+    throw providerError('Gemini', error);
   }
 }
-
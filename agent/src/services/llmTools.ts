@@ -0,0 +1,174 @@
+/**
+ * Blossom Tool Definitions
+ * JSON Schema for the Blossom response (assistantMessage, actions, executionRequest),
+ * declared as a single tool the model is forced to call. Providers with native
+ * tool calling return the arguments as a structured object; the same object is
+ * what the JSON prompt asks other providers to write out.
+ *
+ * The schema mirrors src/types/blossom.ts. It steers the model, it does not
+ * replace validateActions/validateExecutionRequest - tool arguments are still
+ * validated before anything reaches the executors.
+ */
+
+export const BLOSSOM_RESPONSE_TOOL_NAME = 'blossom_response';
+
+export interface BlossomToolCall {
+  name: string;
+  input: Record<string, any>;
+}
+
+const reasoning = {
+  type: 'array',
+  items: { type: 'string' },
+  description: 'Short reasoning bullets for the action',
+};
+
+const perpAction = {
+  type: 'object',
+  properties: {
+    type: { type: 'string', enum: ['perp'] },
+    action: { type: 'string', enum: ['open', 'close'] },
+    market: { type: 'string', description: "e.g. 'ETH-PERP'" },
+    side: { type: 'string', enum: ['long', 'short'] },
+    riskPct: { type: 'number', description: 'Percent of account value at risk, max 5' },
+    entry: { type: 'number' },
+    takeProfit: { type: 'number' },
+    stopLoss: { type: 'number' },
+    reasoning,
+  },
+  required: ['type', 'action', 'market', 'side', 'riskPct', 'reasoning'],
+};
+
+const defiAction = {
+  type: 'object',
+  properties: {
+    type: { type: 'string', enum: ['defi'] },
+    action: { type: 'string', enum: ['deposit', 'withdraw'] },
+    protocol: { type: 'string' },
+    asset: { type: 'string' },
+    amountUsd: { type: 'number' },
+    apr: { type: 'number' },
+    reasoning,
+  },
+  required: ['type', 'action', 'protocol', 'asset', 'amountUsd', 'apr', 'reasoning'],
+};
+
+const eventAction = {
+  type: 'object',
+  properties: {
+    type: { type: 'string', enum: ['event'] },
+    action: { type: 'string', enum: ['open', 'close', 'update'] },
+    eventKey: { type: 'string' },
+    label: { type: 'string' },
+    side: { type: 'string', enum: ['YES', 'NO'] },
+    stakeUsd: { type: 'number' },
+    maxPayoutUsd: { type: 'number' },
+    maxLossUsd: { type: 'number' },
+    reasoning,
+    positionId: { type: 'string', description: "Required for action 'update'" },
+    overrideRiskCap: { type: 'boolean' },
+    requestedStakeUsd: { type: 'number' },
+  },
+  required: ['type', 'action', 'eventKey', 'label', 'side', 'stakeUsd', 'maxPayoutUsd', 'maxLossUsd', 'reasoning'],
+};
+
+const decimalString = { type: 'string', description: 'Decimal string, e.g. "0.01" or "10"' };
+
+const swapRequest = {
+  type: 'object',
+  properties: {
+    kind: { type: 'string', enum: ['swap'] },
+    chain: { type: 'string', enum: ['sepolia'] },
+    tokenIn: { type: 'string', enum: ['ETH', 'WETH', 'USDC'] },
+    tokenOut: { type: 'string', enum: ['WETH', 'USDC'] },
+    amountIn: decimalString,
+    amountOut: decimalString,
+    slippageBps: { type: 'number', description: 'Basis points, default 50' },
+    fundingPolicy: { type: 'string', enum: ['auto', 'require_tokenIn'] },
+  },
+  required: ['kind', 'chain', 'tokenIn', 'tokenOut', 'amountIn'],
+};
+
+const perpRequest = {
+  type: 'object',
+  properties: {
+    kind: { type: 'string', enum: ['perp'] },
+    chain: { type: 'string', enum: ['sepolia'] },
+    market: { type: 'string' },
+    side: { type: 'string', enum: ['long', 'short'] },
+    leverage: { type: 'number', description: 'Only when the user names a leverage' },
+    riskPct: { type: 'number' },
+    marginUsd: { type: 'number' },
+    entryPrice: { type: 'number' },
+    takeProfitPrice: { type: 'number' },
+    stopLossPrice: { type: 'number' },
+  },
+  required: ['kind', 'market', 'side'],
+};
+
+const lendRequest = {
+  type: 'object',
+  properties: {
+    kind: { type: 'string', enum: ['lend', 'lend_supply'] },
+    chain: { type: 'string', enum: ['sepolia'] },
+    asset: { type: 'string', enum: ['USDC'] },
+    amount: decimalString,
+    protocol: { type: 'string', enum: ['demo', 'aave'] },
+    vault: { type: 'string' },
+  },
+  required: ['kind', 'chain', 'asset', 'amount'],
+};
+
+const eventRequest = {
+  type: 'object',
+  properties: {
+    kind: { type: 'string', enum: ['event'] },
+    chain: { type: 'string', enum: ['sepolia'] },
+    marketId: { type: 'string' },
+    outcome: { type: 'string', enum: ['YES', 'NO'] },
+    stakeUsd: { type: 'number' },
+    price: { type: 'number' },
+  },
+  required: ['kind', 'chain', 'marketId', 'outcome', 'stakeUsd'],
+};
+
+export const BLOSSOM_RESPONSE_SCHEMA = {
+  type: 'object',
+  properties: {
+    assistantMessage: {
+      type: 'string',
+      description: 'Reply shown to the user. Never mention JSON, tools or technical details.',
+    },
+    actions: {
+      type: 'array',
+      description: 'Simulated BlossomAction objects; may be empty',
+      items: { anyOf: [perpAction, defiAction, eventAction] },
+    },
+    executionRequest: {
+      description: 'Required for on-chain swap, perp, lending and event requests; omit otherwise',
+      anyOf: [swapRequest, perpRequest, lendRequest, eventRequest],
+    },
+  },
+  required: ['assistantMessage', 'actions'],
+};
+
+const TOOL_DESCRIPTION = 'Reply to the user with the Blossom assistant message, any proposed actions and an optional execution request.';
+
+export function openAIResponseTool() {
+  return {
+    type: 'function' as const,
+    function: {
+      name: BLOSSOM_RESPONSE_TOOL_NAME,
+      description: TOOL_DESCRIPTION,
+      parameters: BLOSSOM_RESPONSE_SCHEMA,
+    },
+  };
+}
+
+export function anthropicResponseTool() {
+  return {
+    name: BLOSSOM_RESPONSE_TOOL_NAME,
+    description: TOOL_DESCRIPTION,
+    input_schema: BLOSSOM_RESPONSE_SCHEMA,
+  };
+}
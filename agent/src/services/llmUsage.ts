@@ -0,0 +1,231 @@
+/**
+ * LLM Usage Controls
+ * Provider chain configuration, per-user token budgets, the response cache and
+ * cost accounting for callLlm.
+ *
+ * Environment:
+ *   BLOSSOM_MODEL_PROVIDERS            ordered chain, e.g. "anthropic,openai,gemini"
+ *                                      (defaults to BLOSSOM_MODEL_PROVIDER)
+ *   BLOSSOM_LLM_TIMEOUT_MS             per-provider timeout (default 30000);
+ *                                      BLOSSOM_<PROVIDER>_TIMEOUT_MS overrides one provider
+ *   BLOSSOM_LLM_RATE_LIMIT_COOLDOWN_MS skip a provider this long after a 429 without
+ *                                      Retry-After (default 60000)
+ *   BLOSSOM_LLM_USER_TOKEN_BUDGET      tokens per user per window, 0 = unlimited (default 0)
+ *   BLOSSOM_LLM_IP_TOKEN_BUDGET        tokens per client IP per window for unauthenticated
+ *                                      callers (defaults to the user budget)
+ *   BLOSSOM_LLM_BUDGET_WINDOW_SECONDS  budget window (default 86400)
+ *   BLOSSOM_LLM_CACHE_TTL_MS           response cache TTL, 0 = disabled (default 120000)
+ *   BLOSSOM_LLM_CACHE_MAX_ENTRIES      default 500
+ *   BLOSSOM_LLM_PRICING                JSON {"model-prefix": [inputUsdPer1M, outputUsdPer1M]}
+ */
+
+import { createHash } from 'crypto';
+import { getLlmTokenUsage } from '../telemetry/logger';
+
+export type ModelProvider = 'openai' | 'anthropic' | 'gemini' | 'stub';
+
+export interface LlmUsage {
+  inputTokens: number;
+  outputTokens: number;
+}
+
+const PROVIDERS: ModelProvider[] = ['openai', 'anthropic', 'gemini', 'stub'];
+
+function envInt(name: string, fallback: number): number {
+  const value = parseInt(process.env[name] || '', 10);
+  return Number.isFinite(value) ? value : fallback;
+}
+
+/**
+ * Ordered provider chain; unknown names are dropped, an empty chain is stub
+ */
+export function getProviderChain(): ModelProvider[] {
+  const raw = process.env.BLOSSOM_MODEL_PROVIDERS || process.env.BLOSSOM_MODEL_PROVIDER || '';
+  const chain = raw
+    .split(',')
+    .map(name => name.trim().toLowerCase() as ModelProvider)
+    .filter((name, i, all) => PROVIDERS.includes(name) && all.indexOf(name) === i);
+  return chain.length > 0 ? chain : ['stub'];
+}
+
+export function providerTimeoutMs(provider: ModelProvider): number {
+  return envInt(`BLOSSOM_${provider.toUpperCase()}_TIMEOUT_MS`, envInt('BLOSSOM_LLM_TIMEOUT_MS', 30000));
+}
+
+// ============================================
+// Rate-limit cooldowns
+// ============================================
+
+const cooldownUntil = new Map<ModelProvider, number>();
+
+export function isCoolingDown(provider: ModelProvider): boolean {
+  const until = cooldownUntil.get(provider);
+  return until !== undefined && until > Date.now();
+}
+
+/**
+ * Park a provider after a rate limit; Retry-After (seconds) wins over the default
+ */
+export function startCooldown(provider: ModelProvider, retryAfterSeconds?: number): number {
+  const ms = retryAfterSeconds && retryAfterSeconds > 0
+    ? retryAfterSeconds * 1000
+    : envInt('BLOSSOM_LLM_RATE_LIMIT_COOLDOWN_MS', 60000);
+  cooldownUntil.set(provider, Date.now() + ms);
+  return ms;
+}
+
+// ============================================
+// Per-user token budgets
+// ============================================
+
+// 'user' for signed-in wallets and access codes, 'ip' for everyone else
+export type LlmBudgetScope = 'user' | 'ip';
+
+export class LlmBudgetExceededError extends Error {
+  readonly code = 'LLM_BUDGET_EXCEEDED';
+
+  constructor(public readonly used: number, public readonly budget: number) {
+    super(`LLM token budget exceeded (${used}/${budget} tokens)`);
+    this.name = 'LlmBudgetExceededError';
+  }
+}
+
+/**
+ * Throw if the caller has spent their token budget for the current window
+ * Fails open when usage cannot be read
+ */
+export async function assertWithinBudget(userHash: string, scope: LlmBudgetScope = 'user'): Promise<void> {
+  const userBudget = envInt('BLOSSOM_LLM_USER_TOKEN_BUDGET', 0);
+  const budget = scope === 'ip' ? envInt('BLOSSOM_LLM_IP_TOKEN_BUDGET', userBudget) : userBudget;
+  if (budget <= 0) return;
+
+  const since = Math.floor(Date.now() / 1000) - envInt('BLOSSOM_LLM_BUDGET_WINDOW_SECONDS', 86400);
+  const used = await getLlmTokenUsage(userHash, since);
+  if (used !== null && used >= budget) {
+    throw new LlmBudgetExceededError(used, budget);
+  }
+}
+
+// ============================================
+// Response cache
+// ============================================
+
+export interface LlmCacheKeyInput {
+  prompt: string;        // The user's message; normalized before hashing
+  portfolio?: unknown;   // Portfolio snapshot the prompt was built from
+  scope?: string;        // Anything else that changes the answer (e.g. venue)
+}
+
+interface CacheEntry<T> {
+  value: T;
+  expiresAt: number;
+}
+
+const responseCache = new Map<string, CacheEntry<unknown>>();
+
+function stableStringify(value: unknown): string {
+  if (value === null || typeof value !== 'object') return JSON.stringify(value) ?? 'null';
+  if (Array.isArray(value)) return `[${value.map(stableStringify).join(',')}]`;
+  const entries = Object.keys(value as Record<string, unknown>)
+    .sort()
+    .filter(key => (value as Record<string, unknown>)[key] !== undefined)
+    .map(key => `${JSON.stringify(key)}:${stableStringify((value as Record<string, unknown>)[key])}`);
+  return `{${entries.join(',')}}`;
+}
+
+function sha256(text: string): string {
+  return createHash('sha256').update(text).digest('hex');
+}
+
+export function normalizePrompt(prompt: string): string {
+  return prompt.toLowerCase().replace(/\s+/g, ' ').trim();
+}
+
+/**
+ * Cache key: provider chain + system prompt + normalized prompt + portfolio hash
+ */
+export function buildCacheKey(chain: ModelProvider[], systemPrompt: string, input: LlmCacheKeyInput): string {
+  return sha256([
+    chain.join(','),
+    sha256(systemPrompt),
+    input.scope ?? '',
+    normalizePrompt(input.prompt),
+    sha256(stableStringify(input.portfolio ?? null)),
+  ].join('\n'));
+}
+
+export function getCachedResponse<T>(key: string): T | undefined {
+  const entry = responseCache.get(key);
+  if (!entry) return undefined;
+  if (entry.expiresAt <= Date.now()) {
+    responseCache.delete(key);
+    return undefined;
+  }
+  return entry.value as T;
+}
+
+export function setCachedResponse<T>(key: string, value: T): void {
+  const ttlMs = envInt('BLOSSOM_LLM_CACHE_TTL_MS', 120000);
+  if (ttlMs <= 0) return;
+
+  // Map keeps insertion order, so the first key is the oldest
+  const maxEntries = envInt('BLOSSOM_LLM_CACHE_MAX_ENTRIES', 500);
+  while (responseCache.size >= maxEntries) {
+    const oldest = responseCache.keys().next().value;
+    if (oldest === undefined) break;
+    responseCache.delete(oldest);
+  }
+  responseCache.set(key, { value, expiresAt: Date.now() + ttlMs });
+}
+
+/**
+ * Clear the response cache and provider cooldowns
+ */
+export function resetLlmUsageState(): void {
+  responseCache.clear();
+  cooldownUntil.clear();
+}
+
+// ============================================
+// Cost
+// ============================================
+
+// USD per 1M tokens [input, output]; matched by longest model-name prefix
+const DEFAULT_PRICING: Record<string, [number, number]> = {
+  'gpt-4o-mini': [0.15, 0.6],
+  'gpt-4o': [2.5, 10],
+  'gpt-4.1-mini': [0.4, 1.6],
+  'gpt-4.1': [2, 8],
+  'claude-3-5-haiku': [0.8, 4],
+  'claude-3-5-sonnet': [3, 15],
+  'claude-3-7-sonnet': [3, 15],
+  'claude-sonnet-4': [3, 15],
+  'gemini-1.5-flash': [0.075, 0.3],
+  'gemini-1.5-pro': [1.25, 5],
+  'gemini-2.0-flash': [0.1, 0.4],
+};
+
+function pricingTable(): Record<string, [number, number]> {
+  const raw = process.env.BLOSSOM_LLM_PRICING;
+  if (!raw) return DEFAULT_PRICING;
+  try {
+    return { ...DEFAULT_PRICING, ...JSON.parse(raw) };
+  } catch {
+    console.warn('[llmUsage] Ignoring invalid BLOSSOM_LLM_PRICING');
+    return DEFAULT_PRICING;
+  }
+}
+
+/**
+ * Estimated USD cost of a call; 0 for models without a price
+ */
+export function estimateCostUsd(model: string | undefined, usage: LlmUsage | undefined): number {
+  if (!model || !usage) return 0;
+  const table = pricingTable();
+  const prefix = Object.keys(table)
+    .filter(name => model.startsWith(name))
+    .sort((a, b) => b.length - a.length)[0];
+  if (!prefix) return 0;
+  const [inputPer1M, outputPer1M] = table[prefix];
+  return (usage.inputTokens * inputPer1M + usage.outputTokens * outputPer1M) / 1_000_000;
+}
@@ -0,0 +1,165 @@
+/**
+ * Perps Funding Engine
+ * Background loop that periodically accrues funding on open sim perp positions
+ * and on open DemoPerpEngine positions recorded in the execution ledger.
+ */
+
+import * as perpsSim from '../plugins/perps-sim';
+import { PriceSymbol } from './prices';
+import { listLoadedSimUsers, hydrateRetainedSimAccounts } from './simStore';
+import { getFundingRate, computeFundingPaymentUsd, isFundingEnabled } from './funding';
+import { PERPS_FUNDING_INTERVAL_MS } from '../config';
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+
+// Lazy-loaded ledger module (use any to avoid rootDir issues with typeof import)
+let ledger: any = null;
+
+async function getLedger() {
+  if (!ledger) {
+    ledger = await import('../ledger/ledger');
+  }
+  return ledger;
+}
+
+async function fetchRates(symbols: Iterable<PriceSymbol>): Promise<Partial<Record<PriceSymbol, number>>> {
+  const rates: Partial<Record<PriceSymbol, number>> = {};
+  await Promise.all(Array.from(new Set(symbols)).map(async symbol => {
+    try {
+      rates[symbol] = (await getFundingRate(symbol)).ratePerHour;
+    } catch (error: any) {
+      console.warn(`[funding] Rate fetch failed for ${symbol}:`, error.message);
+    }
+  }));
+  return rates;
+}
+
+/**
+ * Accrue funding on all loaded sim accounts
+ * (every account with open positions is loaded, see hydrateRetainedSimAccounts)
+ */
+export async function accrueSimFunding(now: number = Date.now()): Promise<number> {
+  const userIds = listLoadedSimUsers().filter(userId => perpsSim.getOpenPerpSymbols(userId).length > 0);
+  if (userIds.length === 0) {
+    return 0;
+  }
+
+  const rates = await fetchRates(userIds.flatMap(userId => perpsSim.getOpenPerpSymbols(userId)));
+  let netPaidUsd = 0;
+  for (const userId of userIds) {
+    netPaidUsd += perpsSim.accrueFunding(userId, rates, now);
+  }
+  return netPaidUsd;
+}
+
+/**
+ * Accrue funding on open demo_perp positions in the ledger
+ * Notional is size_units (USD, 6 decimals); funding_usd is cumulative
+ */
+export async function accrueLedgerFunding(now: number = Date.now()): Promise<number> {
+  const ledgerModule = await getLedger();
+  const positions = await ledgerModule.getOpenPositions(undefined, undefined, 'demo_perp');
+  if (positions.length === 0) {
+    return 0;
+  }
+
+  const rates = await fetchRates(positions.map((p: any) => perpsSim.getBaseSymbolFromMarket(p.market)));
+  const nowSec = Math.floor(now / 1000);
+  let netPaidUsd = 0;
+
+  for (const position of positions) {
+    const ratePerHour = rates[perpsSim.getBaseSymbolFromMarket(position.market)];
+    const since = position.funding_updated_at ?? position.opened_at;
+    if (ratePerHour === undefined || !since || nowSec <= since) continue;
+
+    const notionalUsd = Number(position.size_units || 0) / 1e6;
+    const paymentUsd = computeFundingPaymentUsd(notionalUsd, position.side, ratePerHour, (nowSec - since) * 1000);
+
+    await ledgerModule.updatePosition(position.id, {
+      funding_usd: (position.funding_usd || 0) + paymentUsd,
+      funding_updated_at: nowSec,
+    });
+    netPaidUsd += paymentUsd;
+  }
+
+  return netPaidUsd;
+}
+
+/**
+ * Run a single funding pass over sim and ledger positions
+ */
+export async function runFundingCycle(): Promise<void> {
+  if (!isFundingEnabled()) {
+    return;
+  }
+
+  const now = Date.now();
+  const simPaidUsd = await accrueSimFunding(now);
+
+  let ledgerPaidUsd = 0;
+  try {
+    ledgerPaidUsd = await accrueLedgerFunding(now);
+  } catch (error: any) {
+    console.warn('[funding] Ledger accrual skipped:', error.message?.slice(0, 100));
+  }
+
+  if (simPaidUsd !== 0 || ledgerPaidUsd !== 0) {
+    console.log(`[funding] Accrued sim=${simPaidUsd.toFixed(4)} ledger=${ledgerPaidUsd.toFixed(4)} USD`);
+  }
+}
+
+/**
+ * Start the funding accrual loop
+ */
+export function startPerpsFundingEngine(intervalMs: number = PERPS_FUNDING_INTERVAL_MS): void {
+  if (isRunning) {
+    console.log('[funding] Already running');
+    return;
+  }
+
+  console.log(`[funding] Starting perps funding engine (every ${intervalMs}ms)`);
+  isRunning = true;
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runFundingCycle();
+    } catch (error: any) {
+      console.error('[funding] Cycle error:', error.message?.slice(0, 100));
+    }
+
+    // Schedule next pass
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  // Load persisted accounts with open positions; their owners may not send a request after a restart
+  hydrateRetainedSimAccounts().then(userIds => {
+    if (userIds.length > 0) {
+      console.log(`[funding] Hydrated ${userIds.length} sim account(s) with open positions`);
+    }
+  });
+
+  // First accrual after one interval (positions accrue pro rata, so nothing is lost)
+  pollTimeout = setTimeout(poll, intervalMs);
+}
+
+/**
+ * Stop the funding accrual loop
+ */
+export function stopPerpsFundingEngine(): void {
+  console.log('[funding] Stopping perps funding engine');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the engine is running
+ */
+export function isFundingEngineRunning(): boolean {
+  return isRunning;
+}
@@ -0,0 +1,125 @@
+/**
+ * Perps Mark-to-Market Engine
+ * Background loop that revalues open simulated perp positions from the price
+ * service, triggers take-profit / stop-loss closes and liquidations, and
+ * records the resulting closures on each user's sim account.
+ */
+
+import * as perpsSim from '../plugins/perps-sim';
+import { PerpCloseEvent } from '../plugins/perps-sim/types';
+import { getPrice, PriceSymbol } from './prices';
+import { listLoadedSimUsers, hydrateRetainedSimAccounts } from './simStore';
+import { logEvent, hashAddress } from '../telemetry/logger';
+import { PERPS_MARK_INTERVAL_MS, PERPS_MAINTENANCE_MARGIN_BPS } from '../config';
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+
+/**
+ * Run a single revaluation pass over all loaded sim accounts
+ * (every account with open positions is loaded, see hydrateRetainedSimAccounts)
+ * Prices are fetched once per symbol per pass
+ */
+export async function runMarkCycle(): Promise<PerpCloseEvent[]> {
+  const userIds = listLoadedSimUsers();
+  const symbolsByUser = new Map<string, PriceSymbol[]>();
+  const symbols = new Set<PriceSymbol>();
+
+  for (const userId of userIds) {
+    const userSymbols = perpsSim.getOpenPerpSymbols(userId);
+    if (userSymbols.length > 0) {
+      symbolsByUser.set(userId, userSymbols);
+      userSymbols.forEach(symbol => symbols.add(symbol));
+    }
+  }
+
+  if (symbols.size === 0) {
+    return [];
+  }
+
+  const marks: Partial<Record<PriceSymbol, number>> = {};
+  await Promise.all(Array.from(symbols).map(async symbol => {
+    try {
+      const snapshot = await getPrice(symbol);
+      // Static, stale or disputed prices are skipped so positions aren't liquidated on a bad mark
+      if (snapshot.confidence !== 'low') {
+        marks[symbol] = snapshot.priceUsd;
+      }
+    } catch (error: any) {
+      console.warn(`[markEngine] Price fetch failed for ${symbol}:`, error.message);
+    }
+  }));
+
+  const closures: PerpCloseEvent[] = [];
+  for (const userId of symbolsByUser.keys()) {
+    const userClosures = perpsSim.markPositionsToMarket(userId, marks, PERPS_MAINTENANCE_MARGIN_BPS);
+    for (const closure of userClosures) {
+      console.log(
+        `[markEngine] ${closure.reason} ${closure.market} ${closure.side} @ ${closure.exitPrice.toFixed(2)} ` +
+        `pnl=${closure.realizedPnlUsd.toFixed(2)}`
+      );
+      logEvent('perp_auto_close', {
+        mode: 'sim',
+        userHash: hashAddress(userId),
+        success: closure.reason !== 'liquidation',
+        notes: [closure.reason, closure.market, `pnl:${closure.realizedPnlUsd.toFixed(2)}`],
+      });
+    }
+    closures.push(...userClosures);
+  }
+
+  return closures;
+}
+
+/**
+ * Start the mark-to-market loop
+ */
+export function startPerpsMarkEngine(intervalMs: number = PERPS_MARK_INTERVAL_MS): void {
+  if (isRunning) {
+    console.log('[markEngine] Already running');
+    return;
+  }
+
+  console.log(`[markEngine] Starting perps mark-to-market engine (every ${intervalMs}ms)`);
+  isRunning = true;
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runMarkCycle();
+    } catch (error: any) {
+      console.error('[markEngine] Cycle error:', error.message?.slice(0, 100));
+    }
+
+    // Schedule next pass
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  // Load persisted accounts with open positions first; their owners may not send a request after a restart
+  hydrateRetainedSimAccounts().then(userIds => {
+    if (userIds.length > 0) {
+      console.log(`[markEngine] Hydrated ${userIds.length} sim account(s) with open positions`);
+    }
+    poll();
+  });
+}
+
+/**
+ * Stop the mark-to-market loop
+ */
+export function stopPerpsMarkEngine(): void {
+  console.log('[markEngine] Stopping perps mark-to-market engine');
+  isRunning = false;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the engine is running
+ */
+export function isMarkEngineRunning(): boolean {
+  return isRunning;
+}
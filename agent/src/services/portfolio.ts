@@ -0,0 +1,354 @@
+/**
+ * Unified Portfolio
+ * Merges Sepolia and Solana devnet holdings across a user's linked wallets
+ *
+ * Ethereum: native ETH + configured ERC20s (tokenMetadata)
+ * Solana: native SOL + every SPL token account (getTokenAccountsByOwner)
+ * Values are in USD from the price service; tokens without a feed are listed unvalued
+ */
+
+import { formatUnits } from 'viem';
+import { getPrice, type PriceSymbol } from './prices';
+import { listConfiguredTokens, NATIVE_ETH } from './tokenMetadata';
+import { getSolanaTokenMetadata, NATIVE_SOL } from './solanaTokens';
+
+export interface Holding {
+  chain: 'ethereum' | 'solana';
+  network: 'sepolia' | 'devnet';
+  wallet: string;
+  token: string;              // ERC20 address / SPL mint, or NATIVE_ETH / NATIVE_SOL
+  symbol: string;
+  decimals: number;
+  raw: string;                // Base units
+  formatted: string;
+  priceUsd?: number;
+  valueUsd?: number;
+}
+
+export interface WalletRef {
+  chain: 'ethereum' | 'solana';
+  network: 'sepolia' | 'devnet';
+  address: string;
+  label?: string;
+}
+
+export interface WalletHoldings {
+  wallet: WalletRef;
+  holdings: Holding[];
+  totalUsd: number;
+  error?: string;
+}
+
+export interface UnifiedPortfolio {
+  userAddress: string;
+  wallets: WalletHoldings[];
+  totals: {
+    usd: number;
+    byChain: Record<string, number>;
+    bySymbol: Record<string, number>;
+  };
+  prices: Partial<Record<PriceSymbol, { priceUsd: number; source: string }>>;
+  timestamp: number;
+}
+
+interface RawHolding extends Omit<Holding, 'priceUsd' | 'valueUsd' | 'formatted'> {
+  priceSymbol?: PriceSymbol;
+}
+
+/**
+ * Native ETH and configured ERC20 balances on Sepolia
+ */
+export async function getEthereumHoldings(address: string): Promise<RawHolding[]> {
+  const { createFailoverPublicClient } = await import('../providers/rpcProvider');
+  const { erc20_balanceOf } = await import('../executors/erc20Rpc');
+  const publicClient = createFailoverPublicClient();
+  const wallet = address.toLowerCase();
+
+  const holdings: RawHolding[] = [{
+    chain: 'ethereum',
+    network: 'sepolia',
+    wallet,
+    token: NATIVE_ETH,
+    symbol: 'ETH',
+    decimals: 18,
+    raw: (await publicClient.getBalance({ address: wallet as `0x${string}` })).toString(),
+    priceSymbol: 'ETH',
+  }];
+
+  const tokens = listConfiguredTokens();
+  const balances = await Promise.allSettled(tokens.map(token => erc20_balanceOf(token.address, wallet)));
+  tokens.forEach((token, i) => {
+    const result = balances[i];
+    if (result.status !== 'fulfilled' || result.value === 0n) return;
+    holdings.push({
+      chain: 'ethereum',
+      network: 'sepolia',
+      wallet,
+      token: token.address,
+      symbol: token.symbol,
+      decimals: token.decimals,
+      raw: result.value.toString(),
+      priceSymbol: token.priceSymbol,
+    });
+  });
+
+  return holdings;
+}
+
+/**
+ * Native SOL and SPL token balances on devnet
+ * Multiple token accounts for the same mint are summed
+ */
+export async function getSolanaHoldings(address: string): Promise<RawHolding[]> {
+  const { SolanaClient } = await import('../solana/solanaClient');
+  const client = new SolanaClient();
+
+  const [{ lamports }, accounts] = await Promise.all([
+    client.getBalance(address),
+    client.getTokenAccountsByOwner(address),
+  ]);
+
+  const holdings: RawHolding[] = [{
+    chain: 'solana',
+    network: 'devnet',
+    wallet: address,
+    token: NATIVE_SOL,
+    symbol: 'SOL',
+    decimals: 9,
+    raw: String(lamports),
+    priceSymbol: 'SOL',
+  }];
+
+  const byMint = new Map<string, { amount: bigint; decimals: number }>();
+  for (const account of accounts) {
+    const existing = byMint.get(account.mint);
+    byMint.set(account.mint, {
+      amount: (existing?.amount ?? 0n) + BigInt(account.amount),
+      decimals: account.decimals,
+    });
+  }
+
+  for (const [mint, { amount, decimals }] of byMint) {
+    if (amount === 0n) continue;
+    const metadata = getSolanaTokenMetadata(mint, decimals);
+    holdings.push({
+      chain: 'solana',
+      network: 'devnet',
+      wallet: address,
+      token: mint,
+      symbol: metadata.symbol,
+      decimals,
+      raw: amount.toString(),
+      priceSymbol: metadata.priceSymbol,
+    });
+  }
+
+  return holdings;
+}
+
+export function isSolanaAddress(address: string): boolean {
+  return /^[1-9A-HJ-NP-Za-km-z]{32,44}$/.test(address);
+}
+
+export const WALLET_LINK_SIGNATURE_MAX_AGE_SECONDS = 5 * 60;
+
+export type WalletLinkAction = 'link' | 'unlink';
+
+export interface WalletLinkRequest {
+  action: WalletLinkAction;
+  userAddress: string;
+  solanaAddress: string;
+  nonce: string;
+  issuedAt: number;
+  evmSignature: string;       // personal_sign by userAddress
+  solanaSignature?: string;   // ed25519 by solanaAddress (base58 or base64), required to link
+}
+
+// Nonces seen within the signature window; a signed message is accepted once
+const usedLinkNonces = new Map<string, number>();
+
+/**
+ * Message both wallets sign to link (the EVM user alone signs to unlink)
+ * The nonce and timestamp make each signature single use
+ */
+export function buildWalletLinkMessage(
+  action: WalletLinkAction,
+  userAddress: string,
+  solanaAddress: string,
+  nonce: string,
+  issuedAt: number
+): string {
+  return [
+    action === 'link' ? 'Link Solana wallet on Blossom' : 'Unlink Solana wallet on Blossom',
+    `User: ${userAddress.toLowerCase()}`,
+    `Solana wallet: ${solanaAddress}`,
+    `Nonce: ${nonce}`,
+    `Issued at: ${new Date(issuedAt * 1000).toISOString()}`,
+  ].join('\n');
+}
+
+function decodeSolanaSignature(signature: string, base58Decode: (s: string) => Buffer): Buffer | null {
+  let bytes: Buffer;
+  try {
+    bytes = base58Decode(signature);
+  } catch {
+    bytes = Buffer.from(signature, 'base64');
+  }
+  if (bytes.length !== 64) {
+    bytes = Buffer.from(signature, 'base64');
+  }
+  return bytes.length === 64 ? bytes : null;
+}
+
+/**
+ * Check a link/unlink request; returns an error string or null when valid
+ * A valid request consumes its nonce
+ */
+export async function verifyWalletLinkSignature(
+  request: WalletLinkRequest,
+  now: number = Math.floor(Date.now() / 1000)
+): Promise<string | null> {
+  if (!Number.isInteger(request.issuedAt) || Math.abs(now - request.issuedAt) > WALLET_LINK_SIGNATURE_MAX_AGE_SECONDS) {
+    return 'Signature expired; sign a fresh link message';
+  }
+  if (typeof request.nonce !== 'string' || !/^[0-9a-zA-Z-]{8,64}$/.test(request.nonce)) {
+    return 'nonce must be 8-64 alphanumeric characters';
+  }
+  for (const [nonce, seenAt] of usedLinkNonces) {
+    if (now - seenAt > 2 * WALLET_LINK_SIGNATURE_MAX_AGE_SECONDS) usedLinkNonces.delete(nonce);
+  }
+  if (usedLinkNonces.has(request.nonce)) {
+    return 'Nonce already used';
+  }
+
+  const message = buildWalletLinkMessage(request.action, request.userAddress, request.solanaAddress, request.nonce, request.issuedAt);
+
+  const { verifyMessage } = await import('viem');
+  try {
+    const valid = await verifyMessage({
+      address: request.userAddress as `0x${string}`,
+      message,
+      signature: request.evmSignature as `0x${string}`,
+    });
+    if (!valid) return 'Signature does not prove ownership of userAddress';
+  } catch {
+    return 'Signature does not prove ownership of userAddress';
+  }
+
+  if (request.action === 'link') {
+    const { base58Decode, verifySignature } = await import('../solana/solanaClient');
+    const bytes = typeof request.solanaSignature === 'string'
+      ? decodeSolanaSignature(request.solanaSignature, base58Decode)
+      : null;
+    let valid = false;
+    try {
+      valid = !!bytes && verifySignature(Buffer.from(message, 'utf8'), bytes, request.solanaAddress);
+    } catch {
+      valid = false;
+    }
+    if (!valid) return 'Signature does not prove ownership of solanaAddress';
+  }
+
+  usedLinkNonces.set(request.nonce, now);
+  return null;
+}
+
+/**
+ * Value raw holdings in USD (one price lookup per feed)
+ */
+export async function valueHoldings(
+  raw: RawHolding[],
+  prices: Map<PriceSymbol, { priceUsd: number; source: string }> = new Map()
+): Promise<Holding[]> {
+  const symbols = Array.from(new Set(raw.map(h => h.priceSymbol).filter((s): s is PriceSymbol => !!s)));
+  await Promise.all(symbols.filter(s => !prices.has(s)).map(async symbol => {
+    const snapshot = await getPrice(symbol);
+    prices.set(symbol, { priceUsd: snapshot.priceUsd, source: snapshot.source });
+  }));
+
+  return raw.map(({ priceSymbol, ...holding }) => {
+    const formatted = formatUnits(BigInt(holding.raw), holding.decimals);
+    const price = priceSymbol ? prices.get(priceSymbol) : undefined;
+    return {
+      ...holding,
+      formatted,
+      priceUsd: price?.priceUsd,
+      valueUsd: price ? Number(formatted) * price.priceUsd : undefined,
+    };
+  });
+}
+
+/**
+ * Holdings of a single wallet, valued
+ */
+export async function getWalletHoldings(wallet: WalletRef): Promise<Holding[]> {
+  const raw = wallet.chain === 'solana'
+    ? await getSolanaHoldings(wallet.address)
+    : await getEthereumHoldings(wallet.address);
+  return valueHoldings(raw);
+}
+
+/**
+ * Portfolio across the user's own EVM address and every linked wallet
+ * A wallet that fails to load is reported with its error; the rest still count
+ */
+export async function getUnifiedPortfolio(userAddress: string, extraWallets: WalletRef[] = []): Promise<UnifiedPortfolio> {
+  const { listLinkedWallets } = await import('../../execution-ledger/db');
+
+  const wallets: WalletRef[] = [{ chain: 'ethereum', network: 'sepolia', address: userAddress.toLowerCase() }];
+  const seen = new Set(wallets.map(w => `${w.chain}:${w.address}`));
+  const linked: WalletRef[] = listLinkedWallets(userAddress).map(w => ({
+    chain: w.chain,
+    network: w.network as WalletRef['network'],
+    address: w.address,
+    label: w.label ?? undefined,
+  }));
+  for (const wallet of [...linked, ...extraWallets]) {
+    const key = `${wallet.chain}:${wallet.address}`;
+    if (seen.has(key)) continue;
+    seen.add(key);
+    wallets.push(wallet);
+  }
+
+  const loaded = await Promise.allSettled(wallets.map(wallet =>
+    wallet.chain === 'solana' ? getSolanaHoldings(wallet.address) : getEthereumHoldings(wallet.address)
+  ));
+
+  const prices = new Map<PriceSymbol, { priceUsd: number; source: string }>();
+  const results: WalletHoldings[] = [];
+  for (let i = 0; i < wallets.length; i++) {
+    const result = loaded[i];
+    if (result.status === 'rejected') {
+      results.push({ wallet: wallets[i], holdings: [], totalUsd: 0, error: String(result.reason?.message || result.reason).slice(0, 200) });
+      continue;
+    }
+    const holdings = await valueHoldings(result.value, prices);
+    results.push({
+      wallet: wallets[i],
+      holdings,
+      totalUsd: holdings.reduce((sum, h) => sum + (h.valueUsd ?? 0), 0),
+    });
+  }
+
+  const byChain: Record<string, number> = {};
+  const bySymbol: Record<string, number> = {};
+  for (const { holdings } of results) {
+    for (const holding of holdings) {
+      if (holding.valueUsd === undefined) continue;
+      byChain[holding.chain] = (byChain[holding.chain] ?? 0) + holding.valueUsd;
+      bySymbol[holding.symbol] = (bySymbol[holding.symbol] ?? 0) + holding.valueUsd;
+    }
+  }
+
+  return {
+    userAddress: userAddress.toLowerCase(),
+    wallets: results,
+    totals: {
+      usd: results.reduce((sum, w) => sum + w.totalUsd, 0),
+      byChain,
+      bySymbol,
+    },
+    prices: Object.fromEntries(prices),
+    timestamp: Date.now(),
+  };
+}
@@ -0,0 +1,263 @@
+/**
+ * Price History
+ * Records oracle prices as ticks in the execution ledger, where each tick is
+ * folded into 1m/1h/1d OHLC candles, and serves them back for charts and for
+ * realized volatility in the sims.
+ *
+ * Ticks come from two places: every fresh oracle aggregation (onPriceUpdate),
+ * and a background sampler that prices PRICE_HISTORY_SYMBOLS on an interval so
+ * candles keep filling when nothing else is asking. Stale prices are not
+ * recorded and static fallbacks never reach the listener.
+ */
+
+import {
+  PRICE_HISTORY_SYMBOLS,
+  PRICE_HISTORY_SAMPLE_MS,
+  PRICE_HISTORY_TICK_RETENTION_HOURS,
+  PRICE_HISTORY_1M_RETENTION_DAYS,
+  PRICE_HISTORY_1H_RETENTION_DAYS,
+} from '../config';
+import { getPrice, onPriceUpdate, type PriceSnapshot, type PriceSymbol } from './prices';
+
+// Mirrors execution-ledger/db.ts (kept local to avoid rootDir issues)
+export type PriceCandlePeriod = '1m' | '1h' | '1d';
+
+export const PRICE_CANDLE_PERIOD_SECONDS: Record<PriceCandlePeriod, number> = {
+  '1m': 60,
+  '1h': 3600,
+  '1d': 86400,
+};
+
+interface PriceCandleRow {
+  bucket_start: number;
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  tick_count: number;
+}
+
+export interface PriceCandle {
+  time: number;      // Bucket start, unix seconds (UTC)
+  open: number;
+  high: number;
+  low: number;
+  close: number;
+  ticks: number;
+}
+
+export interface RealizedVolatility {
+  symbol: PriceSymbol;
+  period: PriceCandlePeriod;
+  samples: number;            // Log returns used
+  periodVolatility: number;   // Stdev of per-candle log returns
+  annualized: number;         // periodVolatility scaled to a 365-day year (markets trade 24/7)
+  from: number;
+  to: number;
+}
+
+const PRUNE_INTERVAL_MS = 60 * 60 * 1000;
+const SECONDS_PER_YEAR = 365 * 86400;
+
+let isRunning = false;
+let pollTimeout: ReturnType<typeof setTimeout> | null = null;
+let unsubscribe: (() => void) | null = null;
+let lastPrunedAt = 0;
+
+// Lazy-loaded ledger module (use any to avoid rootDir issues with typeof import)
+let ledgerDb: any = null;
+
+async function getLedgerDb() {
+  if (!ledgerDb) {
+    ledgerDb = await import('../../execution-ledger/db');
+  }
+  return ledgerDb;
+}
+
+export function isCandlePeriod(value: unknown): value is PriceCandlePeriod {
+  return typeof value === 'string' && value in PRICE_CANDLE_PERIOD_SECONDS;
+}
+
+/**
+ * Record a snapshot as a tick; returns false when it was skipped
+ */
+export async function recordPriceSnapshot(snapshot: PriceSnapshot): Promise<boolean> {
+  if (snapshot.source === 'static' || snapshot.stale) {
+    return false;
+  }
+  const { insertPriceTick } = await getLedgerDb();
+  insertPriceTick({
+    symbol: snapshot.symbol,
+    priceUsd: snapshot.priceUsd,
+    source: snapshot.source,
+    confidence: snapshot.confidence,
+    ts: Math.floor(snapshot.fetchedAt / 1000),
+  });
+  return true;
+}
+
+/**
+ * Candles for a symbol, oldest first
+ */
+export async function getPriceCandles(
+  symbol: PriceSymbol,
+  period: PriceCandlePeriod,
+  options: { from?: number; to?: number; limit?: number } = {}
+): Promise<PriceCandle[]> {
+  const { listPriceCandles } = await getLedgerDb();
+  const rows: PriceCandleRow[] = listPriceCandles(symbol, period, options);
+  return rows.map(row => ({
+    time: row.bucket_start,
+    open: row.open,
+    high: row.high,
+    low: row.low,
+    close: row.close,
+    ticks: row.tick_count,
+  }));
+}
+
+/**
+ * Standard deviation of log returns between consecutive closes
+ * Returns null with fewer than two returns
+ */
+export function computeLogReturnVolatility(closes: number[]): { stdev: number; samples: number } | null {
+  const returns: number[] = [];
+  for (let i = 1; i < closes.length; i++) {
+    if (closes[i - 1] > 0 && closes[i] > 0) {
+      returns.push(Math.log(closes[i] / closes[i - 1]));
+    }
+  }
+  if (returns.length < 2) return null;
+
+  const mean = returns.reduce((sum, r) => sum + r, 0) / returns.length;
+  const variance = returns.reduce((sum, r) => sum + (r - mean) ** 2, 0) / (returns.length - 1);
+  return { stdev: Math.sqrt(variance), samples: returns.length };
+}
+
+/**
+ * Scale a per-candle volatility to a 365-day year (markets trade 24/7)
+ */
+export function annualizeVolatility(periodVolatility: number, period: PriceCandlePeriod): number {
+  return periodVolatility * Math.sqrt(SECONDS_PER_YEAR / PRICE_CANDLE_PERIOD_SECONDS[period]);
+}
+
+/**
+ * Realized volatility over the last `lookback` candles of a period
+ * (default: 24 hourly candles). Null until enough history has been recorded.
+ * Gaps in the history are not filled, so a return may span more than one period.
+ */
+export async function getRealizedVolatility(
+  symbol: PriceSymbol,
+  options: { period?: PriceCandlePeriod; lookback?: number } = {}
+): Promise<RealizedVolatility | null> {
+  const period = options.period ?? '1h';
+  const lookback = options.lookback ?? 24;
+  const candles = await getPriceCandles(symbol, period, { limit: lookback + 1 });
+  const result = computeLogReturnVolatility(candles.map(candle => candle.close));
+  if (!result) return null;
+
+  return {
+    symbol: symbol.toUpperCase(),
+    period,
+    samples: result.samples,
+    periodVolatility: result.stdev,
+    annualized: annualizeVolatility(result.stdev, period),
+    from: candles[0].time,
+    to: candles[candles.length - 1].time,
+  };
+}
+
+/**
+ * Drop ticks and fine-grained candles past their retention
+ */
+export async function prunePriceHistoryNow(now: number = Date.now()): Promise<{ ticks: number; candles: number }> {
+  const { prunePriceHistory } = await getLedgerDb();
+  const nowSeconds = Math.floor(now / 1000);
+  return prunePriceHistory({
+    ticksBefore: nowSeconds - PRICE_HISTORY_TICK_RETENTION_HOURS * 3600,
+    candlesBefore: {
+      '1m': nowSeconds - PRICE_HISTORY_1M_RETENTION_DAYS * 86400,
+      '1h': nowSeconds - PRICE_HISTORY_1H_RETENTION_DAYS * 86400,
+    },
+  });
+}
+
+function configuredSymbols(): PriceSymbol[] {
+  return PRICE_HISTORY_SYMBOLS.split(',').map(s => s.trim().toUpperCase()).filter(Boolean);
+}
+
+/**
+ * Price every configured symbol once; ticks are written by the update listener
+ */
+export async function runPriceHistorySample(): Promise<void> {
+  await Promise.all(configuredSymbols().map(async symbol => {
+    try {
+      await getPrice(symbol);
+    } catch (error: any) {
+      console.warn(`[priceHistory] Price fetch failed for ${symbol}:`, error.message);
+    }
+  }));
+
+  if (Date.now() - lastPrunedAt >= PRUNE_INTERVAL_MS) {
+    lastPrunedAt = Date.now();
+    const pruned = await prunePriceHistoryNow();
+    if (pruned.ticks > 0 || pruned.candles > 0) {
+      console.log(`[priceHistory] Pruned ${pruned.ticks} ticks, ${pruned.candles} candles`);
+    }
+  }
+}
+
+/**
+ * Start recording oracle prices and sampling the configured symbols
+ */
+export function startPriceHistoryRecorder(intervalMs: number = PRICE_HISTORY_SAMPLE_MS): void {
+  if (isRunning) {
+    console.log('[priceHistory] Already running');
+    return;
+  }
+
+  console.log(`[priceHistory] Recording ${configuredSymbols().join(', ')} (every ${intervalMs}ms)`);
+  isRunning = true;
+
+  unsubscribe = onPriceUpdate(snapshot => {
+    recordPriceSnapshot(snapshot).catch((error: any) => {
+      console.warn(`[priceHistory] Failed to record ${snapshot.symbol}:`, error.message);
+    });
+  });
+
+  const poll = async () => {
+    if (!isRunning) return;
+
+    try {
+      await runPriceHistorySample();
+    } catch (error: any) {
+      console.error('[priceHistory] Sample error:', error.message?.slice(0, 100));
+    }
+
+    // Schedule next sample
+    pollTimeout = setTimeout(poll, intervalMs);
+  };
+
+  poll();
+}
+
+/**
+ * Stop recording
+ */
+export function stopPriceHistoryRecorder(): void {
+  console.log('[priceHistory] Stopping price history recorder');
+  isRunning = false;
+  unsubscribe?.();
+  unsubscribe = null;
+  if (pollTimeout) {
+    clearTimeout(pollTimeout);
+    pollTimeout = null;
+  }
+}
+
+/**
+ * Check if the recorder is running
+ */
+export function isPriceHistoryRecorderRunning(): boolean {
+  return isRunning;
+}
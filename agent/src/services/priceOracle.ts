@@ -0,0 +1,206 @@
+/**
+ * Price Oracle
+ * Queries every price source that covers a symbol in parallel and aggregates
+ * the answers:
+ *   - observations older than their max age are stale and only used when
+ *     nothing fresh is available; the max age is the feed's own (e.g. a
+ *     Chainlink heartbeat), else the source's, else maxAgeSeconds
+ *   - with three or more fresh observations, any further than maxDeviationBps
+ *     from the median are dropped as outliers
+ *   - the price is the median of what remains
+ *
+ * Confidence:
+ *   high    two or more fresh sources agree within maxDeviationBps
+ *   medium  a single fresh source
+ *   low     only stale observations, or sources that disagree
+ *
+ * Sources implement PriceSource (see priceSources/); prices.ts owns the
+ * configured instance, the cache and the static fallback.
+ */
+
+export type PriceSymbol = string; // Uppercase ticker, e.g. 'ETH'
+
+export type PriceConfidence = 'high' | 'medium' | 'low';
+
+export interface SourcePrice {
+  priceUsd: number;
+  observedAt: number; // ms; when the source last updated, not when we asked
+  maxAgeSeconds?: number; // This feed's staleness bound, when it updates on its own schedule
+}
+
+export interface PriceSource {
+  id: string;
+  supports(symbol: PriceSymbol): boolean;
+  fetchPrice(symbol: PriceSymbol): Promise<SourcePrice>;
+}
+
+export interface SourceObservation {
+  source: string;
+  status: 'used' | 'outlier' | 'stale' | 'error';
+  priceUsd?: number;
+  observedAt?: number;
+  error?: string;
+}
+
+export interface AggregatedPrice {
+  symbol: PriceSymbol;
+  priceUsd: number;
+  source: string;          // The single source used, or 'median'
+  confidence: PriceConfidence;
+  stale: boolean;
+  deviationBps: number;    // Widest spread of a used observation from the median
+  observedAt: number;      // Oldest used observation
+  sources: SourceObservation[];
+}
+
+export interface PriceOracleOptions {
+  timeoutMs: number;
+  maxAgeSeconds: number;
+  sourceMaxAgeSeconds?: Record<string, number>; // Per source id, overrides maxAgeSeconds
+  maxDeviationBps: number;
+}
+
+export class PriceUnavailableError extends Error {
+  readonly code = 'PRICE_UNAVAILABLE';
+
+  constructor(public readonly symbol: string) {
+    super(`No price available for ${symbol}`);
+    this.name = 'PriceUnavailableError';
+  }
+}
+
+const CONFIDENCE_RANK: Record<PriceConfidence, number> = { low: 0, medium: 1, high: 2 };
+
+export function meetsConfidence(actual: PriceConfidence, required: PriceConfidence): boolean {
+  return CONFIDENCE_RANK[actual] >= CONFIDENCE_RANK[required];
+}
+
+export function lowestConfidence(values: PriceConfidence[]): PriceConfidence | undefined {
+  return values.reduce<PriceConfidence | undefined>(
+    (lowest, value) => (lowest === undefined || CONFIDENCE_RANK[value] < CONFIDENCE_RANK[lowest] ? value : lowest),
+    undefined
+  );
+}
+
+export function median(values: number[]): number {
+  const sorted = [...values].sort((a, b) => a - b);
+  const mid = Math.floor(sorted.length / 2);
+  return sorted.length % 2 === 1 ? sorted[mid] : (sorted[mid - 1] + sorted[mid]) / 2;
+}
+
+function deviationBps(price: number, reference: number): number {
+  return Math.round((Math.abs(price - reference) / reference) * 10000);
+}
+
+/**
+ * Parse comma-separated SYMBOL:VALUE[:...] entries into a map keyed by uppercase symbol
+ */
+export function parseSymbolList(raw: string): Map<PriceSymbol, string[]> {
+  const entries = new Map<PriceSymbol, string[]>();
+  for (const entry of raw.split(',').map(e => e.trim()).filter(Boolean)) {
+    const [symbol, ...values] = entry.split(':').map(part => part.trim());
+    if (!symbol || values.length === 0 || values.some(value => !value)) {
+      console.warn(`[priceOracle] Ignoring malformed entry: ${entry}`);
+      continue;
+    }
+    entries.set(symbol.toUpperCase(), values);
+  }
+  return entries;
+}
+
+export class PriceOracle {
+  private sources: PriceSource[] = [];
+
+  constructor(private readonly options: PriceOracleOptions) {}
+
+  /**
+   * Add a source, replacing any source with the same id
+   */
+  addSource(source: PriceSource): void {
+    this.sources = [...this.sources.filter(existing => existing.id !== source.id), source];
+  }
+
+  removeSource(id: string): void {
+    this.sources = this.sources.filter(source => source.id !== id);
+  }
+
+  listSources(): string[] {
+    return this.sources.map(source => source.id);
+  }
+
+  supports(symbol: PriceSymbol): boolean {
+    return this.sources.some(source => source.supports(symbol));
+  }
+
+  private async observe(source: PriceSource, symbol: PriceSymbol): Promise<SourceObservation> {
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    try {
+      const price = await Promise.race([
+        source.fetchPrice(symbol),
+        new Promise<never>((_, reject) => {
+          timer = setTimeout(() => reject(new Error(`timed out after ${this.options.timeoutMs}ms`)), this.options.timeoutMs);
+        }),
+      ]);
+      if (!(price.priceUsd > 0) || !Number.isFinite(price.priceUsd)) {
+        throw new Error(`invalid price ${price.priceUsd}`);
+      }
+      const maxAgeSeconds = price.maxAgeSeconds
+        ?? this.options.sourceMaxAgeSeconds?.[source.id]
+        ?? this.options.maxAgeSeconds;
+      const maxAgeMs = maxAgeSeconds * 1000;
+      const stale = Date.now() - price.observedAt > maxAgeMs;
+      return { source: source.id, status: stale ? 'stale' : 'used', priceUsd: price.priceUsd, observedAt: price.observedAt };
+    } catch (error: any) {
+      return { source: source.id, status: 'error', error: error.message };
+    } finally {
+      clearTimeout(timer);
+    }
+  }
+
+  /**
+   * Aggregate every source covering the symbol; null when none returned a price
+   */
+  async aggregate(symbol: PriceSymbol): Promise<AggregatedPrice | null> {
+    const covering = this.sources.filter(source => source.supports(symbol));
+    if (covering.length === 0) return null;
+
+    const observations = await Promise.all(covering.map(source => this.observe(source, symbol)));
+    const fresh = observations.filter(o => o.status === 'used');
+    const stale = fresh.length === 0;
+    const pool = stale ? observations.filter(o => o.status === 'stale') : fresh;
+    if (pool.length === 0) return null;
+
+    let used = pool;
+    let mid = median(pool.map(o => o.priceUsd!));
+    if (!stale && pool.length >= 3) {
+      used = pool.filter(o => deviationBps(o.priceUsd!, mid) <= this.options.maxDeviationBps);
+      if (used.length === 0) {
+        used = pool; // No majority (e.g. two camps): keep everything, confidence ends up low
+      }
+      for (const o of pool) {
+        if (!used.includes(o)) o.status = 'outlier';
+      }
+      mid = median(used.map(o => o.priceUsd!));
+    }
+    for (const o of used) o.status = 'used';
+
+    const spread = Math.max(...used.map(o => deviationBps(o.priceUsd!, mid)));
+    let confidence: PriceConfidence;
+    if (stale || (used.length >= 2 && spread > this.options.maxDeviationBps)) {
+      confidence = 'low';
+    } else {
+      confidence = used.length >= 2 ? 'high' : 'medium';
+    }
+
+    return {
+      symbol,
+      priceUsd: mid,
+      source: used.length === 1 ? used[0].source : 'median',
+      confidence,
+      stale,
+      deviationBps: spread,
+      observedAt: Math.min(...used.map(o => o.observedAt!)),
+      sources: observations,
+    };
+  }
+}
@@ -0,0 +1,115 @@
+/**
+ * Chainlink Price Source
+ * Reads latestRoundData from USD aggregators over CHAINLINK_RPC_URL
+ * (defaults to ETH_TESTNET_RPC_URL). The built-in feeds are Sepolia's; point
+ * CHAINLINK_RPC_URL at another network together with CHAINLINK_FEEDS.
+ *
+ * Feeds only update on deviation or once per heartbeat, so each answer is
+ * stale after its feed's heartbeat plus a grace period rather than the
+ * oracle-wide PRICE_MAX_AGE_SECONDS.
+ */
+
+import { CHAINLINK_RPC_URL, CHAINLINK_FEEDS } from '../../config';
+import { parseSymbolList, type PriceSource, type PriceSymbol, type SourcePrice } from '../priceOracle';
+
+interface ChainlinkFeed {
+  address: string;
+  heartbeatSeconds?: number;
+}
+
+// Chainlink USD data feeds on Sepolia
+const SEPOLIA_FEEDS: Record<PriceSymbol, ChainlinkFeed> = {
+  ETH: { address: '0x694AA1769357215DE4FAC081bf1f309aDC325306', heartbeatSeconds: 3600 },
+  BTC: { address: '0x1b44F3514812d835EB1BDB0acB33d3fA3351Ee43', heartbeatSeconds: 3600 },
+  LINK: { address: '0xc59E3633BAAC79493d908e63626716e204A45EdF', heartbeatSeconds: 3600 },
+  USDC: { address: '0xA2F78ab2355fe2f984D808B5CeE7FD0A93D5270E', heartbeatSeconds: 86400 },
+};
+
+// Slack on top of the heartbeat for the update transaction to land
+const HEARTBEAT_GRACE_SECONDS = 600;
+
+const AGGREGATOR_ABI = [
+  {
+    name: 'decimals',
+    type: 'function',
+    stateMutability: 'view',
+    inputs: [],
+    outputs: [{ name: '', type: 'uint8' }],
+  },
+  {
+    name: 'latestRoundData',
+    type: 'function',
+    stateMutability: 'view',
+    inputs: [],
+    outputs: [
+      { name: 'roundId', type: 'uint80' },
+      { name: 'answer', type: 'int256' },
+      { name: 'startedAt', type: 'uint256' },
+      { name: 'updatedAt', type: 'uint256' },
+      { name: 'answeredInRound', type: 'uint80' },
+    ],
+  },
+] as const;
+
+/**
+ * Null when no RPC is configured
+ */
+export function createChainlinkSource(): PriceSource | null {
+  if (!CHAINLINK_RPC_URL) return null;
+
+  const feeds = new Map<PriceSymbol, ChainlinkFeed>(Object.entries(SEPOLIA_FEEDS));
+  for (const [symbol, [address, heartbeat]] of parseSymbolList(CHAINLINK_FEEDS)) {
+    const heartbeatSeconds = heartbeat === undefined ? undefined : Number(heartbeat);
+    if (heartbeatSeconds !== undefined && !(Number.isInteger(heartbeatSeconds) && heartbeatSeconds > 0)) {
+      console.warn(`[chainlink] Ignoring invalid heartbeat for ${symbol}: ${heartbeat}`);
+      feeds.set(symbol, { address });
+      continue;
+    }
+    feeds.set(symbol, { address, heartbeatSeconds });
+  }
+
+  const decimalsByFeed = new Map<string, number>();
+  let client: any = null;
+
+  const getClient = async () => {
+    if (!client) {
+      const { createPublicClient, http } = await import('viem');
+      client = createPublicClient({ transport: http(CHAINLINK_RPC_URL) });
+    }
+    return client;
+  };
+
+  return {
+    id: 'chainlink',
+    supports: symbol => feeds.has(symbol),
+    async fetchPrice(symbol): Promise<SourcePrice> {
+      const entry = feeds.get(symbol);
+      if (!entry) {
+        throw new Error(`No Chainlink feed for ${symbol}`);
+      }
+      const feed = entry.address as `0x${string}`;
+      const publicClient = await getClient();
+
+      let decimals = decimalsByFeed.get(feed);
+      if (decimals === undefined) {
+        decimals = Number(await publicClient.readContract({ address: feed, abi: AGGREGATOR_ABI, functionName: 'decimals' }));
+        decimalsByFeed.set(feed, decimals);
+      }
+
+      const [, answer, , updatedAt] = await publicClient.readContract({
+        address: feed,
+        abi: AGGREGATOR_ABI,
+        functionName: 'latestRoundData',
+      }) as readonly [bigint, bigint, bigint, bigint, bigint];
+      if (answer <= 0n) {
+        throw new Error(`Invalid Chainlink answer for ${symbol}: ${answer}`);
+      }
+
+      return {
+        priceUsd: Number(answer) / 10 ** decimals,
+        observedAt: Number(updatedAt) * 1000,
+        maxAgeSeconds: entry.heartbeatSeconds === undefined ? undefined : entry.heartbeatSeconds + HEARTBEAT_GRACE_SECONDS,
+      };
+    },
+  };
+}
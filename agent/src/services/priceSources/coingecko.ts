@@ -0,0 +1,53 @@
+/**
+ * CoinGecko Price Source
+ * Public simple/price API; extra symbols via PRICE_COINGECKO_IDS (SYMBOL:coingecko-id)
+ */
+
+import { PRICE_COINGECKO_IDS } from '../../config';
+import { parseSymbolList, type PriceSource, type PriceSymbol, type SourcePrice } from '../priceOracle';
+
+const DEFAULT_IDS: Record<PriceSymbol, string> = {
+  ETH: 'ethereum',
+  BTC: 'bitcoin',
+  SOL: 'solana',
+  USDC: 'usd-coin',
+  AVAX: 'avalanche-2',
+  LINK: 'chainlink',
+};
+
+export function createCoinGeckoSource(): PriceSource {
+  const ids = new Map<PriceSymbol, string>(Object.entries(DEFAULT_IDS));
+  for (const [symbol, [id]] of parseSymbolList(PRICE_COINGECKO_IDS)) {
+    ids.set(symbol, id);
+  }
+
+  return {
+    id: 'coingecko',
+    supports: symbol => ids.has(symbol),
+    async fetchPrice(symbol): Promise<SourcePrice> {
+      const coinId = ids.get(symbol);
+      if (!coinId) {
+        throw new Error(`Unsupported symbol: ${symbol}`);
+      }
+
+      const url = `https://api.coingecko.com/api/v3/simple/price?ids=${coinId}&vs_currencies=usd&include_last_updated_at=true`;
+      const response = await fetch(url, {
+        headers: {
+          'Accept': 'application/json',
+        },
+      });
+      if (!response.ok) {
+        throw new Error(`CoinGecko API error: ${response.status}`);
+      }
+
+      const data = await response.json() as Record<string, { usd: number; last_updated_at?: number }>;
+      const price = data[coinId]?.usd;
+      if (typeof price !== 'number' || price <= 0) {
+        throw new Error(`Invalid price data from CoinGecko: ${price}`);
+      }
+
+      const updatedAt = data[coinId]?.last_updated_at;
+      return { priceUsd: price, observedAt: updatedAt ? updatedAt * 1000 : Date.now() };
+    },
+  };
+}
@@ -0,0 +1,58 @@
+/**
+ * dFlow Price Source
+ * Prices a token by quoting a one-token swap into USDC through the dFlow Quote API
+ * (Solana mainnet mints). SOL is built in; more via PRICE_DFLOW_TOKENS
+ * (SYMBOL:MINT:DECIMALS). Only active when dFlow swap quotes are available.
+ */
+
+import { PRICE_DFLOW_TOKENS, PRICE_DFLOW_USDC_MINT } from '../../config';
+import { getSwapQuote, isDflowCapabilityAvailable } from '../../integrations/dflow/dflowClient';
+import { parseSymbolList, type PriceSource, type PriceSymbol, type SourcePrice } from '../priceOracle';
+
+const USDC_DECIMALS = 6;
+
+interface DflowToken {
+  mint: string;
+  decimals: number;
+}
+
+/**
+ * Null when dFlow swap quotes are not configured
+ */
+export function createDflowSource(): PriceSource | null {
+  if (!isDflowCapabilityAvailable('swapsQuotes')) return null;
+
+  const tokens = new Map<PriceSymbol, DflowToken>([
+    ['SOL', { mint: 'So11111111111111111111111111111111111111112', decimals: 9 }],
+  ]);
+  for (const [symbol, [mint, decimals]] of parseSymbolList(PRICE_DFLOW_TOKENS)) {
+    const parsed = parseInt(decimals ?? '', 10);
+    if (Number.isNaN(parsed)) {
+      console.warn(`[priceSources/dflow] Ignoring ${symbol}: missing decimals`);
+      continue;
+    }
+    tokens.set(symbol, { mint, decimals: parsed });
+  }
+
+  return {
+    id: 'dflow',
+    supports: symbol => tokens.has(symbol),
+    async fetchPrice(symbol): Promise<SourcePrice> {
+      const token = tokens.get(symbol);
+      if (!token) {
+        throw new Error(`No dFlow mint for ${symbol}`);
+      }
+
+      const quote = await getSwapQuote({
+        tokenIn: token.mint,
+        tokenOut: PRICE_DFLOW_USDC_MINT,
+        amountIn: (10n ** BigInt(token.decimals)).toString(),
+      });
+      if (!quote.ok || !quote.data?.amountOut) {
+        throw new Error(quote.error || 'dFlow quote missing amountOut');
+      }
+
+      return { priceUsd: Number(quote.data.amountOut) / 10 ** USDC_DECIMALS, observedAt: Date.now() };
+    },
+  };
+}
@@ -0,0 +1,120 @@
+/**
+ * Uniswap V3 TWAP Price Source
+ * Time-weighted average price over UNISWAP_TWAP_WINDOW_SECONDS from pools listed
+ * in UNISWAP_TWAP_POOLS (SYMBOL:POOL). Each pool must pair the token with a USD
+ * stablecoin, which is taken at $1. Harder to move within a block than a spot
+ * quote, so it is a useful check against the off-chain sources.
+ */
+
+import { ETH_TESTNET_RPC_URL, UNISWAP_TWAP_POOLS, UNISWAP_TWAP_WINDOW_SECONDS } from '../../config';
+import { parseSymbolList, type PriceSource, type PriceSymbol, type SourcePrice } from '../priceOracle';
+
+const POOL_ABI = [
+  { name: 'token0', type: 'function', stateMutability: 'view', inputs: [], outputs: [{ name: '', type: 'address' }] },
+  { name: 'token1', type: 'function', stateMutability: 'view', inputs: [], outputs: [{ name: '', type: 'address' }] },
+  {
+    name: 'observe',
+    type: 'function',
+    stateMutability: 'view',
+    inputs: [{ name: 'secondsAgos', type: 'uint32[]' }],
+    outputs: [
+      { name: 'tickCumulatives', type: 'int56[]' },
+      { name: 'secondsPerLiquidityCumulativeX128s', type: 'uint160[]' },
+    ],
+  },
+] as const;
+
+const ERC20_ABI = [
+  { name: 'decimals', type: 'function', stateMutability: 'view', inputs: [], outputs: [{ name: '', type: 'uint8' }] },
+  { name: 'symbol', type: 'function', stateMutability: 'view', inputs: [], outputs: [{ name: '', type: 'string' }] },
+] as const;
+
+interface PoolInfo {
+  decimals0: number;
+  decimals1: number;
+  baseIsToken0: boolean;
+}
+
+function isUsdStable(symbol: string): boolean {
+  const s = symbol.toUpperCase();
+  return s.includes('USDC') || s === 'USDT' || s === 'DAI';
+}
+
+/**
+ * Price of token0 in token1 from an average tick, adjusted for decimals
+ */
+export function tickToPrice(tick: number, decimals0: number, decimals1: number): number {
+  return Math.pow(1.0001, tick) * Math.pow(10, decimals0 - decimals1);
+}
+
+/**
+ * Null when no pools are configured or there is no RPC
+ */
+export function createUniswapTwapSource(): PriceSource | null {
+  const pools = parseSymbolList(UNISWAP_TWAP_POOLS);
+  if (pools.size === 0 || !ETH_TESTNET_RPC_URL) return null;
+
+  const poolInfo = new Map<string, PoolInfo>();
+  let client: any = null;
+
+  const getClient = async () => {
+    if (!client) {
+      const { createPublicClient, http } = await import('viem');
+      client = createPublicClient({ transport: http(ETH_TESTNET_RPC_URL) });
+    }
+    return client;
+  };
+
+  const loadPool = async (pool: `0x${string}`): Promise<PoolInfo> => {
+    const known = poolInfo.get(pool);
+    if (known) return known;
+
+    const publicClient = await getClient();
+    const [token0, token1] = await Promise.all([
+      publicClient.readContract({ address: pool, abi: POOL_ABI, functionName: 'token0' }),
+      publicClient.readContract({ address: pool, abi: POOL_ABI, functionName: 'token1' }),
+    ]);
+    const [decimals0, symbol0, decimals1, symbol1] = await Promise.all([
+      publicClient.readContract({ address: token0, abi: ERC20_ABI, functionName: 'decimals' }),
+      publicClient.readContract({ address: token0, abi: ERC20_ABI, functionName: 'symbol' }),
+      publicClient.readContract({ address: token1, abi: ERC20_ABI, functionName: 'decimals' }),
+      publicClient.readContract({ address: token1, abi: ERC20_ABI, functionName: 'symbol' }),
+    ]);
+
+    if (isUsdStable(symbol0) === isUsdStable(symbol1)) {
+      throw new Error(`Pool ${pool} (${symbol0}/${symbol1}) must pair one USD stablecoin with one other token`);
+    }
+    const info = { decimals0: Number(decimals0), decimals1: Number(decimals1), baseIsToken0: isUsdStable(symbol1) };
+    poolInfo.set(pool, info);
+    return info;
+  };
+
+  return {
+    id: 'uniswap_twap',
+    supports: (symbol: PriceSymbol) => pools.has(symbol),
+    async fetchPrice(symbol): Promise<SourcePrice> {
+      const [address] = pools.get(symbol) ?? [];
+      if (!address) {
+        throw new Error(`No Uniswap TWAP pool for ${symbol}`);
+      }
+      const pool = address as `0x${string}`;
+      const info = await loadPool(pool);
+
+      const publicClient = await getClient();
+      const window = UNISWAP_TWAP_WINDOW_SECONDS;
+      const [tickCumulatives] = await publicClient.readContract({
+        address: pool,
+        abi: POOL_ABI,
+        functionName: 'observe',
+        args: [[window, 0]],
+      }) as readonly [readonly bigint[], readonly bigint[]];
+
+      const averageTick = Number(tickCumulatives[1] - tickCumulatives[0]) / window;
+      const token0InToken1 = tickToPrice(averageTick, info.decimals0, info.decimals1);
+      const priceUsd = info.baseIsToken0 ? token0InToken1 : 1 / token0InToken1;
+
+      // The average runs up to the latest block, so it is as fresh as the chain
+      return { priceUsd, observedAt: Date.now() };
+    },
+  };
+}
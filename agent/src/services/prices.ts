@@ -1,14 +1,45 @@
 /**
  * Price Service
- * Fetches real market prices with safe fallbacks
+ * Fetches market prices through the price oracle (priceOracle.ts) with caching
+ * and a static fallback for the core symbols.
+ *
+ * Every snapshot says where the price came from and how far to trust it:
+ *   source      'coingecko' | 'chainlink' | 'uniswap_twap' | 'dflow' (one source),
+ *               'median' (several), or 'static' (fallback table)
+ *   confidence  'high' | 'medium' | 'low' - static and stale prices are always low
  */
 
-export type PriceSymbol = 'ETH' | 'BTC' | 'SOL' | 'USDC' | 'AVAX' | 'LINK';
+import {
+  PRICE_ORACLE_SOURCES,
+  PRICE_CACHE_TTL_MS,
+  PRICE_SOURCE_TIMEOUT_MS,
+  PRICE_MAX_AGE_SECONDS,
+  PRICE_SOURCE_MAX_AGE_SECONDS,
+  PRICE_MAX_DEVIATION_BPS,
+} from '../config';
+import {
+  PriceOracle,
+  PriceUnavailableError,
+  type PriceSource,
+  type PriceSymbol,
+  type PriceConfidence,
+  type SourceObservation,
+} from './priceOracle';
+import { createCoinGeckoSource } from './priceSources/coingecko';
+import { createChainlinkSource } from './priceSources/chainlink';
+import { createUniswapTwapSource } from './priceSources/uniswapTwap';
+import { createDflowSource } from './priceSources/dflow';
+
+export type { PriceSymbol, PriceConfidence, PriceSource, SourceObservation } from './priceOracle';
+export { PriceUnavailableError } from './priceOracle';
 
 export interface PriceSnapshot {
   symbol: PriceSymbol;
   priceUsd: number;
-  source: 'coingecko' | 'static';
+  source: string;
+  confidence: PriceConfidence;
+  stale?: boolean;                 // Only stale observations were available
+  sources?: SourceObservation[];   // Per-source detail (absent for static)
   fetchedAt: number;
 }
 
@@ -25,90 +56,156 @@ const STATIC_PRICES: Record<PriceSymbol, number> = {
   LINK: 14,
 };
 
-// Cache TTL: 12 seconds
-const CACHE_TTL_MS = 12 * 1000;
+const SOURCE_FACTORIES: Record<string, () => PriceSource | null> = {
+  coingecko: createCoinGeckoSource,
+  chainlink: createChainlinkSource,
+  uniswap_twap: createUniswapTwapSource,
+  dflow: createDflowSource,
+};
+
+let oracle: PriceOracle | null = null;
+
+/**
+ * Parse PRICE_SOURCE_MAX_AGE_SECONDS (comma-separated SOURCE:SECONDS) into a map keyed by source id
+ */
+export function parseSourceMaxAges(raw: string): Record<string, number> {
+  const maxAges: Record<string, number> = {};
+  for (const entry of raw.split(',').map(e => e.trim()).filter(Boolean)) {
+    const [source, value] = entry.split(':').map(part => part.trim());
+    const seconds = Number(value);
+    if (!source || !Number.isInteger(seconds) || seconds <= 0) {
+      console.warn(`[prices] Ignoring malformed PRICE_SOURCE_MAX_AGE_SECONDS entry: ${entry}`);
+      continue;
+    }
+    maxAges[source.toLowerCase()] = seconds;
+  }
+  return maxAges;
+}
+
+type PriceUpdateListener = (snapshot: PriceSnapshot) => void;
+const updateListeners = new Set<PriceUpdateListener>();
+
+/**
+ * The configured oracle; built on first use from PRICE_ORACLE_SOURCES
+ */
+export function getPriceOracle(): PriceOracle {
+  if (!oracle) {
+    oracle = new PriceOracle({
+      timeoutMs: PRICE_SOURCE_TIMEOUT_MS,
+      maxAgeSeconds: PRICE_MAX_AGE_SECONDS,
+      sourceMaxAgeSeconds: parseSourceMaxAges(PRICE_SOURCE_MAX_AGE_SECONDS),
+      maxDeviationBps: PRICE_MAX_DEVIATION_BPS,
+    });
+    for (const name of PRICE_ORACLE_SOURCES.split(',').map(s => s.trim().toLowerCase()).filter(Boolean)) {
+      const factory = SOURCE_FACTORIES[name];
+      if (!factory) {
+        console.warn(`[prices] Unknown price source in PRICE_ORACLE_SOURCES: ${name}`);
+        continue;
+      }
+      const source = factory();
+      if (source) {
+        oracle.addSource(source);
+      }
+    }
+    console.log(`[prices] Price sources: ${oracle.listSources().join(', ') || 'none (static only)'}`);
+  }
+  return oracle;
+}
+
+/**
+ * Add a price source at runtime (replaces one with the same id)
+ */
+export function registerPriceSource(source: PriceSource): void {
+  getPriceOracle().addSource(source);
+  priceCache.clear();
+}
+
+/**
+ * Subscribe to every price freshly aggregated from the oracle (cache hits and
+ * static fallbacks are not reported). Returns an unsubscribe function.
+ */
+export function onPriceUpdate(listener: PriceUpdateListener): () => void {
+  updateListeners.add(listener);
+  return () => {
+    updateListeners.delete(listener);
+  };
+}
+
+/**
+ * Whether any source (or the static table) can price the symbol
+ */
+export function hasPriceFeed(symbol: string): boolean {
+  const key = symbol.toUpperCase();
+  return key in STATIC_PRICES || getPriceOracle().supports(key);
+}
 
 /**
  * Get price for a symbol, with caching and fallback
+ * Throws PriceUnavailableError for symbols no source or static price covers
  */
 export async function getPrice(symbol: PriceSymbol): Promise<PriceSnapshot> {
+  const key = symbol.toUpperCase();
+
   // Check cache first
-  const cached = priceCache.get(symbol);
-  if (cached && Date.now() - cached.fetchedAt < CACHE_TTL_MS) {
+  const cached = priceCache.get(key);
+  if (cached && Date.now() - cached.fetchedAt < PRICE_CACHE_TTL_MS) {
     return cached;
   }
 
-  // Try to fetch from CoinGecko
-  try {
-    const price = await fetchFromCoinGecko(symbol);
+  const aggregated = await getPriceOracle().aggregate(key);
+  if (aggregated) {
+    if (aggregated.confidence === 'low') {
+      console.warn(`[prices] Low-confidence ${key} price from ${aggregated.source}` +
+        `${aggregated.stale ? ' (stale)' : ` (spread ${aggregated.deviationBps} bps)`}`);
+    }
     const snapshot: PriceSnapshot = {
-      symbol,
-      priceUsd: price,
-      source: 'coingecko',
+      symbol: key,
+      priceUsd: aggregated.priceUsd,
+      source: aggregated.source,
+      confidence: aggregated.confidence,
+      stale: aggregated.stale || undefined,
+      sources: aggregated.sources,
       fetchedAt: Date.now(),
     };
-    priceCache.set(symbol, snapshot);
+    priceCache.set(key, snapshot);
+    for (const listener of updateListeners) {
+      try {
+        listener(snapshot);
+      } catch (error: any) {
+        console.warn('[prices] Price update listener failed:', error.message);
+      }
+    }
     return snapshot;
-  } catch (error) {
-    console.warn(`Failed to fetch ${symbol} price from CoinGecko, using static fallback:`, error);
   }
 
   // Fallback to static price
+  const staticPrice = STATIC_PRICES[key];
+  if (staticPrice === undefined) {
+    throw new PriceUnavailableError(key);
+  }
+  console.warn(`[prices] No source returned a ${key} price, using static fallback`);
   const snapshot: PriceSnapshot = {
-    symbol,
-    priceUsd: STATIC_PRICES[symbol],
+    symbol: key,
+    priceUsd: staticPrice,
     source: 'static',
+    confidence: 'low',
     fetchedAt: Date.now(),
   };
-  priceCache.set(symbol, snapshot);
+  priceCache.set(key, snapshot);
   return snapshot;
 }
 
 /**
- * Fetch price from CoinGecko public API
+ * Clear price cache (useful for testing)
  */
-async function fetchFromCoinGecko(symbol: PriceSymbol): Promise<number> {
-  // CoinGecko API mapping
-  const coinGeckoIds: Record<PriceSymbol, string> = {
-    ETH: 'ethereum',
-    BTC: 'bitcoin',
-    SOL: 'solana',
-    USDC: 'usd-coin',
-    AVAX: 'avalanche-2',
-    LINK: 'chainlink',
-  };
-
-  const coinId = coinGeckoIds[symbol];
-  if (!coinId) {
-    throw new Error(`Unsupported symbol: ${symbol}`);
-  }
-
-  const url = `https://api.coingecko.com/api/v3/simple/price?ids=${coinId}&vs_currencies=usd`;
-  
-  const response = await fetch(url, {
-    headers: {
-      'Accept': 'application/json',
-    },
-  });
-
-  if (!response.ok) {
-    throw new Error(`CoinGecko API error: ${response.status}`);
-  }
-
-  const data = await response.json() as Record<string, { usd: number }>;
-  const price = data[coinId]?.usd;
-  
-  if (typeof price !== 'number' || price <= 0) {
-    throw new Error(`Invalid price data from CoinGecko: ${price}`);
-  }
-
-  return price;
+export function clearPriceCache(): void {
+  priceCache.clear();
 }
 
 /**
- * Clear price cache (useful for testing)
+ * Drop the oracle so the next lookup rebuilds it from config (useful for testing)
  */
-export function clearPriceCache(): void {
+export function resetPriceOracle(): void {
+  oracle = null;
   priceCache.clear();
 }
-
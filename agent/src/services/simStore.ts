@@ -0,0 +1,296 @@
+/**
+ * Sim Account Store
+ * Per-user simulation state for the perps, defi and event sims
+ *
+ * Sims read and mutate state synchronously from an in-memory map keyed by
+ * user id. Every mutation is written through (debounced) to the execution
+ * ledger's sim_accounts table, and accounts are hydrated from it on first
+ * access, so a user's book survives restarts and resets stay scoped per user.
+ *
+ * Accounts idle for SIM_ACCOUNT_IDLE_MS, or beyond the SIM_MAX_LOADED_ACCOUNTS
+ * most recently used, are flushed and dropped from memory; the next request
+ * hydrates them again (with SIM_PERSIST_DISABLED their state is simply lost).
+ * Accounts a sim retains (open perp positions) stay loaded for the background
+ * engines, and are hydrated at boot by hydrateRetainedSimAccounts.
+ *
+ * Uses dynamic imports for the ledger (same as ledger/ledger.ts) so the sims
+ * keep working in-memory when the ledger DB is unavailable.
+ */
+
+import { randomUUID } from 'crypto';
+
+export type SimKind = 'perps' | 'defi' | 'event';
+
+const SIM_KINDS: SimKind[] = ['perps', 'defi', 'event'];
+
+/**
+ * Shared fallback account for requests that carry no wallet or session id
+ */
+export const ANONYMOUS_SIM_USER = 'anonymous';
+
+/**
+ * HTTP-only cookie holding the server-issued sim session id of a signed-out client
+ */
+export const SIM_SESSION_COOKIE = 'blossom_sim_session';
+
+// Debounce window for write-through persistence
+const PERSIST_DEBOUNCE_MS = 250;
+
+const SIM_ACCOUNT_IDLE_MS = parseInt(process.env.SIM_ACCOUNT_IDLE_MS || '1800000', 10);
+const SIM_MAX_LOADED_ACCOUNTS = parseInt(process.env.SIM_MAX_LOADED_ACCOUNTS || '5000', 10);
+
+const UUID_PATTERN = /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/;
+
+const simStates = new Map<string, Partial<Record<SimKind, unknown>>>();
+const hydrations = new Map<string, Promise<void>>();
+const pendingPersists = new Map<string, ReturnType<typeof setTimeout>>();
+// Last request time per loaded account, oldest first
+const lastAccess = new Map<string, number>();
+// Per-sim predicates for state that must stay in memory
+const retainers: Partial<Record<SimKind, (state: unknown) => boolean>> = {};
+
+// Lazy-loaded ledger module (use any to avoid rootDir issues with typeof import)
+let ledgerDb: any = null;
+
+async function getLedgerDb() {
+  if (!ledgerDb) {
+    ledgerDb = await import('../../execution-ledger/db');
+  }
+  return ledgerDb;
+}
+
+function isPersistenceEnabled(): boolean {
+  return process.env.SIM_PERSIST_DISABLED !== 'true';
+}
+
+/**
+ * Normalize a raw wallet address / session id into a sim user id
+ * EVM addresses are lowercased; anything else is trimmed and length-capped
+ */
+export function normalizeSimUserId(raw: unknown): string {
+  if (typeof raw !== 'string') {
+    return ANONYMOUS_SIM_USER;
+  }
+  const trimmed = raw.trim();
+  if (!trimmed) {
+    return ANONYMOUS_SIM_USER;
+  }
+  if (/^0x[a-fA-F0-9]{40}$/.test(trimmed)) {
+    return trimmed.toLowerCase();
+  }
+  return trimmed.slice(0, 128);
+}
+
+/**
+ * Resolve the sim user id for an HTTP request
+ * The signed-in wallet (verified bearer token) owns its account; otherwise the
+ * server-issued session cookie does. Client-supplied addresses are never trusted.
+ * Returns the anonymous account when neither is present.
+ */
+export function resolveSimUserId(
+  req: { cookies?: Record<string, string | undefined> },
+  wallet: string | null
+): string {
+  if (wallet) {
+    return normalizeSimUserId(wallet);
+  }
+  const sessionId = req.cookies?.[SIM_SESSION_COOKIE];
+  if (typeof sessionId === 'string' && UUID_PATTERN.test(sessionId)) {
+    return `session:${sessionId}`;
+  }
+  return ANONYMOUS_SIM_USER;
+}
+
+/**
+ * Issue a new sim session id for SIM_SESSION_COOKIE
+ */
+export function newSimSessionId(): string {
+  return randomUUID();
+}
+
+/**
+ * Keep accounts loaded while a sim's state matches `retain`
+ */
+export function setSimRetention(kind: SimKind, retain: (state: unknown) => boolean): void {
+  retainers[kind] = retain;
+}
+
+function isRetained(entry: Partial<Record<SimKind, unknown>> | undefined): boolean {
+  return !!entry && SIM_KINDS.some(kind => entry[kind] !== undefined && !!retainers[kind]?.(entry[kind]));
+}
+
+/**
+ * Get (or lazily create) a sim's state for a user
+ * Callers may mutate the returned object in place and then call markSimDirty
+ */
+export function getSimState<T>(kind: SimKind, userId: string, init: () => T): T {
+  let entry = simStates.get(userId);
+  if (!entry) {
+    entry = {};
+    simStates.set(userId, entry);
+  }
+  if (entry[kind] === undefined) {
+    entry[kind] = init();
+  }
+  return entry[kind] as T;
+}
+
+/**
+ * Replace a sim's state for a user (used by resets)
+ */
+export function setSimState<T>(kind: SimKind, userId: string, state: T): void {
+  let entry = simStates.get(userId);
+  if (!entry) {
+    entry = {};
+    simStates.set(userId, entry);
+  }
+  entry[kind] = state;
+  markSimDirty(userId);
+}
+
+/**
+ * Schedule a write-through of the user's sim state to the ledger
+ */
+export function markSimDirty(userId: string): void {
+  if (!isPersistenceEnabled() || pendingPersists.has(userId)) {
+    return;
+  }
+
+  const timer = setTimeout(() => {
+    pendingPersists.delete(userId);
+    persistSimAccount(userId).catch(() => {
+      // Already logged in persistSimAccount
+    });
+  }, PERSIST_DEBOUNCE_MS);
+  timer.unref?.();
+  pendingPersists.set(userId, timer);
+}
+
+/**
+ * Hydrate a user's sim state from the ledger (no-op after the first call)
+ * Must be awaited before the sims are touched for a user in a request
+ */
+export function loadSimAccount(userId: string): Promise<void> {
+  touchSimAccount(userId);
+  let hydration = hydrations.get(userId);
+  if (!hydration) {
+    // Cache the promise so concurrent requests for the same user wait on one load
+    hydration = hydrateSimAccount(userId);
+    hydrations.set(userId, hydration);
+  }
+  return hydration;
+}
+
+async function hydrateSimAccount(userId: string): Promise<void> {
+  if (!isPersistenceEnabled()) {
+    return;
+  }
+
+  try {
+    const db = await getLedgerDb();
+    const row = db.getSimAccount(userId);
+    if (!row) {
+      return;
+    }
+
+    const persisted = JSON.parse(row.state_json) as Partial<Record<SimKind, unknown>>;
+    let entry = simStates.get(userId);
+    if (!entry) {
+      entry = {};
+      simStates.set(userId, entry);
+    }
+    for (const kind of SIM_KINDS) {
+      // In-memory state wins if a sim was touched before hydration finished
+      if (persisted[kind] !== undefined && entry[kind] === undefined) {
+        entry[kind] = persisted[kind];
+      }
+    }
+  } catch (error: any) {
+    console.warn(`[simStore] Could not load sim account ${userId}, using in-memory state:`, error.message);
+  }
+}
+
+/**
+ * Write a user's current sim state to the ledger immediately
+ */
+export async function persistSimAccount(userId: string): Promise<void> {
+  const entry = simStates.get(userId);
+  if (!entry || !isPersistenceEnabled()) {
+    return;
+  }
+
+  try {
+    const db = await getLedgerDb();
+    db.upsertSimAccount(userId, JSON.stringify(entry));
+  } catch (error: any) {
+    console.warn(`[simStore] Could not persist sim account ${userId}:`, error.message);
+  }
+}
+
+/**
+ * Hydrate every persisted account a sim retains, returning their user ids
+ * Run at boot so the background engines see accounts nobody has requested yet
+ */
+export async function hydrateRetainedSimAccounts(): Promise<string[]> {
+  if (!isPersistenceEnabled()) {
+    return [];
+  }
+
+  const userIds: string[] = [];
+  try {
+    const db = await getLedgerDb();
+    for (const row of db.listSimAccounts()) {
+      if (isRetained(JSON.parse(row.state_json))) {
+        userIds.push(row.user_id);
+      }
+    }
+  } catch (error: any) {
+    console.warn('[simStore] Could not list persisted sim accounts:', error.message);
+    return [];
+  }
+
+  await Promise.all(userIds.map(userId => loadSimAccount(userId)));
+  return userIds;
+}
+
+/**
+ * List user ids with sim state currently loaded in memory
+ */
+export function listLoadedSimUsers(): string[] {
+  return Array.from(simStates.keys());
+}
+
+/**
+ * Mark an account as just used and evict idle or least recently used ones
+ */
+function touchSimAccount(userId: string, now: number = Date.now()): void {
+  lastAccess.delete(userId);
+  lastAccess.set(userId, now);
+
+  for (const [candidate, lastUsed] of lastAccess) {
+    if (lastAccess.size <= SIM_MAX_LOADED_ACCOUNTS && now - lastUsed < SIM_ACCOUNT_IDLE_MS) {
+      break;
+    }
+    if (!isRetained(simStates.get(candidate))) {
+      evictSimAccount(candidate);
+    }
+  }
+}
+
+/**
+ * Drop an account from memory, flushing any pending write first
+ */
+function evictSimAccount(userId: string): void {
+  const pending = pendingPersists.get(userId);
+  if (pending) {
+    clearTimeout(pending);
+    pendingPersists.delete(userId);
+    // persistSimAccount captures the entry before its first await
+    persistSimAccount(userId).catch(() => {
+      // Already logged in persistSimAccount
+    });
+  }
+  simStates.delete(userId);
+  hydrations.delete(userId);
+  lastAccess.delete(userId);
+}
@@ -0,0 +1,80 @@
+/**
+ * Solana Token Registry
+ * Symbol, decimals and price feed for SPL mints on devnet
+ * Known mints come from config; unknown mints keep the decimals reported by
+ * their token account and are listed without a USD value
+ */
+
+import { SOLANA_USDC_MINT, SOLANA_EXTRA_TOKENS } from '../config';
+import { priceSymbolFor } from './tokenMetadata';
+import type { PriceSymbol } from './prices';
+
+export interface SolanaTokenMetadata {
+  mint: string;
+  symbol: string;
+  decimals: number;
+  priceSymbol?: PriceSymbol;
+  source: 'config' | 'account';
+}
+
+// Sentinel for native SOL
+export const NATIVE_SOL = 'sol';
+
+export const WRAPPED_SOL_MINT = 'So11111111111111111111111111111111111111112';
+
+const registry = new Map<string, SolanaTokenMetadata>();
+
+function register(mint: string, symbol: string, decimals: number): void {
+  registry.set(mint, { mint, symbol, decimals, priceSymbol: priceSymbolFor(symbol), source: 'config' });
+}
+
+function loadRegistry(): void {
+  if (registry.size > 0) return;
+
+  register(WRAPPED_SOL_MINT, 'WSOL', 9);
+  if (SOLANA_USDC_MINT) register(SOLANA_USDC_MINT, 'USDC', 6);
+
+  for (const entry of SOLANA_EXTRA_TOKENS.split(',').map(e => e.trim()).filter(Boolean)) {
+    const [mint, symbol, decimals] = entry.split(':');
+    const parsed = parseInt(decimals, 10);
+    if (!mint || !symbol || Number.isNaN(parsed)) {
+      console.warn(`[solanaTokens] Ignoring malformed SOLANA_EXTRA_TOKENS entry: ${entry}`);
+      continue;
+    }
+    register(mint, symbol.toUpperCase(), parsed);
+  }
+}
+
+/**
+ * Add (or replace) a mint in the registry
+ */
+export function registerSolanaToken(mint: string, symbol: string, decimals: number): void {
+  loadRegistry();
+  register(mint, symbol.toUpperCase(), decimals);
+}
+
+/**
+ * Metadata for a mint (or NATIVE_SOL)
+ * accountDecimals is used for mints the registry doesn't know
+ */
+export function getSolanaTokenMetadata(mint: string, accountDecimals?: number): SolanaTokenMetadata {
+  if (mint === NATIVE_SOL) {
+    return { mint: NATIVE_SOL, symbol: 'SOL', decimals: 9, priceSymbol: 'SOL', source: 'config' };
+  }
+
+  loadRegistry();
+  const known = registry.get(mint);
+  if (known) return known;
+
+  return {
+    mint,
+    symbol: `${mint.slice(0, 4)}…${mint.slice(-4)}`,
+    decimals: accountDecimals ?? 0,
+    source: 'account',
+  };
+}
+
+export function listSolanaTokens(): SolanaTokenMetadata[] {
+  loadRegistry();
+  return Array.from(registry.values());
+}
@@ -1,6 +1,6 @@
 /**
  * Centralized State Management
- * Helper functions for resetting and building portfolio snapshots
+ * Helper functions for resetting and building per-user portfolio snapshots
  */
 
 import * as perpsSim from '../plugins/perps-sim';
@@ -9,28 +9,39 @@ import * as eventSim from '../plugins/event-sim';
 import { BlossomPortfolioSnapshot } from '../types/blossom';
 
 /**
- * Reset all simulation states to initial
+ * Reset a user's simulation states to initial
  */
-export function resetAllSims(): void {
-  perpsSim.resetPerpsAccount();
-  defiSim.resetDefiState();
-  eventSim.resetEventState();
+export function resetAllSims(userId: string): void {
+  perpsSim.resetPerpsAccount(userId);
+  defiSim.resetDefiState(userId);
+  eventSim.resetEventState(userId);
 }
 
 /**
- * Build a fresh portfolio snapshot from all sims
+ * Build a fresh portfolio snapshot from a user's sims
  */
-export function getPortfolioSnapshot(): BlossomPortfolioSnapshot {
-  const perpsSnapshot = perpsSim.getPerpsSnapshot();
-  const defiSnapshot = defiSim.getDefiSnapshot();
-  const eventSnapshot = eventSim.getEventSnapshot();
-  const eventExposureUsd = eventSim.getEventExposureUsd();
+export function getPortfolioSnapshot(userId: string): BlossomPortfolioSnapshot {
+  const perpsSnapshot = perpsSim.getPerpsSnapshot(userId);
+  const defiSnapshot = defiSim.getDefiSnapshot(userId);
+  const eventSnapshot = eventSim.getEventSnapshot(userId);
+  const eventExposureUsd = eventSim.getEventExposureUsd(userId);
 
   // Calculate open perp exposure
   const openPerpExposureUsd = perpsSnapshot.positions
     .filter(p => !p.isClosed)
     .reduce((sum, p) => sum + p.sizeUsd, 0);
 
+  // Perp PnL (unrealized is refreshed by the mark-to-market engine)
+  const perpUnrealizedPnlUsd = perpsSnapshot.positions
+    .filter(p => !p.isClosed)
+    .reduce((sum, p) => sum + (p.unrealizedPnlUsd || 0), 0);
+  const perpRealizedPnlUsd = perpsSnapshot.positions
+    .filter(p => p.isClosed)
+    .reduce((sum, p) => sum + (p.realizedPnlUsd || 0), 0);
+  // Net funding paid across open and closed perps (already included in PnL above)
+  const perpFundingPaidUsd = perpsSnapshot.positions
+    .reduce((sum, p) => sum + (p.fundingPaidUsd || 0), 0);
+
   // Build strategies array (combine all position types)
   const strategies = [
     ...perpsSnapshot.positions.map(p => ({
@@ -55,6 +66,9 @@ export function getPortfolioSnapshot(): BlossomPortfolioSnapshot {
     balances: perpsSnapshot.balances,
     openPerpExposureUsd,
     eventExposureUsd,
+    perpUnrealizedPnlUsd,
+    perpRealizedPnlUsd,
+    perpFundingPaidUsd,
     defiPositions: defiSnapshot.positions.map(p => ({
       id: p.id,
       protocol: p.protocol,
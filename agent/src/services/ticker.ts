@@ -4,7 +4,7 @@
  */
 
 import { getPrice, PriceSymbol } from './prices';
-import { getEventSnapshot } from '../plugins/event-sim';
+import { getEventMarkets } from '../plugins/event-sim';
 import { fetchKalshiMarkets, fetchPolymarketMarkets, RawPredictionMarket } from './predictionData';
 import { getMarketDataProvider } from '../providers/providerRegistry';
 import { DFLOW_ENABLED } from '../config';
@@ -70,7 +70,7 @@ const STATIC_EVENT_TICKER: EventTickerItem[] = [
  */
 export async function getOnchainTicker(): Promise<TickerPayload> {
   const symbols: PriceSymbol[] = ['BTC', 'ETH', 'SOL', 'AVAX', 'LINK'];
-  const priceData: Array<{ symbol: string; priceUsd: number; change24hPct: number; source: 'coingecko' | 'static' }> = [];
+  const priceData: Array<{ symbol: string; priceUsd: number; change24hPct: number; source: string }> = [];
   let hasLiveData = false;
   let hasStaticFallback = false;
 
@@ -87,7 +87,7 @@ export async function getOnchainTicker(): Promise<TickerPayload> {
           source: snapshot.source,
         });
         
-        if (snapshot.source === 'coingecko') {
+        if (snapshot.source !== 'static') {
           hasLiveData = true;
         } else {
           hasStaticFallback = true;
@@ -271,8 +271,7 @@ export async function getEventMarketsTicker(): Promise<TickerPayload> {
     }
 
     // Fallback to seeded markets if no live data
-    const eventSnapshot = getEventSnapshot();
-    const allMarketsSeeded = eventSnapshot.markets;
+    const allMarketsSeeded = getEventMarkets();
 
     // Separate markets by source
     const kalshiMarketsSeeded: Array<{ label: string; impliedProb: number }> = [];
@@ -0,0 +1,143 @@
+/**
+ * Token Metadata
+ * Resolves symbol, decimals and price feed for ERC20 addresses on Sepolia
+ * Configured tokens are known up front; anything else is read on-chain once and cached
+ */
+
+import {
+  DEMO_USDC_ADDRESS,
+  DEMO_WETH_ADDRESS,
+  USDC_ADDRESS_SEPOLIA,
+  WETH_ADDRESS_SEPOLIA,
+  AAVE_USDC_ADDRESS,
+  AAVE_WETH_ADDRESS,
+} from '../config';
+import { hasPriceFeed, type PriceSymbol } from './prices';
+
+export interface TokenMetadata {
+  address: string;
+  symbol: string;
+  decimals: number;
+  priceSymbol?: PriceSymbol;   // Undefined when no price feed covers the token
+  source: 'config' | 'onchain';
+}
+
+// Sentinel for native ETH (plan value)
+export const NATIVE_ETH = 'eth';
+
+const metadataCache = new Map<string, TokenMetadata>();
+
+const ERC20_METADATA_ABI = [
+  {
+    name: 'decimals',
+    type: 'function',
+    stateMutability: 'view',
+    inputs: [],
+    outputs: [{ name: '', type: 'uint8' }],
+  },
+  {
+    name: 'symbol',
+    type: 'function',
+    stateMutability: 'view',
+    inputs: [],
+    outputs: [{ name: '', type: 'string' }],
+  },
+] as const;
+
+/**
+ * Map a token symbol to the price feed that values it
+ * Wrapped and bridged variants price as their underlying; stablecoins as USDC;
+ * anything else prices as itself if a price source covers it
+ */
+export function priceSymbolFor(symbol: string): PriceSymbol | undefined {
+  const s = symbol.toUpperCase();
+  if (s === 'ETH' || s === 'WETH' || s === 'STETH') return 'ETH';
+  if (s === 'BTC' || s === 'WBTC' || s === 'CBBTC') return 'BTC';
+  if (s === 'SOL' || s === 'WSOL') return 'SOL';
+  if (s === 'AVAX' || s === 'WAVAX') return 'AVAX';
+  if (s === 'LINK') return 'LINK';
+  if (s.includes('USDC') || s === 'USDT' || s === 'DAI') return 'USDC';
+  return hasPriceFeed(s) ? s : undefined;
+}
+
+function configuredTokens(): TokenMetadata[] {
+  const tokens: Array<[string | undefined, string, number]> = [
+    [DEMO_USDC_ADDRESS, 'USDC', 6],
+    [DEMO_WETH_ADDRESS, 'WETH', 18],
+    [USDC_ADDRESS_SEPOLIA, 'USDC', 6],
+    [WETH_ADDRESS_SEPOLIA, 'WETH', 18],
+    [AAVE_USDC_ADDRESS, 'USDC', 6],
+    [AAVE_WETH_ADDRESS, 'WETH', 18],
+  ];
+  return tokens
+    .filter(([address]) => !!address)
+    .map(([address, symbol, decimals]) => ({
+      address: address!.toLowerCase(),
+      symbol,
+      decimals,
+      priceSymbol: priceSymbolFor(symbol),
+      source: 'config' as const,
+    }));
+}
+
+/**
+ * Tokens known from config, one entry per address
+ */
+export function listConfiguredTokens(): TokenMetadata[] {
+  const byAddress = new Map<string, TokenMetadata>();
+  for (const token of configuredTokens()) {
+    if (!byAddress.has(token.address)) byAddress.set(token.address, token);
+  }
+  return Array.from(byAddress.values());
+}
+
+/**
+ * Resolve metadata for a token address (or NATIVE_ETH)
+ * Throws if the token is unknown and its decimals can't be read on-chain
+ */
+export async function getTokenMetadata(address: string): Promise<TokenMetadata> {
+  const key = address.toLowerCase();
+
+  if (key === NATIVE_ETH) {
+    return { address: NATIVE_ETH, symbol: 'ETH', decimals: 18, priceSymbol: 'ETH', source: 'config' };
+  }
+
+  const cached = metadataCache.get(key);
+  if (cached) {
+    return cached;
+  }
+
+  const configured = configuredTokens().find(token => token.address === key);
+  if (configured) {
+    metadataCache.set(key, configured);
+    return configured;
+  }
+
+  const { createFailoverPublicClient } = await import('../providers/rpcProvider');
+  const publicClient = createFailoverPublicClient();
+  const token = key as `0x${string}`;
+  const decimals = await publicClient.readContract({ address: token, abi: ERC20_METADATA_ABI, functionName: 'decimals' });
+  let symbol = 'UNKNOWN';
+  try {
+    symbol = await publicClient.readContract({ address: token, abi: ERC20_METADATA_ABI, functionName: 'symbol' });
+  } catch {
+    // Some tokens return bytes32 symbols; decimals are what matter for accounting
+  }
+
+  const metadata: TokenMetadata = {
+    address: key,
+    symbol,
+    decimals: Number(decimals),
+    priceSymbol: priceSymbolFor(symbol),
+    source: 'onchain',
+  };
+  metadataCache.set(key, metadata);
+  return metadata;
+}
+
+/**
+ * Clear metadata cache (useful for testing)
+ */
+export function clearTokenMetadataCache(): void {
+  metadataCache.clear();
+}
@@ -2,11 +2,482 @@
  * Solana Devnet Client
  * Minimal RPC client for Solana devnet execution
  *
- * No external dependencies - uses native fetch for RPC calls
+ * No external dependencies - uses native fetch for RPC calls and node:crypto
+ * for ed25519. Builds legacy transactions natively: system transfers, account
+ * creation, SPL token (mint, transfer, ATA creation) and memo instructions.
+ *
+ * Works against any RPC endpoint, including a local solana-test-validator
+ * (SOLANA_RPC_URL=http://127.0.0.1:8899).
  */
 
+import * as crypto from 'crypto';
+
 const DEFAULT_DEVNET_RPC = 'https://api.devnet.solana.com';
 
+export const LAMPORTS_PER_SOL = 1_000_000_000;
+
+// Program ids
+export const SYSTEM_PROGRAM_ID = '11111111111111111111111111111111';
+export const TOKEN_PROGRAM_ID = 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA';
+export const TOKEN_2022_PROGRAM_ID = 'TokenzQdBNbLqP5VEhdkAS6EPFLC1PE1cBmqFKdd1uHa';
+export const ASSOCIATED_TOKEN_PROGRAM_ID = 'ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL';
+export const MEMO_PROGRAM_ID = 'MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr';
+
+export const MINT_SIZE = 82;
+
+// Reuse a fetched blockhash for this long (blockhashes stay valid for ~150 slots / ~60s)
+const BLOCKHASH_CACHE_MS = 20_000;
+
+// ============================================================================
+// Encoding
+// ============================================================================
+
+const BASE58_ALPHABET = '123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz';
+
+export function base58Decode(str: string): Buffer {
+  const bytes = [0];
+  for (const char of str) {
+    let value = BASE58_ALPHABET.indexOf(char);
+    if (value === -1) throw new Error(`Invalid base58 character: ${char}`);
+    for (let i = 0; i < bytes.length; i++) {
+      const product = bytes[i] * 58 + value;
+      bytes[i] = product % 256;
+      value = Math.floor(product / 256);
+    }
+    while (value > 0) {
+      bytes.push(value % 256);
+      value = Math.floor(value / 256);
+    }
+  }
+  // Strip the leading zero from the accumulator, then restore leading '1's
+  while (bytes.length > 1 && bytes[bytes.length - 1] === 0) bytes.pop();
+  if (bytes.length === 1 && bytes[0] === 0) bytes.pop();
+  for (const char of str) {
+    if (char !== '1') break;
+    bytes.push(0);
+  }
+  return Buffer.from(bytes.reverse());
+}
+
+export function base58Encode(buffer: Uint8Array): string {
+  const digits = [0];
+  for (let i = 0; i < buffer.length; i++) {
+    let carry = buffer[i];
+    for (let j = 0; j < digits.length; j++) {
+      carry += digits[j] << 8;
+      digits[j] = carry % 58;
+      carry = Math.floor(carry / 58);
+    }
+    while (carry > 0) {
+      digits.push(carry % 58);
+      carry = Math.floor(carry / 58);
+    }
+  }
+  let output = '';
+  for (let i = 0; i < buffer.length && buffer[i] === 0; i++) {
+    output += BASE58_ALPHABET[0];
+  }
+  // All-zero input: the accumulator's lone zero digit is already covered above
+  if (output.length === buffer.length) return output;
+  for (let i = digits.length - 1; i >= 0; i--) {
+    output += BASE58_ALPHABET[digits[i]];
+  }
+  return output;
+}
+
+function encodeCompactU16(value: number): Buffer {
+  if (value < 128) return Buffer.from([value]);
+  if (value < 16384) return Buffer.from([(value & 0x7f) | 0x80, value >> 7]);
+  return Buffer.from([(value & 0x7f) | 0x80, ((value >> 7) & 0x7f) | 0x80, value >> 14]);
+}
+
+function pubkeyBytes(pubkey: string): Buffer {
+  const bytes = base58Decode(pubkey);
+  if (bytes.length !== 32) {
+    throw new Error(`Invalid Solana public key: ${pubkey}`);
+  }
+  return bytes;
+}
+
+function u64(value: bigint | number): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(value));
+  return buf;
+}
+
+// ============================================================================
+// Keys and signing (ed25519 via node:crypto)
+// ============================================================================
+
+// DER prefixes wrapping a raw 32-byte ed25519 key
+const ED25519_PKCS8_PREFIX = Buffer.from('302e020100300506032b657004220420', 'hex');
+const ED25519_SPKI_PREFIX = Buffer.from('302a300506032b6570032100', 'hex');
+
+export interface SolanaKeypair {
+  publicKey: string;          // base58
+  secretKey: Uint8Array;      // 64 bytes: 32-byte seed + 32-byte public key
+}
+
+function privateKeyObject(secretKey: Uint8Array): crypto.KeyObject {
+  return crypto.createPrivateKey({
+    key: Buffer.concat([ED25519_PKCS8_PREFIX, Buffer.from(secretKey.slice(0, 32))]),
+    format: 'der',
+    type: 'pkcs8',
+  });
+}
+
+/**
+ * Load a keypair from a base58 secret key or a solana-keygen JSON byte array
+ */
+export function keypairFromSecretKey(secret: string | Uint8Array): SolanaKeypair {
+  let bytes: Buffer;
+  if (typeof secret !== 'string') {
+    bytes = Buffer.from(secret);
+  } else if (secret.trim().startsWith('[')) {
+    bytes = Buffer.from(JSON.parse(secret));
+  } else {
+    bytes = base58Decode(secret.trim());
+  }
+  if (bytes.length !== 64) {
+    throw new Error(`Invalid Solana secret key length: ${bytes.length}`);
+  }
+
+  const derived = crypto
+    .createPublicKey(privateKeyObject(bytes))
+    .export({ format: 'der', type: 'spki' })
+    .subarray(ED25519_SPKI_PREFIX.length);
+  if (!derived.equals(bytes.subarray(32))) {
+    throw new Error('Solana secret key does not match its public key');
+  }
+
+  return { publicKey: base58Encode(derived), secretKey: new Uint8Array(bytes) };
+}
+
+/**
+ * Generate a fresh keypair
+ */
+export function generateKeypair(): SolanaKeypair {
+  const { privateKey, publicKey } = crypto.generateKeyPairSync('ed25519');
+  const seed = privateKey.export({ format: 'der', type: 'pkcs8' }).subarray(ED25519_PKCS8_PREFIX.length);
+  const pub = publicKey.export({ format: 'der', type: 'spki' }).subarray(ED25519_SPKI_PREFIX.length);
+  return { publicKey: base58Encode(pub), secretKey: new Uint8Array(Buffer.concat([seed, pub])) };
+}
+
+export function signMessage(message: Uint8Array, secretKey: Uint8Array): Buffer {
+  return crypto.sign(null, Buffer.from(message), privateKeyObject(secretKey));
+}
+
+export function verifySignature(message: Uint8Array, signature: Uint8Array, publicKey: string): boolean {
+  const key = crypto.createPublicKey({
+    key: Buffer.concat([ED25519_SPKI_PREFIX, pubkeyBytes(publicKey)]),
+    format: 'der',
+    type: 'spki',
+  });
+  return crypto.verify(null, Buffer.from(message), key, Buffer.from(signature));
+}
+
+// ============================================================================
+// Program derived addresses
+// ============================================================================
+
+const ED25519_P = (1n << 255n) - 19n;
+const ED25519_D = (-121665n * modPow(121666n, ED25519_P - 2n, ED25519_P)) % ED25519_P + ED25519_P;
+
+function modPow(base: bigint, exp: bigint, mod: bigint): bigint {
+  let result = 1n;
+  base %= mod;
+  while (exp > 0n) {
+    if (exp & 1n) result = (result * base) % mod;
+    base = (base * base) % mod;
+    exp >>= 1n;
+  }
+  return result;
+}
+
+/**
+ * Whether 32 bytes decompress to a point on the ed25519 curve
+ * (PDAs must be off-curve so no private key can exist for them)
+ */
+function isOnCurve(bytes: Uint8Array): boolean {
+  const le = Buffer.from(bytes);
+  le[31] &= 0x7f;
+  const y = BigInt('0x' + Buffer.from(le).reverse().toString('hex')) % ED25519_P;
+  const y2 = (y * y) % ED25519_P;
+  const u = (y2 - 1n + ED25519_P) % ED25519_P;
+  const v = (ED25519_D * y2 + 1n) % ED25519_P;
+  const x2 = (u * modPow(v, ED25519_P - 2n, ED25519_P)) % ED25519_P;
+  if (x2 === 0n) return true;
+  // Euler's criterion: x^2 must be a quadratic residue
+  return modPow(x2, (ED25519_P - 1n) / 2n, ED25519_P) === 1n;
+}
+
+/**
+ * Find a program derived address and its bump seed
+ */
+export function findProgramAddress(seeds: Uint8Array[], programId: string): [string, number] {
+  const program = pubkeyBytes(programId);
+  for (let bump = 255; bump >= 0; bump--) {
+    const hash = crypto
+      .createHash('sha256')
+      .update(Buffer.concat([...seeds.map(seed => Buffer.from(seed)), Buffer.from([bump]), program, Buffer.from('ProgramDerivedAddress')]))
+      .digest();
+    if (!isOnCurve(hash)) {
+      return [base58Encode(hash), bump];
+    }
+  }
+  throw new Error('Unable to find a viable program address bump seed');
+}
+
+/**
+ * Associated token account for (owner, mint)
+ */
+export function getAssociatedTokenAddress(owner: string, mint: string): string {
+  return findProgramAddress(
+    [pubkeyBytes(owner), pubkeyBytes(TOKEN_PROGRAM_ID), pubkeyBytes(mint)],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+export interface AccountMeta {
+  pubkey: string;
+  isSigner: boolean;
+  isWritable: boolean;
+}
+
+export interface TransactionInstruction {
+  programId: string;
+  keys: AccountMeta[];
+  data: Buffer;
+}
+
+export function systemTransferInstruction(from: string, to: string, lamports: bigint | number): TransactionInstruction {
+  const data = Buffer.alloc(4);
+  data.writeUInt32LE(2, 0); // SystemInstruction::Transfer
+  return {
+    programId: SYSTEM_PROGRAM_ID,
+    keys: [
+      { pubkey: from, isSigner: true, isWritable: true },
+      { pubkey: to, isSigner: false, isWritable: true },
+    ],
+    data: Buffer.concat([data, u64(lamports)]),
+  };
+}
+
+export function createAccountInstruction(params: {
+  from: string;
+  newAccount: string;
+  lamports: bigint | number;
+  space: number;
+  owner: string;
+}): TransactionInstruction {
+  const data = Buffer.alloc(4);
+  data.writeUInt32LE(0, 0); // SystemInstruction::CreateAccount
+  return {
+    programId: SYSTEM_PROGRAM_ID,
+    keys: [
+      { pubkey: params.from, isSigner: true, isWritable: true },
+      { pubkey: params.newAccount, isSigner: true, isWritable: true },
+    ],
+    data: Buffer.concat([data, u64(params.lamports), u64(params.space), pubkeyBytes(params.owner)]),
+  };
+}
+
+export function memoInstruction(text: string, signers: string[] = []): TransactionInstruction {
+  return {
+    programId: MEMO_PROGRAM_ID,
+    keys: signers.map(pubkey => ({ pubkey, isSigner: true, isWritable: false })),
+    data: Buffer.from(text, 'utf8'),
+  };
+}
+
+/**
+ * Create the associated token account if it doesn't exist (no-op otherwise)
+ */
+export function createAssociatedTokenAccountIdempotentInstruction(
+  payer: string,
+  owner: string,
+  mint: string
+): TransactionInstruction {
+  return {
+    programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+    keys: [
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: getAssociatedTokenAddress(owner, mint), isSigner: false, isWritable: true },
+      { pubkey: owner, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: SYSTEM_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from([1]), // AssociatedTokenAccountInstruction::CreateIdempotent
+  };
+}
+
+export function initializeMintInstruction(
+  mint: string,
+  decimals: number,
+  mintAuthority: string,
+  freezeAuthority?: string
+): TransactionInstruction {
+  return {
+    programId: TOKEN_PROGRAM_ID,
+    keys: [{ pubkey: mint, isSigner: false, isWritable: true }],
+    data: Buffer.concat([
+      Buffer.from([20, decimals]), // TokenInstruction::InitializeMint2
+      pubkeyBytes(mintAuthority),
+      freezeAuthority ? Buffer.concat([Buffer.from([1]), pubkeyBytes(freezeAuthority)]) : Buffer.from([0]),
+    ]),
+  };
+}
+
+export function mintToInstruction(
+  mint: string,
+  destination: string,
+  authority: string,
+  amount: bigint | number
+): TransactionInstruction {
+  return {
+    programId: TOKEN_PROGRAM_ID,
+    keys: [
+      { pubkey: mint, isSigner: false, isWritable: true },
+      { pubkey: destination, isSigner: false, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+    ],
+    data: Buffer.concat([Buffer.from([7]), u64(amount)]), // TokenInstruction::MintTo
+  };
+}
+
+/**
+ * SPL transfer between token accounts (checked: the mint and decimals must match)
+ */
+export function splTransferCheckedInstruction(params: {
+  source: string;
+  mint: string;
+  destination: string;
+  owner: string;
+  amount: bigint | number;
+  decimals: number;
+}): TransactionInstruction {
+  return {
+    programId: TOKEN_PROGRAM_ID,
+    keys: [
+      { pubkey: params.source, isSigner: false, isWritable: true },
+      { pubkey: params.mint, isSigner: false, isWritable: false },
+      { pubkey: params.destination, isSigner: false, isWritable: true },
+      { pubkey: params.owner, isSigner: true, isWritable: false },
+    ],
+    data: Buffer.concat([Buffer.from([12]), u64(params.amount), Buffer.from([params.decimals])]), // TransferChecked
+  };
+}
+
+// ============================================================================
+// Messages and transactions (legacy format)
+// ============================================================================
+
+export interface CompiledMessage {
+  message: Buffer;
+  signers: string[];          // Required signers, in signature order
+}
+
+/**
+ * Compile instructions into a legacy message
+ * Accounts are ordered: fee payer, writable signers, readonly signers,
+ * writable non-signers, readonly non-signers (program ids)
+ */
+export function compileMessage(params: {
+  feePayer: string;
+  instructions: TransactionInstruction[];
+  recentBlockhash: string;
+}): CompiledMessage {
+  const metas = new Map<string, { isSigner: boolean; isWritable: boolean }>();
+  const touch = (pubkey: string, isSigner: boolean, isWritable: boolean) => {
+    const existing = metas.get(pubkey);
+    metas.set(pubkey, {
+      isSigner: isSigner || (existing?.isSigner ?? false),
+      isWritable: isWritable || (existing?.isWritable ?? false),
+    });
+  };
+
+  touch(params.feePayer, true, true);
+  for (const ix of params.instructions) {
+    for (const key of ix.keys) touch(key.pubkey, key.isSigner, key.isWritable);
+  }
+  for (const ix of params.instructions) touch(ix.programId, false, false);
+
+  const rank = (pubkey: string) => {
+    if (pubkey === params.feePayer) return 0;
+    const meta = metas.get(pubkey)!;
+    if (meta.isSigner) return meta.isWritable ? 1 : 2;
+    return meta.isWritable ? 3 : 4;
+  };
+  // Array.prototype.sort is stable, so insertion order is kept within a rank
+  const accounts = Array.from(metas.keys()).sort((a, b) => rank(a) - rank(b));
+  const indexOf = new Map(accounts.map((pubkey, i) => [pubkey, i]));
+
+  const signers = accounts.filter(pubkey => metas.get(pubkey)!.isSigner);
+  const readonlySigned = signers.filter(pubkey => !metas.get(pubkey)!.isWritable).length;
+  const readonlyUnsigned = accounts.filter(pubkey => {
+    const meta = metas.get(pubkey)!;
+    return !meta.isSigner && !meta.isWritable;
+  }).length;
+
+  const instructions = params.instructions.map(ix => Buffer.concat([
+    Buffer.from([indexOf.get(ix.programId)!]),
+    encodeCompactU16(ix.keys.length),
+    Buffer.from(ix.keys.map(key => indexOf.get(key.pubkey)!)),
+    encodeCompactU16(ix.data.length),
+    ix.data,
+  ]));
+
+  const blockhash = base58Decode(params.recentBlockhash);
+  if (blockhash.length !== 32) {
+    throw new Error(`Invalid blockhash: ${params.recentBlockhash}`);
+  }
+
+  const message = Buffer.concat([
+    Buffer.from([signers.length, readonlySigned, readonlyUnsigned]),
+    encodeCompactU16(accounts.length),
+    ...accounts.map(pubkeyBytes),
+    blockhash,
+    encodeCompactU16(instructions.length),
+    ...instructions,
+  ]);
+
+  return { message, signers };
+}
+
+/**
+ * Sign a compiled message with every required signer
+ * Returns the wire transaction and its id (the fee payer's signature)
+ */
+export function signTransaction(
+  compiled: CompiledMessage,
+  keypairs: SolanaKeypair[]
+): { transaction: Buffer; signature: string } {
+  const signatures = compiled.signers.map(pubkey => {
+    const keypair = keypairs.find(k => k.publicKey === pubkey);
+    if (!keypair) {
+      throw new Error(`Missing signer for ${pubkey}`);
+    }
+    return signMessage(compiled.message, keypair.secretKey);
+  });
+
+  return {
+    transaction: Buffer.concat([encodeCompactU16(signatures.length), ...signatures, compiled.message]),
+    signature: base58Encode(signatures[0]),
+  };
+}
+
+export class BlockhashExpiredError extends Error {
+  constructor(signature: string, lastValidBlockHeight: number) {
+    super(`Transaction ${signature} expired (block height passed ${lastValidBlockHeight})`);
+    this.name = 'BlockhashExpiredError';
+  }
+}
+
 export interface SolanaClientConfig {
   rpcUrl?: string;
 }
@@ -22,11 +493,33 @@ export interface BalanceResult {
   sol: number;
 }
 
+export interface SentTransaction {
+  signature: string;
+  blockhash: string;
+  lastValidBlockHeight: number;
+}
+
+export interface TokenAmount {
+  amount: string;             // Base units
+  decimals: number;
+  uiAmount: number | null;
+}
+
+export interface TokenAccount {
+  pubkey: string;             // Token account address
+  mint: string;
+  owner: string;
+  programId: string;
+  amount: string;             // Base units
+  decimals: number;
+}
+
 /**
  * Solana RPC client for devnet operations
  */
 export class SolanaClient {
   private rpcUrl: string;
+  private blockhashCache: { blockhash: string; lastValidBlockHeight: number; fetchedAt: number } | null = null;
 
   constructor(config: SolanaClientConfig = {}) {
     this.rpcUrl = config.rpcUrl || process.env.SOLANA_RPC_URL || DEFAULT_DEVNET_RPC;
@@ -78,6 +571,30 @@ export class SolanaClient {
     return result.value;
   }
 
+  /**
+   * Latest blockhash, reused for a short window to save RPC calls
+   * Pass refresh=true after an expiry to force a new one
+   */
+  async getLatestBlockhash(refresh: boolean = false): Promise<{ blockhash: string; lastValidBlockHeight: number }> {
+    const cached = this.blockhashCache;
+    if (!refresh && cached && Date.now() - cached.fetchedAt < BLOCKHASH_CACHE_MS) {
+      return { blockhash: cached.blockhash, lastValidBlockHeight: cached.lastValidBlockHeight };
+    }
+
+    const result = await this.rpcCall<{
+      value: { blockhash: string; lastValidBlockHeight: number };
+    }>('getLatestBlockhash', [{ commitment: 'confirmed' }]);
+    this.blockhashCache = { ...result.value, fetchedAt: Date.now() };
+    return result.value;
+  }
+
+  /**
+   * Current block height (compared against lastValidBlockHeight for expiry)
+   */
+  async getBlockHeight(commitment: 'processed' | 'confirmed' | 'finalized' = 'confirmed'): Promise<number> {
+    return this.rpcCall('getBlockHeight', [{ commitment }]);
+  }
+
   /**
    * Send a signed transaction (base64 encoded)
    */
@@ -122,11 +639,14 @@ export class SolanaClient {
 
   /**
    * Confirm a transaction with timeout
+   * With lastValidBlockHeight, gives up early (BlockhashExpiredError) once the
+   * transaction can no longer land
    */
   async confirmTransaction(
     signature: string,
     commitment: 'processed' | 'confirmed' | 'finalized' = 'confirmed',
-    timeoutMs: number = 30000
+    timeoutMs: number = 30000,
+    lastValidBlockHeight?: number
   ): Promise<TransactionResult> {
     const start = Date.now();
 
@@ -134,6 +654,13 @@ export class SolanaClient {
       const statuses = await this.getSignatureStatuses([signature]);
       const status = statuses[0];
 
+      if (!status && lastValidBlockHeight !== undefined) {
+        const blockHeight = await this.getBlockHeight();
+        if (blockHeight > lastValidBlockHeight) {
+          throw new BlockhashExpiredError(signature, lastValidBlockHeight);
+        }
+      }
+
       if (status) {
         if (status.err) {
           throw new Error(`Transaction failed: ${JSON.stringify(status.err)}`);
@@ -161,6 +688,201 @@ export class SolanaClient {
     throw new Error(`Transaction confirmation timeout after ${timeoutMs}ms`);
   }
 
+  /**
+   * Build, sign and send a transaction (does not wait for confirmation)
+   * The fee payer defaults to the first signer
+   */
+  async signAndSendTransaction(
+    instructions: TransactionInstruction[],
+    signers: SolanaKeypair[],
+    options: { feePayer?: string; skipPreflight?: boolean; refreshBlockhash?: boolean } = {}
+  ): Promise<SentTransaction> {
+    if (signers.length === 0) {
+      throw new Error('At least one signer is required');
+    }
+
+    const { blockhash, lastValidBlockHeight } = await this.getLatestBlockhash(options.refreshBlockhash);
+    const compiled = compileMessage({
+      feePayer: options.feePayer || signers[0].publicKey,
+      instructions,
+      recentBlockhash: blockhash,
+    });
+    const { transaction } = signTransaction(compiled, signers);
+
+    const signature = await this.sendTransaction(transaction.toString('base64'), {
+      skipPreflight: options.skipPreflight,
+    });
+    return { signature, blockhash, lastValidBlockHeight };
+  }
+
+  /**
+   * Send a transaction and wait for confirmation
+   * Re-signs with a fresh blockhash if the previous one expired before landing
+   */
+  async sendAndConfirmTransaction(
+    instructions: TransactionInstruction[],
+    signers: SolanaKeypair[],
+    options: {
+      feePayer?: string;
+      skipPreflight?: boolean;
+      commitment?: 'processed' | 'confirmed' | 'finalized';
+      timeoutMs?: number;
+      maxRetries?: number;
+    } = {}
+  ): Promise<TransactionResult> {
+    const { commitment = 'confirmed', timeoutMs = 60000, maxRetries = 2 } = options;
+
+    for (let attempt = 0; ; attempt++) {
+      const sent = await this.signAndSendTransaction(instructions, signers, {
+        feePayer: options.feePayer,
+        skipPreflight: options.skipPreflight,
+        refreshBlockhash: attempt > 0,
+      });
+      try {
+        return await this.confirmTransaction(sent.signature, commitment, timeoutMs, sent.lastValidBlockHeight);
+      } catch (error) {
+        if (!(error instanceof BlockhashExpiredError) || attempt >= maxRetries) throw error;
+        this.blockhashCache = null;
+      }
+    }
+  }
+
+  /**
+   * Transfer SOL, optionally with a memo
+   */
+  async transferSol(
+    from: SolanaKeypair,
+    to: string,
+    lamports: bigint | number,
+    memo?: string
+  ): Promise<TransactionResult> {
+    const instructions = [systemTransferInstruction(from.publicKey, to, lamports)];
+    if (memo) instructions.push(memoInstruction(memo, [from.publicKey]));
+    return this.sendAndConfirmTransaction(instructions, [from]);
+  }
+
+  /**
+   * Transfer SPL tokens between owners' associated token accounts
+   * Creates the recipient's token account when missing (payer: the sender)
+   */
+  async transferToken(params: {
+    owner: SolanaKeypair;
+    mint: string;
+    to: string;
+    amount: bigint | number;
+    decimals?: number;
+    memo?: string;
+  }): Promise<TransactionResult> {
+    const decimals = params.decimals ?? await this.getMintDecimals(params.mint);
+    const instructions = [
+      createAssociatedTokenAccountIdempotentInstruction(params.owner.publicKey, params.to, params.mint),
+      splTransferCheckedInstruction({
+        source: getAssociatedTokenAddress(params.owner.publicKey, params.mint),
+        mint: params.mint,
+        destination: getAssociatedTokenAddress(params.to, params.mint),
+        owner: params.owner.publicKey,
+        amount: params.amount,
+        decimals,
+      }),
+    ];
+    if (params.memo) instructions.push(memoInstruction(params.memo, [params.owner.publicKey]));
+    return this.sendAndConfirmTransaction(instructions, [params.owner]);
+  }
+
+  /**
+   * Create a new SPL mint owned by the payer (used for local validator tests)
+   */
+  async createMint(payer: SolanaKeypair, decimals: number, mint: SolanaKeypair = generateKeypair()): Promise<string> {
+    const lamports = await this.getMinimumBalanceForRentExemption(MINT_SIZE);
+    await this.sendAndConfirmTransaction([
+      createAccountInstruction({
+        from: payer.publicKey,
+        newAccount: mint.publicKey,
+        lamports,
+        space: MINT_SIZE,
+        owner: TOKEN_PROGRAM_ID,
+      }),
+      initializeMintInstruction(mint.publicKey, decimals, payer.publicKey),
+    ], [payer, mint]);
+    return mint.publicKey;
+  }
+
+  /**
+   * Mint tokens to an owner's associated token account (creating it if needed)
+   */
+  async mintTo(
+    authority: SolanaKeypair,
+    mint: string,
+    owner: string,
+    amount: bigint | number
+  ): Promise<TransactionResult> {
+    return this.sendAndConfirmTransaction([
+      createAssociatedTokenAccountIdempotentInstruction(authority.publicKey, owner, mint),
+      mintToInstruction(mint, getAssociatedTokenAddress(owner, mint), authority.publicKey, amount),
+    ], [authority]);
+  }
+
+  async getMinimumBalanceForRentExemption(space: number): Promise<number> {
+    return this.rpcCall('getMinimumBalanceForRentExemption', [space]);
+  }
+
+  /**
+   * Decimals of an SPL mint (byte 44 of the mint account)
+   */
+  async getMintDecimals(mint: string): Promise<number> {
+    const account = await this.getAccountInfo(mint);
+    if (!account) {
+      throw new Error(`Mint not found: ${mint}`);
+    }
+    return Buffer.from(account.data, 'base64')[44];
+  }
+
+  /**
+   * Balance of a token account (null if it doesn't exist)
+   */
+  async getTokenAccountBalance(tokenAccount: string): Promise<TokenAmount | null> {
+    try {
+      const result = await this.rpcCall<{ value: TokenAmount }>('getTokenAccountBalance', [tokenAccount]);
+      return result.value;
+    } catch (error: any) {
+      if (/could not find account|Invalid param/i.test(error.message)) return null;
+      throw error;
+    }
+  }
+
+  /**
+   * Discover every SPL token account an owner holds (classic Token and Token-2022)
+   */
+  async getTokenAccountsByOwner(owner: string): Promise<TokenAccount[]> {
+    const accounts: TokenAccount[] = [];
+
+    for (const programId of [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]) {
+      const result = await this.rpcCall<{
+        value: Array<{
+          pubkey: string;
+          account: {
+            data: { parsed: { info: { mint: string; owner: string; tokenAmount: TokenAmount } } };
+          };
+        }>;
+      }>('getTokenAccountsByOwner', [owner, { programId }, { encoding: 'jsonParsed', commitment: 'confirmed' }]);
+
+      for (const { pubkey, account } of result.value) {
+        const info = account.data?.parsed?.info;
+        if (!info) continue;
+        accounts.push({
+          pubkey,
+          mint: info.mint,
+          owner: info.owner,
+          programId,
+          amount: info.tokenAmount.amount,
+          decimals: info.tokenAmount.decimals,
+        });
+      }
+    }
+
+    return accounts;
+  }
+
   /**
    * Request airdrop (devnet only)
    */
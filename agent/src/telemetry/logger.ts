@@ -78,6 +78,11 @@ export type TelemetryEventType =
   | 'tx_timeout'
   | 'preflight_check'
   | 'execution_complete'
+  | 'perp_auto_close'
+  | 'intent_dead_lettered'
+  | 'execution_discrepancy'
+  | 'bridge_alert'
+  | 'llm_call'
   | 'error';
 
 /**
@@ -106,6 +111,14 @@ export interface TelemetryPayload {
   // Action-specific
   executionKind?: string;
   venue?: string;
+
+  // LLM calls
+  provider?: string;
+  model?: string;
+  inputTokens?: number;
+  outputTokens?: number;
+  costUsd?: number;
+  cached?: boolean;
 }
 
 /**
@@ -254,6 +267,33 @@ export async function logRequestToDb(params: {
   }
 }
 
+/**
+ * Record an LLM provider call (tokens, cost, latency) in the database
+ */
+export async function trackLlmUsage(params: import('../../telemetry/db').LlmUsageRecord): Promise<void> {
+  try {
+    const db = await getDbTelemetry();
+    if (!db) return;
+    db.recordLlmUsage(params);
+  } catch (e) {
+    // Fail open
+  }
+}
+
+/**
+ * Tokens a user has been billed since a unix timestamp
+ * Returns null when the database is unavailable (budgets then fail open)
+ */
+export async function getLlmTokenUsage(userHash: string, since: number): Promise<number | null> {
+  try {
+    const db = await getDbTelemetry();
+    if (!db) return null;
+    return db.getLlmTokensUsed(userHash, since);
+  } catch (e) {
+    return null;
+  }
+}
+
 /**
  * Create a scoped logger for a specific request
  */
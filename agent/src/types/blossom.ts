@@ -74,6 +74,29 @@ export type BlossomExecutionRequest =
       outcome: "YES" | "NO";
       stakeUsd: number; // USD amount to stake
       price?: number; // Optional: YES/NO price at time of quote
+    }
+  | {
+      kind: "multi";
+      chain: "sepolia";
+      legs: BlossomPlanLeg[]; // Executed in order as one ExecutionRouter plan
+    };
+
+// One leg of a multi-leg plan; amountFromLeg consumes an earlier leg's full output
+export type BlossomPlanLeg =
+  | {
+      kind: "swap";
+      tokenIn: "WETH" | "USDC";
+      tokenOut: "WETH" | "USDC";
+      amountIn?: string;      // decimal string; omitted when amountFromLeg is set
+      amountFromLeg?: number;
+      slippageBps: number;
+    }
+  | {
+      kind: "lend_supply";
+      asset: "WETH" | "USDC";
+      amount?: string;        // decimal string; omitted when amountFromLeg is set
+      amountFromLeg?: number;
+      protocol?: "demo" | "aave";
     };
 
 export interface BlossomPortfolioSnapshot {
@@ -81,6 +104,9 @@ export interface BlossomPortfolioSnapshot {
   balances: { symbol: string; balanceUsd: number }[];
   openPerpExposureUsd: number;
   eventExposureUsd: number;
+  perpUnrealizedPnlUsd?: number; // Sum over open sim perps (mark-to-market)
+  perpRealizedPnlUsd?: number; // Sum over closed sim perps (manual, TP/SL, liquidation)
+  perpFundingPaidUsd?: number; // Net funding paid by sim perps (negative = received)
   defiPositions: {
     id: string;
     protocol: string;
@@ -0,0 +1,75 @@
+/**
+ * Wallet Sign-In Tests
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { privateKeyToAccount } from 'viem/accounts';
+
+vi.mock('../../config', () => ({
+  WALLET_AUTH_SECRET: 'test-secret',
+  WALLET_AUTH_TTL_SECONDS: 3600,
+}));
+
+import {
+  createWalletChallenge,
+  verifyWalletChallenge,
+  verifyWalletAuthToken,
+  getAuthenticatedWallet,
+} from '../walletAuth';
+
+const NOW = 1_750_000_000;
+const account = privateKeyToAccount('0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d');
+const other = privateKeyToAccount('0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a');
+
+async function signIn(now = NOW) {
+  const challenge = createWalletChallenge(account.address, now);
+  const signature = await account.signMessage({ message: challenge.message });
+  return verifyWalletChallenge({ address: account.address, nonce: challenge.nonce, signature }, now + 10);
+}
+
+describe('walletAuth', () => {
+  it('issues a token for a signed challenge', async () => {
+    const session = await signIn();
+    if (typeof session === 'string') throw new Error(session);
+
+    expect(session.address).toBe(account.address.toLowerCase());
+    expect(session.expiresAt).toBe(NOW + 10 + 3600);
+    expect(verifyWalletAuthToken(session.token, NOW + 60)).toBe(account.address.toLowerCase());
+    expect(verifyWalletAuthToken(session.token, NOW + 7200)).toBeNull();
+  });
+
+  it('rejects a signature from another wallet and replayed nonces', async () => {
+    const challenge = createWalletChallenge(account.address, NOW);
+    const forged = await other.signMessage({ message: challenge.message });
+    expect(await verifyWalletChallenge({ address: account.address, nonce: challenge.nonce, signature: forged }, NOW))
+      .toMatch(/does not match/);
+
+    // The nonce was consumed by the failed attempt
+    const signature = await account.signMessage({ message: challenge.message });
+    expect(await verifyWalletChallenge({ address: account.address, nonce: challenge.nonce, signature }, NOW))
+      .toMatch(/expired/);
+  });
+
+  it('rejects expired challenges and challenges for another address', async () => {
+    const challenge = createWalletChallenge(account.address, NOW);
+    const signature = await account.signMessage({ message: challenge.message });
+    expect(await verifyWalletChallenge({ address: account.address, nonce: challenge.nonce, signature }, NOW + 600))
+      .toMatch(/expired/);
+
+    const theirs = createWalletChallenge(other.address, NOW);
+    const mine = await account.signMessage({ message: theirs.message });
+    expect(await verifyWalletChallenge({ address: account.address, nonce: theirs.nonce, signature: mine }, NOW))
+      .toMatch(/different address/);
+  });
+
+  it('rejects tampered tokens', async () => {
+    const session = await signIn(Math.floor(Date.now() / 1000));
+    if (typeof session === 'string') throw new Error(session);
+    const [, mac] = session.token.split('.');
+    const payload = Buffer.from(`${other.address.toLowerCase()}:${session.expiresAt}`).toString('base64url');
+
+    expect(verifyWalletAuthToken(`${payload}.${mac}`)).toBeNull();
+    expect(getAuthenticatedWallet({ headers: { authorization: `Bearer ${session.token}` } })).toBe(account.address.toLowerCase());
+    expect(getAuthenticatedWallet({ headers: { 'x-wallet-address': account.address } })).toBeNull();
+  });
+});
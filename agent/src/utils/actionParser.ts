@@ -147,8 +147,9 @@ export async function buildBlossomPrompts(args: {
   userMessage: string;
   portfolio: BlossomPortfolioSnapshot | null;
   venue: 'hyperliquid' | 'event_demo';
+  conversation?: string; // Prior turns of the thread (services/conversation.ts)
 }): Promise<{ systemPrompt: string; userPrompt: string; isPredictionMarketQuery: boolean }> {
-  const { userMessage, portfolio, venue } = args;
+  const { userMessage, portfolio, venue, conversation } = args;
 
   const systemPrompt = `You are Blossom, an AI trading copilot. You speak clearly and concisely, like a professional portfolio manager. You always:
 
@@ -187,6 +188,14 @@ CRITICAL - Token Inference:
 - Always infer the most logical interpretation. Ask only if truly ambiguous.
 - If user has a balance and wants to swap, infer they want to swap FROM their largest balance.
 
+CRITICAL - Conversation Context:
+- The user prompt may include "Conversation So Far" and "Most Recent Proposal". Use them to resolve follow-ups.
+- "that", "it", "this position" refer to the most recent proposal or position unless the user names another one.
+- "same thing but on SOL" means repeat the most recent proposal with only the asset changed; keep size, side, leverage and risk.
+- "half of that", "double it", "a bit less" scale the most recent proposal's amount, stake or risk accordingly.
+- When closing or changing an existing position, use the positionId or draft id from the context - never invent one.
+- If the reference is ambiguous (several candidate positions), ask which one instead of guessing.
+
 CRITICAL - Output Format:
 - You MUST respond with a single JSON object with top-level keys: "assistantMessage" (string), "actions" (array), and optionally "executionRequest" (object).
 - No commentary or text outside the JSON object.
@@ -422,7 +431,8 @@ Example JSON output (event market bet):
     }
   }
 
-  let userPrompt = `**User Request:**\n${userMessage}\n\n`;
+  let userPrompt = conversation ? conversation : '';
+  userPrompt += `**User Request:**\n${userMessage}\n\n`;
 
   // Inject DefiLlama vault data if DeFi intent
   if (isDefiIntent && topVaults.length > 0) {
@@ -0,0 +1,148 @@
+/**
+ * Wallet Sign-In
+ * Proves control of an EVM address with an EIP-191 signature over a one-time
+ * challenge, then issues an HMAC-signed bearer token for that address.
+ *
+ *   1. POST /api/auth/wallet/challenge → message with a random nonce
+ *   2. The wallet signs the message (personal_sign)
+ *   3. POST /api/auth/wallet/verify → token, sent as `Authorization: Bearer <token>`
+ *
+ * Unlike X-Wallet-Address, the token cannot be forged without the server secret.
+ */
+
+import { createHmac, randomBytes, timingSafeEqual } from 'crypto';
+import { verifyMessage } from 'viem';
+import { WALLET_AUTH_SECRET, WALLET_AUTH_TTL_SECONDS } from '../config';
+
+const CHALLENGE_TTL_SECONDS = 300;
+const MAX_PENDING_CHALLENGES = 10_000;
+
+const secret = WALLET_AUTH_SECRET || randomBytes(32).toString('hex');
+if (!WALLET_AUTH_SECRET) {
+  console.warn('[walletAuth] WALLET_AUTH_SECRET not set; using a random secret (tokens are invalidated on restart)');
+}
+
+interface PendingChallenge {
+  address: string;
+  message: string;
+  expiresAt: number;
+}
+
+// Challenges are single use; an unused one expires after CHALLENGE_TTL_SECONDS
+const challenges = new Map<string, PendingChallenge>();
+
+export interface WalletChallenge {
+  nonce: string;
+  message: string;
+  expiresAt: number;
+}
+
+export interface WalletSession {
+  address: string;
+  token: string;
+  expiresAt: number;
+}
+
+function isEvmAddress(value: unknown): value is string {
+  return typeof value === 'string' && /^0x[0-9a-fA-F]{40}$/.test(value);
+}
+
+function sign(payload: string): string {
+  return createHmac('sha256', secret).update(payload).digest('base64url');
+}
+
+function pruneChallenges(now: number): void {
+  for (const [nonce, challenge] of challenges) {
+    if (challenge.expiresAt <= now) challenges.delete(nonce);
+  }
+}
+
+export function buildWalletSignInMessage(address: string, nonce: string, issuedAt: number): string {
+  return [
+    'Sign in to Blossom',
+    `Address: ${address.toLowerCase()}`,
+    `Nonce: ${nonce}`,
+    `Issued at: ${new Date(issuedAt * 1000).toISOString()}`,
+  ].join('\n');
+}
+
+/**
+ * Start a sign-in for an address
+ */
+export function createWalletChallenge(address: string, now: number = Math.floor(Date.now() / 1000)): WalletChallenge {
+  if (!isEvmAddress(address)) {
+    throw new Error('address must be an EVM address');
+  }
+  pruneChallenges(now);
+  if (challenges.size >= MAX_PENDING_CHALLENGES) {
+    throw new Error('Too many pending sign-in challenges, try again shortly');
+  }
+
+  const nonce = randomBytes(16).toString('hex');
+  const message = buildWalletSignInMessage(address, nonce, now);
+  const expiresAt = now + CHALLENGE_TTL_SECONDS;
+  challenges.set(nonce, { address: address.toLowerCase(), message, expiresAt });
+  return { nonce, message, expiresAt };
+}
+
+/**
+ * Check the signed challenge and issue a token; returns an error string on failure
+ */
+export async function verifyWalletChallenge(
+  params: { address: string; nonce: string; signature: string },
+  now: number = Math.floor(Date.now() / 1000)
+): Promise<WalletSession | string> {
+  const challenge = challenges.get(params.nonce);
+  // Consumed on first use, whatever the outcome
+  challenges.delete(params.nonce);
+
+  if (!challenge || challenge.expiresAt <= now) {
+    return 'Unknown or expired challenge';
+  }
+  if (!isEvmAddress(params.address) || params.address.toLowerCase() !== challenge.address) {
+    return 'Challenge was issued for a different address';
+  }
+
+  let valid = false;
+  try {
+    valid = await verifyMessage({
+      address: params.address as `0x${string}`,
+      message: challenge.message,
+      signature: params.signature as `0x${string}`,
+    });
+  } catch {
+    valid = false;
+  }
+  if (!valid) {
+    return 'Signature does not match the address';
+  }
+
+  const expiresAt = now + WALLET_AUTH_TTL_SECONDS;
+  const payload = Buffer.from(`${challenge.address}:${expiresAt}`).toString('base64url');
+  return { address: challenge.address, token: `${payload}.${sign(payload)}`, expiresAt };
+}
+
+/**
+ * Address a token was issued for (lowercased), or null if it is invalid or expired
+ */
+export function verifyWalletAuthToken(token: string, now: number = Math.floor(Date.now() / 1000)): string | null {
+  const [payload, mac] = token.split('.');
+  if (!payload || !mac) return null;
+
+  const expected = Buffer.from(sign(payload));
+  const actual = Buffer.from(mac);
+  if (expected.length !== actual.length || !timingSafeEqual(expected, actual)) return null;
+
+  const [address, expiresAt] = Buffer.from(payload, 'base64url').toString().split(':');
+  if (!isEvmAddress(address) || !(Number(expiresAt) > now)) return null;
+  return address;
+}
+
+/**
+ * Signed-in wallet of a request (Authorization: Bearer <token>), or null
+ */
+export function getAuthenticatedWallet(req: { headers: Record<string, any> }): string | null {
+  const header = req.headers?.authorization;
+  if (typeof header !== 'string' || !header.startsWith('Bearer ')) return null;
+  return verifyWalletAuthToken(header.slice('Bearer '.length).trim());
+}
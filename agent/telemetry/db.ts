@@ -371,6 +371,102 @@ export function logRequest(params: {
   );
 }
 
+// ============================================
+// LLM Usage Operations
+// ============================================
+
+export interface LlmUsageRecord {
+  userHash?: string;
+  correlationId?: string;
+  provider: string;
+  model?: string;
+  inputTokens?: number;
+  outputTokens?: number;
+  costUsd?: number;
+  latencyMs?: number;
+  cached?: boolean;
+  status?: 'ok' | 'error' | 'timeout' | 'rate_limited';
+  errorMessage?: string;
+}
+
+export function recordLlmUsage(params: LlmUsageRecord): void {
+  const db = getDatabase();
+  db.prepare(`
+    INSERT INTO llm_usage (user_hash, correlation_id, provider, model, input_tokens, output_tokens, cost_usd, latency_ms, cached, status, error_message)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `).run(
+    params.userHash ?? null,
+    params.correlationId ?? null,
+    params.provider,
+    params.model ?? null,
+    params.inputTokens ?? 0,
+    params.outputTokens ?? 0,
+    params.costUsd ?? 0,
+    params.latencyMs ?? null,
+    params.cached ? 1 : 0,
+    params.status ?? 'ok',
+    params.errorMessage ?? null
+  );
+}
+
+/**
+ * Tokens billed to a user since a unix timestamp (cache hits are free)
+ */
+export function getLlmTokensUsed(userHash: string, since: number): number {
+  const db = getDatabase();
+  const row = db.prepare(`
+    SELECT COALESCE(SUM(input_tokens + output_tokens), 0) as tokens
+    FROM llm_usage
+    WHERE user_hash = ? AND created_at >= ? AND cached = 0
+  `).get(userHash, since) as any;
+  return row?.tokens ?? 0;
+}
+
+export interface LlmUsageSummary {
+  provider: string;
+  model: string | null;
+  calls: number;
+  failures: number;
+  cacheHits: number;
+  inputTokens: number;
+  outputTokens: number;
+  costUsd: number;
+  avgLatencyMs: number | null;
+}
+
+export function getLlmUsageSummary(windowHours = 24): LlmUsageSummary[] {
+  const db = getDatabase();
+  const since = Math.floor(Date.now() / 1000) - windowHours * 3600;
+  const rows = db.prepare(`
+    SELECT
+      provider,
+      model,
+      COUNT(*) as calls,
+      SUM(CASE WHEN status != 'ok' THEN 1 ELSE 0 END) as failures,
+      SUM(cached) as cache_hits,
+      SUM(input_tokens) as input_tokens,
+      SUM(output_tokens) as output_tokens,
+      SUM(cost_usd) as cost_usd,
+      AVG(CASE WHEN cached = 0 THEN latency_ms END) as avg_latency
+    FROM llm_usage
+    WHERE created_at >= ?
+    GROUP BY provider, model
+    ORDER BY cost_usd DESC
+  `).all(since) as any[];
+
+  return rows.map(row => ({
+    provider: row.provider,
+    model: row.model,
+    calls: row.calls,
+    failures: row.failures ?? 0,
+    cacheHits: row.cache_hits ?? 0,
+    inputTokens: row.input_tokens ?? 0,
+    outputTokens: row.output_tokens ?? 0,
+    costUsd: Math.round((row.cost_usd ?? 0) * 1e6) / 1e6,
+    avgLatencyMs: row.avg_latency != null ? Math.round(row.avg_latency) : null,
+  }));
+}
+
 // ============================================
 // Metrics / Summary Operations
 // ============================================
@@ -85,3 +85,23 @@ CREATE TABLE IF NOT EXISTS request_log (
 
 CREATE INDEX IF NOT EXISTS idx_request_log_endpoint ON request_log(endpoint);
 CREATE INDEX IF NOT EXISTS idx_request_log_created ON request_log(created_at);
+
+-- LLM usage table (one row per provider call; drives per-user token budgets)
+CREATE TABLE IF NOT EXISTS llm_usage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_hash TEXT,  -- hashAddress() of the sim user, NULL for unattributed calls
+    correlation_id TEXT,
+    provider TEXT NOT NULL,  -- openai/anthropic/gemini
+    model TEXT,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd REAL NOT NULL DEFAULT 0,
+    latency_ms INTEGER,
+    cached INTEGER NOT NULL DEFAULT 0,  -- 1 when served from the response cache
+    status TEXT NOT NULL DEFAULT 'ok',  -- ok/error/timeout/rate_limited
+    error_message TEXT,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_usage_user ON llm_usage(user_hash, created_at);
+CREATE INDEX IF NOT EXISTS idx_llm_usage_created ON llm_usage(created_at);
@@ -9,7 +9,7 @@ import { derivePerpPositionsFromStrategies } from '../lib/derivePerpPositions';
 import { USE_AGENT_BACKEND, executionMode as configExecutionMode, executionAuthMode, ethTestnetIntent, fundingRouteMode, enableDemoSwap } from '../lib/config';
 import { callAgent, executeIntent, confirmIntent, type IntentExecutionResult } from '../lib/apiClient';
 import { getAddress, connectWallet, sendTransaction, type PreparedTx } from '../lib/walletAdapter';
-import { callBlossomChat } from '../lib/blossomApi';
+import { callBlossomChat, streamBlossomChat, type ChatRequest, type ChatResponse } from '../lib/blossomApi';
 import QuickStartPanel from './QuickStartPanel';
 import BlossomHelperOverlay from './BlossomHelperOverlay';
 import { HelpCircle } from 'lucide-react';
@@ -1518,8 +1518,35 @@ export default function Chat({ selectedStrategyId, executionMode = 'auto', onReg
     if (USE_AGENT_BACKEND) {
       // Agent mode: call backend
       console.log('[Chat] Using AGENT backend mode - request will go to backend');
+
+      // The reply streams into one assistant message as the model writes it;
+      // that message is then replaced by the final reply (or error)
+      const streamMessageId = `assistant-${Date.now()}-${Math.random().toString(36).substr(2, 9)}`;
+      let streamedText = '';
+      const showStreamedText = (text: string) => {
+        if (!streamedText) {
+          setIsTyping(false);
+          appendMessageToChat(targetChatId, {
+            id: streamMessageId,
+            text,
+            isUser: false,
+            timestamp: new Date().toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit' }),
+          });
+        } else {
+          updateMessageInChat(targetChatId, streamMessageId, { text });
+        }
+        streamedText = text;
+      };
+      const showAssistantMessage = (message: ChatMessage) => {
+        if (streamedText) {
+          updateMessageInChat(targetChatId, streamMessageId, { ...message, id: streamMessageId });
+        } else {
+          appendMessageToChat(targetChatId, message);
+        }
+      };
+
       try {
-        const response = await callBlossomChat({
+        const chatRequest: ChatRequest = {
           userMessage: userText,
           venue,
           clientPortfolio: {
@@ -1528,7 +1555,18 @@ export default function Chat({ selectedStrategyId, executionMode = 'auto', onReg
             openPerpExposureUsd: account.openPerpExposure,
             eventExposureUsd: account.eventExposureUsd,
           },
-        });
+        };
+        let response: ChatResponse;
+        try {
+          response = await streamBlossomChat(chatRequest, {
+            onToken: token => showStreamedText(streamedText + token),
+            onMessage: showStreamedText,
+          });
+        } catch (streamError) {
+          // Proxies that buffer or drop SSE break the stream; ask again without it
+          console.warn('[Chat] Streaming chat failed, retrying without streaming:', streamError);
+          response = await callBlossomChat(chatRequest);
+        }
 
         // Handle error codes for explicit UI states
         if (response.errorCode) {
@@ -1559,7 +1597,7 @@ export default function Chat({ selectedStrategyId, executionMode = 'auto', onReg
             isUser: false,
             timestamp: new Date().toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit' }),
           };
-          appendMessageToChat(targetChatId, errorChatMessage);
+          showAssistantMessage(errorChatMessage);
           
           // Do NOT update portfolio on error
           return;
@@ -1878,8 +1916,8 @@ export default function Chat({ selectedStrategyId, executionMode = 'auto', onReg
           console.log('[Chat] Draft source:', (response as any).draftId ? 'server-created' : (draftId ? 'frontend-created' : 'none'));
         }
         
-        // Always append - never replace (using stable chat id)
-        appendMessageToChat(targetChatId, blossomResponse);
+        // Append, or finish the streamed message in place (using stable chat id)
+        showAssistantMessage(blossomResponse);
       } catch (error: any) {
         console.error('Agent backend error:', error);
         const errorMessage: ChatMessage = {
@@ -1888,8 +1926,8 @@ export default function Chat({ selectedStrategyId, executionMode = 'auto', onReg
           isUser: false,
           timestamp: new Date().toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit' }),
         };
-        // Always append - never replace (using stable chat id)
-        appendMessageToChat(targetChatId, errorMessage);
+        // A partially streamed reply is replaced rather than left looking complete
+        showAssistantMessage(errorMessage);
       } finally {
         // 5. Only AFTER the send logic has run (success or failure) do we clear the input DOM value AND state.
         //    This ensures the text doesn't disappear if there's an error or early return.
@@ -7,9 +7,10 @@
  */
 
 import { useEffect, useRef } from 'react';
-import { useAccount, useChainId } from 'wagmi';
+import { useAccount, useChainId, useSignMessage } from 'wagmi';
 import { useWallet } from '@solana/wallet-adapter-react';
 import { sepolia } from 'wagmi/chains';
+import { signInWithWallet, signOutWallet } from '../../lib/blossomApi';
 
 // Update the legacy walletAdapter's explicit connection state
 // This is a minimal bridge that keeps existing code working
@@ -37,6 +38,7 @@ export default function WalletStateBridge() {
   // EVM wallet state
   const { address: evmAddress, isConnected: evmConnected } = useAccount();
   const evmChainId = useChainId();
+  const { signMessageAsync } = useSignMessage();
 
   // Solana wallet state
   const { publicKey: solPublicKey, connected: solConnected } = useWallet();
@@ -51,6 +53,18 @@ export default function WalletStateBridge() {
     address: null,
   });
 
+  // Sign the EVM wallet in with the agent so requests carry its bearer token
+  function signInEvmWallet(address: string) {
+    signInWithWallet(address, message => signMessageAsync({ message }))
+      .then(() => window.dispatchEvent(new CustomEvent('blossom-wallet-connection-change')))
+      .catch(error => {
+        // Rejected or failed: keep going signed out (session-scoped sim account)
+        if (import.meta.env.DEV) {
+          console.warn('[WalletStateBridge] Wallet sign-in skipped:', error?.message);
+        }
+      });
+  }
+
   // Sync EVM wallet state
   useEffect(() => {
     const currentAddress = evmAddress?.toLowerCase() ?? null;
@@ -67,6 +81,7 @@ export default function WalletStateBridge() {
         console.log('[WalletStateBridge] EVM wallet connected:', currentAddress.slice(0, 10));
       }
       setExplicitlyConnected(true, currentAddress);
+      signInEvmWallet(currentAddress);
 
       // Trigger portfolio sync
       window.dispatchEvent(new CustomEvent('blossom-wallet-connection-change'));
@@ -83,6 +98,8 @@ export default function WalletStateBridge() {
         console.log('[WalletStateBridge] EVM wallet disconnected');
       }
 
+      signOutWallet();
+
       // Only clear if no Solana wallet connected
       if (!solConnected) {
         setExplicitlyConnected(false, null);
@@ -95,6 +112,8 @@ export default function WalletStateBridge() {
         console.log('[WalletStateBridge] EVM address changed:', currentAddress.slice(0, 10));
       }
       setExplicitlyConnected(true, currentAddress);
+      signOutWallet();
+      signInEvmWallet(currentAddress);
       window.dispatchEvent(new CustomEvent('blossom-wallet-connection-change'));
     }
   }, [evmConnected, evmAddress, evmChainId, solConnected]);
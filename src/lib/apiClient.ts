@@ -85,6 +85,42 @@ export function setBackendHealthy(healthy: boolean): void {
   }
 }
 
+/**
+ * Wallet sign-in token issued by /api/auth/wallet/verify
+ * Sent as `Authorization: Bearer <token>` so the agent trusts the address
+ */
+export interface WalletAuthSession {
+  address: string;
+  token: string;
+  expiresAt: number; // Unix seconds
+}
+
+const WALLET_AUTH_STORAGE_KEY = 'blossom_wallet_auth';
+
+/**
+ * Stored sign-in token, if it is still valid (and for `address`, when given)
+ */
+export function getWalletAuth(address?: string): WalletAuthSession | null {
+  if (typeof window === 'undefined') return null;
+  try {
+    const session = JSON.parse(localStorage.getItem(WALLET_AUTH_STORAGE_KEY) || 'null') as WalletAuthSession | null;
+    if (!session?.token || session.expiresAt <= Date.now() / 1000) return null;
+    if (address && session.address.toLowerCase() !== address.toLowerCase()) return null;
+    return session;
+  } catch {
+    return null;
+  }
+}
+
+export function setWalletAuth(session: WalletAuthSession | null): void {
+  if (typeof window === 'undefined') return;
+  if (session) {
+    localStorage.setItem(WALLET_AUTH_STORAGE_KEY, JSON.stringify(session));
+  } else {
+    localStorage.removeItem(WALLET_AUTH_STORAGE_KEY);
+  }
+}
+
 /**
  * Wrapper for making requests to the agent API
  * Blocks requests if backend is not healthy (except health checks)
@@ -119,6 +155,10 @@ export async function callAgent(
   if (walletAddress) {
     headers.set('X-Wallet-Address', walletAddress);
   }
+  const walletAuth = getWalletAuth();
+  if (walletAuth && !headers.has('Authorization')) {
+    headers.set('Authorization', `Bearer ${walletAuth.token}`);
+  }
   // Add correlation ID if provided in options
   if (options.correlationId) {
     headers.set('x-correlation-id', options.correlationId);
@@ -132,6 +172,8 @@ export async function callAgent(
     const response = await fetch(url, {
       ...options,
       headers,
+      // Send the sim session cookie the agent issues to signed-out clients
+      credentials: 'include',
       // Add timeout to prevent hanging
       signal: AbortSignal.timeout(timeoutMs),
     });
@@ -3,12 +3,14 @@
  * Front-end integration layer for calling the backend agent service
  */
 
-import { callAgent } from './apiClient';
+import { callAgent, getWalletAuth, setWalletAuth, type WalletAuthSession } from './apiClient';
 
 export interface ChatRequest {
   userMessage: string;
   venue: 'hyperliquid' | 'event_demo';
   clientPortfolio?: any; // keep flexible for now
+  threadId?: string; // Continue a conversation (defaults to the most recent one)
+  newThread?: boolean; // Start a fresh conversation
 }
 
 export interface ExecutionResult {
@@ -50,6 +52,7 @@ export interface ChatResponse {
   portfolio: any; // matches BlossomPortfolioSnapshot from backend
   executionResults?: ExecutionResult[]; // Unified execution results
   errorCode?: 'INSUFFICIENT_BALANCE' | 'SESSION_EXPIRED' | 'RELAYER_FAILED' | 'SLIPPAGE_FAILURE' | 'LLM_REFUSAL' | 'UNKNOWN_ERROR';
+  threadId?: string; // Conversation thread the turn was recorded on
 }
 
 export interface CloseRequest {
@@ -129,6 +132,63 @@ export async function callBlossomChat(req: ChatRequest): Promise<ChatResponse> {
   return res.json();
 }
 
+export interface ChatStreamHandlers {
+  onToken?: (text: string) => void;        // Assistant text as the model writes it
+  onMessage?: (assistantMessage: string) => void; // Final text (may differ from the streamed preview)
+}
+
+/**
+ * Call Blossom chat endpoint over server-sent events
+ * Resolves with the same ChatResponse as callBlossomChat once the stream ends
+ */
+export async function streamBlossomChat(req: ChatRequest, handlers: ChatStreamHandlers = {}): Promise<ChatResponse> {
+  const res = await callAgent('/api/chat', {
+    method: 'POST',
+    headers: { Accept: 'text/event-stream' },
+    body: JSON.stringify({ ...req, stream: true }),
+  });
+
+  if (!res.ok) {
+    throw new Error(`Blossom agent error: ${res.status}`);
+  }
+  if (!res.body || !(res.headers.get('content-type') || '').includes('text/event-stream')) {
+    // Server answered with plain JSON (e.g. an older agent)
+    return res.json();
+  }
+
+  const reader = res.body.getReader();
+  const decoder = new TextDecoder();
+  let buffer = '';
+
+  while (true) {
+    const { value, done } = await reader.read();
+    if (done) break;
+    buffer += decoder.decode(value, { stream: true });
+
+    let boundary: number;
+    while ((boundary = buffer.indexOf('\n\n')) !== -1) {
+      const frame = buffer.slice(0, boundary);
+      buffer = buffer.slice(boundary + 2);
+
+      let event = 'message';
+      let data = '';
+      for (const line of frame.split('\n')) {
+        if (line.startsWith('event: ')) event = line.slice(7);
+        else if (line.startsWith('data: ')) data += line.slice(6);
+      }
+      if (!data) continue; // heartbeat comment
+
+      const payload = JSON.parse(data);
+      if (event === 'token') handlers.onToken?.(payload.text);
+      else if (event === 'message') handlers.onMessage?.(payload.assistantMessage);
+      else if (event === 'error') throw new Error(payload.error || 'Blossom agent stream error');
+      else if (event === 'done') return payload as ChatResponse;
+    }
+  }
+
+  throw new Error('Blossom agent stream ended without a response');
+}
+
 /**
  * Close a strategy
  */
@@ -160,3 +220,49 @@ export async function resetSim(): Promise<{ portfolio: any; message: string }> {
   return res.json();
 }
 
+
+/**
+ * Sign in a wallet with the agent: sign its challenge, store the bearer token
+ * Reuses a still-valid token for the same address. Throws if the user rejects
+ * the signature or the agent refuses it.
+ */
+export async function signInWithWallet(
+  address: string,
+  signMessage: (message: string) => Promise<string>
+): Promise<WalletAuthSession> {
+  const existing = getWalletAuth(address);
+  if (existing) {
+    return existing;
+  }
+
+  const challengeRes = await callAgent('/api/auth/wallet/challenge', {
+    method: 'POST',
+    body: JSON.stringify({ address }),
+  });
+  if (!challengeRes.ok) {
+    throw new Error(`Blossom agent error: ${challengeRes.status}`);
+  }
+  const { nonce, message } = await challengeRes.json();
+
+  const signature = await signMessage(message);
+
+  const verifyRes = await callAgent('/api/auth/wallet/verify', {
+    method: 'POST',
+    body: JSON.stringify({ address, nonce, signature }),
+  });
+  if (!verifyRes.ok) {
+    throw new Error(`Blossom agent error: ${verifyRes.status}`);
+  }
+  const { token, expiresAt } = await verifyRes.json();
+
+  const session: WalletAuthSession = { address: address.toLowerCase(), token, expiresAt };
+  setWalletAuth(session);
+  return session;
+}
+
+/**
+ * Forget the stored wallet sign-in (on disconnect)
+ */
+export function signOutWallet(): void {
+  setWalletAuth(null);
+}